@@ -0,0 +1,145 @@
+//! Parser throughput over synthetic Booli search pages of growing size
+//!
+//! `cargo bench --bench parsing`; compare runs with `-- --save-baseline <name>`
+//! and `-- --baseline <name>` to check a parser change.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use housing_scout::scrapers::booli::parse_card_lines;
+use housing_scout::scrapers::cards::parse_listing_cards;
+use housing_scout::scrapers::detail::parse_detail_page;
+use housing_scout::scrapers::fast_path::parse_search_page;
+use housing_scout::scrapers::parse::{parse_price, parse_rooms, parse_sqm};
+use housing_scout::scrapers::selectors::CardSelectors;
+use housing_scout::scrapers::AreaSearch;
+use serde_json::json;
+use std::hint::black_box;
+
+/// Cards per page; Booli shows about 40, the rest stand in for long scrolls
+const SIZES: [usize; 3] = [40, 200, 1000];
+
+fn search() -> AreaSearch {
+    AreaSearch {
+        name: "Södermalm".to_string(),
+        area_ids: vec![115341],
+        url: None,
+        city: "Stockholm".to_string(),
+    }
+}
+
+/// A search page as Chrome renders it, with `n` listing cards
+fn rendered_page(n: usize) -> String {
+    let cards: String = (0..n)
+        .map(|i| {
+            format!(
+                r#"<li><a class="object-card-link" href="/annons/{id}" aria-label="2 rum lägenhet på Götgatan {street} Södermalm, Stockholms kommun">
+<img src="https://bcdn.se/images/{id}/1.jpg" alt="Götgatan {street}">
+<ul><li aria-label="54,5&nbsp;kvadratmeter">54,5 m²</li><li aria-label="våning {floor}">vån {floor}</li><li aria-label="3&nbsp;449&nbsp;kr/mån">3 449 kr/mån</li></ul>
+<span class="object-card__price--logo">4&nbsp;250&nbsp;000 kr</span>
+<div class="tag">Balkong</div><div class="tag">Hiss</div>
+</a></li>"#,
+                id = 5_000_000 + i,
+                street = i % 150 + 1,
+                floor = i % 7,
+            )
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html><html lang=\"sv\"><head><title>Bostäder till salu</title></head><body><ul>{}</ul></body></html>",
+        cards
+    )
+}
+
+/// A search page as served over plain HTTP, one card per line
+fn text_page(n: usize) -> String {
+    let lines: String = (0..n)
+        .map(|i| {
+            format!(
+                "- [Idag[Spara Götgatan {street}Götgatan {street}Lägenhet · Södermalm · Stockholm5 195 000 kr70 m²2 rumvån 3 · 3 449 kr/månHissBalkong](https://www.booli.se/annons/{id})\n",
+                id = 5_000_000 + i,
+                street = i % 150 + 1,
+            )
+        })
+        .collect();
+    format!("<!DOCTYPE html>\n<html lang=\"sv\">\n<body>\n{}</body>\n</html>", lines)
+}
+
+/// A search page with its listings in `__NEXT_DATA__`
+fn next_data_page(n: usize) -> String {
+    let listings: serde_json::Map<String, serde_json::Value> = (0..n)
+        .map(|i| {
+            let id = 5_000_000 + i;
+            let listing = json!({
+                "__typename": "Listing",
+                "booliId": id,
+                "url": format!("/annons/{}", id),
+                "streetAddress": format!("Götgatan {}", i % 150 + 1),
+                "descriptiveAreaName": "Södermalm",
+                "municipalityName": "Stockholm",
+                "listPrice": { "raw": 4_250_000 },
+                "rent": { "raw": 3449 },
+                "livingArea": { "raw": 54.5 },
+                "rooms": { "raw": 2 },
+                "floor": { "raw": i % 7 },
+                "latitude": 59.31,
+                "longitude": 18.07,
+                "amenities": [{ "key": "balcony" }, { "key": "elevator" }],
+            });
+            (format!("Listing:{}", id), listing)
+        })
+        .collect();
+    let data = json!({ "props": { "pageProps": { "__APOLLO_STATE__": listings } } });
+    format!(
+        r#"<!DOCTYPE html><html><body><div id="__next"></div><script id="__NEXT_DATA__" type="application/json">{}</script></body></html>"#,
+        data
+    )
+}
+
+fn search_pages(c: &mut Criterion) {
+    let search = search();
+    let cards = CardSelectors::default();
+    let mut group = c.benchmark_group("search_page");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+
+        let page = rendered_page(n);
+        group.bench_with_input(BenchmarkId::new("cards", n), &page, |b, page| {
+            b.iter(|| parse_listing_cards(black_box(page), &search, &cards))
+        });
+
+        let page = text_page(n);
+        group.bench_with_input(BenchmarkId::new("card_lines", n), &page, |b, page| {
+            b.iter(|| parse_card_lines(black_box(page)))
+        });
+
+        let page = next_data_page(n);
+        group.bench_with_input(BenchmarkId::new("next_data", n), &page, |b, page| {
+            b.iter(|| parse_search_page(black_box(page), &search))
+        });
+    }
+    group.finish();
+}
+
+fn detail_page(c: &mut Criterion) {
+    let file = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/listings/1001.html");
+    let page = std::fs::read_to_string(file).expect("the detail page fixture exists");
+    c.bench_function("detail_page", |b| b.iter(|| parse_detail_page(black_box(&page))));
+}
+
+fn numbers(c: &mut Criterion) {
+    let texts = [
+        "5 195 000 kr70 m²2 rumvån 3 · 3 449 kr/mån",
+        "2 rum lägenhet på Götgatan 120 Södermalm, Stockholms kommun",
+        "54,5 kvadratmeter",
+        "Snart till salu61 m²2,5 rum",
+    ];
+    c.bench_function("numbers", |b| {
+        b.iter(|| {
+            for text in texts {
+                black_box((parse_price(text), parse_sqm(text), parse_rooms(text)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, search_pages, detail_page, numbers);
+criterion_main!(benches);
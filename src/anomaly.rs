@@ -0,0 +1,132 @@
+use crate::models::Property;
+use crate::stats::median;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Settings for flagging under-priced listings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    /// Flag listings at least this far below their cohort median (0.15 = 15%)
+    pub threshold: f64,
+    /// Cohorts with fewer comparable listings than this are not trusted
+    pub min_cohort_size: usize,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.15,
+            min_cohort_size: 5,
+        }
+    }
+}
+
+/// Size bands in square meters; listings are compared within their band
+const SIZE_BANDS: [(i32, &str); 5] = [
+    (35, "<35 kvm"),
+    (55, "35-55 kvm"),
+    (75, "55-75 kvm"),
+    (100, "75-100 kvm"),
+    (i32::MAX, "100+ kvm"),
+];
+
+/// A listing priced well below comparable listings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Anomaly {
+    /// Cohort the listing was compared against, e.g. "Södermalm 55-75 kvm"
+    pub cohort: String,
+    pub cohort_size: usize,
+    pub cohort_median_price_per_sqm: f64,
+    pub price_per_sqm: f64,
+    /// How far below the cohort median, 0.0 - 1.0
+    pub discount: f64,
+}
+
+/// Median price per sqm per area and per area + size band
+#[derive(Debug, Clone, Default)]
+pub struct PriceBaseline {
+    cohorts: BTreeMap<String, (usize, f64)>,
+}
+
+impl PriceBaseline {
+    /// Build cohort statistics from accumulated listings
+    pub fn from_properties(properties: &[Property]) -> Self {
+        let mut values: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+
+        for property in properties {
+            let (Some(area), Some(per_sqm)) = (area_key(property), price_per_sqm(property)) else {
+                continue;
+            };
+            values.entry(area.clone()).or_default().push(per_sqm);
+            values
+                .entry(format!("{} {}", area, size_band(property.sqm.unwrap_or_default())))
+                .or_default()
+                .push(per_sqm);
+        }
+
+        let cohorts = values
+            .into_iter()
+            .filter_map(|(key, mut v)| {
+                let count = v.len();
+                median(&mut v).map(|m| (key, (count, m)))
+            })
+            .collect();
+
+        Self { cohorts }
+    }
+
+    /// Compare a listing against its size cohort, falling back to the whole area
+    pub fn check(&self, property: &Property, config: &AnomalyConfig) -> Option<Anomaly> {
+        let area = area_key(property)?;
+        let per_sqm = price_per_sqm(property)?;
+
+        let sized = format!("{} {}", area, size_band(property.sqm.unwrap_or_default()));
+        let (cohort, (count, cohort_median)) = [sized, area]
+            .into_iter()
+            .find_map(|key| {
+                self.cohorts
+                    .get(&key)
+                    .filter(|(count, _)| *count >= config.min_cohort_size)
+                    .map(|stats| (key, *stats))
+            })?;
+
+        let discount = 1.0 - per_sqm / cohort_median;
+        (discount >= config.threshold).then(|| Anomaly {
+            cohort: display_cohort(property, &cohort),
+            cohort_size: count,
+            cohort_median_price_per_sqm: cohort_median,
+            price_per_sqm: per_sqm,
+            discount,
+        })
+    }
+}
+
+fn area_key(property: &Property) -> Option<String> {
+    property.location.area.as_ref().map(|a| a.trim().to_lowercase())
+}
+
+fn price_per_sqm(property: &Property) -> Option<f64> {
+    let (price, sqm) = (property.price?, property.sqm?);
+    (price.is_positive() && sqm > 0).then(|| price.as_f64() / sqm as f64)
+}
+
+fn size_band(sqm: i32) -> &'static str {
+    SIZE_BANDS
+        .iter()
+        .find(|(upper, _)| sqm < *upper)
+        .map(|(_, label)| *label)
+        .unwrap_or("100+ kvm")
+}
+
+/// Cohort keys are lowercased; show them with the listing's own area spelling
+fn display_cohort(property: &Property, key: &str) -> String {
+    match &property.location.area {
+        Some(area) => {
+            let lower = area.trim().to_lowercase();
+            key.replacen(&lower, area.trim(), 1)
+        }
+        None => key.to_string(),
+    }
+}
@@ -0,0 +1,327 @@
+//! `scout bot`: searching and marking listings from a Telegram chat
+//!
+//! ```text
+//! /search 2 rooms under 5M Södermalm
+//! /search balkong-soder        a saved search by name
+//! /fav 123456                  also /unfav, /hide and /unhide; /fav_123456
+//!                              is the same and can be tapped in a reply
+//! /stats Södermalm             per-area figures of the last 30 days
+//! ```
+//!
+//! Text without a command is searched for like `/search`.
+
+use crate::cli::{FilterArgs, SortKey};
+use crate::config::Config;
+use crate::export::{self, filter_records, sort_records, RecordContext};
+use crate::filter::Filter;
+use crate::i18n::Language;
+use crate::secrets::Secret;
+use crate::stats::area_stats;
+use crate::storage::{Annotation, JsonStore};
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Days `/stats` aggregates over
+const STATS_DAYS: i64 = 30;
+
+/// Settings for `scout bot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotConfig {
+    /// Bot token from @BotFather
+    pub token: Option<Secret>,
+    /// Chats the bot answers; any other chat is told its ID to add here
+    pub allowed_chats: Vec<i64>,
+    /// Listings shown per `/search`, best scoring first
+    pub results: usize,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            allowed_chats: Vec::new(),
+            results: 5,
+        }
+    }
+}
+
+/// A message to the bot
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotCommand {
+    Search(String),
+    Favorite { id: String, favorite: bool },
+    Hide { id: String, hidden: bool },
+    Stats { area: Option<String> },
+    Help,
+}
+
+impl BotCommand {
+    /// What `text` asks for; unknown commands and ones missing their
+    /// listing ID get the help text
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let Some(command) = text.strip_prefix('/') else {
+            return BotCommand::Search(text.to_string());
+        };
+        let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        // Commands in group chats are addressed, as in "/search@scout_bot"
+        let name = name.split('@').next().unwrap_or(name).to_lowercase();
+        let args = args.trim();
+        let (name, id) = match name.split_once('_') {
+            Some((name, id)) if args.is_empty() => (name.to_string(), Some(id.to_string())),
+            _ => (name.clone(), args.split_whitespace().next().map(str::to_string)),
+        };
+
+        match (name.as_str(), id) {
+            ("search" | "sok" | "sök", _) => BotCommand::Search(args.to_string()),
+            ("fav", Some(id)) => BotCommand::Favorite { id, favorite: true },
+            ("unfav", Some(id)) => BotCommand::Favorite { id, favorite: false },
+            ("hide", Some(id)) => BotCommand::Hide { id, hidden: true },
+            ("unhide", Some(id)) => BotCommand::Hide { id, hidden: false },
+            ("stats", _) => BotCommand::Stats {
+                area: (!args.is_empty()).then(|| args.to_string()),
+            },
+            _ => BotCommand::Help,
+        }
+    }
+}
+
+const ROOM_UNITS: &[&str] = &["rooms", "room", "rum", "rok", "r"];
+const SQM_UNITS: &[&str] = &["sqm", "kvm", "m2", "m²"];
+const PRICE_WORDS: &[&str] = &["under", "max", "below", "upto", "<"];
+/// Words left out of the place a search names
+const FILLER_WORDS: &[&str] = &[
+    "in", "i", "at", "on", "på", "and", "och", "with", "med", "min", "minst", "over", "över", "from", "från", "kr", "sek",
+];
+
+/// Filters for a `/search`: a saved search by name, or free text such as
+/// "2 rooms under 5M Södermalm" or "2 rum max 4,5 mkr 50 kvm Hornstull"
+///
+/// A number with a room unit is a minimum room count, with a m² unit a
+/// minimum size, and after "under" or "max" a maximum price; whatever words
+/// are left name the place, matched against area and address.
+pub fn search_filters(query: &str, config: &Config) -> Result<FilterArgs> {
+    let query = query.trim();
+    if let Some(search) = config.searches.iter().find(|s| s.name.eq_ignore_ascii_case(query)) {
+        return Ok(FilterArgs {
+            search: Some(search.name.clone()),
+            ..FilterArgs::default()
+        });
+    }
+
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let original: Vec<&str> = query.split_whitespace().collect();
+    let mut filters = FilterArgs::default();
+    let mut place = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let next = words.get(i + 1).map(String::as_str);
+        if PRICE_WORDS.contains(&words[i].as_str()) {
+            if let Some((price, used)) = next.and_then(|next| price(next, words.get(i + 2).map(String::as_str))) {
+                filters.max_price = Some(price);
+                i += 1 + used;
+                continue;
+            }
+        }
+        // "5M" on its own is a price too
+        if let Some((price, used)) = price(&words[i], next).filter(|_| words[i].ends_with(['m', 'k']) || words[i].ends_with("mkr")) {
+            filters.max_price = Some(price);
+            i += used;
+            continue;
+        }
+        if let Some((rooms, used)) = with_unit(&words[i], next, ROOM_UNITS) {
+            filters.min_rooms = Some(rooms as f32);
+            i += used;
+            continue;
+        }
+        if let Some((sqm, used)) = with_unit(&words[i], next, SQM_UNITS) {
+            filters.min_sqm = Some(sqm.round() as i32);
+            i += used;
+            continue;
+        }
+        if !FILLER_WORDS.contains(&words[i].as_str()) {
+            place.push(original[i]);
+        }
+        i += 1;
+    }
+
+    if !place.is_empty() {
+        let place = place.join(" ");
+        filters.filter = Some(Filter::parse(&format!("area ~ {0:?} || address ~ {0:?}", place))?);
+    }
+    Ok(filters)
+}
+
+/// "4,5" or "4.5"
+fn number(word: &str) -> Option<f64> {
+    word.replace(',', ".").parse().ok().filter(|n: &f64| n.is_finite() && *n >= 0.0)
+}
+
+/// A number with one of `units`, written together ("2rum") or apart
+/// ("2 rum"), and how many words it took
+fn with_unit(word: &str, next: Option<&str>, units: &[&str]) -> Option<(f64, usize)> {
+    if let (Some(n), Some(next)) = (number(word), next) {
+        if units.contains(&next) {
+            return Some((n, 2));
+        }
+    }
+    units.iter().find_map(|unit| word.strip_suffix(unit).and_then(number)).map(|n| (n, 1))
+}
+
+/// A price such as "5m", "4,5 mkr", "4500k" or "4500000", and how many words
+/// it took; bare numbers below a thousand are taken as millions
+fn price(word: &str, next: Option<&str>) -> Option<(i64, usize)> {
+    let split = word.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.')).unwrap_or(word.len());
+    let (amount, unit) = word.split_at(split);
+    let amount = number(amount)?;
+    let (unit, used) = match (unit, next) {
+        ("", Some(next)) if multiplier(next).is_some() => (next, 2),
+        _ => (unit, 1),
+    };
+    let multiplier = multiplier(unit)?;
+    let price = if multiplier == 1.0 && amount < 1_000.0 {
+        amount * 1_000_000.0
+    } else {
+        amount * multiplier
+    };
+    Some((price.round() as i64, used))
+}
+
+fn multiplier(unit: &str) -> Option<f64> {
+    match unit {
+        "" | "kr" | "sek" | ":-" => Some(1.0),
+        "k" | "tkr" | "tusen" => Some(1_000.0),
+        "m" | "mkr" | "mn" | "milj" | "miljoner" | "million" => Some(1_000_000.0),
+        _ => None,
+    }
+}
+
+/// The answer to `command`, in `language`
+///
+/// A listing that isn't stored or a search that finds nothing is answered
+/// as such; `Err` is for failures reading or writing the store.
+pub async fn reply(command: &BotCommand, store: &JsonStore, config: &Config, language: Language) -> Result<String> {
+    match command {
+        BotCommand::Search(query) => search(query, store, config, language).await,
+        BotCommand::Favorite { id, favorite } => {
+            let key = if *favorite { "bot.favorited" } else { "bot.unfavorited" };
+            annotate(id, store, language, key, |annotation| annotation.favorite = *favorite).await
+        }
+        BotCommand::Hide { id, hidden } => {
+            let key = if *hidden { "bot.hidden" } else { "bot.unhidden" };
+            annotate(id, store, language, key, |annotation| annotation.hidden = *hidden).await
+        }
+        BotCommand::Stats { area } => stats(area.as_deref(), store, language).await,
+        BotCommand::Help => {
+            let mut help = language.t("bot.help").to_string();
+            if !config.searches.is_empty() {
+                let names: Vec<&str> = config.searches.iter().map(|s| s.name.as_str()).collect();
+                help.push_str("\n\n");
+                help.push_str(&language.format("bot.saved_searches", &[("names", &names.join(", "))]));
+            }
+            Ok(help)
+        }
+    }
+}
+
+/// The best scoring listings of the latest run matching `query`
+async fn search(query: &str, store: &JsonStore, config: &Config, language: Language) -> Result<String> {
+    let filters = search_filters(query, config)?;
+    let properties = store.load_latest_properties().await?.unwrap_or_default();
+    let context = RecordContext::load(store, config, &properties).await?;
+    let mut records = export::records(&properties, config, &context);
+    filter_records(&mut records, &filters, config)?;
+    if records.is_empty() {
+        return Ok(language.format("bot.no_matches", &[("query", &query)]));
+    }
+    sort_records(&mut records, SortKey::Score, true);
+
+    let shown = records.len().min(config.bot.results.max(1));
+    let mut reply = language.format("bot.matches", &[("count", &records.len()), ("shown", &shown)]);
+    for record in &records[..shown] {
+        let property = record.property;
+        reply.push_str("\n\n");
+        reply.push_str(&property.address);
+        if let Some(area) = &property.location.area {
+            reply.push_str(&format!(", {}", area));
+        }
+        reply.push_str(&format!(
+            "\n{} · {} {:.2}",
+            language.summary(property),
+            language.t("score"),
+            record.score.total
+        ));
+        if let Some(cost) = record.metrics.monthly_cost {
+            reply.push_str(&format!(" · {}", language.format("notify.monthly_cost", &[("cost", &format!("{:.0}", cost))])));
+        }
+        if record.anomaly.is_some() {
+            reply.push_str(" · 🔥");
+        }
+        reply.push_str(&format!("\n{}\n{} · {}", property.url, command("fav", &property.id), command("hide", &property.id)));
+    }
+    Ok(reply)
+}
+
+/// "/fav_123" where Telegram makes that a tappable command, else "/fav 123"
+fn command(name: &str, id: &str) -> String {
+    if id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("/{}_{}", name, id)
+    } else {
+        format!("/{} {}", name, id)
+    }
+}
+
+/// Change the annotation of listing `id`, replying with `key`
+async fn annotate(
+    id: &str,
+    store: &JsonStore,
+    language: Language,
+    key: &str,
+    change: impl FnOnce(&mut Annotation),
+) -> Result<String> {
+    let properties = store.load_all_properties().await?;
+    let Some(property) = properties.iter().find(|p| p.id == id) else {
+        return Ok(language.format("bot.unknown_listing", &[("id", &id)]));
+    };
+    let mut annotations = store.load_annotations().await?;
+    change(annotations.entry(id));
+    annotations.prune();
+    store.save_annotations(&annotations).await?;
+    Ok(language.format(key, &[("address", &property.address)]))
+}
+
+/// Per-area figures of the last [`STATS_DAYS`] days, for one area or all
+async fn stats(area: Option<&str>, store: &JsonStore, language: Language) -> Result<String> {
+    let mut properties = store.load_all_properties().await?;
+    if let Some(area) = area {
+        properties.retain(|p| p.location.area.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(area)));
+    }
+    let history = store.load_history().await?;
+    let stats = area_stats(&properties, &history, Utc::now(), STATS_DAYS);
+    if stats.is_empty() {
+        return Ok(language.format("bot.no_stats", &[("days", &STATS_DAYS)]));
+    }
+
+    let mut reply = language.format("bot.stats_title", &[("days", &STATS_DAYS)]);
+    for row in &stats {
+        reply.push_str(&format!(
+            "\n\n{}: {}",
+            row.area,
+            language.format("bot.stats_listings", &[("count", &row.listings)])
+        ));
+        if let Some(price) = row.median_price {
+            reply.push_str(&format!("\n{}", language.format("bot.median_price", &[("price", &format!("{:.0}", price))])));
+        }
+        if let Some(per_sqm) = row.median_price_per_sqm {
+            let trend = row.price_per_sqm_trend.map(|t| format!(" ({:+.1}%)", t * 100.0)).unwrap_or_default();
+            reply.push_str(&format!(
+                "\n{}{}",
+                language.format("scrape.per_sqm", &[("per_sqm", &format!("{:.0}", per_sqm))]),
+                trend
+            ));
+        }
+    }
+    Ok(reply)
+}
@@ -0,0 +1,602 @@
+use crate::filter::{Filter, MaxAge};
+use crate::schema::SchemaType;
+use crate::storage::Status;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Housing Scout - scrape and track Booli listings
+#[derive(Debug, Parser)]
+#[command(name = "scout", version, about)]
+pub struct Cli {
+    /// Config file (defaults to ./scout.toml when present)
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Log format on stderr
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, with the fields of the enclosing source,
+    /// page and listing spans, for Loki or Elasticsearch
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Scrape listings and store the run (default)
+    Scrape(ScrapeArgs),
+    /// Export the latest stored properties
+    Export(ExportArgs),
+    /// Show which agencies and brokers list the most properties
+    Brokers(BrokersArgs),
+    /// Show per-area price, fee and time-on-market aggregates
+    Stats(StatsArgs),
+    /// List the highest scoring properties from the latest run
+    Rank(RankArgs),
+    /// Search listing descriptions, e.g. "quiet top floor near water"
+    Search(SearchArgs),
+    /// Mark a property as favorite/hidden/visited, rate it or add a note
+    Annotate(AnnotateArgs),
+    /// Show tracked properties grouped by pipeline status
+    Pipeline(PipelineArgs),
+    /// Show what changed between two stored runs
+    Diff(DiffArgs),
+    /// List recent scrape runs, or show one run's sources and errors
+    Runs(RunsArgs),
+    /// Load old scraped_properties.json / raw_scrape/ output into data/
+    Import(ImportArgs),
+    /// Run an SQL query against the stored properties, history and sold listings
+    Query(QueryArgs),
+    /// Write a printable report for one property
+    Report(ReportArgs),
+    /// Print the JSON Schema or TypeScript types of stored and exported data
+    Schema(SchemaArgs),
+    /// Scrape final prices of sold listings, for the price model and bid premiums
+    Sold(SoldArgs),
+    /// Turn a Booli or Hemnet search URL into scout.toml search settings
+    AddSearch(AddSearchArgs),
+    /// Sync saved searches and favorites with a Booli account
+    Booli(BooliArgs),
+    /// Store tokens and passwords in the OS keyring, referenced from scout.toml by name
+    Secrets(SecretsArgs),
+    /// List listing URLs from Booli's sitemaps, without parsing search pages
+    Discover(DiscoverArgs),
+    /// Serve the stored data as a GraphQL API for dashboards
+    Serve(ServeArgs),
+    /// Answer searches, favorites and stats requests from a Telegram chat
+    Bot,
+    /// Check hand-picked listings every few minutes and notify on any change
+    Watch(WatchArgs),
+}
+
+#[derive(Debug, Default, Args)]
+pub struct ScrapeArgs {
+    /// Only scrape listing cards; skip visiting each detail page
+    #[arg(long)]
+    pub skip_details: bool,
+
+    /// Download listing photos into the local image cache
+    #[arg(long)]
+    pub download_images: bool,
+
+    /// Save search page HTML and screenshots under debug/<run ID>/
+    #[arg(long)]
+    pub debug_artifacts: bool,
+
+    /// Continue an interrupted run, by run ID, reusing the pages it already fetched
+    #[arg(long, value_name = "RUN_ID", conflicts_with_all = ["skip_details", "download_images"])]
+    pub resume: Option<String>,
+
+    /// Search pages loaded at the same time, instead of [scrape] concurrency
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: Option<u16>,
+
+    /// Threads parsing search pages, instead of [scrape] parse_threads (0 = one per core)
+    #[arg(long, value_name = "N")]
+    pub parse_threads: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+    pub format: ExportFormat,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Template file for `--format template`; rendered with `records`,
+    /// `count` and `generated_at`
+    #[arg(long, required_if_eq("format", "template"))]
+    pub template: Option<PathBuf>,
+
+    /// Only export these property IDs (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    pub ids: Vec<String>,
+
+    #[command(flatten)]
+    pub filters: FilterArgs,
+
+    /// Sort by this field
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+
+    /// Sort in descending order
+    #[arg(long, requires = "sort")]
+    pub desc: bool,
+}
+
+/// Filters on raw and derived property values
+#[derive(Debug, Clone, Default, Args)]
+pub struct FilterArgs {
+    /// Maximum price in SEK
+    #[arg(long)]
+    pub max_price: Option<i64>,
+
+    /// Minimum size in square meters
+    #[arg(long)]
+    pub min_sqm: Option<i32>,
+
+    /// Minimum number of rooms
+    #[arg(long)]
+    pub min_rooms: Option<f32>,
+
+    /// Maximum price per square meter in SEK
+    #[arg(long)]
+    pub max_price_per_sqm: Option<f64>,
+
+    /// Maximum monthly fee per square meter in SEK
+    #[arg(long)]
+    pub max_fee_per_sqm: Option<f64>,
+
+    /// Maximum total monthly cost (interest + fee) in SEK
+    #[arg(long)]
+    pub max_monthly_cost: Option<f64>,
+
+    /// Maximum walking distance to a tunnelbana/pendeltåg station in meters
+    #[arg(long)]
+    pub max_metro_distance: Option<f64>,
+
+    /// Minimum year 9 merit rating of the best nearby school
+    #[arg(long)]
+    pub min_school_merit: Option<f64>,
+
+    /// Maximum noise level at the building in dB(A)
+    #[arg(long)]
+    pub max_noise: Option<f64>,
+
+    /// Exclude listings in a mapped flood risk zone
+    #[arg(long)]
+    pub no_flood_risk: bool,
+
+    /// Only listings with fiber broadband
+    #[arg(long)]
+    pub fiber: bool,
+
+    /// Exclude listings whose building has a pipe replacement ("stambyte")
+    /// planned or still to come, as far as the description tells
+    #[arg(long)]
+    pub no_pipe_replacement: bool,
+
+    /// Only listings on the market at most this long, e.g. 3d, 12h or 2w;
+    /// counted from publication, or first seen when the source has no date
+    #[arg(long)]
+    pub max_age: Option<MaxAge>,
+
+    /// Only listings priced well below comparable listings
+    #[arg(long)]
+    pub underpriced: bool,
+
+    /// Only listings marked as favorite
+    #[arg(long)]
+    pub favorites: bool,
+
+    /// Include listings marked as hidden
+    #[arg(long)]
+    pub include_hidden: bool,
+
+    /// Only listings in these pipeline statuses (comma separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub status: Vec<Status>,
+
+    /// Filter expression, e.g. 'price < 5m && sqm >= 55 && amenities ~ "balcony"'
+    #[arg(long)]
+    pub filter: Option<Filter>,
+
+    /// Apply a saved search from the config, by name
+    #[arg(long)]
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    Price,
+    Sqm,
+    Rooms,
+    Fee,
+    PricePerSqm,
+    FeePerSqm,
+    MonthlyCost,
+    Score,
+    /// Asking price relative to the predicted sale price
+    PredictedDelta,
+    /// Walking distance to the nearest station
+    MetroDistance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Pretty-printed JSON array of properties
+    Json,
+    /// One JSON object per line (JSON Lines)
+    Jsonl,
+    /// iCalendar file with upcoming viewings
+    Ics,
+    /// Static HTML site; --output names the directory to write
+    Site,
+    /// Render a Tera template given with --template
+    Template,
+    /// Upsert rows into the Google Sheet configured under [sheets]
+    Sheets,
+    /// Create or update pages in the Notion database configured under [notion]
+    Notion,
+    /// Upsert listings and price history into the Airtable base configured under [airtable]
+    Airtable,
+}
+
+#[derive(Debug, Args)]
+pub struct BrokersArgs {
+    /// Aggregate per agency or per individual broker
+    #[arg(long, value_enum, default_value_t = BrokerGroup::Agency)]
+    pub by: BrokerGroup,
+
+    /// Only include listings in this area
+    #[arg(long)]
+    pub area: Option<String>,
+
+    /// Include every property ever stored, not just the latest run
+    #[arg(long)]
+    pub all: bool,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Length of the period in days; the trend compares against the period before
+    #[arg(long, default_value_t = 30)]
+    pub days: i64,
+
+    /// Only show this area
+    #[arg(long)]
+    pub area: Option<String>,
+
+    /// Show final prices vs asking prices of the sales in the period instead,
+    /// from `scout sold`
+    #[arg(long)]
+    pub premiums: bool,
+
+    /// Show how the monthly fee per m² of each housing association developed
+    /// instead, across its listings and annual reports, flagging sharp rises
+    #[arg(long, conflicts_with = "premiums")]
+    pub fees: bool,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BrokerGroup {
+    Agency,
+    Broker,
+}
+
+#[derive(Debug, Args)]
+pub struct RankArgs {
+    /// Number of properties to list
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub top: usize,
+
+    #[command(flatten)]
+    pub filters: FilterArgs,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SearchArgs {
+    /// What to look for
+    pub query: String,
+
+    /// Number of matches to list
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub top: usize,
+
+    /// Rank by embedding similarity instead of keywords (needs the `semantic` feature)
+    #[arg(long)]
+    pub semantic: bool,
+
+    /// Search every property ever stored, not just the latest run
+    #[arg(long)]
+    pub all: bool,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AnnotateArgs {
+    /// Property ID
+    pub id: String,
+
+    /// Move the property to this pipeline status
+    #[arg(long, value_enum)]
+    pub status: Option<Status>,
+
+    #[arg(long, conflicts_with = "unfavorite")]
+    pub favorite: bool,
+
+    #[arg(long)]
+    pub unfavorite: bool,
+
+    /// Leave the property out of exports, rankings and notifications
+    #[arg(long, conflicts_with = "unhide")]
+    pub hide: bool,
+
+    #[arg(long)]
+    pub unhide: bool,
+
+    /// Mark the property as visited (e.g. after a viewing)
+    #[arg(long)]
+    pub visited: bool,
+
+    /// Personal rating from 1 to 5
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=5))]
+    pub rating: Option<u8>,
+
+    /// Add a free-text note
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct PipelineArgs {
+    /// Also list properties still in the New status
+    #[arg(long)]
+    pub all: bool,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RunsArgs {
+    /// Run ID to show in detail
+    pub id: Option<String>,
+
+    /// Number of recent runs to list
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub last: usize,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Older run, by run ID (see `scout runs`) or date (YYYY-MM-DD, the
+    /// day's last run); defaults to the run before `to`
+    pub from: Option<String>,
+
+    /// Newer run, by run ID or date; defaults to the latest run
+    pub to: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = DiffFormat::Table)]
+    pub format: DiffFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    Table,
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// Directory holding earlier scrape output, searched recursively
+    pub dir: PathBuf,
+
+    /// Show what would be imported without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// SQL statement, e.g. "SELECT area, avg(price_per_sqm) FROM properties GROUP BY area"
+    #[arg(required_unless_present = "schema")]
+    pub sql: Option<String>,
+
+    /// Print the available tables and columns
+    #[arg(long)]
+    pub schema: bool,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = QueryFormat::Table)]
+    pub format: QueryFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QueryFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Property ID
+    pub id: String,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Html)]
+    pub format: ReportFormat,
+
+    /// Output file (defaults to report-<id>.<format>)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Printable HTML page
+    Html,
+    /// PDF printed with headless Chrome
+    Pdf,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    /// Type to describe
+    #[arg(long = "type", value_enum, default_value_t = SchemaType::Property)]
+    pub ty: SchemaType,
+
+    /// Print TypeScript declarations instead of JSON Schema
+    #[arg(long)]
+    pub typescript: bool,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct SoldArgs {
+    /// Booli slutpriser search URL (default: Södermalm)
+    #[arg(long, conflicts_with = "backfill")]
+    pub url: Option<String>,
+
+    /// Find the final prices of tracked listings that left the market, on
+    /// the slutpriser pages of the configured areas; run it regularly
+    #[arg(long)]
+    pub backfill: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AddSearchArgs {
+    /// Search URL copied from booli.se or hemnet.se
+    pub url: String,
+
+    /// Name of the search (defaults to the site and area IDs)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Notify about new listings matching the search
+    #[arg(long)]
+    pub notify: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BooliArgs {
+    #[command(subcommand)]
+    pub command: BooliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BooliCommand {
+    /// Print the account's saved searches as scout.toml search settings
+    Searches {
+        /// Notify about new listings matching the imported searches
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Save every scout favorite as a favorite on Booli
+    PushFavorites,
+}
+
+#[derive(Debug, Args)]
+pub struct SecretsArgs {
+    #[command(subcommand)]
+    pub command: SecretsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SecretsCommand {
+    /// Store a secret, reading its value from stdin
+    Set { name: String },
+    /// Remove a stored secret
+    Delete { name: String },
+    /// Show which provider a secret would be read from, without printing it
+    Check { name: String },
+}
+
+#[derive(Debug, Args)]
+pub struct DiscoverArgs {
+    /// Sold listings instead of listings for sale
+    #[arg(long)]
+    pub sold: bool,
+
+    /// Only listings in this municipality, e.g. "Stockholm"
+    #[arg(long)]
+    pub municipality: Option<String>,
+
+    /// Only listings modified on or after this day (YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<chrono::NaiveDate>,
+
+    /// Sitemap index to start from
+    #[arg(long, default_value = crate::scrapers::sitemap::BOOLI_SITEMAP_URL)]
+    pub url: String,
+
+    /// Print JSON instead of one URL per line
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address to listen on, instead of [server] bind
+    #[arg(long)]
+    pub bind: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Listing URLs or IDs to add to the watchlist
+    pub listings: Vec<String>,
+
+    /// Stop watching the given listings instead
+    #[arg(long, requires = "listings")]
+    pub remove: bool,
+
+    /// Show the watched listings as of their last check and exit
+    #[arg(long)]
+    pub list: bool,
+
+    /// Check once and exit, e.g. from cron
+    #[arg(long)]
+    pub once: bool,
+
+    /// Minutes between checks, instead of [watch] interval_minutes
+    #[arg(long)]
+    pub every: Option<u64>,
+}
@@ -0,0 +1,71 @@
+use crate::cli::AddSearchArgs;
+use crate::filter::{KeywordRules, SavedSearch};
+use crate::scrapers::{AreaSearch, SearchParams, SearchSite};
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Snippet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scrape: Option<ScrapeAreas>,
+    searches: Vec<SavedSearch>,
+}
+
+#[derive(Serialize)]
+struct ScrapeAreas {
+    areas: Vec<AreaSearch>,
+}
+
+/// Print the `[[scrape.areas]]` and `[[searches]]` entries for a browser search URL
+pub async fn run(args: &AddSearchArgs) -> Result<()> {
+    let params = SearchParams::from_url(&args.url)?;
+    let ids: Vec<String> = params.area_ids.iter().map(u64::to_string).collect();
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => format!("{:?} {}", params.site, ids.join(",")).trim().to_string(),
+    };
+
+    println!("# Add to scout.toml:\n");
+    print!("{}", config_snippet(&[(name, params.clone())], args.notify)?);
+    if params.site == SearchSite::Hemnet {
+        eprintln!(
+            "\nHemnet location IDs ({}) don't match Booli areas; add a [[scrape.areas]] entry from a Booli search of the same area",
+            if ids.is_empty() { "none".to_string() } else { ids.join(", ") }
+        );
+    }
+    Ok(())
+}
+
+/// scout.toml entries scraping and filtering by each named search
+///
+/// Booli applies the price, size and type bounds itself; the saved searches
+/// repeat them for notifications and `--search`.
+pub(crate) fn config_snippet(searches: &[(String, SearchParams)], notify: bool) -> Result<String> {
+    let areas: Vec<AreaSearch> = searches
+        .iter()
+        .filter_map(|(name, params)| {
+            Some(AreaSearch {
+                name: name.clone(),
+                area_ids: Vec::new(),
+                url: Some(params.booli_url()?),
+                city: params.location.clone(),
+            })
+        })
+        .collect();
+    let snippet = Snippet {
+        scrape: (!areas.is_empty()).then_some(ScrapeAreas { areas }),
+        searches: searches
+            .iter()
+            .map(|(name, params)| SavedSearch {
+                name: name.clone(),
+                filter: params.filter(),
+                area: None,
+                keywords: KeywordRules::default(),
+                notify,
+                channels: Vec::new(),
+                outputs: Vec::new(),
+            })
+            .collect(),
+    };
+    Ok(toml::to_string(&snippet)?)
+}
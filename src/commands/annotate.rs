@@ -0,0 +1,67 @@
+use crate::cli::AnnotateArgs;
+use crate::storage::{JsonStore, Note};
+use anyhow::Result;
+use chrono::Utc;
+use chrono_tz::Europe::Stockholm;
+use tracing::info;
+
+/// Update a property's annotation and print it
+pub async fn run(args: &AnnotateArgs) -> Result<()> {
+    let store = JsonStore::new("data");
+    let mut annotations = store.load_annotations().await?;
+
+    let known = store.load_all_properties().await?.iter().any(|p| p.id == args.id);
+    if !known {
+        anyhow::bail!("No stored property with ID {}", args.id);
+    }
+
+    let annotation = annotations.entry(&args.id);
+    let before = annotation.clone();
+    if let Some(status) = args.status {
+        annotation.set_status(status);
+    }
+    if args.favorite || args.unfavorite {
+        annotation.favorite = args.favorite;
+    }
+    if args.hide || args.unhide {
+        annotation.hidden = args.hide;
+    }
+    if args.visited {
+        annotation.visited = true;
+    }
+    if let Some(rating) = args.rating {
+        annotation.rating = Some(rating);
+    }
+    if let Some(text) = &args.note {
+        annotation.notes.push(Note {
+            written_at: Utc::now(),
+            text: text.clone(),
+        });
+    }
+
+    let annotation = annotation.clone();
+    if annotation != before {
+        annotations.prune();
+        store.save_annotations(&annotations).await?;
+        info!("💾 Updated annotations for {}", args.id);
+    }
+
+    let marks: Vec<&str> = [
+        (annotation.favorite, "⭐ favorite"),
+        (annotation.hidden, "🙈 hidden"),
+        (annotation.visited, "👣 visited"),
+    ]
+    .into_iter()
+    .filter_map(|(set, label)| set.then_some(label))
+    .collect();
+    println!("{}: {}", args.id, if marks.is_empty() { "no marks".to_string() } else { marks.join(", ") });
+    println!("   Status: {}", annotation.status);
+    if let Some(rating) = annotation.rating {
+        println!("   Rating: {}/5", rating);
+    }
+    for note in &annotation.notes {
+        println!("   {} - {}", note.written_at.with_timezone(&Stockholm).format("%Y-%m-%d %H:%M"), note.text);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::cli::BooliArgs;
+#[cfg(feature = "browser")]
+use crate::cli::BooliCommand;
+#[cfg(feature = "browser")]
+use crate::commands::add_search::config_snippet;
+use crate::config::Config;
+#[cfg(feature = "browser")]
+use crate::scrapers::{BooliAccount, BooliBrowserScraper, Chrome, SearchParams};
+#[cfg(feature = "browser")]
+use crate::storage::JsonStore;
+#[cfg(feature = "browser")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "browser")]
+use tracing::{info, warn};
+
+/// Import saved searches from, or push favorites to, the configured Booli account
+#[cfg(feature = "browser")]
+pub async fn run(args: &BooliArgs, config: &Config) -> Result<()> {
+    let account = config
+        .scrape
+        .account
+        .clone()
+        .context("No Booli account configured; add [scrape.account] to scout.toml")?;
+
+    match &args.command {
+        BooliCommand::Searches { notify } => import_searches(account, *notify).await,
+        BooliCommand::PushFavorites => push_favorites(account, config).await,
+    }
+}
+
+#[cfg(not(feature = "browser"))]
+pub async fn run(_args: &BooliArgs, _config: &Config) -> Result<()> {
+    anyhow::bail!("Logging into Booli needs a build with `--features browser`")
+}
+
+#[cfg(feature = "browser")]
+async fn import_searches(account: BooliAccount, notify: bool) -> Result<()> {
+    let saved = tokio::task::spawn_blocking(move || {
+        let scraper = BooliBrowserScraper::new()?;
+        scraper.login(&account)?;
+        scraper.saved_searches()
+    })
+    .await??;
+    if saved.is_empty() {
+        println!("The Booli account has no saved searches");
+        return Ok(());
+    }
+
+    let mut searches = Vec::new();
+    for search in saved {
+        match SearchParams::from_url(&search.url) {
+            Ok(params) => searches.push((search.name, params)),
+            Err(e) => warn!("Skipping saved search {:?}: {:#}", search.name, e),
+        }
+    }
+    println!("# {} saved searches from Booli; add to scout.toml:\n", searches.len());
+    print!("{}", config_snippet(&searches, notify)?);
+    Ok(())
+}
+
+#[cfg(feature = "browser")]
+async fn push_favorites(account: BooliAccount, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    let annotations = store.load_annotations().await?;
+    let urls: Vec<(String, String)> = store
+        .load_all_properties()
+        .await?
+        .into_iter()
+        .filter(|p| annotations.get(&p.id).is_some_and(|a| a.favorite))
+        .map(|p| (p.id, p.url))
+        .collect();
+    if urls.is_empty() {
+        println!("No favorites to push; mark some with `scout annotate <id> --favorite`");
+        return Ok(());
+    }
+
+    let interval = std::time::Duration::from_millis(config.scrape.min_interval_ms);
+    let politeness = config.scrape.politeness.booli_browser.clone();
+    let chrome = Chrome::new(config.scrape.chrome.clone());
+    let (saved, failed) = tokio::task::spawn_blocking(move || -> Result<(usize, usize)> {
+        let scraper = BooliBrowserScraper::new()?
+            .with_chrome(chrome)
+            .with_min_interval(interval)
+            .with_politeness(politeness);
+        scraper.login(&account)?;
+        let (mut saved, mut failed) = (0, 0);
+        for (id, url) in &urls {
+            match scraper.save_favorite(url) {
+                Ok(true) => saved += 1,
+                Ok(false) => info!("{} is already a Booli favorite", id),
+                Err(e) => {
+                    warn!("Failed to save {} on Booli: {:#}", id, e);
+                    failed += 1;
+                }
+            }
+        }
+        Ok((saved, failed))
+    })
+    .await??;
+
+    println!("⭐ Saved {} favorites on Booli ({} failed)", saved, failed);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::bot::{self, BotCommand};
+use crate::config::Config;
+use crate::notify::TelegramClient;
+use crate::storage::JsonStore;
+use anyhow::Result;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long one getUpdates call waits for a message
+const POLL: Duration = Duration::from_secs(50);
+/// Wait after a failed poll before trying again
+const RETRY: Duration = Duration::from_secs(10);
+
+/// Answer Telegram messages until Ctrl-C
+pub async fn run(config: &Config) -> Result<()> {
+    let Some(token) = &config.bot.token else {
+        anyhow::bail!("Set [bot] token to the bot's token from @BotFather");
+    };
+    let telegram = TelegramClient::new(&token.resolve()?);
+    let store = JsonStore::new("data");
+    let language = config.language.notifications;
+    if config.bot.allowed_chats.is_empty() {
+        warn!("No [bot] allowed_chats yet; message the bot to learn your chat ID");
+    }
+
+    info!("🤖 Answering Telegram messages (Ctrl-C to stop)");
+    let mut offset = 0;
+    loop {
+        let updates = tokio::select! {
+            updates = telegram.updates(offset, POLL) => updates,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let updates = match updates {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!("{:#}", e);
+                tokio::time::sleep(RETRY).await;
+                continue;
+            }
+        };
+
+        for update in updates {
+            offset = offset.max(update.update_id + 1);
+            let Some((chat, text)) = update.message.and_then(|m| Some((m.chat.id, m.text?))) else {
+                continue;
+            };
+            let reply = if config.bot.allowed_chats.contains(&chat) {
+                let command = BotCommand::parse(&text);
+                info!("💬 {:?} from chat {}", command, chat);
+                bot::reply(&command, &store, config, language).await.unwrap_or_else(|e| {
+                    warn!("Failed to answer {:?}: {:#}", text, e);
+                    format!("⚠️ {:#}", e)
+                })
+            } else {
+                info!("Ignoring chat {}, which isn't in [bot] allowed_chats", chat);
+                language.format("bot.not_allowed", &[("chat", &chat)])
+            };
+            if let Err(e) = telegram.send_message(chat, &reply).await {
+                warn!("Failed to reply in chat {}: {:#}", chat, e);
+            }
+        }
+    }
+}
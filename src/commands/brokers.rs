@@ -0,0 +1,62 @@
+use crate::cli::{BrokerGroup, BrokersArgs};
+use crate::stats::{broker_stats, BrokerGrouping};
+use crate::storage::JsonStore;
+use anyhow::Result;
+
+/// Print per-agency or per-broker listing statistics
+pub async fn run(args: &BrokersArgs) -> Result<()> {
+    let store = JsonStore::new("data");
+    let mut properties = if args.all {
+        store.load_all_properties().await?
+    } else {
+        store.load_latest_properties().await?.unwrap_or_default()
+    };
+
+    if let Some(area) = &args.area {
+        properties.retain(|p| {
+            p.location
+                .area
+                .as_deref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(area))
+        });
+    }
+
+    let grouping = match args.by {
+        BrokerGroup::Agency => BrokerGrouping::Agency,
+        BrokerGroup::Broker => BrokerGrouping::Broker,
+    };
+    let stats = broker_stats(&properties, grouping);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    if stats.is_empty() {
+        println!("No listings with broker information");
+        return Ok(());
+    }
+
+    println!(
+        "{:<32} {:>8} {:>7} {:>14} {:>10} {:>8}  Areas",
+        "Name", "Listings", "Share", "Median price", "kr/kvm", "Bidding"
+    );
+    for row in &stats {
+        let name = match &row.agency {
+            Some(agency) => format!("{} ({})", row.name, agency),
+            None => row.name.clone(),
+        };
+        println!(
+            "{:<32} {:>8} {:>6.0}% {:>14} {:>10} {:>8}  {}",
+            name,
+            row.listings,
+            row.share * 100.0,
+            row.median_price.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+            row.median_price_per_sqm.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+            row.bidding,
+            row.areas.iter().take(3).cloned().collect::<Vec<_>>().join(", "),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use crate::cli::{DiffArgs, DiffFormat};
+use crate::diff::{diff_runs, FieldChange, ListingRef, RunDiff};
+use crate::models::Money;
+use crate::storage::{snapshot_order, JsonStore, ScrapeRun};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Longest before/after value printed in tables before truncating
+const MAX_VALUE_CHARS: usize = 60;
+
+/// Print new, removed and changed listings between two runs
+pub async fn run(args: &DiffArgs) -> Result<()> {
+    let store = JsonStore::new("data");
+    let runs = store.load_runs().await?;
+    let snapshots: Vec<String> = store
+        .snapshot_paths()
+        .await?
+        .iter()
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .collect();
+
+    let to = match &args.to {
+        Some(to) => snapshot_of(to, &runs, &snapshots)?,
+        None => snapshots.last().cloned().context("No stored runs yet")?,
+    };
+    let from = match &args.from {
+        Some(from) => snapshot_of(from, &runs, &snapshots)?,
+        None => snapshots
+            .iter()
+            .rfind(|name| snapshot_order(name) < snapshot_order(&to))
+            .cloned()
+            .with_context(|| format!("No stored run before {}", to))?,
+    };
+
+    let before = store.load_snapshot(&from).await?;
+    let after = store.load_snapshot(&to).await?;
+    let diff = diff_runs(&from, &before, &to, &after);
+
+    match args.format {
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        DiffFormat::Table => print!("{}", table(&diff)),
+        DiffFormat::Markdown => print!("{}", markdown(&diff)),
+    }
+    Ok(())
+}
+
+/// Name of the snapshot holding what `run` (a run ID, or a date) scraped
+///
+/// Runs from before snapshots were kept per run have only their day's
+/// snapshot. A date picks that day's snapshot, or the last run of the day.
+fn snapshot_of(run: &str, runs: &[ScrapeRun], snapshots: &[String]) -> Result<String> {
+    let stored = |name: &str| snapshots.iter().any(|snapshot| snapshot == name);
+    if let Some(found) = runs.iter().find(|r| r.id == run) {
+        let day = found.started_at.format("%Y-%m-%d").to_string();
+        return [found.id.as_str(), day.as_str()]
+            .into_iter()
+            .find(|name| stored(name))
+            .map(str::to_string)
+            .with_context(|| format!("Run {} stored no listings", run));
+    }
+    if stored(run) {
+        return Ok(run.to_string());
+    }
+    if NaiveDate::parse_from_str(run, "%Y-%m-%d").is_ok() {
+        let day = snapshot_order(run);
+        if let Some(last) = snapshots.iter().rfind(|name| snapshot_order(name).starts_with(&day)) {
+            return Ok(last.clone());
+        }
+    }
+    anyhow::bail!("No stored run {}; give a run ID from `scout runs` or a date (YYYY-MM-DD)", run)
+}
+
+fn table(diff: &RunDiff) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Changes from {} to {}", diff.from, diff.to);
+    if diff.is_empty() {
+        let _ = writeln!(out, "Nothing changed");
+        return out;
+    }
+
+    if !diff.added.is_empty() {
+        let _ = writeln!(out, "\n🆕 {} new listings", diff.added.len());
+        for listing in &diff.added {
+            let _ = writeln!(out, "   {:<12} {:<40} {:>15}", listing.id, listing.address, price(listing));
+        }
+    }
+    if !diff.removed.is_empty() {
+        let _ = writeln!(out, "\n🗑  {} removed listings", diff.removed.len());
+        for listing in &diff.removed {
+            let _ = writeln!(out, "   {:<12} {:<40} {:>15}", listing.id, listing.address, price(listing));
+        }
+    }
+    if !diff.changed.is_empty() {
+        let _ = writeln!(out, "\n✏️  {} changed listings", diff.changed.len());
+        for listing in &diff.changed {
+            let cut = listing
+                .price_cut
+                .map(|cut| format!(" 📉 price cut {}", cut))
+                .unwrap_or_default();
+            let _ = writeln!(out, "   {} - {}{}", listing.listing.id, listing.listing.address, cut);
+            for change in &listing.changes {
+                let _ = writeln!(
+                    out,
+                    "      {:<20} {} → {}",
+                    change.field,
+                    short(&change.before),
+                    short(&change.after)
+                );
+            }
+        }
+    }
+    out
+}
+
+fn markdown(diff: &RunDiff) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Changes from {} to {}\n", diff.from, diff.to);
+    if diff.is_empty() {
+        let _ = writeln!(out, "Nothing changed.");
+        return out;
+    }
+
+    for (title, listings) in [("New listings", &diff.added), ("Removed listings", &diff.removed)] {
+        if listings.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "## {} ({})\n", title, listings.len());
+        let _ = writeln!(out, "| ID | Address | Price |\n|---|---|---:|");
+        for listing in listings {
+            let _ = writeln!(
+                out,
+                "| {} | [{}]({}) | {} |",
+                listing.id,
+                cell(&listing.address),
+                listing.url,
+                price(listing)
+            );
+        }
+        out.push('\n');
+    }
+
+    if !diff.changed.is_empty() {
+        let _ = writeln!(out, "## Changed listings ({})\n", diff.changed.len());
+        let _ = writeln!(out, "| ID | Address | Field | Before | After |\n|---|---|---|---|---|");
+        for listing in &diff.changed {
+            for change in &listing.changes {
+                let field = if is_price_cut(change) {
+                    format!("**{}** 📉", change.field)
+                } else {
+                    change.field.clone()
+                };
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    listing.listing.id,
+                    cell(&listing.listing.address),
+                    field,
+                    cell(&short(&change.before)),
+                    cell(&short(&change.after))
+                );
+            }
+        }
+    }
+    out
+}
+
+fn is_price_cut(change: &FieldChange) -> bool {
+    change.field == "price"
+        && matches!(
+            (money(&change.before), money(&change.after)),
+            (Some(before), Some(after)) if after.currency == before.currency && after.amount_minor < before.amount_minor
+        )
+}
+
+/// A serialized [`Money`] value
+fn price(listing: &ListingRef) -> String {
+    listing.price.map(|price| price.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn money(value: &Value) -> Option<Money> {
+    value.get("amount_minor")?;
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// Compact one-line rendering of a JSON value
+fn short(value: &Value) -> String {
+    let text = match (value, money(value)) {
+        (_, Some(money)) => money.to_string(),
+        (Value::Null, _) => "-".to_string(),
+        (Value::String(s), _) => s.clone(),
+        (other, _) => other.to_string(),
+    };
+    let text = text.replace('\n', " ");
+    if text.chars().count() > MAX_VALUE_CHARS {
+        format!("{}…", text.chars().take(MAX_VALUE_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,38 @@
+use crate::cli::DiscoverArgs;
+use crate::config::Config;
+use crate::scrapers::sitemap::{discover, Discovery, DiscoveryKind, SitemapEntry};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Print listing URLs found in the sitemaps, remembering them to report new ones next time
+pub async fn run(args: &DiscoverArgs, config: &Config) -> Result<()> {
+    let kind = if args.sold { DiscoveryKind::Sold } else { DiscoveryKind::ForSale };
+    let discovery = Discovery {
+        kind,
+        municipality: args.municipality.clone(),
+        since: args.since,
+        min_interval: Duration::from_millis(config.scrape.min_interval_ms),
+    };
+    let found = discover(&args.url, &discovery).await?;
+
+    let store = JsonStore::new("data");
+    let previous: Vec<SitemapEntry> = store.load_cache(kind.cache_name()).await.unwrap_or_default();
+    let known: HashSet<&str> = previous.iter().map(|entry| entry.loc.as_str()).collect();
+    let new = found.iter().filter(|entry| !known.contains(entry.loc.as_str())).count();
+    if let Err(e) = store.save_cache(kind.cache_name(), &found).await {
+        warn!("Failed to remember discovered listings: {}", e);
+    }
+    info!("🗺️  {} listing URLs, {} not seen in the last discovery", found.len(), new);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&found)?);
+    } else {
+        for entry in &found {
+            println!("{}", entry.loc);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::config::Config;
+use crate::export::{self, filter_records, sort_records, viewings_calendar, write_site, RecordContext};
+use crate::storage::{write_json_array, write_json_lines, JsonStore};
+use crate::templates::render_file;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::json;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Export the latest stored snapshot in the requested format
+pub async fn run(args: &ExportArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    let Some(properties) = store.load_latest_properties().await? else {
+        anyhow::bail!("No stored runs found - run `scout scrape` first");
+    };
+    let context = RecordContext::load(&store, config, &properties).await?;
+
+    let mut selected = export::records(&properties, config, &context);
+    selected.retain(|r| args.ids.is_empty() || args.ids.contains(&r.property.id));
+    filter_records(&mut selected, &args.filters, config)?;
+    if let Some(key) = args.sort {
+        sort_records(&mut selected, key, args.desc);
+    }
+
+    let output = match args.format {
+        ExportFormat::Json | ExportFormat::Jsonl => {
+            write_json(&selected, args.format == ExportFormat::Jsonl, args.output.as_deref()).await?;
+            if let Some(path) = &args.output {
+                info!("💾 Exported {} properties to {}", selected.len(), path.display());
+            }
+            return Ok(());
+        }
+        ExportFormat::Ics => viewings_calendar(&selected),
+        ExportFormat::Template => {
+            let path = args.template.as_deref().context("--template is required")?;
+            let context = json!({
+                "records": selected,
+                "count": selected.len(),
+                "generated_at": Utc::now(),
+            });
+            render_file(path, &context)?
+        }
+        ExportFormat::Site => {
+            let dir = args.output.clone().unwrap_or_else(|| PathBuf::from("site"));
+            let history = store.load_history().await?;
+            let pages = write_site(&selected, &history, &dir, config.language.reports).await?;
+            info!("🌐 Wrote static site with {} properties to {}", pages, dir.display());
+            return Ok(());
+        }
+        ExportFormat::Airtable => {
+            let history = store.load_history().await?;
+            let sync = export::airtable::sync(&selected, &history, &config.airtable).await?;
+            info!("🗂️ Synced {} properties and {} price observations to Airtable", sync.listings, sync.observations);
+            return Ok(());
+        }
+        ExportFormat::Notion => {
+            let sync = export::notion::sync(&selected, &config.notion).await?;
+            info!("📝 Synced {} properties to Notion ({} added, {} updated)", selected.len(), sync.added, sync.updated);
+            return Ok(());
+        }
+        ExportFormat::Sheets => {
+            let sync = export::sheets::sync(&selected, &config.sheets).await?;
+            info!("📊 Synced {} properties to Google Sheets ({} added, {} updated)", selected.len(), sync.added, sync.updated);
+            return Ok(());
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            tokio::fs::write(path, output).await?;
+            info!("💾 Exported {} properties to {}", selected.len(), path.display());
+        }
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+/// Write `records` as a JSON array or JSON Lines to `output` or stdout, one
+/// record at a time
+async fn write_json<T: Serialize>(records: &[T], lines: bool, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(path) if lines => write_json_lines(path, records).await,
+        Some(path) => write_json_array(path, records).await,
+        None => {
+            let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+            if lines {
+                for record in records {
+                    serde_json::to_writer(&mut out, record)?;
+                    writeln!(out)?;
+                }
+            } else {
+                serde_json::to_writer_pretty(&mut out, records)?;
+                writeln!(out)?;
+            }
+            out.flush()?;
+            Ok(())
+        }
+    }
+}
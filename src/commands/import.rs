@@ -0,0 +1,78 @@
+use crate::cli::ImportArgs;
+use crate::models::Property;
+use crate::storage::{read_legacy_dir, JsonStore};
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, warn};
+
+/// Import old scrape output into the store, one snapshot per scrape date
+///
+/// Listings already stored for a date are kept; only missing ones are added.
+pub async fn run(args: &ImportArgs) -> Result<()> {
+    let import = read_legacy_dir(&args.dir).await?;
+    for (path, reason) in &import.skipped {
+        warn!("Skipped {}: {}", path.display(), reason);
+    }
+    let read = import.properties.len();
+
+    // The same listing usually appears in both scraped_properties.json and
+    // raw_scrape/; keep the latest copy per listing and day
+    let mut by_date: BTreeMap<NaiveDate, BTreeMap<String, Property>> = BTreeMap::new();
+    for property in import.properties {
+        let day = by_date.entry(property.scraped_at.date_naive()).or_default();
+        match day.get(&property.id) {
+            Some(existing) if existing.scraped_at >= property.scraped_at => {}
+            _ => {
+                day.insert(property.id.clone(), property);
+            }
+        }
+    }
+
+    let store = JsonStore::new("data");
+    let mut history = store.load_history().await?;
+    let stored: HashSet<String> = store
+        .snapshot_paths()
+        .await?
+        .iter()
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .collect();
+    let mut added_total = 0;
+    for (date, imported) in by_date {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let mut snapshot = if stored.contains(&date_str) {
+            store.load_snapshot(&date_str).await?
+        } else {
+            Vec::new()
+        };
+        let added: Vec<Property> = imported
+            .into_values()
+            .filter(|p| !snapshot.iter().any(|existing| existing.id == p.id))
+            .collect();
+
+        println!("{}: {} new listings ({} already stored)", date_str, added.len(), snapshot.len());
+        if added.is_empty() || args.dry_run {
+            added_total += added.len();
+            continue;
+        }
+
+        history.backfill(&added);
+        added_total += added.len();
+        snapshot.extend(added);
+        let path = store.save_snapshot(date, &snapshot).await?;
+        info!("💾 Saved {}", path.display());
+    }
+
+    if !args.dry_run {
+        store.save_history(&history).await?;
+    }
+    println!(
+        "Read {} listings from {} files ({} skipped); {} {}",
+        read,
+        import.files,
+        import.skipped.len(),
+        added_total,
+        if args.dry_run { "would be imported" } else { "imported" }
+    );
+    Ok(())
+}
@@ -0,0 +1,22 @@
+pub mod add_search;
+pub mod annotate;
+pub mod booli;
+pub mod bot;
+pub mod brokers;
+pub mod diff;
+pub mod discover;
+pub mod export;
+pub mod import;
+pub mod pipeline;
+pub mod query;
+pub mod rank;
+pub mod report;
+pub mod runs;
+pub mod schema;
+pub mod scrape;
+pub mod search;
+pub mod secrets;
+pub mod serve;
+pub mod sold;
+pub mod stats;
+pub mod watch;
@@ -0,0 +1,80 @@
+use crate::cli::PipelineArgs;
+use crate::models::Money;
+use crate::storage::{JsonStore, Status};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+struct PipelineEntry {
+    id: String,
+    address: String,
+    price: Option<Money>,
+    status: Status,
+    /// When the property entered its current status
+    since: Option<DateTime<Utc>>,
+    url: String,
+}
+
+/// Print tracked properties grouped by pipeline status
+pub async fn run(args: &PipelineArgs) -> Result<()> {
+    let store = JsonStore::new("data");
+    let annotations = store.load_annotations().await?;
+    let properties = store.load_all_properties().await?;
+
+    let mut entries: Vec<PipelineEntry> = properties
+        .into_iter()
+        .filter(|p| !annotations.is_hidden(&p.id))
+        .filter_map(|p| {
+            let annotation = annotations.get(&p.id);
+            let status = annotations.status(&p.id);
+            if status == Status::New && !args.all {
+                return None;
+            }
+            Some(PipelineEntry {
+                since: annotation.and_then(|a| a.status_history.last()).map(|c| c.changed_at),
+                id: p.id,
+                address: p.address,
+                price: p.price,
+                status,
+                url: p.url,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.status.cmp(&b.status).then(b.since.cmp(&a.since)));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No properties in the pipeline yet; move one with `scout annotate <id> --status shortlisted`");
+        return Ok(());
+    }
+
+    let mut counts: HashMap<Status, usize> = HashMap::new();
+    for entry in &entries {
+        *counts.entry(entry.status).or_default() += 1;
+    }
+
+    for status in Status::value_variants() {
+        let Some(count) = counts.get(status) else {
+            continue;
+        };
+        println!("{} ({})", status, count);
+        for entry in entries.iter().filter(|e| e.status == *status) {
+            let since = entry
+                .since
+                .map(|at| format!(", since {}", at.format("%Y-%m-%d")))
+                .unwrap_or_default();
+            let price = entry.price.map(|price| price.to_string()).unwrap_or_else(|| "no price yet".to_string());
+            println!("   {} - {} ({}{})", entry.id, entry.address, price, since);
+        }
+        println!();
+    }
+
+    Ok(())
+}
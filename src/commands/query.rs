@@ -0,0 +1,89 @@
+use crate::cli::{QueryArgs, QueryFormat};
+use crate::config::Config;
+use crate::storage::sql::SCHEMA;
+use crate::storage::{JsonStore, QueryEngine, QueryResult, SqlIndex};
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+/// Run an ad-hoc SQL query over the stored data and print the rows
+pub async fn run(args: &QueryArgs, config: &Config) -> Result<()> {
+    let Some(sql) = args.sql.as_deref().filter(|_| !args.schema) else {
+        print!("{}", SCHEMA);
+        return Ok(());
+    };
+
+    let store = JsonStore::new("data");
+    let result = match config.analytics.engine {
+        QueryEngine::Sqlite => SqlIndex::load(&store).await?.query(sql)?,
+        #[cfg(feature = "duckdb")]
+        QueryEngine::Duckdb => crate::storage::DuckIndex::open(&store).await?.query(sql)?,
+        #[cfg(not(feature = "duckdb"))]
+        QueryEngine::Duckdb => anyhow::bail!("The duckdb engine needs a build with `--features duckdb`"),
+    };
+
+    match args.format {
+        QueryFormat::Table => print_table(&result),
+        QueryFormat::Csv => print_csv(&result),
+        QueryFormat::Json => {
+            let rows: Vec<Map<String, Value>> = result
+                .rows
+                .into_iter()
+                .map(|row| result.columns.iter().cloned().zip(row).collect())
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+    }
+    Ok(())
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_f64() {
+            Some(x) if n.is_f64() => format!("{:.2}", x),
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn print_table(result: &QueryResult) {
+    let cells: Vec<Vec<String>> = result.rows.iter().map(|row| row.iter().map(text).collect()).collect();
+    let widths: Vec<usize> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count().min(48))
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |values: &[String]| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| {
+                let value: String = value.chars().take(*width).collect();
+                format!("{:<width$}", value, width = width)
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    println!("{}", line(&result.columns));
+    println!("{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    for row in &cells {
+        println!("{}", line(row));
+    }
+    println!("({} rows)", cells.len());
+}
+
+fn print_csv(result: &QueryResult) {
+    print!("{}", result.to_csv());
+}
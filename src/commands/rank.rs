@@ -0,0 +1,46 @@
+use crate::cli::{RankArgs, SortKey};
+use crate::config::Config;
+use crate::export::{self, RecordContext, filter_records, sort_records};
+use crate::storage::JsonStore;
+use anyhow::Result;
+
+/// Print the top scoring properties from the latest run
+pub async fn run(args: &RankArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    let Some(properties) = store.load_latest_properties().await? else {
+        anyhow::bail!("No stored runs found - run `scout scrape` first");
+    };
+    let context = RecordContext::load(&store, config, &properties).await?;
+
+    // Score against the whole run so filtering doesn't shift relative ranks
+    let mut records = export::records(&properties, config, &context);
+    filter_records(&mut records, &args.filters, config)?;
+    sort_records(&mut records, SortKey::Score, true);
+    records.truncate(args.top);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&records)?);
+        return Ok(());
+    }
+
+    for (i, record) in records.iter().enumerate() {
+        let property = record.property;
+        println!(
+            "{:>2}. {:.2}  {} ({})",
+            i + 1,
+            record.score.total,
+            property.address,
+            config.language.cli.summary(property)
+        );
+        let components: Vec<String> = record
+            .score
+            .components
+            .iter()
+            .map(|(name, value)| format!("{} {:.2}", name, value))
+            .collect();
+        println!("       {}", components.join(" · "));
+        println!("       {}", property.url);
+    }
+
+    Ok(())
+}
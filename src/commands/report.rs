@@ -0,0 +1,80 @@
+use crate::cli::{ReportArgs, ReportFormat};
+use crate::config::Config;
+use crate::export::report::{local_photos, ReportOptions};
+use crate::export::{self, property_report, RecordContext};
+#[cfg(feature = "browser")]
+use crate::scrapers::Chrome;
+use crate::scrapers::ChromeConfig;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Write a printable report for one stored property
+pub async fn run(args: &ReportArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+
+    // Score within the latest run when the property is in it, like `rank` does
+    let latest = store.load_latest_properties().await?.unwrap_or_default();
+    let properties = if latest.iter().any(|p| p.id == args.id) {
+        latest
+    } else {
+        store.load_all_properties().await?
+    };
+    if !properties.iter().any(|p| p.id == args.id) {
+        anyhow::bail!("No stored property with ID {}", args.id);
+    }
+
+    let context = RecordContext::load(&store, config, &properties).await?;
+    let records = export::records(&properties, config, &context);
+    let record = records
+        .iter()
+        .find(|r| r.property.id == args.id)
+        .context("Property missing from records")?;
+    let history = store.load_history().await?;
+    let options = ReportOptions {
+        photos: local_photos(record.property),
+        language: config.language.reports,
+        ..Default::default()
+    };
+    let html = property_report(record, history.get(&args.id), &options);
+
+    let extension = match args.format {
+        ReportFormat::Html => "html",
+        ReportFormat::Pdf => "pdf",
+    };
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("report-{}.{}", args.id, extension)));
+
+    match args.format {
+        ReportFormat::Html => tokio::fs::write(&output, html).await?,
+        ReportFormat::Pdf => {
+            let html_path = output.with_extension("html");
+            tokio::fs::write(&html_path, &html).await?;
+            let chrome = config.scrape.chrome.clone();
+            let pdf = tokio::task::spawn_blocking(move || print_to_pdf(&html_path, chrome)).await??;
+            tokio::fs::write(&output, pdf).await?;
+        }
+    }
+
+    info!("💾 Wrote report to {}", output.display());
+    Ok(())
+}
+
+/// Print an HTML file to PDF with headless Chrome
+#[cfg(feature = "browser")]
+fn print_to_pdf(html_path: &Path, config: ChromeConfig) -> Result<Vec<u8>> {
+    let chrome = Chrome::new(config);
+    let absolute = std::fs::canonicalize(html_path)?;
+    let tab = chrome.tab()?;
+    tab.navigate_to(&format!("file://{}", absolute.display()))?;
+    tab.wait_until_navigated()?;
+    tab.print_to_pdf(None).context("Failed to print report to PDF")
+}
+
+#[cfg(not(feature = "browser"))]
+fn print_to_pdf(_html_path: &Path, _config: ChromeConfig) -> Result<Vec<u8>> {
+    anyhow::bail!("PDF reports need a build with `--features browser`; the HTML report was written")
+}
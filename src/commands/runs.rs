@@ -0,0 +1,76 @@
+use crate::cli::RunsArgs;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+
+/// Print recorded scrape runs
+pub async fn run(args: &RunsArgs) -> Result<()> {
+    let store = JsonStore::new("data");
+    let runs = store.load_runs().await?;
+
+    if let Some(id) = &args.id {
+        let run = runs
+            .iter()
+            .find(|run| run.id == *id)
+            .with_context(|| format!("No recorded run with ID {}", id))?;
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(run)?);
+        } else {
+            println!(
+                "Run {} ({:?}, scout {}), areas: {}",
+                run.id,
+                run.status,
+                run.version,
+                run.params.areas.join(", ")
+            );
+            print!("{}", run.table());
+            for source in run.sources.iter().filter(|s| !s.errors.is_empty()) {
+                for error in &source.errors {
+                    println!("\n{}: {}", source.source, error);
+                }
+            }
+            for source in run.sources.iter().filter(|s| !s.health.is_empty()) {
+                println!("\n{}: extracted less than usual", source.source);
+                for warning in &source.health {
+                    println!("   {}", warning);
+                }
+            }
+            for source in &run.sources {
+                for invalid in &source.rejected {
+                    println!("\n{}: dropped {} ({})", source.source, invalid.id, invalid.url);
+                    for error in &invalid.errors {
+                        println!("   {} {}", error.field, error.message);
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let recent = &runs[runs.len().saturating_sub(args.last)..];
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(recent)?);
+        return Ok(());
+    }
+    if recent.is_empty() {
+        println!("No recorded runs yet");
+        return Ok(());
+    }
+
+    println!(
+        "{:<18} {:<17} {:<10} {:>8} {:>5} {:>7}  Version",
+        "ID", "Started", "Status", "Listings", "New", "Failed"
+    );
+    for run in recent.iter().rev() {
+        println!(
+            "{:<18} {:<17} {:<10} {:>8} {:>5} {:>7}  {}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M"),
+            format!("{:?}", run.status).to_lowercase(),
+            run.listings,
+            run.new_listings,
+            format!("{}/{}", run.failed_sources(), run.sources.len()),
+            run.version
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::cli::SchemaArgs;
+use crate::schema::{json_schema, typescript};
+use anyhow::{Context, Result};
+use tracing::info;
+
+/// Print or write the schema of a stored or exported type
+pub async fn run(args: &SchemaArgs) -> Result<()> {
+    let schema = json_schema(args.ty);
+    let text = if args.typescript {
+        typescript(&schema)?
+    } else {
+        format!("{}\n", serde_json::to_string_pretty(&schema)?)
+    };
+
+    match &args.output {
+        Some(path) => {
+            tokio::fs::write(path, text)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            info!("💾 Saved schema to {}", path.display());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
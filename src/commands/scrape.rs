@@ -0,0 +1,597 @@
+use crate::cli::ScrapeArgs;
+use crate::config::Config;
+use crate::enrich;
+use crate::events;
+use crate::images;
+use crate::export::{self, RecordContext};
+use crate::filter::SavedSearch;
+use crate::models::OrientationSource;
+use crate::notify::{self, Notification, NotificationEvent};
+use crate::output;
+use crate::relist;
+use crate::scrapers::selectors::CardRules;
+use crate::scrapers::{
+    artifacts, configured_sources, health, merge_listings, run_sources, snapshot, Budget, DebugArtifacts, SourceOutcome,
+};
+use crate::scripts::Scripts;
+use crate::shutdown::{Interrupted, Shutdown};
+use crate::storage::{JsonStore, RunParams, RunStatus, ScrapeRun, SourceSummary, Status};
+use crate::templates::{Templates, SUMMARY};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Utc};
+use chrono_tz::Europe::Stockholm;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, info_span, warn, Instrument};
+
+/// Scrape Booli, store the run and print a summary of every property
+pub async fn run(args: &ScrapeArgs, config: &Config) -> Result<()> {
+    info!("🏠 Housing Scout - Booli Scraper");
+    info!("==========================================");
+    info!("");
+
+    let store = JsonStore::new("data");
+    let mut run = match &args.resume {
+        Some(id) => {
+            let mut run = store
+                .load_runs()
+                .await?
+                .into_iter()
+                .find(|run| run.id == *id)
+                .with_context(|| format!("No recorded run with ID {}", id))?;
+            if run.status == RunStatus::Completed {
+                anyhow::bail!("Run {} already completed", id);
+            }
+            run.resume();
+            run
+        }
+        None => ScrapeRun::start(RunParams {
+            sources: config.scrape.sources.clone(),
+            areas: config.scrape.areas.iter().map(|a| a.name.clone()).collect(),
+            details: !args.skip_details,
+            download_images: args.download_images || config.images.download,
+        }),
+    };
+    let checkpoint = Arc::new(store.checkpoint(&run.id)?);
+    // Loaded up front so a broken script or rules file fails before the
+    // scrape, not after
+    let scripts = Scripts::load(&config.scripts)?;
+    let cards = CardRules::load(&config.scrape.rules_dir, "booli")?;
+    cards.compile()?;
+    let shutdown = Shutdown::listen();
+
+    let debug = if args.debug_artifacts || config.scrape.debug.enabled {
+        DebugArtifacts::for_run(&config.scrape.debug, &run.id)
+    } else {
+        DebugArtifacts::default()
+    }
+    .with_snapshots(&config.scrape.debug);
+    let budget = Arc::new(Budget::new(config.scrape.budget.clone()));
+    let mut scrape = config.scrape.clone();
+    if let Some(concurrency) = args.concurrency {
+        scrape.concurrency = concurrency.into();
+    }
+    if let Some(threads) = args.parse_threads {
+        scrape.parse_threads = threads;
+    }
+    let sources = configured_sources(&scrape, run.params.details, &checkpoint, &shutdown, &debug, &budget)?;
+    if sources.is_empty() {
+        anyhow::bail!("No sources configured in [scrape] sources");
+    }
+    // Record the run up front so it can be resumed if this process dies
+    if let Err(e) = store.save_run(&run).await {
+        warn!("Failed to save the run record: {}", e);
+    }
+    let names: Vec<&str> = sources.iter().map(|s| s.source_name()).collect();
+    let verb = if args.resume.is_some() { "resuming" } else { "scraping" };
+    info!("Run {}: {} {} ({})...", run.id, verb, names.join(", "), run.params.areas.join(", "));
+    info!("");
+
+    // Sources that finished before an interruption aren't scraped again
+    let (finished, pending): (Vec<_>, Vec<_>) =
+        sources.into_iter().partition(|source| checkpoint.source(source.source_name()).is_some());
+    let mut outcomes: Vec<SourceOutcome> = finished
+        .iter()
+        .filter_map(|source| {
+            let found = checkpoint.source(source.source_name())?;
+            info!("{}: reusing {} listings from the checkpoint", source.source_name(), found.len());
+            Some(SourceOutcome {
+                source: source.source_name().to_string(),
+                duration: Duration::ZERO,
+                result: Ok(found),
+                rejected: Vec::new(),
+            })
+        })
+        .collect();
+    outcomes.extend(
+        run_sources(
+            pending,
+            config.scrape.max_concurrent_sources,
+            Duration::from_secs(config.scrape.source_timeout_secs),
+            &shutdown,
+        )
+        .instrument(info_span!("run", run_id = %run.id))
+        .await,
+    );
+    run.truncated = budget.truncations();
+
+    // Keep whatever the working sources found
+    let mut properties = Vec::new();
+    for outcome in outcomes {
+        let (listings, fill_rates, errors) = match outcome.result {
+            Ok(found) => {
+                info!("{}: {} listings in {:.0?}", outcome.source, found.len(), outcome.duration);
+                checkpoint.record_source(&outcome.source, &found);
+                let count = found.len();
+                let fill_rates = health::fill_rates(&found);
+                merge_listings(&mut properties, found);
+                (count, fill_rates, Vec::new())
+            }
+            Err(e) => {
+                warn!("{} failed after {:.0?}: {:#}", outcome.source, outcome.duration, e);
+                (0, Default::default(), vec![format!("{:#}", e)])
+            }
+        };
+        if !outcome.rejected.is_empty() {
+            warn!("{}: dropped {} invalid listings", outcome.source, outcome.rejected.len());
+        }
+        run.sources.push(SourceSummary {
+            source: outcome.source,
+            listings,
+            duration_secs: outcome.duration.as_secs_f64(),
+            errors,
+            rejected: outcome.rejected,
+            fill_rates,
+            health: Vec::new(),
+        });
+    }
+
+    // Catch markup changes that don't fail a source, only make it extract less
+    if config.scrape.health.enabled {
+        match store.load_runs().await {
+            Ok(earlier) => health::check_run(&mut run, &earlier, &config.scrape.health),
+            Err(e) => warn!("Failed to load earlier runs for health checks: {}", e),
+        }
+    }
+    // Show what changed on the pages of sources that now extract less
+    snapshot::review(&run, &config.scrape.debug, &cards);
+
+    if shutdown.requested() {
+        run.listings = properties.len();
+        return abort(&mut run, &store).await;
+    }
+    if run.failed_sources() == run.sources.len() {
+        run.finish(RunStatus::Failed);
+        if let Err(e) = store.save_run(&run).await {
+            warn!("Failed to save the run record: {}", e);
+        }
+        eprint!("{}", run.table());
+        anyhow::bail!("Every source failed to scrape");
+    }
+    let parsed = properties.len();
+    properties.retain(|property| scripts.keep(property));
+    if properties.len() < parsed {
+        info!("Script dropped {} of {} listings", parsed - properties.len(), parsed);
+    }
+    for property in &mut properties {
+        property.run_id = Some(run.id.clone());
+    }
+
+    enrich::enrich(&mut properties, &config.enrich, &store).await;
+    // Every listing stored so far, for photo matches and relistings
+    let stored = store.load_all_properties().await?;
+
+    if run.params.download_images {
+        if let Err(e) = images::download_images(&mut properties, &config.images).await {
+            warn!("Failed to download images: {}", e);
+        }
+        if let Err(e) = images::hash_images(&mut properties, &store).await {
+            warn!("Failed to hash images: {}", e);
+        }
+        images::find_photo_matches(&mut properties, &stored);
+    }
+
+    if shutdown.requested() {
+        run.listings = properties.len();
+        return abort(&mut run, &store).await;
+    }
+
+    // Track price and bid progression across runs
+    let mut history = store.load_history().await?;
+    let new_ids: HashSet<String> = properties
+        .iter()
+        .filter(|p| history.get(&p.id).is_none())
+        .map(|p| p.id.clone())
+        .collect();
+    // Known listings whose price changed or whose bidding started since the last run
+    let changes: HashMap<String, NotificationEvent> = properties
+        .iter()
+        .filter_map(|p| {
+            let last = history.get(&p.id)?.observations.last()?;
+            Some((p.id.clone(), NotificationEvent::since(last, p)?))
+        })
+        .collect();
+    run.listings = properties.len();
+    run.new_listings = new_ids.len();
+    let previous = store.load_latest_properties().await?.unwrap_or_default();
+    let events = events::listing_events(&properties, &history, &previous, &run.id, Utc::now());
+
+    // Relistings of stored listings continue the history of the first listing
+    for relist in relist::find_relists(&properties, &stored, &history) {
+        info!("🔁 {} relists {}", relist.to, relist.from);
+        history.link_relist(&relist.from, &relist.to);
+    }
+    history.record(&properties);
+    store.save_history(&history).await?;
+    let snapshot = store.save_properties(&run.id, &properties).await?;
+    info!("💾 Saved run snapshot to {}", snapshot.display());
+
+    run.finish(RunStatus::Completed);
+    if let Err(e) = store.save_run(&run).await {
+        warn!("Failed to save the run record: {}", e);
+    }
+    if let Err(e) = checkpoint.remove() {
+        warn!("{:#}", e);
+    }
+    match artifacts::prune(&config.scrape.debug) {
+        Ok(0) => {}
+        Ok(removed) => info!("🧹 Removed debug artifacts of {} old runs", removed),
+        Err(e) => warn!("Failed to clean up debug artifacts: {:#}", e),
+    }
+
+    // Display results
+    info!("\n✅ Scraped {} properties\n", properties.len());
+
+    let context = RecordContext::load(&store, config, &properties).await?;
+    let records = export::records(&properties, config, &context);
+
+    // Under-priced listings go fast, so list them before everything else
+    let lang = config.language.cli;
+    let underpriced: Vec<_> = records.iter().filter(|r| r.anomaly.is_some()).collect();
+    if !underpriced.is_empty() {
+        println!("{}", lang.format("scrape.underpriced", &[("count", &underpriced.len())]));
+        for record in &underpriced {
+            if let Some(anomaly) = &record.anomaly {
+                let line = lang.format(
+                    "scrape.below_median",
+                    &[
+                        ("address", &record.property.address),
+                        ("per_sqm", &format!("{:.0}", anomaly.price_per_sqm)),
+                        ("discount", &format!("{:.0}", anomaly.discount * 100.0)),
+                        ("cohort", &anomaly.cohort),
+                        ("median", &format!("{:.0}", anomaly.cohort_median_price_per_sqm)),
+                        ("count", &anomaly.cohort_size),
+                    ],
+                );
+                println!("   {}", line);
+            }
+        }
+        println!();
+    }
+
+    let templates = Templates::load(&config.templates)?;
+    for (i, record) in records.iter().enumerate() {
+        if let Some(summary) = templates.render(SUMMARY, &json!({ "index": i + 1, "property": record, "language": lang })) {
+            println!("{}", summary.trim_end());
+            continue;
+        }
+
+        let property = record.property;
+        let metrics = &record.metrics;
+        let mut flag = if record.anomaly.is_some() { "🔥 " } else { "" }.to_string();
+        if record.annotation.as_ref().is_some_and(|a| a.favorite) {
+            flag.push_str("⭐ ");
+        }
+        println!("{}. {}{} ({})", i + 1, flag, property.address, lang.price(property));
+        let size = lang.size(property.rooms, property.sqm);
+        if !size.is_empty() {
+            println!("   {}", size);
+        }
+        if record.status() != Status::New {
+            println!("   {}: {}", lang.t("status"), record.status());
+        }
+        if property.floor_plan_mismatch() {
+            let warning = lang.format(
+                "scrape.floor_plan_mismatch",
+                &[
+                    ("plan", &property.floor_plan_sqm.unwrap_or_default()),
+                    ("sqm", &property.sqm.unwrap_or_default()),
+                ],
+            );
+            println!("   {}", warning);
+        }
+        if let Some(per_sqm) = metrics.price_per_sqm {
+            let mut line = format!("   {}", lang.format("scrape.per_sqm", &[("per_sqm", &format!("{:.0}", per_sqm))]));
+            if let (Some(fee), Some(fee_per_sqm)) = (property.monthly_fee, metrics.fee_per_sqm) {
+                line.push_str(&lang.format(
+                    "scrape.fee",
+                    &[("fee", &fee), ("per_sqm", &format!("{:.0}", fee_per_sqm))],
+                ));
+            }
+            if let Some(cost) = property.operating_cost {
+                line.push_str(&lang.format("scrape.operating_cost", &[("cost", &cost)]));
+            }
+            println!("{}", line);
+        }
+        if let Some(cost) = &record.finance {
+            let whole = |value: f64| format!("{:.0}", value);
+            let line = lang.format(
+                "scrape.monthly_cost",
+                &[
+                    ("total", &whole(cost.total)),
+                    ("interest", &whole(cost.interest)),
+                    ("amortization", &whole(cost.amortization)),
+                    ("rate", &cost.amortization_rate),
+                    ("fee", &whole(cost.fee)),
+                    ("loan", &whole(cost.loan)),
+                    ("ltv", &whole(cost.ltv * 100.0)),
+                ],
+            );
+            println!("   {}", line);
+        }
+        if let Some(prediction) = &record.prediction {
+            let lowball = if prediction.is_lowball(&config.prediction) { lang.t("scrape.lowball") } else { "" };
+            let line = lang.format(
+                "scrape.predicted",
+                &[
+                    ("price", &prediction.predicted_price),
+                    ("delta", &format!("{:+.1}", prediction.delta_pct * 100.0)),
+                ],
+            );
+            println!("   {}{}", line, lowball);
+        }
+        if property.bidding_in_progress {
+            let bidding = lang.t("scrape.bidding");
+            match (property.current_bid, property.bid_premium()) {
+                (Some(bid), Some(premium)) => {
+                    let premium = lang.format("vs_asking", &[("premium", &format!("{:+.1}", premium))]);
+                    println!("   {}: {} ({})", bidding, bid, premium)
+                }
+                (Some(bid), None) => println!("   {}: {}", bidding, bid),
+                _ => println!("   {}", bidding),
+            }
+        }
+        if let Some(entry) = history.get(&property.id) {
+            if let Some(first) = entry.relisted_from.first() {
+                let days = (Utc::now() - entry.first_seen).num_days();
+                let line = lang.format(
+                    "scrape.relisted",
+                    &[
+                        ("id", first),
+                        ("date", &entry.first_seen.format("%Y-%m-%d")),
+                        ("days", &days),
+                    ],
+                );
+                println!("   {}", line);
+            }
+            let bids = entry.bid_progression();
+            if bids.len() > 1 {
+                let steps: Vec<String> = bids.iter().map(|(_, bid)| bid.to_string()).collect();
+                println!("   {}: {}", lang.t("scrape.bid_history"), steps.join(" → "));
+            }
+        }
+        for matched in &property.photo_matches {
+            let first_seen = history
+                .get(&matched.property_id)
+                .map(|h| lang.format("scrape.first_seen", &[("date", &h.first_seen.format("%Y-%m-%d"))]))
+                .unwrap_or_default();
+            let line = lang.format(
+                "scrape.shared_photos",
+                &[
+                    ("count", &matched.shared_photos),
+                    ("id", &matched.property_id),
+                    ("address", &matched.address),
+                    ("first_seen", &first_seen),
+                ],
+            );
+            println!("   {}", line);
+        }
+        if let Some(next) = property.viewings.first() {
+            println!(
+                "   {}: {}",
+                lang.t("next_viewing"),
+                next.with_timezone(&Stockholm).format("%a %d %b %H:%M")
+            );
+        }
+        if let Some(area) = &property.location.area {
+            println!("   {}: {}", lang.t("area"), area);
+        }
+        if property.area_tags.len() > 1 {
+            println!("   {}: {}", lang.t("scrape.found_in"), property.area_tags.join(", "));
+        }
+        if let Some(station) = &property.nearest_station {
+            let walk = lang.format(
+                "scrape.station_walk",
+                &[
+                    ("name", &station.name),
+                    ("kind", &format!("{:?}", station.kind)),
+                    ("distance", &format!("{:.0}", station.walking_m)),
+                ],
+            );
+            println!("   {}: {}", lang.t("station"), walk);
+        }
+        if let Some(nearby) = &property.nearby {
+            let line = lang.format(
+                "scrape.nearby",
+                &[
+                    ("radius", &format!("{:.0}", nearby.radius_m)),
+                    ("groceries", &nearby.groceries),
+                    ("gyms", &nearby.gyms),
+                    ("preschools", &nearby.preschools),
+                    ("parks", &nearby.parks),
+                ],
+            );
+            println!("   {}", line);
+        }
+        if !property.schools.is_empty() {
+            let schools: Vec<String> = property
+                .schools
+                .iter()
+                .map(|school| match school.merit_rating {
+                    Some(merit) => {
+                        let merit = lang.format("report.merit", &[("merit", &format!("{:.0}", merit))]);
+                        format!("{} ({:.0} m, {})", school.name, school.distance_m, merit)
+                    }
+                    None => format!("{} ({:.0} m)", school.name, school.distance_m),
+                })
+                .collect();
+            println!("   {}: {}", lang.t("schools"), schools.join(", "));
+        }
+        if let Some(balcony) = &property.balcony_orientation {
+            let sun = if balcony.afternoon_sun { format!(" ☀️ {}", lang.t("afternoon_sun")) } else { String::new() };
+            let guess = match balcony.source {
+                OrientationSource::Listing => "",
+                OrientationSource::Footprint => lang.t("estimated"),
+            };
+            let faces = lang.format("faces", &[("direction", &balcony.direction)]);
+            println!("   {}: {}{}{}", lang.t("balcony"), faces, guess, sun);
+        }
+        if let Some(noise) = property.noise_db {
+            println!("   {}: {:.0} dB(A)", lang.t("noise"), noise);
+        }
+        if let Some(broadband) = &property.broadband {
+            let technologies = if broadband.technologies.is_empty() {
+                lang.t("unknown").to_string()
+            } else {
+                broadband.technologies.join(", ")
+            };
+            let line = match broadband.max_down_mbps {
+                Some(mbps) => lang.format("scrape.up_to", &[("technologies", &technologies), ("mbps", &mbps)]),
+                None => technologies,
+            };
+            println!("   {}: {}", lang.t("broadband"), line);
+        }
+        if let Some(renovation) = &property.renovation {
+            println!("   {}: {}", lang.t("renovation"), lang.renovation(renovation));
+        }
+        if property.flood_risk == Some(true) {
+            println!("   {}", lang.t("scrape.flood_risk"));
+        }
+        println!("   ID: {}", property.id);
+        println!("   {}: {:.2}", lang.t("score"), record.score.total);
+        println!("   {}: {}", lang.t("features"), property.features.labels(lang).join(", "));
+        if let Some(broker) = &property.broker {
+            let parts: Vec<&str> = [&broker.name, &broker.agency, &broker.phone]
+                .into_iter()
+                .filter_map(|part| part.as_deref())
+                .collect();
+            println!("   {}: {}", lang.t("broker"), parts.join(", "));
+        }
+        if let Some(association) = &property.association {
+            let mut line = association.name.clone();
+            let financials = association.financials.as_ref();
+            if let Some(debt) = financials.and_then(|f| f.debt_per_sqm) {
+                line.push_str(&lang.format("scrape.debt_per_sqm", &[("debt", &format!("{:.0}", debt))]));
+            }
+            println!("   {}: {}", lang.t("association"), line);
+            if let Some(financials) = financials.filter(|f| !f.planned_renovations.is_empty()) {
+                let works: Vec<&str> = financials.planned_renovations.iter().map(|r| r.text.as_str()).collect();
+                println!("   {}", lang.format("scrape.planned", &[("works", &works.join("; "))]));
+            }
+            if association.is_new_conversion(Utc::now().year()) {
+                println!("   {}", lang.t("scrape.new_association"));
+            }
+        }
+        println!("   URL: {}", property.url);
+        println!();
+    }
+
+    let mut written = output::write_outputs(&properties, &run, &config.output, &store).await?;
+    match output::write_search_outputs(&records, &config.searches, &context, &run).await {
+        Ok(paths) => written.extend(paths),
+        Err(e) => warn!("Failed to write saved search outputs: {:#}", e),
+    }
+    info!("💾 Saved {} output files", written.len());
+    if let Err(e) = output::upload_run(&written, &run, &config.scrape.capture, &config.output.s3).await {
+        warn!("Failed to upload outputs: {:#}", e);
+    }
+    if let Err(e) = output::push_run(&properties, &store, &config.output.timeseries, Utc::now()).await {
+        warn!("Failed to write time-series points: {:#}", e);
+    }
+    if config.sheets.sync_after_scrape {
+        match export::sheets::sync(&records, &config.sheets).await {
+            Ok(sync) => info!("📊 Synced Google Sheets ({} added, {} updated)", sync.added, sync.updated),
+            Err(e) => warn!("Failed to sync Google Sheets: {:#}", e),
+        }
+    }
+    if config.airtable.sync_after_scrape {
+        match export::airtable::sync(&records, &history, &config.airtable).await {
+            Ok(sync) => info!("🗂️ Synced Airtable ({} listings, {} price observations)", sync.listings, sync.observations),
+            Err(e) => warn!("Failed to sync Airtable: {:#}", e),
+        }
+    }
+    if config.notion.sync_after_scrape {
+        match export::notion::sync(&records, &config.notion).await {
+            Ok(sync) => info!("📝 Synced Notion ({} added, {} updated)", sync.added, sync.updated),
+            Err(e) => warn!("Failed to sync Notion: {:#}", e),
+        }
+    }
+    events::publish(&events, &config.events).await;
+
+    // Notify about listings we haven't seen before, and price changes and
+    // bidding starts of ones we have
+    let language = config.language.notifications;
+    let mut notifications: Vec<Notification> = records
+        .iter()
+        .filter(|record| new_ids.contains(&record.property.id) || changes.contains_key(&record.property.id))
+        .filter(|record| !record.annotation.as_ref().is_some_and(|a| a.hidden))
+        .filter(|record| {
+            let is_new = new_ids.contains(&record.property.id);
+            !is_new || config.notify.max_age.is_none_or(|age| age.admits(record))
+        })
+        .filter(|record| {
+            record.anomaly.is_some()
+                || config.notify.accepts(record)
+                || config.searches.iter().any(|search| search.notify && search.matches(record))
+        })
+        .map(|record| {
+            let mut notification = match changes.get(&record.property.id) {
+                Some(&event) => Notification::listing_changed(record, event, &templates, language),
+                None => Notification::new_listing(record, &templates, language),
+            };
+            let matching: Vec<&SavedSearch> = config.searches.iter().filter(|search| search.matches(record)).collect();
+            notification.searches = matching.iter().map(|search| search.name.clone()).collect();
+            let description = &record.property.description;
+            let mut keywords: Vec<&str> = matching.iter().flat_map(|search| search.keywords.found(description)).collect();
+            keywords.sort_unstable();
+            keywords.dedup();
+            notification.highlight_keywords(description, &keywords, language);
+            scripts.format(record, &mut notification);
+            notification
+        })
+        .collect();
+    info!(
+        "{} new and {} changed listings, {} above the notification threshold",
+        new_ids.len(),
+        changes.len(),
+        notifications.len()
+    );
+    if config.scrape.health.notify {
+        notifications.extend(health::notifications(&run, language));
+    }
+    let mut ledger = store.load_ledger().await?;
+    let mut outbox = store.load_outbox().await?;
+    notify::dispatch(&config.notify, &notifications, language, &mut ledger, &mut outbox).await;
+    if let Err(e) = store.save_ledger(&ledger).await {
+        warn!("Failed to save the notification ledger: {:#}", e);
+    }
+    if let Err(e) = store.save_outbox(&outbox).await {
+        warn!("Failed to save queued notifications: {:#}", e);
+    }
+
+    println!("{}", run.table().trim_end());
+    Ok(())
+}
+
+/// Record an interrupted run as aborted; its checkpoint stays for `--resume`
+async fn abort(run: &mut ScrapeRun, store: &JsonStore) -> Result<()> {
+    run.finish(RunStatus::Aborted);
+    if let Err(e) = store.save_run(run).await {
+        warn!("Failed to save the run record: {}", e);
+    }
+    eprint!("{}", run.table());
+    eprintln!("Progress saved; continue with `scout scrape --resume {}`", run.id);
+    Err(Interrupted.into())
+}
@@ -0,0 +1,62 @@
+use crate::cli::SearchArgs;
+use crate::config::Config;
+use crate::search::{keyword_search, semantic_search};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use serde_json::json;
+
+/// Print the listings best matching a free-text query
+pub async fn run(args: &SearchArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    let mut properties = if args.all {
+        store.load_all_properties().await?
+    } else {
+        store.load_latest_properties().await?.unwrap_or_default()
+    };
+    let annotations = store.load_annotations().await?;
+    properties.retain(|p| !annotations.is_hidden(&p.id));
+
+    let mut hits = if args.semantic {
+        semantic_search(&properties, &args.query, &config.search, &store).await?
+    } else {
+        keyword_search(&properties, &args.query)
+    };
+    hits.truncate(args.top);
+
+    if args.json {
+        let matches: Vec<_> = hits
+            .iter()
+            .map(|hit| {
+                let property = &properties[hit.index];
+                json!({
+                    "id": property.id,
+                    "address": property.address,
+                    "price": property.price,
+                    "score": hit.score,
+                    "url": property.url,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&matches)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("{}", config.language.cli.format("search.no_matches", &[("query", &args.query)]));
+        return Ok(());
+    }
+
+    for (i, hit) in hits.iter().enumerate() {
+        let property = &properties[hit.index];
+        println!(
+            "{:>2}. {:.3}  {} ({})",
+            i + 1,
+            hit.score,
+            property.address,
+            config.language.cli.summary(property)
+        );
+        println!("       {}", property.url);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::cli::{SecretsArgs, SecretsCommand};
+use crate::secrets::{providers, KeyringSecrets, SecretProvider};
+use anyhow::{bail, Result};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Store, delete or check a named secret
+pub async fn run(args: &SecretsArgs) -> Result<()> {
+    match &args.command {
+        SecretsCommand::Set { name } => {
+            let stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                eprint!("Value for {}: ", name);
+                std::io::stderr().flush()?;
+            }
+            let mut value = String::new();
+            stdin.lock().read_line(&mut value)?;
+            let value = value.trim_end_matches(['\r', '\n']);
+            if value.is_empty() {
+                bail!("No value given for secret {:?}", name);
+            }
+            KeyringSecrets.set(name, value)?;
+            println!("🔑 Stored {} in the keyring; use {{ secret = {:?} }} in scout.toml", name, name);
+        }
+        SecretsCommand::Delete { name } => {
+            if KeyringSecrets.delete(name)? {
+                println!("Deleted {} from the keyring", name);
+            } else {
+                println!("No secret named {} in the keyring", name);
+            }
+        }
+        SecretsCommand::Check { name } => {
+            for provider in providers() {
+                if provider.get(name)?.is_some() {
+                    println!("{} is set (from the {})", name, provider.name());
+                    return Ok(());
+                }
+            }
+            bail!("No secret named {:?}", name);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::cli::ServeArgs;
+use crate::config::Config;
+#[cfg(feature = "server")]
+use crate::storage::JsonStore;
+use anyhow::Result;
+
+/// Serve the stored data over HTTP until Ctrl-C
+#[cfg(feature = "server")]
+pub async fn run(args: &ServeArgs, config: &Config) -> Result<()> {
+    let bind = args.bind.as_deref().unwrap_or(&config.server.bind);
+    crate::server::serve(JsonStore::new("data"), config, bind).await
+}
+
+#[cfg(not(feature = "server"))]
+pub async fn run(_args: &ServeArgs, _config: &Config) -> Result<()> {
+    anyhow::bail!("Serving the stored data needs a build with `--features server`")
+}
@@ -0,0 +1,141 @@
+use crate::cli::SoldArgs;
+use crate::config::Config;
+use crate::models::{Property, SoldListing};
+use crate::prediction::PriceModel;
+#[cfg(feature = "browser")]
+use crate::scrapers::selectors::CardRules;
+#[cfg(feature = "browser")]
+use crate::scrapers::{BooliBrowserScraper, Chrome};
+use crate::scrapers::sold::SODERMALM_SOLD_URL;
+use crate::stats::{match_sales, sale_premium};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashSet};
+#[cfg(feature = "browser")]
+use std::time::Duration as StdDuration;
+use tracing::info;
+
+/// Scrape sold listings and merge them into data/sold.json
+pub async fn run(args: &SoldArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    if args.backfill {
+        backfill(config, &store).await?;
+    } else {
+        let url = args.url.clone().unwrap_or_else(|| SODERMALM_SOLD_URL.to_string());
+        let sold = scrape_sold(&[url], config)?;
+        let found = sold.len();
+        let added = store.merge_sold(sold).await?;
+        info!("💾 Stored {} sold listings ({} new)", found, added);
+    }
+
+    let all = store.load_sold().await?;
+    match PriceModel::train(&all, &config.prediction) {
+        Some(model) => info!("📈 Price model trains on {} sold listings", model.training_rows()),
+        None => info!(
+            "Price model not available ({} sold listings, need {}; requires the `ml` feature)",
+            all.len(),
+            config.prediction.min_training_rows
+        ),
+    }
+
+    Ok(())
+}
+
+/// Look up the sales of tracked listings that left the market and link them
+/// to the listing, for `scout stats --premiums`
+async fn backfill(config: &Config, store: &JsonStore) -> Result<()> {
+    let properties = store.load_all_properties().await?;
+    let history = store.load_history().await?;
+    let on_market: HashSet<String> = store
+        .load_latest_properties()
+        .await?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+    let mut sold: BTreeMap<String, SoldListing> =
+        store.load_sold().await?.into_iter().map(|s| (s.id.clone(), s)).collect();
+    let linked: HashSet<&str> = sold.values().filter_map(|s| s.property_id.as_deref()).collect();
+
+    // Listings gone long enough for the sale to show, but not so long that
+    // they were likely withdrawn rather than sold
+    let now = Utc::now();
+    let after = Duration::days(config.sold.backfill_after_days);
+    let max = Duration::days(config.sold.backfill_max_days);
+    let pending: Vec<(&Property, NaiveDate)> = properties
+        .iter()
+        .filter(|p| !on_market.contains(&p.id) && !linked.contains(p.id.as_str()))
+        .filter_map(|p| {
+            let entry = history.get(&p.id).filter(|h| h.relisted_as.is_none())?;
+            let gone = now - entry.last_seen;
+            (gone >= after && gone <= max).then_some((p, entry.first_seen.date_naive()))
+        })
+        .collect();
+    if pending.is_empty() {
+        info!("No tracked listings are waiting for a final price");
+        return Ok(());
+    }
+
+    let areas: HashSet<&str> = pending.iter().flat_map(|(p, _)| p.area_tags.iter().map(String::as_str)).collect();
+    let mut urls: Vec<String> = config
+        .scrape
+        .areas
+        .iter()
+        .filter(|area| areas.is_empty() || areas.contains(area.name.as_str()))
+        .filter_map(|area| area.sold_url())
+        .collect();
+    urls.sort();
+    urls.dedup();
+    if urls.is_empty() {
+        anyhow::bail!("None of [scrape.areas] is a Booli search to look up sales in");
+    }
+    info!("🔎 Looking up final prices of {} listings that left the market", pending.len());
+
+    let scraped = scrape_sold(&urls, config)?;
+    for sale in scraped {
+        // A sale linked earlier stays linked when scraped again
+        let property_id = sold.get(&sale.id).and_then(|s| s.property_id.clone());
+        sold.insert(sale.id.clone(), SoldListing { property_id, ..sale });
+    }
+    let mut sold: Vec<SoldListing> = sold.into_values().collect();
+    let found = match_sales(&pending, &mut sold);
+
+    for (property, _) in &pending {
+        let Some(sale) = sold.iter().find(|s| s.property_id.as_deref() == Some(property.id.as_str())) else {
+            continue;
+        };
+        let premium = sale_premium(sale, Some(property))
+            .map(|p| format!(" ({:+.1}% vs asking)", p))
+            .unwrap_or_default();
+        println!("💰 {}: sold for {} kr{}", property.address, sale.sold_price, premium);
+    }
+    let added = store.merge_sold(sold).await?;
+    info!(
+        "💾 Found final prices for {} of {} listings ({} new sold listings stored)",
+        found,
+        pending.len(),
+        added
+    );
+    Ok(())
+}
+
+#[cfg(feature = "browser")]
+fn scrape_sold(urls: &[String], config: &Config) -> Result<Vec<SoldListing>> {
+    let cards = CardRules::load(&config.scrape.rules_dir, "booli")?.compile()?;
+    let scraper = BooliBrowserScraper::new()?
+        .with_chrome(Chrome::new(config.scrape.chrome.clone()))
+        .with_min_interval(StdDuration::from_millis(config.scrape.min_interval_ms))
+        .with_politeness(config.scrape.politeness.booli_browser.clone())
+        .with_cards(cards);
+    let mut sold = Vec::new();
+    for url in urls {
+        sold.extend(scraper.scrape_sold(url)?);
+    }
+    Ok(sold)
+}
+
+#[cfg(not(feature = "browser"))]
+fn scrape_sold(_urls: &[String], _config: &Config) -> Result<Vec<SoldListing>> {
+    anyhow::bail!("Scraping sold listings needs a build with `--features browser`")
+}
@@ -0,0 +1,151 @@
+use crate::cli::StatsArgs;
+use crate::config::Config;
+use crate::stats::{area_stats, fee_trends, premium_stats, AreaStats};
+use crate::stats::fees::SHARP_RISE_PERCENT;
+use crate::storage::{JsonStore, QueryEngine};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+
+/// Print per-area aggregates over the last `--days` days
+pub async fn run(args: &StatsArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    if args.premiums {
+        return premiums(args, &store).await;
+    }
+    if args.fees {
+        return fees(args, &store).await;
+    }
+    let stats = match config.analytics.engine {
+        QueryEngine::Sqlite => load_stats(args, &store).await?,
+        #[cfg(feature = "duckdb")]
+        QueryEngine::Duckdb => {
+            crate::storage::DuckIndex::open(&store).await?.area_stats(args.area.as_deref(), Utc::now(), args.days)?
+        }
+        #[cfg(not(feature = "duckdb"))]
+        QueryEngine::Duckdb => anyhow::bail!("The duckdb engine needs a build with `--features duckdb`"),
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    if stats.is_empty() {
+        println!("No listings seen in the last {} days", args.days);
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:>8} {:>14} {:>10} {:>10} {:>8} {:>8}",
+        "Area", "Listings", "Median price", "kr/kvm", "Fee/kvm", "Days", "Trend"
+    );
+    for row in &stats {
+        println!(
+            "{:<24} {:>8} {:>14} {:>10} {:>10} {:>8} {:>8}",
+            row.area,
+            row.listings,
+            row.median_price.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+            row.median_price_per_sqm.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+            row.median_fee_per_sqm.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+            row.avg_days_on_market.map(|d| format!("{:.1}", d)).unwrap_or_default(),
+            row.price_per_sqm_trend.map(|t| format!("{:+.1}%", t * 100.0)).unwrap_or_default(),
+        );
+    }
+    println!("\nTrend: median kr/kvm vs the {} days before", args.days);
+
+    Ok(())
+}
+
+/// Print per-area final vs asking prices of the sales of the last `--days` days
+async fn premiums(args: &StatsArgs, store: &JsonStore) -> Result<()> {
+    let since = (Utc::now() - Duration::days(args.days)).date_naive();
+    let mut stats = premium_stats(&store.load_sold().await?, &store.load_all_properties().await?, Some(since));
+    if let Some(area) = &args.area {
+        stats.retain(|row| row.area.eq_ignore_ascii_case(area));
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!("No sales with an asking price in the last {} days; scrape some with `scout sold`", args.days);
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:>6} {:>8} {:>8} {:>8} {:>8} {:>14}",
+        "Area", "Sales", "Tracked", "Median", "Mean", "Over", "Median price"
+    );
+    for row in &stats {
+        println!(
+            "{:<24} {:>6} {:>8} {:>8} {:>8} {:>7.0}% {:>14}",
+            row.area,
+            row.sales,
+            row.tracked,
+            row.median_premium.map(|p| format!("{:+.1}%", p)).unwrap_or_default(),
+            row.mean_premium.map(|p| format!("{:+.1}%", p)).unwrap_or_default(),
+            row.over_asking * 100.0,
+            row.median_sold_price.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+        );
+    }
+    println!("\nFinal price vs asking; Over: share of sales above asking");
+
+    Ok(())
+}
+
+/// Print the fee trend of every housing association, steepest rise first
+async fn fees(args: &StatsArgs, store: &JsonStore) -> Result<()> {
+    let mut properties = store.load_all_properties().await?;
+    if let Some(area) = &args.area {
+        properties.retain(|p| p.location.area.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(area)));
+    }
+    let trends = fee_trends(&properties, &store.load_history().await?);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&trends)?);
+        return Ok(());
+    }
+    if trends.is_empty() {
+        println!("No housing association with fees seen far enough apart; associations come from detail pages");
+        return Ok(());
+    }
+
+    println!(
+        "{:<32} {:>8} {:>10} {:>10} {:>10} {:>10} {:>8}",
+        "Association", "Listings", "From", "Fee/kvm", "To", "Fee/kvm", "Yearly"
+    );
+    for row in &trends {
+        println!(
+            "{:<32} {:>8} {:>10} {:>10.1} {:>10} {:>10.1} {:>8}{}",
+            row.association,
+            row.listings,
+            row.first_date,
+            row.first_fee_per_sqm,
+            row.last_date,
+            row.last_fee_per_sqm,
+            row.yearly_change.map(|c| format!("{:+.1}%", c)).unwrap_or_default(),
+            if row.sharp_rise { " ⚠️" } else { "" },
+        );
+    }
+    println!("\nMonthly fee per kvm; ⚠️  rising {:.0}% a year or more", SHARP_RISE_PERCENT);
+
+    Ok(())
+}
+
+/// Aggregate over every stored property, read from the JSON store
+async fn load_stats(args: &StatsArgs, store: &JsonStore) -> Result<Vec<AreaStats>> {
+    let mut properties = store.load_all_properties().await?;
+    let history = store.load_history().await?;
+
+    if let Some(area) = &args.area {
+        properties.retain(|p| {
+            p.location
+                .area
+                .as_deref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(area))
+        });
+    }
+
+    Ok(area_stats(&properties, &history, Utc::now(), args.days))
+}
@@ -0,0 +1,162 @@
+use crate::cli::WatchArgs;
+use crate::config::Config;
+use crate::models::Property;
+use crate::notify::{self, Notification, NotificationEvent};
+use crate::storage::JsonStore;
+use crate::watch::{self, Checker, WatchState, WatchedListing, Watchlist};
+use anyhow::Result;
+use chrono::Utc;
+use chrono_tz::Europe::Stockholm;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Add or remove watched listings, then check every watched listing each
+/// interval until Ctrl-C
+pub async fn run(args: &WatchArgs, config: &Config) -> Result<()> {
+    let store = JsonStore::new("data");
+    let mut watchlist = store.load_watchlist().await?;
+
+    if !args.listings.is_empty() {
+        let stored = store.load_all_properties().await?;
+        for target in &args.listings {
+            let (id, url) = watch::resolve(target, &stored)?;
+            if args.remove {
+                match watchlist.listings.remove(&id) {
+                    Some(watched) => info!("Stopped watching {}", watched.label()),
+                    None => warn!("{} isn't watched", id),
+                }
+                continue;
+            }
+            let address = stored.iter().find(|p| p.id == id).map(|p| p.address.clone());
+            let watched = watchlist
+                .listings
+                .entry(id)
+                .or_insert_with(|| WatchedListing::new(url, address, Utc::now()));
+            info!("👀 Watching {}", watched.label());
+        }
+        store.save_watchlist(&watchlist).await?;
+    }
+    if args.list {
+        print_watchlist(&watchlist);
+        return Ok(());
+    }
+    if args.remove {
+        return Ok(());
+    }
+    if watchlist.listings.is_empty() {
+        anyhow::bail!("No listings watched yet; add some with `scout watch <url-or-id>`");
+    }
+
+    let checker = Checker::new(&config.scrape)?;
+    let minutes = args.every.unwrap_or(config.watch.interval_minutes).max(1);
+    loop {
+        check(&mut watchlist, &checker, &store, config).await?;
+        if args.once {
+            return Ok(());
+        }
+        info!("Checking again in {} minutes (Ctrl-C to stop)", minutes);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(minutes * 60)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Check every watched listing once, notifying about and printing what changed
+async fn check(watchlist: &mut Watchlist, checker: &Checker, store: &JsonStore, config: &Config) -> Result<()> {
+    let language = config.language.notifications;
+    let stored = store.load_all_properties().await?;
+    let mut observed: Vec<Property> = Vec::new();
+
+    for (i, (id, watched)) in watchlist.listings.iter_mut().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(Duration::from_millis(config.scrape.min_interval_ms)).await;
+        }
+        let earlier = watched.state.as_ref();
+        let state = match checker.fetch(id, &watched.url).await {
+            Ok(Some(details)) => WatchState::fetched(&details, earlier),
+            Ok(None) => WatchState::removed(earlier),
+            Err(e) => {
+                warn!("Failed to check {}: {:#}", watched.label(), e);
+                continue;
+            }
+        };
+        let now = Utc::now();
+        watched.last_checked = Some(now);
+
+        let changes = earlier.map(|earlier| state.changes_since(earlier, language)).unwrap_or_default();
+        if earlier.is_none() {
+            info!("{}: {}", watched.label(), describe(&state));
+        } else if changes.is_empty() {
+            info!("{}: no changes", watched.label());
+        } else {
+            println!("{} ({})", watched.label(), watched.url);
+            for change in &changes {
+                println!("   {}", change);
+            }
+            let notification = Notification {
+                event: NotificationEvent::Watched,
+                title: language.format("notify.watch_title", &[("address", &watched.label())]),
+                body: changes.join("\n"),
+                url: Some(watched.url.clone()),
+                property_id: Some(id.clone()),
+                state: None,
+                searches: Vec::new(),
+            };
+            notify::send_now(&config.notify, &notification).await;
+        }
+
+        // Price and bid changes go into the price history of stored listings
+        if let Some(property) = stored.iter().find(|p| p.id == *id).filter(|_| !state.removed) {
+            let mut property = property.clone();
+            property.price = state.price.or(property.price);
+            property.current_bid = state.current_bid.or(property.current_bid);
+            property.bidding_in_progress = state.bidding_in_progress;
+            property.scraped_at = now;
+            observed.push(property);
+        }
+        watched.state = Some(state);
+    }
+
+    store.save_watchlist(watchlist).await?;
+    if !observed.is_empty() {
+        let mut history = store.load_history().await?;
+        history.record(&observed);
+        store.save_history(&history).await?;
+    }
+    Ok(())
+}
+
+/// "4995000 kr, bid 5100000 kr, bidding"
+fn describe(state: &WatchState) -> String {
+    if state.removed {
+        return "gone from Booli".to_string();
+    }
+    let mut parts = vec![state.price.map(|p| p.to_string()).unwrap_or_else(|| "no price".to_string())];
+    if let Some(bid) = state.current_bid {
+        parts.push(format!("bid {}", bid));
+    }
+    if state.bidding_in_progress {
+        parts.push("bidding".to_string());
+    }
+    parts.join(", ")
+}
+
+fn print_watchlist(watchlist: &Watchlist) {
+    if watchlist.listings.is_empty() {
+        println!("No listings watched");
+        return;
+    }
+    for (id, watched) in &watchlist.listings {
+        println!("{}: {}", id, watched.label());
+        println!("   {}", watched.url);
+        match (&watched.state, watched.last_checked) {
+            (Some(state), Some(checked)) => println!(
+                "   {} (checked {})",
+                describe(state),
+                checked.with_timezone(&Stockholm).format("%Y-%m-%d %H:%M")
+            ),
+            _ => println!("   Not checked yet"),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::anomaly::AnomalyConfig;
+use crate::bot::BotConfig;
+use crate::currency::CurrencyConfig;
+use crate::enrich::EnrichConfig;
+use crate::events::EventsConfig;
+use crate::export::{AirtableConfig, NotionConfig, SheetsConfig};
+use crate::filter::SavedSearch;
+use crate::finance::FinanceConfig;
+use crate::i18n::LanguageConfig;
+use crate::images::ImageConfig;
+use crate::notify::NotifyConfig;
+use crate::output::OutputConfig;
+use crate::prediction::PredictionConfig;
+use crate::scoring::ScoringConfig;
+use crate::scrapers::ScrapeConfig;
+use crate::scrapers::sold::SoldConfig;
+use crate::scripts::ScriptConfig;
+use crate::search::SearchConfig;
+use crate::server::ServerConfig;
+use crate::storage::AnalyticsConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::templates::TemplateConfig;
+use crate::watch::WatchConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Default config file looked up in the working directory
+pub const DEFAULT_CONFIG_FILE: &str = "scout.toml";
+
+/// User configuration loaded from scout.toml
+///
+/// Every section is optional; missing values fall back to their defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub airtable: AirtableConfig,
+    pub analytics: AnalyticsConfig,
+    pub anomaly: AnomalyConfig,
+    pub bot: BotConfig,
+    pub currency: CurrencyConfig,
+    pub enrich: EnrichConfig,
+    pub events: EventsConfig,
+    pub finance: FinanceConfig,
+    pub images: ImageConfig,
+    pub language: LanguageConfig,
+    pub scoring: ScoringConfig,
+    pub scrape: ScrapeConfig,
+    pub scripts: ScriptConfig,
+    pub notify: NotifyConfig,
+    pub notion: NotionConfig,
+    pub output: OutputConfig,
+    pub prediction: PredictionConfig,
+    pub search: SearchConfig,
+    /// Named filters for `--search <name>` and notifications
+    pub searches: Vec<SavedSearch>,
+    pub server: ServerConfig,
+    pub sheets: SheetsConfig,
+    pub sold: SoldConfig,
+    pub telemetry: TelemetryConfig,
+    pub templates: TemplateConfig,
+    pub watch: WatchConfig,
+}
+
+impl Config {
+    /// Load config from `path`, or from ./scout.toml if it exists
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None => {
+                let default = Path::new(DEFAULT_CONFIG_FILE);
+                if !default.exists() {
+                    return Ok(Self::default());
+                }
+                default
+            }
+        };
+
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config: Self =
+            toml::from_str(&text).with_context(|| format!("Failed to parse config {}", path.display()))?;
+        for search in &mut config.searches {
+            if let Some(area) = &mut search.area {
+                area.load().with_context(|| format!("Failed to load the area of search {:?}", search.name))?;
+            }
+            // A search naming a channel is the same as the channel naming the search
+            for name in &search.channels {
+                let channel = config
+                    .notify
+                    .channels
+                    .iter_mut()
+                    .find(|channel| channel.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+                    .with_context(|| format!("Search {:?} names no configured channel {:?}", search.name, name))?;
+                if !channel.searches.contains(&search.name) {
+                    channel.searches.push(search.name.clone());
+                }
+            }
+        }
+        anyhow::ensure!(
+            !(config.scrape.chrome.stealth && config.scrape.politeness.booli_browser.identity.is_some()),
+            "[scrape.chrome] stealth and an identity for booli_browser contradict each other; keep one"
+        );
+        let politeness = &config.scrape.politeness;
+        for (source, politeness) in [
+            ("booli_graphql", &politeness.booli_graphql),
+            ("booli_browser", &politeness.booli_browser),
+            ("booli_http", &politeness.booli_http),
+        ] {
+            if let Some(identity) = &politeness.identity {
+                anyhow::ensure!(
+                    identity.contact.contains('@'),
+                    "The identity of {} needs a contact email address, not {:?}",
+                    source,
+                    identity.contact
+                );
+            }
+        }
+        Ok(config)
+    }
+
+    /// Look up a saved search by name
+    pub fn saved_search(&self, name: &str) -> Result<&SavedSearch> {
+        self.searches
+            .iter()
+            .find(|search| search.name.eq_ignore_ascii_case(name))
+            .with_context(|| format!("No saved search named {:?} in the config", name))
+    }
+}
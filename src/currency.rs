@@ -0,0 +1,214 @@
+use crate::metrics::PropertyMetrics;
+use crate::models::{Currency, Money, Property};
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, instrument, warn};
+
+const CACHE_NAME: &str = "exchange_rates";
+
+const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+const RIKSBANK_LATEST_URL: &str = "https://api.riksbank.se/swea/v1/Observations/Latest";
+
+/// Where daily exchange rates are fetched from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateProvider {
+    /// ECB euro reference rates, crossed through EUR
+    #[default]
+    Ecb,
+    /// Riksbanken's daily SEK fixings
+    Riksbank,
+}
+
+/// Exchange rates used to compare listings priced in different currencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencyConfig {
+    pub provider: RateProvider,
+    /// Refetch cached rates older than this
+    pub max_age_hours: i64,
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            provider: RateProvider::Ecb,
+            max_age_hours: 24,
+        }
+    }
+}
+
+/// Daily rates as SEK per unit of each currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub provider: RateProvider,
+    /// Day the rates were published for
+    pub date: NaiveDate,
+    pub fetched_at: DateTime<Utc>,
+    pub sek_per_unit: BTreeMap<Currency, f64>,
+}
+
+impl ExchangeRates {
+    /// SEK per unit of `currency`, 1.0 for SEK itself
+    pub fn rate(&self, currency: Currency) -> Option<f64> {
+        match currency {
+            Currency::Sek => Some(1.0),
+            other => self.sek_per_unit.get(&other).copied(),
+        }
+    }
+
+    /// `amount` in whole SEK
+    pub fn to_sek(&self, amount: Money) -> Option<f64> {
+        Some(amount.as_f64() * self.rate(amount.currency)?)
+    }
+}
+
+/// SEK-equivalent values of a non-SEK listing, so mixed result sets compare fairly
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct SekEquivalent {
+    /// SEK per unit of the listing's currency
+    pub rate: f64,
+    pub rate_date: NaiveDate,
+    pub price: Option<f64>,
+    pub monthly_fee: Option<f64>,
+    pub price_per_sqm: Option<f64>,
+    pub fee_per_sqm: Option<f64>,
+}
+
+impl SekEquivalent {
+    pub fn compute(property: &Property, metrics: &PropertyMetrics, rates: &ExchangeRates) -> Option<Self> {
+        let rate = rates.rate(property.currency())?;
+        let convert = |value: Option<f64>| value.map(|value| value * rate);
+        Some(Self {
+            rate,
+            rate_date: rates.date,
+            price: property.price.and_then(|price| rates.to_sek(price)).map(f64::round),
+            monthly_fee: property.monthly_fee.and_then(|fee| rates.to_sek(fee)).map(f64::round),
+            price_per_sqm: convert(metrics.price_per_sqm),
+            fee_per_sqm: convert(metrics.fee_per_sqm),
+        })
+    }
+}
+
+/// Whether `properties` are priced in more than one currency
+pub fn mixed_currencies(properties: &[Property]) -> bool {
+    properties
+        .split_first()
+        .is_some_and(|(first, rest)| rest.iter().any(|p| p.currency() != first.currency()))
+}
+
+/// Today's rates from the cache, fetched from the configured provider when stale
+///
+/// A failed fetch falls back to cached rates of any age.
+pub async fn load_rates(store: &JsonStore, config: &CurrencyConfig) -> Result<ExchangeRates> {
+    let cached = store
+        .load_cache::<ExchangeRates>(CACHE_NAME)
+        .await
+        .filter(|rates| rates.provider == config.provider);
+    if let Some(rates) = &cached {
+        if Utc::now() - rates.fetched_at < Duration::hours(config.max_age_hours) {
+            return Ok(rates.clone());
+        }
+    }
+
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let fetched = match config.provider {
+        RateProvider::Ecb => fetch_ecb(&client).await,
+        RateProvider::Riksbank => fetch_riksbank(&client).await,
+    };
+
+    match (fetched, cached) {
+        (Ok(rates), _) => {
+            info!("💱 Fetched exchange rates for {} from {:?}", rates.date, rates.provider);
+            store.save_cache(CACHE_NAME, &rates).await?;
+            Ok(rates)
+        }
+        (Err(e), Some(stale)) => {
+            warn!("Failed to fetch exchange rates, using those from {}: {:#}", stale.date, e);
+            Ok(stale)
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+#[instrument(name = "http", level = "debug", skip_all, fields(url = ECB_DAILY_URL))]
+async fn fetch_ecb(client: &Client) -> Result<ExchangeRates> {
+    let xml = client.get(ECB_DAILY_URL).send().await?.error_for_status()?.text().await?;
+    parse_ecb(&xml)
+}
+
+/// Parse the ECB daily XML, where each rate is units of a currency per EUR
+fn parse_ecb(xml: &str) -> Result<ExchangeRates> {
+    let date = attribute(xml, "time")
+        .and_then(|time| NaiveDate::parse_from_str(time, "%Y-%m-%d").ok())
+        .context("ECB rates have no date")?;
+
+    let mut per_eur: BTreeMap<&str, f64> = BTreeMap::new();
+    for cube in xml.split("<Cube").skip(1) {
+        if let (Some(code), Some(rate)) = (attribute(cube, "currency"), attribute(cube, "rate")) {
+            if let Ok(rate) = rate.parse() {
+                per_eur.insert(code, rate);
+            }
+        }
+    }
+    let sek = *per_eur.get("SEK").context("ECB rates have no SEK rate")?;
+
+    let mut sek_per_unit = BTreeMap::from([(Currency::Eur, sek)]);
+    for currency in [Currency::Nok, Currency::Dkk] {
+        if let Some(rate) = per_eur.get(currency.code()).filter(|rate| **rate > 0.0) {
+            sek_per_unit.insert(currency, sek / rate);
+        }
+    }
+    Ok(ExchangeRates {
+        provider: RateProvider::Ecb,
+        date,
+        fetched_at: Utc::now(),
+        sek_per_unit,
+    })
+}
+
+/// Value of `name='...'` or `name="..."` in an XML fragment
+fn attribute<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = xml[start..].chars().next()?;
+    let value = &xml[start + 1..];
+    Some(&value[..value.find(quote)?])
+}
+
+#[derive(Deserialize)]
+struct RiksbankObservation {
+    date: NaiveDate,
+    value: f64,
+}
+
+async fn fetch_riksbank(client: &Client) -> Result<ExchangeRates> {
+    let mut sek_per_unit = BTreeMap::new();
+    let mut date = None;
+    for currency in [Currency::Eur, Currency::Nok, Currency::Dkk] {
+        let series = format!("SEK{}PMI", currency.code());
+        let observation: RiksbankObservation = client
+            .get(format!("{}/{}", RIKSBANK_LATEST_URL, series))
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Riksbanken has no rate for {}", series))?
+            .json()
+            .await?;
+        sek_per_unit.insert(currency, observation.value);
+        date = date.max(Some(observation.date));
+    }
+
+    Ok(ExchangeRates {
+        provider: RateProvider::Riksbank,
+        date: date.context("Riksbanken returned no rates")?,
+        fetched_at: Utc::now(),
+        sek_per_unit,
+    })
+}
@@ -0,0 +1,134 @@
+use crate::models::{Money, Property};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Fields that change on every run or only track local files, so they are
+/// left out of field-level diffs
+const IGNORED_FIELDS: &[&str] = &["scraped_at", "run_id", "local_images", "local_floor_plans", "image_hashes"];
+
+/// What changed between two scrape runs
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDiff {
+    pub from: String,
+    pub to: String,
+    /// Listings only in the newer run
+    pub added: Vec<ListingRef>,
+    /// Listings only in the older run (sold or withdrawn)
+    pub removed: Vec<ListingRef>,
+    /// Listings in both runs with at least one changed field; price cuts first
+    pub changed: Vec<ListingChanges>,
+}
+
+impl RunDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListingRef {
+    pub id: String,
+    pub address: String,
+    pub price: Option<Money>,
+    pub url: String,
+}
+
+impl ListingRef {
+    fn new(property: &Property) -> Self {
+        Self {
+            id: property.id.clone(),
+            address: property.address.clone(),
+            price: property.price,
+            url: property.url.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListingChanges {
+    #[serde(flatten)]
+    pub listing: ListingRef,
+    /// Price drop in SEK, when the listed price went down
+    pub price_cut: Option<Money>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// One top-level property field, as JSON before and after
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Compare the properties of two runs by ID
+pub fn diff_runs(from: &str, before: &[Property], to: &str, after: &[Property]) -> RunDiff {
+    let before: BTreeMap<&str, &Property> = before.iter().map(|p| (p.id.as_str(), p)).collect();
+    let after: BTreeMap<&str, &Property> = after.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    let added = after
+        .iter()
+        .filter(|(id, _)| !before.contains_key(*id))
+        .map(|(_, p)| ListingRef::new(p))
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|(id, _)| !after.contains_key(*id))
+        .map(|(_, p)| ListingRef::new(p))
+        .collect();
+
+    let mut changed: Vec<ListingChanges> = after
+        .iter()
+        .filter_map(|(id, new)| {
+            let old = before.get(id)?;
+            let changes = field_changes(old, new);
+            if changes.is_empty() {
+                return None;
+            }
+            Some(ListingChanges {
+                listing: ListingRef::new(new),
+                price_cut: old.price.zip(new.price).and_then(|(old, new)| price_cut(old, new)),
+                changes,
+            })
+        })
+        .collect();
+    changed.sort_by_key(|c| std::cmp::Reverse(c.price_cut.map(|cut| cut.amount_minor).unwrap_or(0)));
+
+    RunDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        added,
+        removed,
+        changed,
+    }
+}
+
+/// How much the price dropped, when it did and stayed in the same currency
+fn price_cut(old: Money, new: Money) -> Option<Money> {
+    (new.currency == old.currency && new.is_positive() && new.amount_minor < old.amount_minor).then(|| Money {
+        amount_minor: old.amount_minor - new.amount_minor,
+        currency: new.currency,
+    })
+}
+
+fn field_changes(before: &Property, after: &Property) -> Vec<FieldChange> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    after
+        .into_iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, new)| {
+            let old = before.get(&field).cloned().unwrap_or(Value::Null);
+            (old != new).then_some(FieldChange {
+                field,
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
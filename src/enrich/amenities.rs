@@ -0,0 +1,106 @@
+use crate::enrich::overpass::{Element, OverpassClient};
+use crate::geo::GeoPoint;
+use crate::models::{NearbyPlaces, Property};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+const CACHE_NAME: &str = "amenities";
+const CACHE_MAX_AGE_DAYS: i64 = 90;
+
+/// Grid cell size in degrees, roughly 250 x 250 m at Stockholm's latitude.
+/// Properties in the same cell share one lookup from the cell center.
+const CELL_LAT_DEG: f64 = 0.00225;
+const CELL_LON_DEG: f64 = 0.0045;
+
+#[derive(Serialize, Deserialize)]
+struct CachedCell {
+    fetched_at: DateTime<Utc>,
+    places: NearbyPlaces,
+}
+
+/// Count groceries, gyms, preschools and parks around every property with coordinates
+pub async fn enrich(
+    properties: &mut [Property],
+    radius_m: f64,
+    overpass: &OverpassClient,
+    store: &JsonStore,
+) -> Result<()> {
+    let mut cache: BTreeMap<String, CachedCell> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let stale_before = Utc::now() - Duration::days(CACHE_MAX_AGE_DAYS);
+    let mut fetched = 0;
+
+    for property in properties.iter_mut() {
+        let Some(point) = property.location.point() else {
+            continue;
+        };
+        let (key, center) = cell(&point, radius_m);
+
+        let fresh = cache.get(&key).filter(|cell| cell.fetched_at > stale_before);
+        let places = match fresh {
+            Some(cell) => cell.places.clone(),
+            None => match count_around(&center, radius_m, overpass).await {
+                Ok(places) => {
+                    fetched += 1;
+                    cache.insert(
+                        key,
+                        CachedCell {
+                            fetched_at: Utc::now(),
+                            places: places.clone(),
+                        },
+                    );
+                    places
+                }
+                Err(e) => {
+                    warn!("Failed to look up amenities near {}: {}", property.address, e);
+                    continue;
+                }
+            },
+        };
+        property.nearby = Some(places);
+    }
+
+    if fetched > 0 {
+        info!("🛒 Looked up amenities for {} new grid cells", fetched);
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+/// Cache key and center of the grid cell containing `point`
+fn cell(point: &GeoPoint, radius_m: f64) -> (String, GeoPoint) {
+    let row = (point.lat / CELL_LAT_DEG).floor();
+    let col = (point.lon / CELL_LON_DEG).floor();
+    let center = GeoPoint::new((row + 0.5) * CELL_LAT_DEG, (col + 0.5) * CELL_LON_DEG);
+    (format!("{}:{}:{}", row, col, radius_m.round()), center)
+}
+
+async fn count_around(center: &GeoPoint, radius_m: f64, overpass: &OverpassClient) -> Result<NearbyPlaces> {
+    let around = format!("(around:{:.0},{},{})", radius_m, center.lat, center.lon);
+    let query = format!(
+        r#"[out:json][timeout:30];(nwr["shop"~"^(supermarket|convenience|greengrocer)$"]{a};nwr["leisure"="fitness_centre"]{a};nwr["amenity"="kindergarten"]{a};nwr["leisure"="park"]{a};);out tags center;"#,
+        a = around
+    );
+
+    let mut places = NearbyPlaces {
+        radius_m,
+        ..Default::default()
+    };
+    for element in overpass.query(&query).await? {
+        classify(&element, &mut places);
+    }
+    Ok(places)
+}
+
+fn classify(element: &Element, places: &mut NearbyPlaces) {
+    match (element.tag("shop"), element.tag("leisure"), element.tag("amenity")) {
+        (Some(_), _, _) => places.groceries += 1,
+        (_, Some("park"), _) => places.parks += 1,
+        (_, Some(_), _) => places.gyms += 1,
+        (_, _, Some("kindergarten")) => places.preschools += 1,
+        _ => {}
+    }
+}
@@ -0,0 +1,370 @@
+use crate::enrich::broadband::encode;
+use crate::models::{Association, BrfFinancials, FeeYear, PlannedRenovation, Property};
+use crate::scrapers::detail::{absolute_url, parse_association, visible_text};
+use crate::scrapers::parse::{leading_number, normalize};
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+const CACHE_NAME: &str = "brf";
+/// Associations publish one report a year
+const REPORT_MAX_AGE_DAYS: i64 = 180;
+
+/// Annual report lookup for housing associations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrfConfig {
+    /// pdftotext executable, from poppler-utils
+    pub pdftotext: String,
+    /// Where to look for the report when the listing links none; `{name}` is
+    /// replaced with the URL-encoded association name
+    pub search_url: Option<String>,
+    /// Skip reports larger than this
+    pub max_report_bytes: u64,
+    /// Wait between listing pages read for their association
+    pub delay_ms: u64,
+}
+
+impl Default for BrfConfig {
+    fn default() -> Self {
+        Self {
+            pdftotext: "pdftotext".to_string(),
+            search_url: Some("https://www.allabrf.se/sok?q={name}".to_string()),
+            max_report_bytes: 30 * 1024 * 1024,
+            delay_ms: 1000,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct BrfCache {
+    /// Association of each listing whose page was read, None if it names none
+    listings: BTreeMap<String, Option<Association>>,
+    /// Reports read, by lower-case association name
+    reports: BTreeMap<String, CachedReport>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedReport {
+    fetched_at: DateTime<Utc>,
+    financials: BrfFinancials,
+}
+
+/// Find every property's housing association and read its latest annual report
+pub async fn enrich(properties: &mut [Property], config: &BrfConfig, store: &JsonStore) -> Result<()> {
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(60))
+        .build()?;
+    let mut cache: BrfCache = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let stale_before = Utc::now() - Duration::days(REPORT_MAX_AGE_DAYS);
+    let mut failed: HashSet<String> = HashSet::new();
+    let listings_before = cache.listings.len();
+    let mut fetched = 0;
+
+    for property in properties.iter_mut() {
+        // Listings from the API don't name their association; their page does
+        if property.association.is_none() {
+            match cache.listings.get(&property.id) {
+                Some(cached) => property.association = cached.clone(),
+                None => {
+                    tokio::time::sleep(std::time::Duration::from_millis(config.delay_ms)).await;
+                    match listing_association(&client, &property.url).await {
+                        Ok(association) => {
+                            cache.listings.insert(property.id.clone(), association.clone());
+                            property.association = association;
+                        }
+                        Err(e) => warn!("Failed to read the association of {}: {:#}", property.address, e),
+                    }
+                }
+            }
+        }
+        let Some(association) = property.association.as_mut() else {
+            continue;
+        };
+
+        let key = association.name.to_lowercase();
+        if let Some(cached) = cache.reports.get(&key).filter(|c| {
+            c.fetched_at > stale_before
+                && association.annual_report_url.as_ref().is_none_or(|url| *url == c.financials.report_url)
+        }) {
+            association.financials = Some(cached.financials.clone());
+            continue;
+        }
+        if failed.contains(&key) {
+            continue;
+        }
+
+        match read_annual_report(&client, config, association).await {
+            Ok(Some(financials)) => {
+                fetched += 1;
+                association.financials = Some(financials.clone());
+                let fetched_at = Utc::now();
+                cache.reports.insert(key, CachedReport { fetched_at, financials });
+            }
+            Ok(None) => {
+                debug!("No annual report found for {}", association.name);
+                failed.insert(key);
+            }
+            Err(e) => {
+                warn!("Failed to read the annual report of {}: {:#}", association.name, e);
+                failed.insert(key);
+            }
+        }
+    }
+
+    if fetched > 0 {
+        info!("🏢 Read {} housing association annual reports", fetched);
+    }
+    if fetched > 0 || cache.listings.len() > listings_before {
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+async fn listing_association(client: &Client, url: &str) -> Result<Option<Association>> {
+    let html = client.get(url).send().await?.error_for_status()?.text().await?;
+    let document = Html::parse_document(&html);
+    Ok(parse_association(&document, &visible_text(&document)))
+}
+
+/// The listing's linked report, or else the first one the search finds
+async fn read_annual_report(
+    client: &Client,
+    config: &BrfConfig,
+    association: &Association,
+) -> Result<Option<BrfFinancials>> {
+    let url = match &association.annual_report_url {
+        Some(url) => Some(url.clone()),
+        None => match &config.search_url {
+            Some(search) => search_report(client, &search.replace("{name}", &encode(&association.name)), &association.name)
+                .await?,
+            None => None,
+        },
+    };
+    let Some(url) = url else {
+        return Ok(None);
+    };
+
+    let response = client.get(&url).send().await?.error_for_status()?;
+    let is_pdf = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("pdf"));
+    let (url, response) = if is_pdf {
+        (url, response)
+    } else {
+        // A page about the report rather than the report itself
+        let html = response.text().await?;
+        let Some(pdf) = pdf_link(&html, &url) else {
+            return Ok(None);
+        };
+        let response = client.get(&pdf).send().await?.error_for_status()?;
+        (pdf, response)
+    };
+    if response.content_length().is_some_and(|len| len > config.max_report_bytes) {
+        anyhow::bail!("{} is larger than max_report_bytes", url);
+    }
+    let pdf = response.bytes().await?;
+    if pdf.len() as u64 > config.max_report_bytes {
+        anyhow::bail!("{} is larger than max_report_bytes", url);
+    }
+
+    let text = pdf_text(&pdf, config).await?;
+    Ok(Some(parse_annual_report(&text, &url)))
+}
+
+/// The first annual report on a search result page, following a link to
+/// the association's page when the results don't link reports directly
+async fn search_report(client: &Client, search_url: &str, name: &str) -> Result<Option<String>> {
+    let html = client.get(search_url).send().await?.error_for_status()?.text().await?;
+    if let Some(pdf) = pdf_link(&html, search_url) {
+        return Ok(Some(pdf));
+    }
+    let page = {
+        let document = Html::parse_document(&html);
+        let link_selector = Selector::parse("a[href]").unwrap();
+        let wanted = name.to_lowercase();
+        document
+            .select(&link_selector)
+            .find(|a| a.text().collect::<String>().to_lowercase().contains(&wanted))
+            .and_then(|a| a.value().attr("href"))
+            .map(|href| absolute_url(href, &origin(search_url)))
+    };
+    let Some(page) = page else {
+        return Ok(None);
+    };
+    let html = client.get(&page).send().await?.error_for_status()?.text().await?;
+    Ok(pdf_link(&html, &page))
+}
+
+/// The first link to a PDF, preferring ones that look like an annual report
+fn pdf_link(html: &str, page_url: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let pdfs: Vec<(String, String)> = document
+        .select(&link_selector)
+        .filter_map(|a| {
+            let href = a.value().attr("href")?;
+            let path = href.split(['?', '#']).next().unwrap_or_default();
+            path.to_lowercase().ends_with(".pdf").then(|| (href.to_string(), a.text().collect::<String>()))
+        })
+        .collect();
+    let is_report = |(href, text): &&(String, String)| {
+        let label = format!("{} {}", href, text).to_lowercase();
+        label.contains("årsredovisning") || label.contains("arsredovisning")
+    };
+    pdfs.iter()
+        .find(is_report)
+        .or(pdfs.first())
+        .map(|(href, _)| absolute_url(href, &origin(page_url)))
+}
+
+/// "https://www.allabrf.se" of "https://www.allabrf.se/sok?q=x"
+fn origin(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(url) => url.origin().ascii_serialization(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Text of a PDF, columns kept apart by runs of spaces
+async fn pdf_text(pdf: &[u8], config: &BrfConfig) -> Result<String> {
+    let mut child = Command::new(&config.pdftotext)
+        .args(["-layout", "-enc", "UTF-8", "-", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", config.pdftotext))?;
+    let mut stdin = child.stdin.take().context("No stdin")?;
+    let pdf = pdf.to_vec();
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(&pdf).await;
+    });
+    let output = child.wait_with_output().await?;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            config.pdftotext,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Words of the units per-sqm figures are given in
+const PER_SQM: [&str; 4] = ["kvm", "m²", "m2", "kr/m"];
+/// Words a planned piece of work is announced with
+const PLANNED: [&str; 6] = ["planer", "kommande", "kommer att", "underhållsplan", "beslutat", "beslut om"];
+/// Kinds of maintenance worth knowing about before buying
+const WORKS: [&str; 13] = [
+    "stambyte", "stammar", "relining", "fasad", "tak", "fönster", "hiss", "balkong", "ventilation",
+    "dränering", "elstig", "renovering", "byte",
+];
+/// Report lines longer than this are running text rather than one item
+const MAX_RENOVATION_CHARS: usize = 300;
+
+/// Figures from the text of an annual report, as `pdftotext -layout` gives it
+///
+/// Debt and fees come from the multi-year overview ("flerårsöversikt"), a
+/// table with a row of years over rows of a label and one value per year.
+/// Planned works are lines mentioning both planning and a kind of work.
+pub fn parse_annual_report(text: &str, report_url: &str) -> BrfFinancials {
+    let mut years: Vec<i32> = Vec::new();
+    let mut year = None;
+    let mut debt_per_sqm = None;
+    let mut fee_history = Vec::new();
+    let mut planned_renovations: Vec<PlannedRenovation> = Vec::new();
+
+    for line in text.lines() {
+        let line = normalize(line);
+        let lower = line.to_lowercase();
+        let cells: Vec<&str> = lower.split("  ").map(str::trim).filter(|c| !c.is_empty()).collect();
+
+        // A row of years heads the columns below it
+        let header: Vec<i32> = cells.iter().filter_map(|c| year_of(c)).collect();
+        if header.len() >= 2 && header.len() + 1 >= cells.len() {
+            year = year.or(header.iter().max().copied());
+            years = header;
+            continue;
+        }
+
+        if let Some((label, values)) = cells.split_first() {
+            let values: Vec<f64> = values.iter().map_while(|cell| figure(cell)).collect();
+            let per_sqm = PER_SQM.iter().any(|unit| label.contains(unit));
+            if per_sqm && !values.is_empty() {
+                if debt_per_sqm.is_none() && ["skuld", "lån", "belåning"].iter().any(|w| label.contains(w)) {
+                    debt_per_sqm = values.first().copied();
+                }
+                if fee_history.is_empty() && label.contains("årsavgift") && !label.contains("andel") {
+                    fee_history = years
+                        .iter()
+                        .zip(&values)
+                        .map(|(year, fee)| FeeYear { year: *year, fee_per_sqm: *fee })
+                        .collect();
+                }
+            }
+        }
+
+        if year.is_none() && ["räkenskapsår", "verksamhetsår", "årsredovisning för"].iter().any(|w| lower.contains(w)) {
+            year = lower.split(|c: char| !c.is_ascii_digit()).filter_map(year_of).max();
+        }
+
+        let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).collect();
+        let is_work = WORKS.iter().any(|work| words.iter().any(|w| w.starts_with(work)));
+        if is_work && PLANNED.iter().any(|p| lower.contains(p)) && lower.len() <= MAX_RENOVATION_CHARS {
+            let text = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            let planned_year = lower
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(year_of)
+                .filter(|y| year.is_none_or(|report| *y >= report))
+                .min();
+            if !planned_renovations.iter().any(|r| r.text == text) {
+                planned_renovations.push(PlannedRenovation { year: planned_year, text });
+            }
+        }
+    }
+
+    BrfFinancials {
+        report_url: report_url.to_string(),
+        year,
+        debt_per_sqm,
+        fee_history,
+        planned_renovations,
+    }
+}
+
+/// A plausible financial year
+fn year_of(text: &str) -> Option<i32> {
+    let text = text.trim();
+    if text.len() != 4 || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok().filter(|y| (1950..=2100).contains(y))
+}
+
+/// A table value: "5 123", "650 kr" or "-1 200"
+fn figure(cell: &str) -> Option<f64> {
+    let (negative, cell) = match cell.strip_prefix(['-', '−']) {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, cell),
+    };
+    let (number, rest) = leading_number(cell)?;
+    let rest = rest.trim();
+    if !(rest.is_empty() || rest.starts_with("kr") || rest.starts_with("tkr")) {
+        return None;
+    }
+    Some(if negative { -number } else { number })
+}
@@ -0,0 +1,139 @@
+use crate::models::{Broadband, Property};
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{info, instrument, warn};
+
+const CACHE_NAME: &str = "broadband";
+const CACHE_MAX_AGE_DAYS: i64 = 90;
+
+/// Technology names as reported by operators, mapped to a common label
+const TECHNOLOGIES: [(&str, &str); 8] = [
+    ("fiber", "fiber"),
+    ("ftth", "fiber"),
+    ("fttb", "fiber"),
+    ("xdsl", "dsl"),
+    ("dsl", "dsl"),
+    ("kabel", "cable"),
+    ("coax", "cable"),
+    ("docsis", "cable"),
+];
+
+/// Broadband lookup endpoint
+///
+/// The national broadband map (PTS Bredbandskartan) has no stable public API,
+/// so the URL is a template: `{address}` and `{city}` are replaced with the
+/// URL-encoded listing address and city.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BroadbandConfig {
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLookup {
+    fetched_at: DateTime<Utc>,
+    broadband: Broadband,
+}
+
+/// Look up available broadband technologies at every property's address
+pub async fn enrich(properties: &mut [Property], config: &BroadbandConfig, store: &JsonStore) -> Result<()> {
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let mut cache: BTreeMap<String, CachedLookup> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let stale_before = Utc::now() - Duration::days(CACHE_MAX_AGE_DAYS);
+    let mut fetched = 0;
+
+    for property in properties.iter_mut().filter(|p| !p.address.is_empty()) {
+        let key = format!("{}, {}", property.address, property.location.city).to_lowercase();
+        if let Some(cached) = cache.get(&key).filter(|c| c.fetched_at > stale_before) {
+            property.broadband = Some(cached.broadband.clone());
+            continue;
+        }
+
+        match lookup(&client, config, property).await {
+            Ok(broadband) => {
+                fetched += 1;
+                property.broadband = Some(broadband.clone());
+                cache.insert(
+                    key,
+                    CachedLookup {
+                        fetched_at: Utc::now(),
+                        broadband,
+                    },
+                );
+            }
+            Err(e) => warn!("Failed to look up broadband for {}: {}", property.address, e),
+        }
+    }
+
+    if fetched > 0 {
+        info!("🌐 Looked up broadband for {} addresses", fetched);
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+#[instrument(name = "http", level = "debug", skip_all, fields(id = %property.id))]
+async fn lookup(client: &Client, config: &BroadbandConfig, property: &Property) -> Result<Broadband> {
+    let url = config
+        .url
+        .replace("{address}", &encode(&property.address))
+        .replace("{city}", &encode(&property.location.city));
+
+    let response = client.get(&url).send().await.context("Failed to send broadband request")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Broadband lookup returned status: {}", response.status());
+    }
+    let json: Value = response.json().await.context("Failed to parse broadband response")?;
+
+    let mut technologies = BTreeSet::new();
+    let mut max_down_mbps = None;
+    scan(&json, &mut technologies, &mut max_down_mbps);
+
+    Ok(Broadband {
+        fiber: technologies.contains("fiber"),
+        technologies: technologies.into_iter().collect(),
+        max_down_mbps,
+    })
+}
+
+/// Collect technology names and the highest download speed anywhere in the response
+fn scan(json: &Value, technologies: &mut BTreeSet<String>, max_down_mbps: &mut Option<u32>) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = key.to_lowercase();
+                if key.contains("down") || key.contains("speed") || key.contains("mbit") {
+                    if let Some(speed) = value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                        *max_down_mbps = Some(max_down_mbps.map_or(speed, |max| max.max(speed)));
+                    }
+                }
+                scan(value, technologies, max_down_mbps);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| scan(item, technologies, max_down_mbps)),
+        Value::String(text) => {
+            let text = text.to_lowercase();
+            if let Some((_, label)) = TECHNOLOGIES.iter().find(|(name, _)| text.contains(name)) {
+                technologies.insert(label.to_string());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Percent-encode a query value
+pub(crate) fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
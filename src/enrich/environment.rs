@@ -0,0 +1,146 @@
+use crate::geo::GeoPoint;
+use crate::models::Property;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+const CACHE_NAME: &str = "environment";
+
+/// Half side of the box queried around a property, in degrees (~20 m).
+/// Noise maps are drawn per facade, so a listing's own building is what counts.
+const QUERY_HALF_SIZE_DEG: f64 = 0.0002;
+
+/// A WFS layer to look up properties in, e.g. Stockholm stad's noise map or
+/// MSB's flood mapping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WfsLayer {
+    /// WFS endpoint, including any API key the provider requires
+    pub url: String,
+    /// Feature type to query, e.g. "od_gis:Buller_Vagtrafik"
+    pub type_name: String,
+    /// Attribute holding the value, e.g. the dB level; unused for flood layers
+    pub value_property: Option<String>,
+}
+
+/// Noise level and flood risk, cached per ~10 m coordinate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CachedLookup {
+    noise_db: Option<f64>,
+    /// Quiet spots have no noise features, so a missing level alone doesn't mean "not looked up"
+    noise_checked: bool,
+    flood_risk: Option<bool>,
+}
+
+/// Look up noise levels and flood risk for every property with coordinates
+pub async fn enrich(
+    properties: &mut [Property],
+    noise: Option<&WfsLayer>,
+    flood: Option<&WfsLayer>,
+    store: &JsonStore,
+) -> Result<()> {
+    if noise.is_none() && flood.is_none() {
+        return Ok(());
+    }
+
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let mut cache: BTreeMap<String, CachedLookup> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let mut fetched = 0;
+
+    for property in properties.iter_mut() {
+        let Some(point) = property.location.point() else {
+            continue;
+        };
+        let key = format!("{:.4},{:.4}", point.lat, point.lon);
+        let entry = cache.entry(key).or_default();
+
+        if let Some(layer) = noise.filter(|_| !entry.noise_checked) {
+            match features_at(&client, layer, &point).await {
+                Ok(features) => {
+                    fetched += 1;
+                    entry.noise_db = max_level(&features, layer.value_property.as_deref());
+                    entry.noise_checked = true;
+                }
+                Err(e) => warn!("Failed to look up noise level for {}: {}", property.address, e),
+            }
+        }
+        if let Some(layer) = flood.filter(|_| entry.flood_risk.is_none()) {
+            match features_at(&client, layer, &point).await {
+                Ok(features) => {
+                    fetched += 1;
+                    entry.flood_risk = Some(!features.is_empty());
+                }
+                Err(e) => warn!("Failed to look up flood risk for {}: {}", property.address, e),
+            }
+        }
+
+        property.noise_db = entry.noise_db;
+        property.flood_risk = entry.flood_risk;
+    }
+
+    if fetched > 0 {
+        info!("🔊 Made {} noise/flood lookups", fetched);
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+/// GeoJSON features of `layer` intersecting a small box around `point`
+async fn features_at(client: &Client, layer: &WfsLayer, point: &GeoPoint) -> Result<Vec<Value>> {
+    // WFS 2.0 uses latitude/longitude axis order for EPSG:4326
+    let bbox = format!(
+        "{},{},{},{},urn:ogc:def:crs:EPSG::4326",
+        point.lat - QUERY_HALF_SIZE_DEG,
+        point.lon - QUERY_HALF_SIZE_DEG * 2.0,
+        point.lat + QUERY_HALF_SIZE_DEG,
+        point.lon + QUERY_HALF_SIZE_DEG * 2.0
+    );
+
+    let response = client
+        .get(&layer.url)
+        .query(&[
+            ("service", "WFS"),
+            ("version", "2.0.0"),
+            ("request", "GetFeature"),
+            ("typeNames", layer.type_name.as_str()),
+            ("outputFormat", "application/json"),
+            ("bbox", bbox.as_str()),
+        ])
+        .send()
+        .await
+        .context("Failed to send WFS request")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("WFS returned status: {}", response.status());
+    }
+
+    let json: Value = response.json().await.context("Failed to parse WFS response")?;
+    Ok(json
+        .get("features")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Highest level among the features; values may be numbers or bands like "60-65"
+fn max_level(features: &[Value], property: Option<&str>) -> Option<f64> {
+    let property = property?;
+    features
+        .iter()
+        .filter_map(|feature| {
+            let value = feature.get("properties")?.get(property)?;
+            value.as_f64().or_else(|| {
+                value
+                    .as_str()?
+                    .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+                    .filter_map(|part| part.replace(',', ".").parse::<f64>().ok())
+                    .max_by(f64::total_cmp)
+            })
+        })
+        .max_by(f64::total_cmp)
+}
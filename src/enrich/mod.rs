@@ -0,0 +1,130 @@
+pub mod amenities;
+pub mod brf;
+pub mod broadband;
+pub mod environment;
+pub mod orientation;
+pub mod overpass;
+pub mod registry;
+pub mod schools;
+pub mod transit;
+
+use crate::models::Property;
+use crate::storage::JsonStore;
+use brf::BrfConfig;
+use broadband::BroadbandConfig;
+use environment::WfsLayer;
+use overpass::{OverpassClient, DEFAULT_OVERPASS_URL};
+use registry::RegistryConfig;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Which external data sources to enrich properties with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichConfig {
+    /// Overpass API endpoint used for OpenStreetMap lookups
+    pub overpass_url: String,
+    /// Look up the nearest tunnelbana/pendeltåg station
+    pub transit: bool,
+    /// Count groceries, gyms, preschools and parks nearby
+    pub amenities: bool,
+    /// Radius in meters for amenity counts
+    pub amenity_radius_m: f64,
+    /// Estimate balcony orientation from OpenStreetMap building footprints
+    pub orientation: bool,
+    /// Look up nearby grundskolor and their Skolverket statistics
+    pub schools: bool,
+    /// Municipality code to load schools for (0180 = Stockholm)
+    pub school_municipality: String,
+    /// Only report schools within this many meters
+    pub school_radius_m: f64,
+    /// Noise map layer; noise levels are looked up when set
+    pub noise: Option<WfsLayer>,
+    /// Flood risk layer; flood risk is looked up when set
+    pub flood: Option<WfsLayer>,
+    /// Broadband lookup; fiber availability is looked up when set
+    pub broadband: Option<BroadbandConfig>,
+    /// Housing association annual reports; read when set
+    pub brf: Option<BrfConfig>,
+    /// Companies registry lookup of housing associations by organisation number
+    pub registry: Option<RegistryConfig>,
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self {
+            overpass_url: DEFAULT_OVERPASS_URL.to_string(),
+            transit: true,
+            amenities: true,
+            amenity_radius_m: 500.0,
+            orientation: true,
+            schools: true,
+            school_municipality: "0180".to_string(),
+            school_radius_m: 1_000.0,
+            noise: None,
+            flood: None,
+            broadband: None,
+            brf: None,
+            registry: None,
+        }
+    }
+}
+
+/// Add data from external sources to scraped properties
+///
+/// Enrichments are best effort: a failing data source is logged and skipped.
+pub async fn enrich(properties: &mut [Property], config: &EnrichConfig, store: &JsonStore) {
+    // Address based lookups don't need coordinates
+    if let Some(broadband) = &config.broadband {
+        if let Err(e) = broadband::enrich(properties, broadband, store).await {
+            warn!("Failed to look up broadband: {}", e);
+        }
+    }
+    if let Some(brf) = &config.brf {
+        if let Err(e) = brf::enrich(properties, brf, store).await {
+            warn!("Failed to read housing association reports: {}", e);
+        }
+    }
+    // After the reports, whose lookup finds the association of API listings
+    if let Some(registry) = &config.registry {
+        if let Err(e) = registry::enrich(properties, registry, store).await {
+            warn!("Failed to look up housing associations: {}", e);
+        }
+    }
+
+    let located = properties.iter().filter(|p| p.location.point().is_some()).count();
+    if located == 0 {
+        return;
+    }
+    info!("🗺️  Enriching {} properties with coordinates...", located);
+
+    let overpass = OverpassClient::new(&config.overpass_url);
+
+    if config.transit {
+        if let Err(e) = transit::enrich(properties, &overpass, store).await {
+            warn!("Failed to look up nearest stations: {}", e);
+        }
+    }
+
+    if config.amenities {
+        if let Err(e) = amenities::enrich(properties, config.amenity_radius_m, &overpass, store).await {
+            warn!("Failed to look up nearby amenities: {}", e);
+        }
+    }
+
+    if config.orientation {
+        if let Err(e) = orientation::enrich(properties, &overpass, store).await {
+            warn!("Failed to estimate balcony orientation: {}", e);
+        }
+    }
+
+    if config.schools {
+        if let Err(e) = schools::enrich(properties, &config.school_municipality, config.school_radius_m, store).await {
+            warn!("Failed to look up nearby schools: {}", e);
+        }
+    }
+
+    if let Err(e) = environment::enrich(properties, config.noise.as_ref(), config.flood.as_ref(), store).await {
+        warn!("Failed to look up noise and flood data: {}", e);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::enrich::overpass::OverpassClient;
+use crate::geo::GeoPoint;
+use crate::models::{Amenity, BalconyOrientation, OrientationSource, Property};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+const CACHE_NAME: &str = "facades";
+
+/// Search radius for the listing's building footprint
+const BUILDING_SEARCH_RADIUS_M: f64 = 30.0;
+
+/// Facades facing this range of bearings get sun in the afternoon and evening
+const AFTERNOON_SUN: std::ops::RangeInclusive<f64> = 200.0..=290.0;
+
+const DIRECTIONS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+/// Directions as written in listings: "västerläge", "sydvästläge", "balkong mot söder"
+const LISTING_DIRECTIONS: [(&str, f64); 8] = [
+    ("sydväst", 225.0),
+    ("nordväst", 315.0),
+    ("sydost", 135.0),
+    ("nordost", 45.0),
+    ("söder", 180.0),
+    ("väster", 270.0),
+    ("öster", 90.0),
+    ("norr", 0.0),
+];
+
+/// Total facade length per compass sector, longest first; cached per building
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Facades {
+    /// (sector bearing, meters of facade)
+    sectors: Vec<(f64, f64)>,
+}
+
+/// Estimate which way the balcony faces for properties that have one
+///
+/// A direction stated in the listing ("balkong i västerläge") wins. Otherwise
+/// the building footprint's long facades are used, preferring one that gets
+/// afternoon sun - the balcony may be on either side, so this is a best guess.
+pub async fn enrich(properties: &mut [Property], overpass: &OverpassClient, store: &JsonStore) -> Result<()> {
+    let mut cache: BTreeMap<String, Facades> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let mut fetched = 0;
+
+    for property in properties.iter_mut().filter(|p| has_balcony(p)) {
+        if let Some(bearing) = stated_direction(property) {
+            property.balcony_orientation = Some(orientation(bearing, OrientationSource::Listing));
+            continue;
+        }
+
+        let Some(point) = property.location.point() else {
+            continue;
+        };
+        let key = format!("{:.4},{:.4}", point.lat, point.lon);
+        if !cache.contains_key(&key) {
+            match building_facades(&point, overpass).await {
+                Ok(facades) => {
+                    fetched += 1;
+                    cache.insert(key.clone(), facades);
+                }
+                Err(e) => {
+                    warn!("Failed to look up the building at {}: {}", property.address, e);
+                    continue;
+                }
+            }
+        }
+
+        // The two longest facade directions are the street and courtyard sides
+        let long_sides = &cache[&key].sectors[..cache[&key].sectors.len().min(2)];
+        let best = long_sides
+            .iter()
+            .find(|(bearing, _)| AFTERNOON_SUN.contains(bearing))
+            .or(long_sides.first());
+        property.balcony_orientation = best.map(|(bearing, _)| orientation(*bearing, OrientationSource::Footprint));
+    }
+
+    if fetched > 0 {
+        info!("🧭 Looked up {} building footprints", fetched);
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+fn has_balcony(property: &Property) -> bool {
+    let description = property.description.to_lowercase();
+    property.features.amenities.iter().any(Amenity::is_outdoor_space)
+        || description.contains("balkong")
+        || description.contains("terrass")
+}
+
+fn stated_direction(property: &Property) -> Option<f64> {
+    let text = format!("{} {}", property.description, property.features.other.join(" ")).to_lowercase();
+    let mentions = |word: &str| {
+        text.contains(&format!("{}läge", word)) || text.contains(&format!("mot {}", word))
+    };
+    LISTING_DIRECTIONS
+        .iter()
+        .find(|(word, _)| mentions(word))
+        .map(|(_, bearing)| *bearing)
+}
+
+fn orientation(bearing: f64, source: OrientationSource) -> BalconyOrientation {
+    let sector = ((bearing / 45.0).round() as usize) % DIRECTIONS.len();
+    BalconyOrientation {
+        bearing,
+        direction: DIRECTIONS[sector].to_string(),
+        afternoon_sun: AFTERNOON_SUN.contains(&bearing),
+        source,
+    }
+}
+
+/// Facade directions of the building containing (or closest to) `point`
+async fn building_facades(point: &GeoPoint, overpass: &OverpassClient) -> Result<Facades> {
+    let query = format!(
+        r#"[out:json][timeout:30];way["building"](around:{:.0},{},{});out geom;"#,
+        BUILDING_SEARCH_RADIUS_M, point.lat, point.lon
+    );
+    let buildings: Vec<Vec<GeoPoint>> = overpass
+        .query(&query)
+        .await?
+        .into_iter()
+        .map(|way| way.geometry)
+        .filter(|outline| outline.len() >= 4)
+        .collect();
+
+    let building = buildings.iter().find(|outline| contains(outline, point)).or_else(|| {
+        buildings.iter().min_by(|a, b| {
+            let distance = |outline: &Vec<GeoPoint>| {
+                outline.iter().map(|corner| corner.distance_m(point)).fold(f64::MAX, f64::min)
+            };
+            distance(a).total_cmp(&distance(b))
+        })
+    });
+
+    Ok(building.map(|outline| facades(outline)).unwrap_or_default())
+}
+
+/// Local planar coordinates in meters (x east, y north) around `origin`
+fn to_local(point: &GeoPoint, origin: &GeoPoint) -> (f64, f64) {
+    let x = (point.lon - origin.lon) * 111_320.0 * origin.lat.to_radians().cos();
+    let y = (point.lat - origin.lat) * 110_540.0;
+    (x, y)
+}
+
+/// Ray casting point-in-polygon test
+fn contains(outline: &[GeoPoint], point: &GeoPoint) -> bool {
+    let mut inside = false;
+    for pair in outline.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        if (a.lat > point.lat) != (b.lat > point.lat)
+            && point.lon < (b.lon - a.lon) * (point.lat - a.lat) / (b.lat - a.lat) + a.lon
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Sum facade lengths per compass sector from the outline's outward edge normals
+fn facades(outline: &[GeoPoint]) -> Facades {
+    let origin = outline[0];
+    let local: Vec<(f64, f64)> = outline.iter().map(|p| to_local(p, &origin)).collect();
+
+    // Shoelace formula: positive area means the outline runs counter-clockwise
+    let area: f64 = local.windows(2).map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1).sum();
+    let clockwise = area < 0.0;
+
+    let mut sectors = [0.0; 8];
+    for w in local.windows(2) {
+        let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+        // Rotate the edge a quarter turn away from the interior
+        let (nx, ny) = if clockwise { (-dy, dx) } else { (dy, -dx) };
+        let bearing = nx.atan2(ny).to_degrees().rem_euclid(360.0);
+        let sector = ((bearing / 45.0).round() as usize) % sectors.len();
+        sectors[sector] += (dx * dx + dy * dy).sqrt();
+    }
+
+    let mut sectors: Vec<(f64, f64)> = sectors
+        .iter()
+        .enumerate()
+        .filter(|(_, length)| **length > 0.0)
+        .map(|(i, length)| (i as f64 * 45.0, length.round()))
+        .collect();
+    sectors.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Facades { sectors }
+}
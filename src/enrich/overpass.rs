@@ -0,0 +1,82 @@
+use crate::geo::GeoPoint;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::instrument;
+
+pub const DEFAULT_OVERPASS_URL: &str = "https://overpass-api.de/api/interpreter";
+
+/// Minimal client for the OpenStreetMap Overpass API
+pub struct OverpassClient {
+    client: Client,
+    url: String,
+}
+
+/// A node, way or relation returned by an Overpass query
+#[derive(Debug, Clone, Deserialize)]
+pub struct Element {
+    pub id: i64,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Set for ways and relations queried with `out center`
+    pub center: Option<GeoPoint>,
+    /// Outline of ways queried with `out geom`
+    #[serde(default)]
+    pub geometry: Vec<GeoPoint>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    elements: Vec<Element>,
+}
+
+impl Element {
+    pub fn point(&self) -> Option<GeoPoint> {
+        match (self.lat, self.lon, self.center) {
+            (Some(lat), Some(lon), _) => Some(GeoPoint::new(lat, lon)),
+            (_, _, Some(center)) => Some(center),
+            _ => None,
+        }
+    }
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.get(key).map(String::as_str)
+    }
+}
+
+impl OverpassClient {
+    pub fn new(url: &str) -> Self {
+        let client = Client::builder()
+            .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            url: url.to_string(),
+        }
+    }
+
+    /// Run an Overpass QL query; it must request `[out:json]`
+    #[instrument(name = "http", level = "debug", skip_all, fields(url = %self.url))]
+    pub async fn query(&self, ql: &str) -> Result<Vec<Element>> {
+        let response = self
+            .client
+            .post(&self.url)
+            .form(&[("data", ql)])
+            .send()
+            .await
+            .context("Failed to send Overpass request")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Overpass returned status: {}", response.status());
+        }
+
+        let body: Response = response.json().await.context("Failed to parse Overpass response")?;
+        Ok(body.elements)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::enrich::broadband::encode;
+use crate::models::{AssociationRegistry, EconomicPlan, Property};
+use crate::secrets::Secret;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::{info, instrument, warn};
+
+const CACHE_NAME: &str = "registry";
+const CACHE_MAX_AGE_DAYS: i64 = 180;
+
+/// Companies registry lookup by organisation number
+///
+/// Bolagsverket's API needs an agreement and its response format varies with
+/// the product, so the URL is a template: `{org_number}` is replaced with the
+/// ten digits of the number. The JSON response is searched for a registration
+/// date and the economic plan, wherever they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    pub url: String,
+    /// Sent as a bearer token when set
+    #[serde(default)]
+    pub token: Option<Secret>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLookup {
+    fetched_at: DateTime<Utc>,
+    registry: AssociationRegistry,
+}
+
+/// Look up the registry data of every property's housing association
+pub async fn enrich(properties: &mut [Property], config: &RegistryConfig, store: &JsonStore) -> Result<()> {
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let token = config.token.as_ref().map(Secret::resolve).transpose()?;
+    let mut cache: BTreeMap<String, CachedLookup> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let stale_before = Utc::now() - Duration::days(CACHE_MAX_AGE_DAYS);
+    let mut fetched = 0;
+
+    for association in properties.iter_mut().filter_map(|p| p.association.as_mut()) {
+        let Some(org_number) = association.org_number.clone() else {
+            continue;
+        };
+        if let Some(cached) = cache.get(&org_number).filter(|c| c.fetched_at > stale_before) {
+            association.registry = Some(cached.registry.clone());
+            continue;
+        }
+
+        match lookup(&client, config, token.as_deref(), &org_number).await {
+            Ok(registry) => {
+                fetched += 1;
+                association.registry = Some(registry.clone());
+                cache.insert(
+                    org_number,
+                    CachedLookup {
+                        fetched_at: Utc::now(),
+                        registry,
+                    },
+                );
+            }
+            Err(e) => warn!("Failed to look up {} in the registry: {}", association.name, e),
+        }
+    }
+
+    if fetched > 0 {
+        info!("🏛️  Looked up {} housing associations in the registry", fetched);
+        store.save_cache(CACHE_NAME, &cache).await?;
+    }
+    Ok(())
+}
+
+#[instrument(name = "http", level = "debug", skip_all, fields(org_number = %org_number))]
+async fn lookup(
+    client: &Client,
+    config: &RegistryConfig,
+    token: Option<&str>,
+    org_number: &str,
+) -> Result<AssociationRegistry> {
+    let digits: String = org_number.chars().filter(char::is_ascii_digit).collect();
+    let url = config.url.replace("{org_number}", &encode(&digits));
+
+    let mut request = client.get(&url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.context("Failed to send registry request")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Registry lookup returned status: {}", response.status());
+    }
+    let json: Value = response.json().await.context("Failed to parse registry response")?;
+
+    let mut registry = AssociationRegistry::default();
+    scan(&json, &mut registry);
+    Ok(registry)
+}
+
+/// Find the registration year and economic plan anywhere in the response
+fn scan(json: &Value, registry: &mut AssociationRegistry) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key: String = key.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+                if key.contains("ekonomiskplan") || key.contains("economicplan") {
+                    if registry.economic_plan.is_none() {
+                        registry.economic_plan = economic_plan(value);
+                    }
+                } else if ["registreringsdatum", "registrationdate", "registrerad", "bildad"]
+                    .iter()
+                    .any(|name| key.contains(name))
+                    && registry.founded_year.is_none()
+                {
+                    registry.founded_year = value.as_str().and_then(year_of);
+                }
+                scan(value, registry);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| scan(item, registry)),
+        _ => {}
+    }
+}
+
+/// "1998-04-02" gives 1998
+fn year_of(date: &str) -> Option<i32> {
+    date.get(..4)?.parse().ok().filter(|y| (1850..=2100).contains(y))
+}
+
+/// A flag, a registration date or a status text
+fn economic_plan(value: &Value) -> Option<EconomicPlan> {
+    let registered = match value {
+        Value::Bool(registered) => *registered,
+        Value::Null => false,
+        Value::String(text) => {
+            let text = text.to_lowercase();
+            year_of(&text).is_some() || (text.contains("registrerad") && !text.contains("ej") && !text.contains("inte"))
+        }
+        Value::Object(_) | Value::Array(_) | Value::Number(_) => return None,
+    };
+    Some(if registered { EconomicPlan::Registered } else { EconomicPlan::Missing })
+}
@@ -0,0 +1,217 @@
+use crate::geo::GeoPoint;
+use crate::models::{NearbySchool, Property};
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Skolverket's open API for school units and their statistics
+const SKOLVERKET_API: &str = "https://api.skolverket.se/planned-educations/v3";
+const ACCEPT: &str = "application/vnd.skolverket.plannededucations.api.v3.hal+json";
+
+const SCHOOLS_CACHE: &str = "schools";
+const STATS_CACHE: &str = "school_stats";
+const SCHOOLS_MAX_AGE_DAYS: i64 = 30;
+/// Statistics are published yearly
+const STATS_MAX_AGE_DAYS: i64 = 180;
+
+/// Nearby schools to report per property
+const SCHOOLS_PER_PROPERTY: usize = 3;
+
+/// A grundskola unit from Skolverket's register
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct School {
+    code: String,
+    name: String,
+    point: GeoPoint,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SchoolCache {
+    fetched_at: DateTime<Utc>,
+    schools: Vec<School>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SchoolStats {
+    fetched_at: Option<DateTime<Utc>>,
+    merit_rating: Option<f64>,
+    certified_teachers_pct: Option<f64>,
+    students_per_teacher: Option<f64>,
+}
+
+/// Attach the closest grundskolor, with their latest statistics, to every
+/// property with coordinates
+pub async fn enrich(
+    properties: &mut [Property],
+    municipality: &str,
+    radius_m: f64,
+    store: &JsonStore,
+) -> Result<()> {
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let schools = load_schools(&client, municipality, store).await?;
+    let mut stats: BTreeMap<String, SchoolStats> = store.load_cache(STATS_CACHE).await.unwrap_or_default();
+    let stale_before = Utc::now() - Duration::days(STATS_MAX_AGE_DAYS);
+    let mut fetched = 0;
+
+    for property in properties.iter_mut() {
+        let Some(point) = property.location.point() else {
+            continue;
+        };
+
+        let mut nearby: Vec<(&School, f64)> = schools
+            .iter()
+            .map(|school| (school, point.distance_m(&school.point)))
+            .filter(|(_, distance)| *distance <= radius_m)
+            .collect();
+        nearby.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        nearby.truncate(SCHOOLS_PER_PROPERTY);
+
+        let mut result = Vec::with_capacity(nearby.len());
+        for (school, distance) in nearby {
+            let fresh = stats
+                .get(&school.code)
+                .filter(|s| s.fetched_at.is_some_and(|at| at > stale_before))
+                .cloned();
+            let school_stats = match fresh {
+                Some(s) => s,
+                None => match fetch_stats(&client, &school.code).await {
+                    Ok(s) => {
+                        fetched += 1;
+                        stats.insert(school.code.clone(), s.clone());
+                        s
+                    }
+                    Err(e) => {
+                        warn!("Failed to fetch statistics for {}: {}", school.name, e);
+                        SchoolStats::default()
+                    }
+                },
+            };
+
+            result.push(NearbySchool {
+                code: school.code.clone(),
+                name: school.name.clone(),
+                distance_m: distance.round(),
+                merit_rating: school_stats.merit_rating,
+                certified_teachers_pct: school_stats.certified_teachers_pct,
+                students_per_teacher: school_stats.students_per_teacher,
+            });
+        }
+        property.schools = result;
+    }
+
+    if fetched > 0 {
+        info!("🏫 Fetched statistics for {} schools", fetched);
+        store.save_cache(STATS_CACHE, &stats).await?;
+    }
+    Ok(())
+}
+
+async fn get_json(client: &Client, url: &str) -> Result<Value> {
+    let response = client
+        .get(url)
+        .header("Accept", ACCEPT)
+        .send()
+        .await
+        .context("Failed to send Skolverket request")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Skolverket returned status: {}", response.status());
+    }
+    response.json().await.context("Failed to parse Skolverket response")
+}
+
+/// Grundskolor in the municipality, from the cache when fresh
+async fn load_schools(client: &Client, municipality: &str, store: &JsonStore) -> Result<Vec<School>> {
+    let cache_name = format!("{}_{}", SCHOOLS_CACHE, municipality);
+    if let Some(cache) = store.load_cache::<SchoolCache>(&cache_name).await {
+        if Utc::now() - cache.fetched_at < Duration::days(SCHOOLS_MAX_AGE_DAYS) {
+            return Ok(cache.schools);
+        }
+    }
+
+    let mut schools = Vec::new();
+    let mut page = 0;
+    loop {
+        let url = format!(
+            "{}/compact-school-units?municipalityCode={}&typeOfSchooling=gr&coordinateSystemType=WGS84&size=100&page={}",
+            SKOLVERKET_API, municipality, page
+        );
+        let json = get_json(client, &url).await?;
+        let body = json.get("body").unwrap_or(&json);
+
+        let units = body
+            .pointer("/_embedded/listedSchoolUnits")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        schools.extend(units.iter().filter_map(parse_school));
+
+        let total_pages = body.pointer("/page/totalPages").and_then(Value::as_u64).unwrap_or(0);
+        page += 1;
+        if units.is_empty() || page >= total_pages {
+            break;
+        }
+    }
+    info!("🏫 Fetched {} schools from Skolverket", schools.len());
+
+    let cache = SchoolCache {
+        fetched_at: Utc::now(),
+        schools,
+    };
+    store.save_cache(&cache_name, &cache).await?;
+    Ok(cache.schools)
+}
+
+fn parse_school(unit: &Value) -> Option<School> {
+    let text = |key: &str| unit.get(key).and_then(Value::as_str).map(str::to_string);
+    // Coordinate field names have varied between API versions
+    let coordinate = |needle: &str| {
+        unit.as_object()?.iter().find_map(|(key, value)| {
+            let key = key.to_lowercase();
+            (key.contains("wgs84") && key.contains(needle))
+                .then(|| value.as_f64().or_else(|| value.as_str()?.replace(',', ".").parse().ok()))
+                .flatten()
+        })
+    };
+
+    Some(School {
+        code: text("code").or_else(|| text("schoolUnitCode"))?,
+        name: text("name")?,
+        point: GeoPoint::new(coordinate("lat")?, coordinate("long")?),
+    })
+}
+
+async fn fetch_stats(client: &Client, code: &str) -> Result<SchoolStats> {
+    let url = format!("{}/school-units/{}/statistics/gr", SKOLVERKET_API, code);
+    let json = get_json(client, &url).await?;
+    let body = json.get("body").unwrap_or(&json);
+
+    Ok(SchoolStats {
+        fetched_at: Some(Utc::now()),
+        merit_rating: latest_value(body, "averageGradesMeritRating9thGrade"),
+        certified_teachers_pct: latest_value(body, "certifiedTeachersQuota"),
+        students_per_teacher: latest_value(body, "studentsPerTeacherQuota"),
+    })
+}
+
+/// Most recent reported value of a statistic; values use Swedish decimal commas
+/// and are missing when too few students took part
+fn latest_value(body: &Value, key: &str) -> Option<f64> {
+    body.get(key)?.as_array()?.iter().find_map(|entry| {
+        if entry.get("valueType").and_then(Value::as_str).is_some_and(|t| t != "EXISTS") {
+            return None;
+        }
+        let value = entry.get("value")?;
+        value
+            .as_f64()
+            .or_else(|| value.as_str()?.replace(',', ".").trim().parse().ok())
+    })
+}
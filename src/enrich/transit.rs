@@ -0,0 +1,149 @@
+use crate::enrich::overpass::{Element, OverpassClient};
+use crate::geo::GeoPoint;
+use crate::models::{NearestStation, Property, StationKind};
+use crate::storage::JsonStore;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Stations rarely change; refetch the list monthly
+const CACHE_MAX_AGE_DAYS: i64 = 30;
+/// Not "stations", which held every rail station as pendeltåg
+const CACHE_NAME: &str = "transit_stations";
+
+/// Words in a station's `network` or `operator` tag marking a pendeltåg stop,
+/// e.g. "SL Pendeltåg" or "Stockholms pendeltåg"
+const PENDELTAG_TAGS: &[&str] = &["pendeltåg", "pendeltag", "commuter"];
+
+/// Stockholm county as (south, west, north, east)
+const STOCKHOLM_BBOX: (f64, f64, f64, f64) = (58.75, 17.25, 60.20, 19.10);
+
+/// Streets rarely run straight to the station; scale straight-line distance
+/// to approximate the walk
+const WALKING_DETOUR_FACTOR: f64 = 1.3;
+
+/// A tunnelbana or pendeltåg station
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Station {
+    pub name: String,
+    pub kind: StationKind,
+    pub point: GeoPoint,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StationCache {
+    fetched_at: DateTime<Utc>,
+    stations: Vec<Station>,
+}
+
+/// Set `nearest_station` on every property with coordinates
+pub async fn enrich(properties: &mut [Property], overpass: &OverpassClient, store: &JsonStore) -> Result<()> {
+    let stations = load_stations(overpass, store).await?;
+
+    for property in properties.iter_mut() {
+        if let Some(point) = property.location.point() {
+            property.nearest_station = nearest_station(&point, &stations);
+        }
+    }
+    Ok(())
+}
+
+/// Stations from the local cache, refreshed from OpenStreetMap when stale
+pub async fn load_stations(overpass: &OverpassClient, store: &JsonStore) -> Result<Vec<Station>> {
+    if let Some(cache) = store.load_cache::<StationCache>(CACHE_NAME).await {
+        if Utc::now() - cache.fetched_at < Duration::days(CACHE_MAX_AGE_DAYS) {
+            return Ok(cache.stations);
+        }
+    }
+
+    let (south, west, north, east) = STOCKHOLM_BBOX;
+    let query = format!(
+        r#"[out:json][timeout:60];node["railway"="station"]({},{},{},{});out body;"#,
+        south, west, north, east
+    );
+    let stations: Vec<Station> = overpass.query(&query).await?.iter().filter_map(station).collect();
+    info!("🚇 Fetched {} stations from OpenStreetMap", stations.len());
+
+    let cache = StationCache {
+        fetched_at: Utc::now(),
+        stations,
+    };
+    store.save_cache(CACHE_NAME, &cache).await?;
+    Ok(cache.stations)
+}
+
+/// Classify an OSM railway station; trams, light rail, heritage lines and
+/// rail stations pendeltåg doesn't serve (regional and long-distance only,
+/// Roslagsbanan, Saltsjöbanan) are skipped
+fn station(element: &Element) -> Option<Station> {
+    let kind = match element.tag("station") {
+        Some("subway") => StationKind::Tunnelbana,
+        Some("light_rail") | Some("tram") | Some("monorail") | Some("funicular") => return None,
+        _ if element.tag("railway:historic").is_some() || element.tag("usage") == Some("tourism") => return None,
+        _ if is_pendeltag(element) => StationKind::Pendeltag,
+        _ => return None,
+    };
+
+    Some(Station {
+        name: element.tag("name")?.to_string(),
+        kind,
+        point: element.point()?,
+    })
+}
+
+fn is_pendeltag(element: &Element) -> bool {
+    ["network", "operator"].iter().filter_map(|key| element.tag(key)).any(|value| {
+        let value = value.to_lowercase();
+        PENDELTAG_TAGS.iter().any(|word| value.contains(word))
+    })
+}
+
+pub fn nearest_station(point: &GeoPoint, stations: &[Station]) -> Option<NearestStation> {
+    stations
+        .iter()
+        .map(|station| (station, point.distance_m(&station.point)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(station, distance)| NearestStation {
+            name: station.name.clone(),
+            kind: station.kind,
+            distance_m: distance.round(),
+            walking_m: (distance * WALKING_DETOUR_FACTOR).round(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(tags: &[(&str, &str)]) -> Element {
+        let tags: serde_json::Map<String, serde_json::Value> =
+            tags.iter().map(|(k, v)| (k.to_string(), v.to_string().into())).collect();
+        serde_json::from_value(serde_json::json!({ "id": 1, "lat": 59.3, "lon": 18.0, "tags": tags })).unwrap()
+    }
+
+    fn kind(tags: &[(&str, &str)]) -> Option<StationKind> {
+        station(&element(tags)).map(|station| station.kind)
+    }
+
+    #[test]
+    fn stations() {
+        assert_eq!(
+            kind(&[("name", "Slussen"), ("station", "subway"), ("network", "SL")]),
+            Some(StationKind::Tunnelbana)
+        );
+        assert_eq!(
+            kind(&[("name", "Årstaberg"), ("network", "SL Pendeltåg"), ("operator", "MTR Pendeltågen")]),
+            Some(StationKind::Pendeltag)
+        );
+        assert_eq!(
+            kind(&[("name", "Flemingsberg"), ("operator", "Stockholms pendeltåg")]),
+            Some(StationKind::Pendeltag)
+        );
+        // Regional and long-distance only
+        assert_eq!(kind(&[("name", "Arlanda C"), ("operator", "SJ")]), None);
+        assert_eq!(kind(&[("name", "Djursholms Ösby"), ("network", "SL"), ("line", "Roslagsbanan")]), None);
+        assert_eq!(kind(&[("name", "Saltsjöbaden"), ("network", "SL"), ("operator", "Saltsjöbanan")]), None);
+        assert_eq!(kind(&[("name", "Gärdet"), ("station", "tram"), ("network", "SL Pendeltåg")]), None);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::events::{EventSink, ListingEvent};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rskafka::client::partition::{Compression, UnknownTopicHandling};
+use rskafka::client::ClientBuilder;
+use rskafka::record::Record;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Kafka topic receiving each event keyed by listing ID
+pub struct KafkaSink {
+    brokers: Vec<String>,
+    topic: String,
+}
+
+impl KafkaSink {
+    pub fn new(brokers: &[String], topic: &str) -> Self {
+        Self {
+            brokers: brokers.to_vec(),
+            topic: topic.to_string(),
+        }
+    }
+}
+
+/// The partition of `key` among `partitions`, stable across runs
+fn partition_of(key: &str, partitions: &[i32]) -> i32 {
+    let hash = Sha256::digest(key.as_bytes());
+    let hash = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+    partitions[hash as usize % partitions.len()]
+}
+
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, events: &[ListingEvent]) -> Result<()> {
+        let client = ClientBuilder::new(self.brokers.clone())
+            .build()
+            .await
+            .context("Failed to connect to the Kafka brokers")?;
+        let partitions: Vec<i32> = client
+            .list_topics()
+            .await
+            .context("Failed to list Kafka topics")?
+            .into_iter()
+            .find(|topic| topic.name == self.topic)
+            .with_context(|| format!("Kafka topic {} doesn't exist", self.topic))?
+            .partitions
+            .into_iter()
+            .collect();
+        if partitions.is_empty() {
+            anyhow::bail!("Kafka topic {} has no partitions", self.topic);
+        }
+
+        let mut batches: HashMap<i32, Vec<Record>> = HashMap::new();
+        for event in events {
+            let record = Record {
+                key: Some(event.id.clone().into_bytes()),
+                value: Some(serde_json::to_vec(event)?),
+                headers: BTreeMap::from([("type".to_string(), event.kind.to_string().into_bytes())]),
+                timestamp: event.occurred_at,
+            };
+            batches.entry(partition_of(&event.id, &partitions)).or_default().push(record);
+        }
+        for (partition, records) in batches {
+            client
+                .partition_client(self.topic.as_str(), partition, UnknownTopicHandling::Retry)
+                .await
+                .with_context(|| format!("Failed to reach partition {} of {}", partition, self.topic))?
+                .produce(records, Compression::NoCompression)
+                .await
+                .with_context(|| format!("Failed to produce to partition {} of {}", partition, self.topic))?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "kafka"
+    }
+}
@@ -0,0 +1,41 @@
+use crate::events::{EventSink, ListingEvent};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+/// NATS server receiving each event on `<subject>.<type>`
+pub struct NatsSink {
+    url: String,
+    subject: String,
+}
+
+impl NatsSink {
+    pub fn new(url: &str, subject: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            subject: subject.trim_end_matches('.').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, events: &[ListingEvent]) -> Result<()> {
+        let client = async_nats::connect(self.url.as_str())
+            .await
+            .context("Failed to connect to the NATS server")?;
+        for event in events {
+            let subject = format!("{}.{}", self.subject, event.kind);
+            client
+                .publish(subject.clone(), serde_json::to_vec(event)?.into())
+                .await
+                .with_context(|| format!("Failed to publish to {}", subject))?;
+        }
+        // Publishing only queues; flushing sends
+        client.flush().await.context("Failed to flush events to the NATS server")?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "nats"
+    }
+}
@@ -0,0 +1,248 @@
+use crate::export::ExportRecord;
+use crate::models::ListingStatus;
+use crate::secrets::Secret;
+use crate::storage::History;
+use anyhow::{Context, Result};
+use chrono::SecondsFormat;
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const AIRTABLE_API: &str = "https://api.airtable.com/v0";
+/// Records per request, Airtable's maximum
+const BATCH: usize = 10;
+/// Airtable allows 5 requests a second per base
+const REQUEST_INTERVAL: Duration = Duration::from_millis(220);
+/// How long Airtable wants clients to back off after a 429
+const RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+const MAX_ATTEMPTS: u32 = 3;
+
+/// An Airtable base kept in sync with the listings and their price history
+///
+/// Listings are upserted into `table` on their ID field, and with
+/// `history_table` set every observed price and bid goes in there too, one
+/// record per listing and observation. Values are sent with typecast on, so
+/// select options are created as needed and a linked record field can hold the
+/// listing ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirtableConfig {
+    /// The app... ID in the base's URL
+    pub base_id: Option<String>,
+    /// Personal access token with data.records:write on the base
+    pub token: Option<Secret>,
+    pub table: String,
+    pub history_table: Option<String>,
+    /// Airtable field names, by the name of the value (see [`FIELDS`]);
+    /// unlisted values use the default name, and an empty name skips one
+    pub fields: BTreeMap<String, String>,
+    /// Sync after every `scout scrape`, not just with `scout export --format airtable`
+    pub sync_after_scrape: bool,
+}
+
+impl Default for AirtableConfig {
+    fn default() -> Self {
+        Self {
+            base_id: None,
+            token: None,
+            table: "Listings".to_string(),
+            history_table: None,
+            fields: BTreeMap::new(),
+            sync_after_scrape: false,
+        }
+    }
+}
+
+/// Values written and their default field names
+pub const FIELDS: [(&str, &str); 16] = [
+    ("id", "ID"),
+    ("address", "Address"),
+    ("area", "Area"),
+    ("price", "Price"),
+    ("rooms", "Rooms"),
+    ("sqm", "Sqm"),
+    ("monthly_fee", "Monthly fee"),
+    ("price_per_sqm", "Price/m²"),
+    ("listing_status", "Listing status"),
+    ("status", "Status"),
+    ("score", "Score"),
+    ("url", "URL"),
+    // Price history table
+    ("key", "Key"),
+    ("observed_at", "Observed at"),
+    ("current_bid", "Current bid"),
+    ("bidding", "Bidding"),
+];
+
+/// What a sync wrote
+#[derive(Debug, Default)]
+pub struct AirtableSyncResult {
+    pub listings: usize,
+    pub observations: usize,
+}
+
+/// Upsert `records`, and their history if configured, into the base
+pub async fn sync(records: &[ExportRecord<'_>], history: &History, config: &AirtableConfig) -> Result<AirtableSyncResult> {
+    let base_id = config.base_id.as_deref().context("Set [airtable] base_id to sync to Airtable")?;
+    let token = config.token.as_ref().context("Set [airtable] token to a personal access token")?;
+    AirtableSync::new(base_id, &token.resolve()?, config)
+        .sync(records, history)
+        .await
+}
+
+/// Client for one Airtable base
+pub struct AirtableSync {
+    client: Client,
+    base_url: String,
+    base_id: String,
+    token: String,
+    table: String,
+    history_table: Option<String>,
+    fields: BTreeMap<String, String>,
+}
+
+impl AirtableSync {
+    pub fn new(base_id: &str, token: &str, config: &AirtableConfig) -> Self {
+        let mut fields: BTreeMap<String, String> =
+            FIELDS.iter().map(|(key, name)| (key.to_string(), name.to_string())).collect();
+        fields.extend(config.fields.clone());
+        Self {
+            client: Client::new(),
+            base_url: AIRTABLE_API.to_string(),
+            base_id: base_id.to_string(),
+            token: token.to_string(),
+            table: config.table.clone(),
+            history_table: config.history_table.clone(),
+            fields,
+        }
+    }
+
+    /// Talk to `base_url` instead of api.airtable.com
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The Airtable field of a value, unless mapped to nothing
+    fn field(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str).filter(|name| !name.is_empty())
+    }
+
+    /// Fields of one record, leaving out unmapped values
+    fn fields(&self, values: Vec<(&str, Value)>) -> Map<String, Value> {
+        values
+            .into_iter()
+            .filter_map(|(key, value)| Some((self.field(key)?.to_string(), value)))
+            .collect()
+    }
+
+    /// Upsert `rows` into `table` on `merge_on`, ten at a time
+    #[instrument(name = "airtable", level = "debug", skip_all, fields(table = table, rows = rows.len()))]
+    async fn upsert(&self, table: &str, merge_on: &str, rows: Vec<Map<String, Value>>) -> Result<()> {
+        let mut url = Url::parse(&self.base_url).with_context(|| format!("Invalid Airtable URL {}", self.base_url))?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("Invalid Airtable URL {}", self.base_url))?
+            .pop_if_empty()
+            .extend([self.base_id.as_str(), table]);
+        for batch in rows.chunks(BATCH) {
+            let body = json!({
+                "performUpsert": { "fieldsToMergeOn": [merge_on] },
+                "typecast": true,
+                "records": batch.iter().map(|fields| json!({ "fields": fields })).collect::<Vec<_>>(),
+            });
+            let mut attempt = 1;
+            loop {
+                let response = self
+                    .client
+                    .patch(url.clone())
+                    .bearer_auth(&self.token)
+                    .json(&body)
+                    .send()
+                    .await
+                    .context("Failed to reach Airtable")?;
+                let status = response.status();
+                if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_ATTEMPTS {
+                    debug!("Airtable rate limited, retrying in {}s", RATE_LIMIT_WAIT.as_secs());
+                    tokio::time::sleep(RATE_LIMIT_WAIT).await;
+                    attempt += 1;
+                    continue;
+                }
+                if !status.is_success() {
+                    let body: Value = response.json().await.unwrap_or_default();
+                    let message = body["error"]["message"].as_str().unwrap_or_default();
+                    anyhow::bail!("Airtable returned status {} for table {}: {}", status, table, message);
+                }
+                break;
+            }
+            tokio::time::sleep(REQUEST_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    pub async fn sync(&self, records: &[ExportRecord<'_>], history: &History) -> Result<AirtableSyncResult> {
+        let id_field = self.field("id").context("The id value needs an Airtable field to match listings on")?;
+        let listings: Vec<_> = records.iter().map(|record| self.fields(listing_values(record))).collect();
+        let mut result = AirtableSyncResult {
+            listings: listings.len(),
+            observations: 0,
+        };
+        self.upsert(&self.table, id_field, listings).await?;
+
+        let Some(history_table) = &self.history_table else {
+            return Ok(result);
+        };
+        let key_field = self
+            .field("key")
+            .context("The key value needs an Airtable field to match observations on")?;
+        let observations: Vec<_> = records
+            .iter()
+            .filter_map(|record| Some((&record.property.id, history.get(&record.property.id)?)))
+            .flat_map(|(id, entry)| {
+                entry.observations.iter().map(move |observation| {
+                    let observed_at = observation.observed_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+                    vec![
+                        ("key", json!(format!("{}@{}", id, observed_at))),
+                        ("id", json!(id)),
+                        ("observed_at", json!(observed_at)),
+                        ("price", json!(observation.price.map(|price| price.major()))),
+                        ("current_bid", json!(observation.current_bid.map(|bid| bid.major()))),
+                        ("bidding", json!(observation.bidding_in_progress)),
+                    ]
+                })
+            })
+            .map(|values| self.fields(values))
+            .collect();
+        result.observations = observations.len();
+        self.upsert(history_table, key_field, observations).await?;
+        Ok(result)
+    }
+}
+
+fn listing_values<'a>(record: &ExportRecord<'_>) -> Vec<(&'a str, Value)> {
+    let property = record.property;
+    let listing = if property.bidding_in_progress {
+        "Bidding"
+    } else {
+        match property.listing_status {
+            ListingStatus::ForSale => "For sale",
+            ListingStatus::ComingSoon => "Coming soon",
+        }
+    };
+    vec![
+        ("id", json!(property.id)),
+        ("address", json!(property.address)),
+        ("area", json!(property.location.area)),
+        ("price", json!(property.price.map(|price| price.major()))),
+        ("rooms", json!(property.rooms)),
+        ("sqm", json!(property.sqm)),
+        ("monthly_fee", json!(property.monthly_fee.map(|fee| fee.major()))),
+        ("price_per_sqm", json!(record.metrics.price_per_sqm.map(f64::round))),
+        ("listing_status", json!(listing)),
+        ("status", json!(record.status().to_string())),
+        ("score", json!((record.score.total * 100.0).round() / 100.0)),
+        ("url", json!(property.url)),
+    ]
+}
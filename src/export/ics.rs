@@ -0,0 +1,89 @@
+use crate::export::ExportRecord;
+use chrono::{DateTime, Duration, Utc};
+
+/// Assumed length of a viewing; Booli usually lists 30–60 minute slots
+const VIEWING_DURATION_MINUTES: i64 = 45;
+
+/// Render an iCalendar (.ics) file with one event per upcoming viewing
+pub fn viewings_calendar(records: &[ExportRecord<'_>]) -> String {
+    let now = Utc::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//housing-scout//viewings//SV".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Visningar".to_string(),
+    ];
+
+    for record in records {
+        let property = record.property;
+        for start in property.viewings.iter().filter(|start| **start >= now) {
+            let end = *start + Duration::minutes(VIEWING_DURATION_MINUTES);
+
+            let mut location = property.address.clone();
+            if let Some(area) = &property.location.area {
+                location.push_str(&format!(", {}", area));
+            }
+            location.push_str(&format!(", {}", property.location.city));
+
+            let mut description = property.summary();
+            if let Some(per_sqm) = record.metrics.price_per_sqm {
+                description.push_str(&format!(", {:.0} kr/kvm", per_sqm));
+            }
+            if let Some(cost) = record.metrics.monthly_cost {
+                description.push_str(&format!("\nBoendekostnad ca {:.0} kr/mån", cost));
+            }
+            description.push_str(&format!("\n{}", property.url));
+
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}-{}@housing-scout", property.id, start.timestamp()));
+            lines.push(format!("DTSTAMP:{}", format_utc(now)));
+            lines.push(format!("DTSTART:{}", format_utc(*start)));
+            lines.push(format!("DTEND:{}", format_utc(end)));
+            lines.push(format!("SUMMARY:{}", escape_text(&format!("Visning: {}", property.address))));
+            lines.push(format!("LOCATION:{}", escape_text(&location)));
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+            lines.push(format!("URL:{}", property.url));
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape TEXT values per RFC 5545 section 3.3.11
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold content lines longer than 75 octets, without splitting UTF-8 characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space of a continuation line counts towards its length
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+
+    folded
+}
@@ -0,0 +1,144 @@
+pub mod airtable;
+pub mod ics;
+pub mod notion;
+pub mod report;
+pub mod select;
+pub mod sheets;
+pub mod site;
+
+pub use airtable::AirtableConfig;
+pub use ics::viewings_calendar;
+pub use notion::NotionConfig;
+pub use report::property_report;
+pub use select::{filter_records, sort_records};
+pub use sheets::SheetsConfig;
+pub use site::write_site;
+
+use crate::anomaly::{Anomaly, PriceBaseline};
+use crate::config::Config;
+use crate::currency::{load_rates, mixed_currencies, ExchangeRates, SekEquivalent};
+use crate::finance::MonthlyCost;
+use crate::metrics::PropertyMetrics;
+use crate::models::Property;
+use crate::prediction::{PriceModel, PricePrediction};
+use crate::scoring::{score_all, Score};
+use crate::scripts::Scripts;
+use crate::storage::{on_market_since, Annotation, Annotations, History, JsonStore, Status};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// A property together with everything derived from it, as written by exporters
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ExportRecord<'a> {
+    #[serde(flatten)]
+    pub property: &'a Property,
+    pub metrics: PropertyMetrics,
+    /// Price and fee in SEK, set when the exported listings mix currencies
+    pub sek: Option<SekEquivalent>,
+    /// Mortgage and monthly cost breakdown at the listed price
+    pub finance: Option<MonthlyCost>,
+    /// Composite score, relative to the other properties exported with it
+    pub score: Score,
+    /// Set when the listing is priced well below comparable listings
+    pub anomaly: Option<Anomaly>,
+    /// Expected sale price, when a price model could be trained
+    pub prediction: Option<PricePrediction>,
+    /// The user's pipeline status, favorite/hidden/visited marks, rating and notes
+    pub annotation: Option<Annotation>,
+    /// When the listing went on the market, see [`on_market_since`]
+    pub listed_since: DateTime<Utc>,
+    /// Whole days since `listed_since`
+    pub days_on_market: i64,
+}
+
+impl ExportRecord<'_> {
+    pub fn status(&self) -> Status {
+        self.annotation.as_ref().map(|a| a.status).unwrap_or_default()
+    }
+}
+
+/// Stored data that records are compared against, rather than derived from
+/// the exported properties themselves
+#[derive(Default)]
+pub struct RecordContext {
+    /// Cohort prices from every stored property, for spotting under-priced listings
+    pub baseline: PriceBaseline,
+    /// Sale price model trained on stored sold listings
+    pub price_model: Option<PriceModel>,
+    pub annotations: Annotations,
+    /// Exchange rates, loaded only for result sets that mix currencies
+    pub rates: Option<ExchangeRates>,
+    /// When each listing was first seen, for its time on the market
+    pub history: History,
+    /// The user's script, for its `score` hook
+    pub scripts: Scripts,
+}
+
+impl RecordContext {
+    /// Build the context from everything in `store`, for exporting `properties`
+    pub async fn load(store: &JsonStore, config: &Config, properties: &[Property]) -> Result<Self> {
+        let baseline = PriceBaseline::from_properties(&store.load_all_properties().await?);
+        let price_model = PriceModel::train(&store.load_sold().await?, &config.prediction);
+        if let Some(model) = &price_model {
+            info!("📈 Trained price model on {} sold listings", model.training_rows());
+        }
+        let rates = if mixed_currencies(properties) {
+            match load_rates(store, &config.currency).await {
+                Ok(rates) => Some(rates),
+                Err(e) => {
+                    warn!("No exchange rates, listings in other currencies won't compare: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Ok(Self {
+            baseline,
+            price_model,
+            annotations: store.load_annotations().await?,
+            rates,
+            history: store.load_history().await?,
+            scripts: Scripts::load(&config.scripts)?,
+        })
+    }
+}
+
+/// Build export records for a set of properties
+pub fn records<'a>(
+    properties: &'a [Property],
+    config: &Config,
+    context: &RecordContext,
+) -> Vec<ExportRecord<'a>> {
+    let scores = score_all(properties, &config.scoring);
+    let now = Utc::now();
+
+    properties
+        .iter()
+        .zip(scores)
+        .map(|(property, score)| {
+            let metrics = PropertyMetrics::compute(property, &config.finance);
+            let listed_since = on_market_since(property, context.history.get(&property.id));
+            let sek = context.rates.as_ref().and_then(|rates| SekEquivalent::compute(property, &metrics, rates));
+            let mut record = ExportRecord {
+                property,
+                metrics,
+                sek,
+                finance: MonthlyCost::for_property(property, &config.finance),
+                score,
+                anomaly: context.baseline.check(property, &config.anomaly),
+                prediction: context.price_model.as_ref().and_then(|model| model.predict(property)),
+                annotation: context.annotations.get(&property.id).cloned(),
+                listed_since,
+                days_on_market: (now - listed_since).num_days(),
+            };
+            if let Some(total) = context.scripts.score(&record) {
+                record.score.total = total;
+            }
+            record
+        })
+        .collect()
+}
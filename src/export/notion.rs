@@ -0,0 +1,285 @@
+use crate::export::ExportRecord;
+use crate::models::ListingStatus;
+use crate::secrets::Secret;
+use anyhow::{Context, Result};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Attempts at a request Notion rate limits before giving up
+const MAX_ATTEMPTS: u32 = 3;
+
+/// A Notion database kept in sync with the listings, one page per property
+///
+/// Pages are matched on their "ID" text property. Properties are set by name
+/// where the database has them, so the board decides what's shown: "ID",
+/// "Price", "Rooms", "Sqm", "Monthly fee", "Price/m²", "Score" (numbers),
+/// "Area", "Listing", "Status" (selects or statuses) and "URL", plus the
+/// address as the page title. The first photo becomes the cover.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotionConfig {
+    /// The ID in the database's URL: notion.so/<workspace>/<ID>?v=...
+    pub database_id: Option<String>,
+    /// Internal integration secret; share the database with the integration
+    pub token: Option<Secret>,
+    /// Sync after every `scout scrape`, not just with `scout export --format notion`
+    pub sync_after_scrape: bool,
+}
+
+/// What a sync changed
+#[derive(Debug, Default)]
+pub struct NotionSyncResult {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// Upsert `records` into the configured database
+pub async fn sync(records: &[ExportRecord<'_>], config: &NotionConfig) -> Result<NotionSyncResult> {
+    let database_id = config
+        .database_id
+        .as_deref()
+        .context("Set [notion] database_id to sync to Notion")?;
+    let token = config.token.as_ref().context("Set [notion] token to an integration secret")?;
+    NotionSync::new(database_id, &token.resolve()?).sync(records).await
+}
+
+/// Client for one Notion database
+pub struct NotionSync {
+    client: Client,
+    base_url: String,
+    database_id: String,
+    token: String,
+}
+
+impl NotionSync {
+    pub fn new(database_id: &str, token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: NOTION_API.to_string(),
+            database_id: database_id.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Talk to `base_url` instead of api.notion.com
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}/{}", self.base_url, path))
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION)
+    }
+
+    /// Send `request`, waiting out rate limits
+    async fn send(&self, request: RequestBuilder) -> Result<Value> {
+        let mut attempt = 1;
+        loop {
+            let retry = request.try_clone().context("Notion request can't be retried")?;
+            let response = retry.send().await.context("Failed to reach Notion")?;
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_ATTEMPTS {
+                let wait = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()?.parse().ok())
+                    .unwrap_or(1);
+                debug!("Notion rate limited, retrying in {}s", wait);
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+                attempt += 1;
+                continue;
+            }
+            let body: Value = response.json().await.unwrap_or_default();
+            if !status.is_success() {
+                let message = body["message"].as_str().unwrap_or_default();
+                anyhow::bail!("Notion returned status {}: {}", status, message);
+            }
+            return Ok(body);
+        }
+    }
+
+    /// Property name to type, from the database schema
+    async fn schema(&self) -> Result<HashMap<String, String>> {
+        let database = self
+            .send(self.request(Method::GET, &format!("databases/{}", self.database_id)))
+            .await
+            .context("Failed to read the Notion database")?;
+        let properties = database["properties"].as_object().cloned().unwrap_or_default();
+        Ok(properties
+            .into_iter()
+            .filter_map(|(name, property)| Some((name, property["type"].as_str()?.to_string())))
+            .collect())
+    }
+
+    /// Every page in the database with an ID, by that ID
+    async fn pages(&self) -> Result<HashMap<String, Value>> {
+        let mut pages = HashMap::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut query = json!({ "page_size": 100 });
+            if let Some(cursor) = &cursor {
+                query["start_cursor"] = json!(cursor);
+            }
+            let path = format!("databases/{}/query", self.database_id);
+            let response = self.send(self.request(Method::POST, &path).json(&query)).await?;
+            for page in response["results"].as_array().into_iter().flatten() {
+                if let Some(Value::String(id)) = page["properties"].get("ID").map(plain) {
+                    pages.insert(id, page.clone());
+                }
+            }
+            match response["next_cursor"].as_str() {
+                Some(next) if response["has_more"].as_bool() == Some(true) => cursor = Some(next.to_string()),
+                _ => return Ok(pages),
+            }
+        }
+    }
+
+    /// Create or update a page per record; pages of other listings are left alone
+    #[instrument(name = "notion", level = "debug", skip_all, fields(records = records.len()))]
+    pub async fn sync(&self, records: &[ExportRecord<'_>]) -> Result<NotionSyncResult> {
+        let schema = self.schema().await?;
+        if schema.get("ID").map(String::as_str) != Some("rich_text") {
+            anyhow::bail!("The Notion database needs a text property named \"ID\" to match listings on");
+        }
+        let title = schema
+            .iter()
+            .find(|(_, kind)| *kind == "title")
+            .map(|(name, _)| name.clone())
+            .context("The Notion database has no title property")?;
+        let pages = self.pages().await?;
+
+        let mut result = NotionSyncResult::default();
+        for record in records {
+            let mut values = fields(record);
+            values.push((title.as_str(), json!(record.property.address)));
+            let cover = record.property.photos().next().map(|image| json!({ "type": "external", "external": { "url": image.url } }));
+
+            let page = pages.get(&record.property.id);
+            let mut properties = Map::new();
+            for (name, value) in values {
+                let Some(kind) = schema.get(name) else {
+                    continue;
+                };
+                if page.is_some_and(|page| same(page["properties"].get(name), &value)) {
+                    continue;
+                }
+                match encode(kind, &value) {
+                    Some(encoded) => {
+                        properties.insert(name.to_string(), encoded);
+                    }
+                    None => debug!("Not setting Notion property {} of type {}", name, kind),
+                }
+            }
+
+            let request = match page {
+                None => {
+                    let mut body = json!({ "parent": { "database_id": self.database_id }, "properties": properties });
+                    if let Some(cover) = cover {
+                        body["cover"] = cover;
+                    }
+                    self.request(Method::POST, "pages").json(&body)
+                }
+                Some(page) => {
+                    let mut body = json!({ "properties": properties });
+                    if let Some(cover) = cover.filter(|_| page["cover"].is_null()) {
+                        body["cover"] = cover;
+                    } else if properties.is_empty() {
+                        continue;
+                    }
+                    let id = page["id"].as_str().unwrap_or_default();
+                    self.request(Method::PATCH, &format!("pages/{}", id)).json(&body)
+                }
+            };
+            match self.send(request).await {
+                Ok(_) if page.is_some() => result.updated += 1,
+                Ok(_) => result.added += 1,
+                Err(e) => warn!("Failed to sync {} to Notion: {:#}", record.property.id, e),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The values set on a listing's page, by property name
+fn fields<'a>(record: &ExportRecord<'_>) -> Vec<(&'a str, Value)> {
+    let property = record.property;
+    let listing = if property.bidding_in_progress {
+        "Bidding"
+    } else {
+        match property.listing_status {
+            ListingStatus::ForSale => "For sale",
+            ListingStatus::ComingSoon => "Coming soon",
+        }
+    };
+    vec![
+        ("ID", json!(property.id)),
+        ("Price", json!(property.price.map(|price| price.major()))),
+        ("Rooms", json!(property.rooms)),
+        ("Sqm", json!(property.sqm)),
+        ("Monthly fee", json!(property.monthly_fee.map(|fee| fee.major()))),
+        ("Price/m²", json!(record.metrics.price_per_sqm.map(f64::round))),
+        ("Score", json!((record.score.total * 100.0).round() / 100.0)),
+        ("Area", json!(property.location.area)),
+        ("Listing", json!(listing)),
+        ("Status", json!(record.status().to_string())),
+        ("URL", json!(property.url)),
+    ]
+}
+
+/// A page property value for a Notion property of type `kind`
+fn encode(kind: &str, value: &Value) -> Option<Value> {
+    let text = || value.as_str().map(|text| json!([{ "type": "text", "text": { "content": text } }])).unwrap_or(json!([]));
+    let option = || value.as_str().map(|name| json!({ "name": name })).unwrap_or(Value::Null);
+    Some(match kind {
+        "title" => json!({ "title": text() }),
+        "rich_text" => json!({ "rich_text": text() }),
+        "number" if value.is_number() || value.is_null() => json!({ "number": value }),
+        "select" => json!({ "select": option() }),
+        "status" if value.is_string() => json!({ "status": option() }),
+        "url" => json!({ "url": value.as_str() }),
+        _ => return None,
+    })
+}
+
+/// A page property's value as a plain string, number or null
+fn plain(property: &Value) -> Value {
+    let text = |parts: &Value| {
+        let text: String = parts
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|part| part["plain_text"].as_str())
+            .collect();
+        Value::String(text)
+    };
+    match property["type"].as_str() {
+        Some("title") => text(&property["title"]),
+        Some("rich_text") => text(&property["rich_text"]),
+        Some("number") => property["number"].clone(),
+        Some("select") => property["select"]["name"].clone(),
+        Some("status") => property["status"]["name"].clone(),
+        Some("url") => property["url"].clone(),
+        _ => Value::Null,
+    }
+}
+
+/// Whether a page property already holds `value`
+fn same(property: Option<&Value>, value: &Value) -> bool {
+    let Some(property) = property else {
+        return false;
+    };
+    match (plain(property), value) {
+        (current, value) if current.is_number() && value.is_number() => current.as_f64() == value.as_f64(),
+        (Value::String(text), Value::Null) => text.is_empty(),
+        (current, value) => current == *value,
+    }
+}
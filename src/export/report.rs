@@ -0,0 +1,409 @@
+use crate::export::ExportRecord;
+use crate::i18n::Language;
+use crate::models::{Money, OrientationSource, Property};
+use crate::storage::{PropertyHistory, Status};
+use chrono::{Datelike, Utc};
+use chrono_tz::Europe::Stockholm;
+use std::fmt::Write;
+
+/// Average walking speed used to turn distances into minutes
+const WALKING_M_PER_MIN: f64 = 80.0;
+
+/// What to include in a property report besides the property's own data
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    /// Image URLs to show, in order
+    pub photos: Vec<String>,
+    /// Embed an OpenStreetMap map of the location
+    pub map: bool,
+    /// Link back to an index page
+    pub back_link: Option<String>,
+    pub language: Language,
+}
+
+/// Photos for a standalone report: downloaded copies when available, so the
+/// report works offline and in PDFs, otherwise the listing's image URLs
+pub fn local_photos(property: &Property) -> Vec<String> {
+    if property.local_images.is_empty() {
+        return property.photos().map(|image| image.url.clone()).collect();
+    }
+    property
+        .local_images
+        .iter()
+        .map(|path| {
+            let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            format!("file://{}", absolute.display())
+        })
+        .collect()
+}
+
+/// Render a printable one-page HTML report for a property
+pub fn property_report(
+    record: &ExportRecord<'_>,
+    history: Option<&PropertyHistory>,
+    options: &ReportOptions,
+) -> String {
+    let property = record.property;
+    let lang = options.language;
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #222; }}
+h1 {{ margin-bottom: 0.2em; }}
+h2 {{ border-bottom: 1px solid #ccc; padding-bottom: 0.2em; margin-top: 1.5em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ padding: 0.2em 1em 0.2em 0; text-align: left; vertical-align: top; }}
+.photos {{ display: grid; grid-template-columns: repeat(3, 1fr); gap: 0.5em; }}
+.photos img {{ width: 100%; height: auto; }}
+.muted {{ color: #777; }}
+.flag {{ color: #b00; font-weight: bold; }}
+@media print {{ body {{ margin: 0; }} h2 {{ break-after: avoid; }} }}
+</style>
+</head>
+<body>
+{back}<h1>{title}</h1>
+<p class="muted">{area}{city} · ID {id} · <a href="{url}">{url}</a></p>
+"#,
+        lang = lang.code(),
+        title = escape_html(&property.address),
+        back = options
+            .back_link
+            .as_deref()
+            .map(|href| format!("<p><a href=\"{}\">{}</a></p>\n", escape_html(href), lang.t("report.all_properties")))
+            .unwrap_or_default(),
+        area = property
+            .location
+            .area
+            .as_deref()
+            .map(|a| format!("{}, ", escape_html(a)))
+            .unwrap_or_default(),
+        city = escape_html(&property.location.city),
+        id = escape_html(&property.id),
+        url = escape_html(&property.url),
+    );
+    if !property.virtual_tours.is_empty() {
+        let links: Vec<String> = property
+            .virtual_tours
+            .iter()
+            .map(|url| format!(r#"<a href="{}">{}</a>"#, escape_html(url), lang.t("report.virtual_tour")))
+            .collect();
+        let _ = writeln!(html, "<p>{}</p>", links.join(" · "));
+    }
+
+    if let Some(anomaly) = &record.anomaly {
+        let text = lang.format(
+            "report.below_median",
+            &[
+                ("discount", &format!("{:.0}", anomaly.discount * 100.0)),
+                ("cohort", &anomaly.cohort),
+                ("median", &format!("{:.0}", anomaly.cohort_median_price_per_sqm)),
+                ("count", &anomaly.cohort_size),
+            ],
+        );
+        let _ = writeln!(html, r#"<p class="flag">{}</p>"#, escape_html(&text));
+    }
+
+    // Key facts
+    let mut facts: Vec<(&str, String)> = vec![(
+        lang.t("price"),
+        property.price.map(money).unwrap_or_else(|| lang.t("no_price_yet").to_string()),
+    )];
+    let size = lang.size(property.rooms, property.sqm);
+    if !size.is_empty() {
+        facts.push((lang.t("size"), size));
+    }
+    if let Some(per_sqm) = record.metrics.price_per_sqm {
+        facts.push((lang.t("price_per_sqm"), format!("{} kr", group(per_sqm.round() as i64))));
+    }
+    if let Some(fee) = property.monthly_fee {
+        facts.push((lang.t("monthly_fee"), lang.format("report.per_month", &[("amount", &money(fee))])));
+    }
+    if let Some(cost) = property.operating_cost {
+        facts.push((lang.t("operating_cost"), lang.format("report.per_month", &[("amount", &money(cost))])));
+    }
+    if let Some(floor) = property.floor {
+        facts.push((lang.t("floor"), floor.to_string()));
+    }
+    if property.bidding_in_progress {
+        let bid = match (property.current_bid, property.bid_premium()) {
+            (Some(bid), Some(premium)) => format!(
+                "{} ({})",
+                money(bid),
+                lang.format("vs_asking", &[("premium", &format!("{:+.1}", premium))])
+            ),
+            (Some(bid), None) => money(bid),
+            _ => lang.t("bidding_in_progress").to_string(),
+        };
+        facts.push((lang.t("current_bid"), bid));
+    }
+    if let Some(prediction) = &record.prediction {
+        facts.push((
+            lang.t("predicted_price"),
+            lang.format(
+                "report.predicted",
+                &[
+                    ("price", &group(prediction.predicted_price)),
+                    ("delta", &format!("{:+.1}", prediction.delta_pct * 100.0)),
+                ],
+            ),
+        ));
+    }
+    if let Some(plan) = property.floor_plan_sqm.filter(|_| property.floor_plan_mismatch()) {
+        facts.push((lang.t("floor_plan_area"), lang.format("report.floor_plan_differs", &[("sqm", &plan)])));
+    }
+    facts.push((lang.t("score"), format!("{:.2}", record.score.total)));
+    if !property.features.is_empty() {
+        facts.push((lang.t("features"), property.features.labels(lang).join(", ")));
+    }
+    if let Some(next) = property.viewings.first() {
+        facts.push((lang.t("next_viewing"), next.with_timezone(&Stockholm).format("%a %d %b %H:%M").to_string()));
+    }
+    if let Some(broker) = &property.broker {
+        let parts: Vec<&str> = [&broker.name, &broker.agency, &broker.phone]
+            .into_iter()
+            .filter_map(|part| part.as_deref())
+            .collect();
+        facts.push((lang.t("broker"), parts.join(", ")));
+    }
+    if let Some(renovation) = &property.renovation {
+        facts.push((lang.t("renovation"), lang.renovation(renovation)));
+    }
+    if let Some(association) = &property.association {
+        facts.push((lang.t("association"), association.name.clone()));
+        if let Some(founded) = association.registry.as_ref().and_then(|r| r.founded_year) {
+            let founded = if association.is_new_conversion(Utc::now().year()) {
+                lang.format("report.new_association", &[("year", &founded)])
+            } else {
+                founded.to_string()
+            };
+            facts.push((lang.t("report.association_founded"), founded));
+        }
+        if let Some(financials) = &association.financials {
+            if let Some(debt) = financials.debt_per_sqm {
+                facts.push((lang.t("report.debt_per_sqm"), format!("{} kr/m²", group(debt.round() as i64))));
+            }
+            if !financials.planned_renovations.is_empty() {
+                let works: Vec<&str> = financials.planned_renovations.iter().map(|r| r.text.as_str()).collect();
+                facts.push((lang.t("report.planned_renovations"), works.join("; ")));
+            }
+        }
+    }
+    table(&mut html, lang.t("report.key_facts"), &facts);
+
+    if let Some(cost) = &record.finance {
+        let per_month = |amount: f64| lang.format("report.kr_per_month", &[("amount", &group(amount as i64))]);
+        let mut rows = vec![
+            (lang.t("report.down_payment"), format!("{} kr", group(cost.down_payment as i64))),
+            (
+                lang.t("report.loan"),
+                lang.format(
+                    "report.loan_ltv",
+                    &[("loan", &group(cost.loan as i64)), ("ltv", &format!("{:.0}", cost.ltv * 100.0))],
+                ),
+            ),
+            (lang.t("report.interest"), per_month(cost.interest)),
+            (
+                lang.t("report.amortization"),
+                lang.format(
+                    "report.amortization_rate",
+                    &[("amount", &group(cost.amortization as i64)), ("rate", &cost.amortization_rate)],
+                ),
+            ),
+            (lang.t("report.fee"), per_month(cost.fee)),
+            (lang.t("report.total"), per_month(cost.total)),
+        ];
+        if let Some(shortfall) = cost.down_payment_shortfall {
+            rows.push((lang.t("report.down_payment_shortfall"), format!("{} kr", group(shortfall as i64))));
+        }
+        table(&mut html, lang.t("monthly_cost"), &rows);
+    }
+
+    // Location and surroundings
+    let mut location: Vec<(&str, String)> = Vec::new();
+    if let Some(station) = &property.nearest_station {
+        location.push((
+            lang.t("report.nearest_station"),
+            lang.format(
+                "report.station_walk",
+                &[
+                    ("name", &station.name),
+                    ("kind", &format!("{:?}", station.kind)),
+                    ("distance", &format!("{:.0}", station.walking_m)),
+                    ("minutes", &format!("{:.0}", (station.walking_m / WALKING_M_PER_MIN).ceil())),
+                ],
+            ),
+        ));
+    }
+    if let Some(nearby) = &property.nearby {
+        location.push((
+            lang.t("report.nearby"),
+            lang.format(
+                "report.nearby_counts",
+                &[
+                    ("groceries", &nearby.groceries),
+                    ("gyms", &nearby.gyms),
+                    ("preschools", &nearby.preschools),
+                    ("parks", &nearby.parks),
+                    ("radius", &format!("{:.0}", nearby.radius_m)),
+                ],
+            ),
+        ));
+    }
+    for school in &property.schools {
+        let mut line = format!("{} ({:.0} m", school.name, school.distance_m);
+        if let Some(merit) = school.merit_rating {
+            let _ = write!(line, ", {}", lang.format("report.merit", &[("merit", &format!("{:.0}", merit))]));
+        }
+        if let Some(ratio) = school.students_per_teacher {
+            let _ = write!(
+                line,
+                ", {}",
+                lang.format("report.students_per_teacher", &[("ratio", &format!("{:.1}", ratio))])
+            );
+        }
+        line.push(')');
+        location.push((lang.t("report.school"), line));
+    }
+    if let Some(balcony) = &property.balcony_orientation {
+        let estimated = if balcony.source == OrientationSource::Footprint { lang.t("estimated") } else { "" };
+        let sun = if balcony.afternoon_sun { format!(", {}", lang.t("afternoon_sun")) } else { String::new() };
+        let faces = lang.format("faces", &[("direction", &balcony.direction)]);
+        location.push((lang.t("balcony"), format!("{}{}{}", faces, estimated, sun)));
+    }
+    if let Some(noise) = property.noise_db {
+        location.push((lang.t("noise"), format!("{:.0} dB(A)", noise)));
+    }
+    if let Some(flood) = property.flood_risk {
+        location.push((lang.t("report.flood_risk"), lang.yes_no(flood).to_string()));
+    }
+    if let Some(broadband) = &property.broadband {
+        location.push((lang.t("broadband"), broadband.technologies.join(", ")));
+    }
+    if !location.is_empty() {
+        table(&mut html, lang.t("report.location"), &location);
+    }
+
+    if let Some(annotation) = record.annotation.as_ref() {
+        let mut rows: Vec<(&str, String)> = Vec::new();
+        if annotation.status != Status::New {
+            rows.push((lang.t("status"), annotation.status.to_string()));
+        }
+        if let Some(rating) = annotation.rating {
+            rows.push((lang.t("report.rating"), format!("{}/5", rating)));
+        }
+        for note in &annotation.notes {
+            rows.push((
+                lang.t("report.note"),
+                format!("{} ({})", note.text, note.written_at.with_timezone(&Stockholm).format("%Y-%m-%d")),
+            ));
+        }
+        if !rows.is_empty() {
+            table(&mut html, lang.t("report.my_notes"), &rows);
+        }
+    }
+
+    if let Some(history) = history.filter(|h| !h.observations.is_empty()) {
+        let _ = writeln!(html, "<h2>{}</h2>", lang.t("report.price_history"));
+        if !history.relisted_from.is_empty() {
+            let ids = history.relisted_from.join(", ");
+            let _ = writeln!(html, "<p>{}</p>", escape_html(&lang.format("report.relisted", &[("ids", &ids)])));
+        }
+        let _ = writeln!(html, "<table>");
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><th>{}</th><th>{}</th></tr>",
+            lang.t("report.date"),
+            lang.t("price"),
+            lang.t("report.bid")
+        );
+        for observation in &history.observations {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                observation.observed_at.with_timezone(&Stockholm).format("%Y-%m-%d"),
+                observation.price.map(money).unwrap_or_default(),
+                observation.current_bid.map(money).unwrap_or_default()
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+
+    if let Some(point) = property.location.point().filter(|_| options.map) {
+        let (dlat, dlon) = (0.004, 0.008);
+        let _ = writeln!(
+            html,
+            r#"<h2>{}</h2>
+<iframe width="100%" height="350" frameborder="0" src="https://www.openstreetmap.org/export/embed.html?bbox={},{},{},{}&amp;layer=mapnik&amp;marker={},{}"></iframe>"#,
+            lang.t("report.map"),
+            point.lon - dlon,
+            point.lat - dlat,
+            point.lon + dlon,
+            point.lat + dlat,
+            point.lat,
+            point.lon
+        );
+    }
+
+    if !options.photos.is_empty() {
+        let _ = writeln!(html, "<h2>{}</h2>\n<div class=\"photos\">", lang.t("report.photos"));
+        for src in &options.photos {
+            let _ = writeln!(html, r#"<img src="{}" alt="">"#, escape_html(src));
+        }
+        let _ = writeln!(html, "</div>");
+    }
+
+    let _ = writeln!(
+        html,
+        r#"<p class="muted">{}</p>
+</body>
+</html>"#,
+        lang.format(
+            "report.scraped",
+            &[("time", &property.scraped_at.with_timezone(&Stockholm).format("%Y-%m-%d %H:%M"))]
+        )
+    );
+    html
+}
+
+fn table(html: &mut String, heading: &str, rows: &[(&str, String)]) {
+    let _ = writeln!(html, "<h2>{}</h2>\n<table>", heading);
+    for (label, value) in rows {
+        let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", label, escape_html(value));
+    }
+    let _ = writeln!(html, "</table>");
+}
+
+/// "4 250 000 kr", grouped like [`group`]
+fn money(amount: Money) -> String {
+    format!("{} {}", group(amount.major()), amount.currency.unit())
+}
+
+/// 4 250 000 - Swedish digit grouping
+fn group(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push('\u{a0}');
+        }
+        grouped.push(c);
+    }
+    if value < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
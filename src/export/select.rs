@@ -0,0 +1,102 @@
+use crate::cli::{FilterArgs, SortKey};
+use crate::config::Config;
+use crate::export::ExportRecord;
+use crate::models::Property;
+use anyhow::Result;
+use std::cmp::Ordering;
+
+/// Drop records that don't pass the command-line filters
+pub fn filter_records(records: &mut Vec<ExportRecord<'_>>, filters: &FilterArgs, config: &Config) -> Result<()> {
+    let search = filters.search.as_deref().map(|name| config.saved_search(name)).transpose()?;
+
+    records.retain(|record| {
+        let p = record.property;
+        let m = &record.metrics;
+        let sek = record.sek.as_ref();
+
+        below(price(record), filters.max_price.map(|v| v as f64))
+            && above(p.sqm.map(|v| v as f64), filters.min_sqm.map(|v| v as f64))
+            && above(p.rooms.map(|v| v as f64), filters.min_rooms.map(|v| v as f64))
+            && below(sek.and_then(|s| s.price_per_sqm).or(m.price_per_sqm), filters.max_price_per_sqm)
+            && below(sek.and_then(|s| s.fee_per_sqm).or(m.fee_per_sqm), filters.max_fee_per_sqm)
+            && below(m.monthly_cost, filters.max_monthly_cost)
+            && below(p.nearest_station.as_ref().map(|s| s.walking_m), filters.max_metro_distance)
+            && above(best_school_merit(p), filters.min_school_merit)
+            && below(p.noise_db, filters.max_noise)
+            && (!filters.no_flood_risk || p.flood_risk == Some(false))
+            && (!filters.fiber || p.broadband.as_ref().is_some_and(|b| b.fiber))
+            && (!filters.no_pipe_replacement || !pipe_replacement_ahead(p))
+            && filters.max_age.is_none_or(|age| age.admits(record))
+            && (!filters.underpriced || record.anomaly.is_some())
+            && (!filters.favorites || record.annotation.as_ref().is_some_and(|a| a.favorite))
+            && (filters.include_hidden || !record.annotation.as_ref().is_some_and(|a| a.hidden))
+            && (filters.status.is_empty() || filters.status.contains(&record.status()))
+            && filters.filter.as_ref().is_none_or(|filter| filter.matches(record))
+            && search.is_none_or(|search| search.matches(record))
+    });
+    Ok(())
+}
+
+/// Sort records by `key`; records missing the value always sort last
+///
+/// Prices and fees compare in SEK when the records mix currencies.
+pub fn sort_records(records: &mut [ExportRecord<'_>], key: SortKey, descending: bool) {
+    records.sort_by(|a, b| {
+        match (sort_value(a, key), sort_value(b, key)) {
+            (Some(a), Some(b)) if descending => b.total_cmp(&a),
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    });
+}
+
+fn sort_value(record: &ExportRecord<'_>, key: SortKey) -> Option<f64> {
+    let p = record.property;
+    let sek = record.sek.as_ref();
+    match key {
+        SortKey::Price => price(record),
+        SortKey::Sqm => p.sqm.map(|sqm| sqm as f64),
+        SortKey::Rooms => p.rooms.map(|rooms| rooms as f64),
+        SortKey::Fee => sek.and_then(|s| s.monthly_fee).or(p.monthly_fee.map(|fee| fee.as_f64())),
+        SortKey::PricePerSqm => sek.and_then(|s| s.price_per_sqm).or(record.metrics.price_per_sqm),
+        SortKey::FeePerSqm => sek.and_then(|s| s.fee_per_sqm).or(record.metrics.fee_per_sqm),
+        SortKey::MonthlyCost => record.metrics.monthly_cost,
+        SortKey::Score => Some(record.score.total),
+        SortKey::PredictedDelta => record.prediction.as_ref().map(|p| p.delta_pct),
+        SortKey::MetroDistance => p.nearest_station.as_ref().map(|s| s.walking_m),
+    }
+}
+
+/// Listed price, in SEK when the records mix currencies
+fn price(record: &ExportRecord<'_>) -> Option<f64> {
+    let sek = record.sek.as_ref().and_then(|s| s.price);
+    sek.or(record.property.price.map(|price| price.as_f64()))
+}
+
+fn best_school_merit(property: &Property) -> Option<f64> {
+    property.schools.iter().filter_map(|s| s.merit_rating).max_by(f64::total_cmp)
+}
+
+/// Whether the description says a pipe replacement is planned or still to come
+fn pipe_replacement_ahead(property: &Property) -> bool {
+    let pipes = property.renovation.as_ref().and_then(|r| r.pipes.as_ref());
+    pipes.is_some_and(|pipes| pipes.status.is_ahead())
+}
+
+/// `value <= max`, where an unset limit always passes and an unknown value fails
+fn below(value: Option<f64>, max: Option<f64>) -> bool {
+    match max {
+        Some(max) => value.is_some_and(|v| v <= max),
+        None => true,
+    }
+}
+
+/// `value >= min`, where an unset limit always passes and an unknown value fails
+fn above(value: Option<f64>, min: Option<f64>) -> bool {
+    match min {
+        Some(min) => value.is_some_and(|v| v >= min),
+        None => true,
+    }
+}
@@ -0,0 +1,313 @@
+use crate::export::ExportRecord;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A Google Sheet kept in sync with the listings, one row per property ID
+///
+/// Rows are matched on the ID column. Only the columns written here are
+/// touched, so notes in columns of your own stay put; new listings are
+/// appended and changed values (price, status, ...) updated in place.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetsConfig {
+    /// The long ID in the sheet's URL: docs.google.com/spreadsheets/d/<ID>/edit
+    pub spreadsheet_id: Option<String>,
+    /// Tab the listings go in
+    pub sheet: String,
+    /// Service account key file (JSON) from the Google Cloud console; share
+    /// the spreadsheet with the account's email address
+    pub credentials: Option<PathBuf>,
+    /// Sync after every `scout scrape`, not just with `scout export --format sheets`
+    pub sync_after_scrape: bool,
+}
+
+impl Default for SheetsConfig {
+    fn default() -> Self {
+        Self {
+            spreadsheet_id: None,
+            sheet: "Listings".to_string(),
+            credentials: None,
+            sync_after_scrape: false,
+        }
+    }
+}
+
+/// What a sync changed
+#[derive(Debug, Default)]
+pub struct SheetSync {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// Upsert `records` into the configured sheet
+#[cfg(feature = "sheets")]
+pub async fn sync(records: &[ExportRecord<'_>], config: &SheetsConfig) -> anyhow::Result<SheetSync> {
+    api::sync(records, config).await
+}
+
+#[cfg(not(feature = "sheets"))]
+pub async fn sync(_records: &[ExportRecord<'_>], _config: &SheetsConfig) -> anyhow::Result<SheetSync> {
+    anyhow::bail!("Google Sheets sync needs a build with `--features sheets`")
+}
+
+#[cfg(feature = "sheets")]
+mod api {
+    use super::{SheetSync, SheetsConfig};
+    use crate::export::ExportRecord;
+    use crate::models::ListingStatus;
+    use anyhow::{Context, Result};
+    use chrono::Utc;
+    use jsonwebtoken::{Algorithm, EncodingKey, Header};
+    use reqwest::{Client, Url};
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use tracing::instrument;
+
+    const SHEETS_API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+    const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+    /// Columns written, by header
+    const COLUMNS: [&str; 12] = [
+        "ID",
+        "Address",
+        "Area",
+        "Price",
+        "Rooms",
+        "Sqm",
+        "Monthly fee",
+        "Price/m²",
+        "Listing",
+        "Status",
+        "Score",
+        "URL",
+    ];
+
+    /// The record's value for each of [`COLUMNS`]
+    fn row(record: &ExportRecord<'_>) -> Vec<Value> {
+        let property = record.property;
+        let listing = if property.bidding_in_progress {
+            "bidding"
+        } else {
+            match property.listing_status {
+                ListingStatus::ForSale => "for sale",
+                ListingStatus::ComingSoon => "coming soon",
+            }
+        };
+        vec![
+            json!(property.id),
+            json!(property.address),
+            json!(property.location.area.clone().unwrap_or_default()),
+            json!(property.price.map(|price| price.major())),
+            json!(property.rooms),
+            json!(property.sqm),
+            json!(property.monthly_fee.map(|fee| fee.major())),
+            json!(record.metrics.price_per_sqm.map(f64::round)),
+            json!(listing),
+            json!(record.status().to_string()),
+            json!((record.score.total * 100.0).round() / 100.0),
+            json!(property.url),
+        ]
+    }
+
+    /// The parts of a service account key file used here
+    #[derive(Deserialize)]
+    struct ServiceAccount {
+        client_email: String,
+        private_key: String,
+        token_uri: String,
+    }
+
+    #[derive(Serialize)]
+    struct Claims<'a> {
+        iss: &'a str,
+        scope: &'a str,
+        aud: &'a str,
+        iat: i64,
+        exp: i64,
+    }
+
+    /// Trade a signed assertion for an access token (OAuth 2.0 JWT bearer grant)
+    #[instrument(name = "http", level = "debug", skip_all, fields(operation = "token"))]
+    async fn access_token(client: &Client, config: &SheetsConfig) -> Result<String> {
+        let path = config
+            .credentials
+            .as_ref()
+            .context("Set [sheets] credentials to a service account key file")?;
+        let key = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let account: ServiceAccount =
+            serde_json::from_str(&key).with_context(|| format!("{} is not a service account key", path.display()))?;
+
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            iss: &account.client_email,
+            scope: SCOPE,
+            aud: &account.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let key = EncodingKey::from_rsa_pem(account.private_key.as_bytes()).context("Invalid service account key")?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)?;
+
+        let response: Value = client
+            .post(&account.token_uri)
+            .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", &assertion)])
+            .send()
+            .await
+            .context("Failed to request a Google access token")?
+            .error_for_status()
+            .context("Google refused the service account")?
+            .json()
+            .await?;
+        response["access_token"]
+            .as_str()
+            .map(str::to_string)
+            .context("No access token in Google's response")
+    }
+
+    /// `<SHEETS_API>/<spreadsheet>/<path...>`
+    fn api_url(spreadsheet: &str, path: &[&str]) -> Result<Url> {
+        let mut url = Url::parse(SHEETS_API)?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("Invalid Sheets API URL"))?
+            .push(spreadsheet)
+            .extend(path);
+        Ok(url)
+    }
+
+    /// Column letters for a zero-based index: 0 -> A, 26 -> AA
+    fn column(mut index: usize) -> String {
+        let mut letters = Vec::new();
+        loop {
+            letters.push(b'A' + (index % 26) as u8);
+            if index < 26 {
+                break;
+            }
+            index = index / 26 - 1;
+        }
+        letters.reverse();
+        String::from_utf8(letters).unwrap_or_default()
+    }
+
+    /// Whether a cell already shows `value`; the sheet gives numbers back as
+    /// numbers and empty cells as nothing
+    fn same(cell: Option<&Value>, value: &Value) -> bool {
+        match (cell, value) {
+            (None, Value::Null) => true,
+            (Some(Value::String(text)), Value::Null) => text.is_empty(),
+            (Some(cell), value) if cell.is_number() && value.is_number() => cell.as_f64() == value.as_f64(),
+            (Some(cell), value) => cell == value,
+            (None, _) => false,
+        }
+    }
+
+    pub async fn sync(records: &[ExportRecord<'_>], config: &SheetsConfig) -> Result<SheetSync> {
+        let spreadsheet = config
+            .spreadsheet_id
+            .as_deref()
+            .context("Set [sheets] spreadsheet_id to sync to Google Sheets")?;
+        let client = Client::new();
+        let token = access_token(&client, config).await?;
+        let sheet = format!("'{}'", config.sheet.replace('\'', "''"));
+
+        let existing: Value = client
+            .get(api_url(spreadsheet, &["values", &sheet])?)
+            .query(&[("valueRenderOption", "UNFORMATTED_VALUE")])
+            .bearer_auth(&token)
+            .send()
+            .await
+            .context("Failed to read the sheet")?
+            .error_for_status()
+            .with_context(|| format!("Failed to read sheet {} of spreadsheet {}", config.sheet, spreadsheet))?
+            .json()
+            .await?;
+        let rows: Vec<Vec<Value>> = existing
+            .get("values")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default();
+
+        // Find our columns in the header, adding the missing ones after the rest
+        let mut header: Vec<Value> = rows.first().cloned().unwrap_or_default();
+        let header_len = header.len();
+        let positions: Vec<usize> = COLUMNS
+            .iter()
+            .map(|name| match header.iter().position(|cell| cell.as_str() == Some(name)) {
+                Some(i) => i,
+                None => {
+                    header.push(json!(name));
+                    header.len() - 1
+                }
+            })
+            .collect();
+        let id_column = positions[0];
+        let row_of: HashMap<String, usize> = rows
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, row)| {
+                let id = match row.get(id_column)? {
+                    Value::String(id) => id.clone(),
+                    other => other.to_string(),
+                };
+                Some((id, i))
+            })
+            .collect();
+
+        let mut updates = Vec::new();
+        if header.len() > header_len || rows.is_empty() {
+            updates.push(json!({ "range": format!("{}!A1", sheet), "values": [header] }));
+        }
+        let mut appended = Vec::new();
+        let mut sync = SheetSync::default();
+        for record in records {
+            let values = row(record);
+            match row_of.get(&record.property.id) {
+                Some(&i) => {
+                    let mut changed = false;
+                    for (value, &col) in values.iter().zip(&positions) {
+                        if !same(rows[i].get(col), value) {
+                            updates.push(json!({
+                                "range": format!("{}!{}{}", sheet, column(col), i + 1),
+                                "values": [[value]],
+                            }));
+                            changed = true;
+                        }
+                    }
+                    sync.updated += usize::from(changed);
+                }
+                None => {
+                    let mut cells = vec![Value::String(String::new()); header.len()];
+                    for (value, &col) in values.into_iter().zip(&positions) {
+                        cells[col] = value;
+                    }
+                    appended.push(cells);
+                }
+            }
+        }
+        sync.added = appended.len();
+
+        // New rows go below everything there, including rows of other IDs
+        let first_new = rows.len().max(1) + 1;
+        for (offset, cells) in appended.into_iter().enumerate() {
+            updates.push(json!({ "range": format!("{}!A{}", sheet, first_new + offset), "values": [cells] }));
+        }
+        if updates.is_empty() {
+            return Ok(sync);
+        }
+
+        client
+            .post(api_url(spreadsheet, &["values:batchUpdate"])?)
+            .bearer_auth(&token)
+            .json(&json!({ "valueInputOption": "RAW", "data": updates }))
+            .send()
+            .await
+            .context("Failed to update the sheet")?
+            .error_for_status()
+            .context("Failed to update the sheet")?;
+        Ok(sync)
+    }
+}
@@ -0,0 +1,222 @@
+use crate::export::report::{escape_html, property_report, ReportOptions};
+use crate::export::ExportRecord;
+use crate::i18n::Language;
+use crate::storage::History;
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::path::Path;
+
+/// Render records into a static site under `dir`
+///
+/// - `index.html` — all properties, with client-side filters and sorting
+/// - `property/<id>.html` — one page per property with photos and a map
+/// - `images/` — copies of downloaded photos, so the site is self-contained
+///
+/// Pages are written in `language`. Returns the number of property pages written.
+pub async fn write_site(
+    records: &[ExportRecord<'_>],
+    history: &History,
+    dir: &Path,
+    language: Language,
+) -> Result<usize> {
+    let pages = dir.join("property");
+    let images = dir.join("images");
+    for path in [&pages, &images] {
+        tokio::fs::create_dir_all(path)
+            .await
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+    }
+
+    let mut rows = Vec::with_capacity(records.len());
+    for record in records {
+        let property = record.property;
+
+        // Photos downloaded into the image cache are copied in; otherwise link the originals
+        let mut photos = Vec::new();
+        for path in &property.local_images {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            let target = images.join(name);
+            if !tokio::fs::try_exists(&target).await.unwrap_or(false) {
+                tokio::fs::copy(path, &target)
+                    .await
+                    .with_context(|| format!("Failed to copy {}", path.display()))?;
+            }
+            photos.push(format!("../images/{}", name.to_string_lossy()));
+        }
+        if photos.is_empty() {
+            photos = property.photos().map(|image| image.url.clone()).collect();
+        }
+
+        let options = ReportOptions {
+            photos: photos.clone(),
+            map: true,
+            back_link: Some("../index.html".to_string()),
+            language,
+        };
+        let html = property_report(record, history.get(&property.id), &options);
+        let page = pages.join(format!("{}.html", file_safe(&property.id)));
+        tokio::fs::write(&page, html)
+            .await
+            .with_context(|| format!("Failed to write {}", page.display()))?;
+
+        rows.push(json!({
+            "id": property.id,
+            "page": format!("property/{}.html", file_safe(&property.id)),
+            "address": property.address,
+            "area": property.location.area,
+            "price": property.price.map(|m| m.major()),
+            "rooms": property.rooms,
+            "sqm": property.sqm,
+            "fee": property.monthly_fee.map(|m| m.major()),
+            "pricePerSqm": record.metrics.price_per_sqm.map(f64::round),
+            "monthlyCost": record.metrics.monthly_cost,
+            "score": (record.score.total * 100.0).round() / 100.0,
+            "underpriced": record.anomaly.is_some(),
+            "thumb": photos.first().map(|p| p.trim_start_matches("../").to_string()),
+        }));
+    }
+
+    // Closing tags inside the embedded JSON would end the script element
+    let data = serde_json::to_string(&rows)?.replace("</", "<\\/");
+    let mut index = INDEX_TEMPLATE.replace("{{lang}}", language.code());
+    for key in INDEX_TEXTS {
+        index = index.replace(&format!("{{{{{}}}}}", key), &escape_html(language.t(key)));
+    }
+    let shown = language.format("site.shown", &[("count", &records.len())]);
+    let index = index
+        .replace("{{site.shown}}", &escape_html(&shown))
+        .replace("{{count}}", &records.len().to_string())
+        .replace("{{data}}", &data);
+    let index_path = dir.join("index.html");
+    tokio::fs::write(&index_path, index)
+        .await
+        .with_context(|| format!("Failed to write {}", index_path.display()))?;
+
+    Ok(records.len())
+}
+
+/// Texts of the index page, replaced by key
+const INDEX_TEXTS: &[&str] = &[
+    "site.search",
+    "site.search_placeholder",
+    "site.max_price",
+    "site.min_sqm",
+    "site.min_rooms",
+    "site.max_cost",
+    "site.underpriced",
+    "site.address",
+    "price",
+    "site.rooms",
+    "site.sqm",
+    "site.price_per_sqm",
+    "report.fee",
+    "site.monthly",
+    "score",
+];
+
+/// Property IDs come from listing URLs; keep them safe as file names
+fn file_safe(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+const INDEX_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="{{lang}}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Housing Scout</title>
+<style>
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #222; }
+.filters { display: flex; flex-wrap: wrap; gap: 1em; margin-bottom: 1em; }
+.filters label { display: flex; flex-direction: column; font-size: 0.85em; color: #555; }
+table { border-collapse: collapse; width: 100%; }
+th { cursor: pointer; text-align: left; border-bottom: 2px solid #ccc; }
+td, th { padding: 0.4em 0.8em 0.4em 0; vertical-align: middle; }
+tr:nth-child(even) td { background: #f7f7f7; }
+td.num { text-align: right; white-space: nowrap; }
+img.thumb { width: 96px; height: 64px; object-fit: cover; }
+.flag { color: #b00; }
+</style>
+</head>
+<body>
+<h1>Housing Scout</h1>
+<p><span id="shown">{{count}}</span> {{site.shown}}</p>
+<div class="filters">
+  <label>{{site.search}} <input id="q" type="search" placeholder="{{site.search_placeholder}}"></label>
+  <label>{{site.max_price}} <input id="maxPrice" type="number" step="100000"></label>
+  <label>{{site.min_sqm}} <input id="minSqm" type="number"></label>
+  <label>{{site.min_rooms}} <input id="minRooms" type="number" step="0.5"></label>
+  <label>{{site.max_cost}} <input id="maxCost" type="number" step="500"></label>
+  <label>{{site.underpriced}} <input id="underpriced" type="checkbox"></label>
+</div>
+<table>
+<thead><tr>
+  <th></th>
+  <th data-key="address">{{site.address}}</th>
+  <th data-key="price">{{price}}</th>
+  <th data-key="rooms">{{site.rooms}}</th>
+  <th data-key="sqm">{{site.sqm}}</th>
+  <th data-key="pricePerSqm">{{site.price_per_sqm}}</th>
+  <th data-key="fee">{{report.fee}}</th>
+  <th data-key="monthlyCost">{{site.monthly}}</th>
+  <th data-key="score">{{score}}</th>
+</tr></thead>
+<tbody id="rows"></tbody>
+</table>
+<script>
+const properties = {{data}};
+let sortKey = "score", descending = true;
+
+const fmt = (v) => v == null ? "" : Math.round(v).toLocaleString("sv-SE");
+const esc = (s) => String(s ?? "").replace(/[&<>"]/g, (c) => ({"&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;"}[c]));
+const num = (id) => { const v = document.getElementById(id).value; return v === "" ? null : Number(v); };
+
+function render() {
+  const q = document.getElementById("q").value.toLowerCase();
+  const maxPrice = num("maxPrice"), minSqm = num("minSqm"), minRooms = num("minRooms"), maxCost = num("maxCost");
+  const underpriced = document.getElementById("underpriced").checked;
+
+  const shown = properties
+    .filter((p) => !q || (p.address + " " + (p.area || "")).toLowerCase().includes(q))
+    .filter((p) => maxPrice == null || (p.price != null && p.price <= maxPrice))
+    .filter((p) => minSqm == null || (p.sqm != null && p.sqm >= minSqm))
+    .filter((p) => minRooms == null || (p.rooms != null && p.rooms >= minRooms))
+    .filter((p) => maxCost == null || (p.monthlyCost != null && p.monthlyCost <= maxCost))
+    .filter((p) => !underpriced || p.underpriced)
+    .sort((a, b) => {
+      const x = a[sortKey], y = b[sortKey];
+      if (x == null) return 1;
+      if (y == null) return -1;
+      const order = typeof x === "string" ? x.localeCompare(y, "sv") : x - y;
+      return descending ? -order : order;
+    });
+
+  document.getElementById("shown").textContent = shown.length;
+  document.getElementById("rows").innerHTML = shown.map((p) => `<tr>
+    <td>${p.thumb ? `<img class="thumb" src="${esc(p.thumb)}" alt="">` : ""}</td>
+    <td><a href="${esc(p.page)}">${esc(p.address)}</a>${p.underpriced ? ' <span class="flag">🔥</span>' : ""}<br><small>${esc(p.area)}</small></td>
+    <td class="num">${fmt(p.price)}</td>
+    <td class="num">${p.rooms ?? ""}</td>
+    <td class="num">${p.sqm ?? ""}</td>
+    <td class="num">${fmt(p.pricePerSqm)}</td>
+    <td class="num">${fmt(p.fee)}</td>
+    <td class="num">${fmt(p.monthlyCost)}</td>
+    <td class="num">${p.score.toFixed(2)}</td>
+  </tr>`).join("");
+}
+
+document.querySelectorAll("th[data-key]").forEach((th) => th.addEventListener("click", () => {
+  descending = sortKey === th.dataset.key ? !descending : th.dataset.key !== "address";
+  sortKey = th.dataset.key;
+  render();
+}));
+document.querySelectorAll(".filters input").forEach((input) => input.addEventListener("input", render));
+render();
+</script>
+</body>
+</html>
+"#;
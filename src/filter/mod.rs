@@ -0,0 +1,441 @@
+//! Filter expressions over export records
+//!
+//! ```text
+//! price < 5m && sqm >= 55 && amenities ~ "balcony" && area in ["Södermalm", "Hornstull"]
+//! ```
+//!
+//! Fields are paths into the exported JSON (`location.area`, `metrics.fee_per_sqm`);
+//! common nested fields also have short names, see [`ALIASES`]. Comparisons on
+//! a missing value are false, except `!=` and `== null`.
+
+mod parser;
+
+use crate::export::ExportRecord;
+use crate::geo::Shape;
+use crate::output::SearchOutput;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Short field names and the record paths they stand for
+pub const ALIASES: &[(&str, &str)] = &[
+    ("area", "location.area"),
+    ("amenities", "features.amenities"),
+    ("city", "location.city"),
+    ("latitude", "location.latitude"),
+    ("longitude", "location.longitude"),
+    ("price_per_sqm", "metrics.price_per_sqm"),
+    ("price_sek", "sek.price"),
+    ("fee_per_sqm", "metrics.fee_per_sqm"),
+    ("monthly_cost", "metrics.monthly_cost"),
+    ("score", "score.total"),
+    ("status", "annotation.status"),
+    ("favorite", "annotation.favorite"),
+    ("rating", "annotation.rating"),
+    ("station", "nearest_station.name"),
+    ("metro_distance", "nearest_station.walking_m"),
+    ("predicted_price", "prediction.predicted_price"),
+    ("underpriced", "anomaly"),
+    ("pipes", "renovation.pipes.status"),
+];
+
+/// A parsed filter expression; (de)serializes as its source text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Truthy(String),
+    Compare {
+        field: String,
+        comparison: Comparison,
+        value: Literal,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Case-insensitive substring, or any list element containing it
+    Contains,
+    NotContains,
+    In,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    List(Vec<Literal>),
+}
+
+impl Filter {
+    pub fn parse(source: &str) -> Result<Self> {
+        Ok(Self {
+            source: source.to_string(),
+            expr: parser::parse(source)?,
+        })
+    }
+
+    pub fn matches(&self, record: &ExportRecord<'_>) -> bool {
+        match serde_json::to_value(record) {
+            Ok(value) => self.matches_value(&value),
+            Err(_) => false,
+        }
+    }
+
+    /// Evaluate against an already serialized record
+    pub fn matches_value(&self, record: &Value) -> bool {
+        self.expr.eval(record)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl TryFrom<String> for Filter {
+    type Error = anyhow::Error;
+
+    fn try_from(source: String) -> Result<Self> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Filter> for String {
+    fn from(filter: Filter) -> Self {
+        filter.source
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> Result<Self> {
+        Self::parse(source)
+    }
+}
+
+impl Expr {
+    fn eval(&self, record: &Value) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(record) && b.eval(record),
+            Expr::Or(a, b) => a.eval(record) || b.eval(record),
+            Expr::Not(inner) => !inner.eval(record),
+            Expr::Truthy(field) => truthy(&lookup(record, field)),
+            Expr::Compare { field, comparison, value } => compare(&lookup(record, field), *comparison, value),
+        }
+    }
+}
+
+/// Value of a field; money amounts read as whole units, so `price < 5m` works
+fn lookup<'a>(record: &'a Value, field: &str) -> Cow<'a, Value> {
+    let value = find(record, field);
+    match value.get("amount_minor").and_then(Value::as_i64) {
+        Some(minor) => Cow::Owned(Value::from(minor as f64 / 100.0)),
+        None => Cow::Borrowed(value),
+    }
+}
+
+fn find<'a>(record: &'a Value, field: &str) -> &'a Value {
+    let path = match record.get(field) {
+        Some(value) => return value,
+        None => ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == field)
+            .map(|(_, path)| *path)
+            .unwrap_or(field),
+    };
+    path.split('.')
+        .try_fold(record, |value, key| value.get(key))
+        .unwrap_or(&Value::Null)
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn compare(actual: &Value, comparison: Comparison, expected: &Literal) -> bool {
+    match comparison {
+        Comparison::Eq => equals(actual, expected),
+        Comparison::Ne => !equals(actual, expected),
+        Comparison::Contains => contains(actual, expected),
+        Comparison::NotContains => !actual.is_null() && !contains(actual, expected),
+        Comparison::In => match expected {
+            Literal::List(items) => items.iter().any(|item| equals(actual, item)),
+            other => equals(actual, other),
+        },
+        Comparison::Lt | Comparison::Le | Comparison::Gt | Comparison::Ge => {
+            let ordering = match (actual, expected) {
+                (Value::Number(n), Literal::Number(x)) => n.as_f64().and_then(|n| n.partial_cmp(x)),
+                // Lexical, so ISO dates compare chronologically
+                (Value::String(s), Literal::Str(x)) => Some(s.as_str().cmp(x.as_str())),
+                _ => None,
+            };
+            ordering.is_some_and(|ordering| match comparison {
+                Comparison::Lt => ordering.is_lt(),
+                Comparison::Le => ordering.is_le(),
+                Comparison::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+    }
+}
+
+fn equals(actual: &Value, expected: &Literal) -> bool {
+    match (actual, expected) {
+        (Value::Null, Literal::Null) => true,
+        (Value::Number(n), Literal::Number(x)) => n.as_f64() == Some(*x),
+        (Value::String(s), Literal::Str(x)) => s.to_lowercase() == x.to_lowercase(),
+        (Value::Bool(b), Literal::Bool(x)) => b == x,
+        (Value::Array(items), Literal::List(expected)) => {
+            items.len() == expected.len() && items.iter().zip(expected).all(|(a, e)| equals(a, e))
+        }
+        _ => false,
+    }
+}
+
+fn contains(actual: &Value, needle: &Literal) -> bool {
+    match actual {
+        Value::String(s) => match needle {
+            Literal::Str(needle) => s.to_lowercase().contains(&needle.to_lowercase()),
+            _ => false,
+        },
+        Value::Array(items) => items.iter().any(|item| equals(item, needle) || contains(item, needle)),
+        _ => false,
+    }
+}
+
+/// A listing age limit such as "3d", "12h" or "2w"; (de)serializes as its text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MaxAge(chrono::Duration);
+
+impl MaxAge {
+    /// Whether the record's listing went on the market within the limit
+    pub fn admits(&self, record: &ExportRecord<'_>) -> bool {
+        chrono::Utc::now() - record.listed_since <= self.0
+    }
+}
+
+impl std::str::FromStr for MaxAge {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let number: i64 = number
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Expected an age like 3d, 12h or 2w, got {:?}", text))?;
+        let duration = match unit.trim() {
+            "h" => chrono::Duration::hours(number),
+            "d" | "" => chrono::Duration::days(number),
+            "w" => chrono::Duration::weeks(number),
+            other => anyhow::bail!("Unknown age unit {:?}; use h, d or w", other),
+        };
+        Ok(Self(duration))
+    }
+}
+
+impl TryFrom<String> for MaxAge {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        text.parse()
+    }
+}
+
+impl From<MaxAge> for String {
+    fn from(age: MaxAge) -> Self {
+        age.to_string()
+    }
+}
+
+impl fmt::Display for MaxAge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hours = self.0.num_hours();
+        match (hours % 24, hours / 24) {
+            (0, days) if days % 7 == 0 && days > 0 => write!(f, "{}w", days / 7),
+            (0, days) => write!(f, "{}d", days),
+            _ => write!(f, "{}h", hours),
+        }
+    }
+}
+
+/// A named search kept in scout.toml, usable with `--search <name>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(default)]
+    pub filter: Option<Filter>,
+    /// Only listings with coordinates inside this shape match
+    #[serde(default)]
+    pub area: Option<Shape>,
+    /// Words the listing's description must or must not contain
+    #[serde(default, skip_serializing_if = "KeywordRules::is_empty")]
+    pub keywords: KeywordRules,
+    /// Notify about new listings matching this search, whatever their score
+    #[serde(default)]
+    pub notify: bool,
+    /// Notification channels, by name, that get this search's listings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+    /// Files and databases this search's listings are written to after every scrape
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<SearchOutput>,
+}
+
+impl SavedSearch {
+    pub fn matches(&self, record: &ExportRecord<'_>) -> bool {
+        let inside = match &self.area {
+            Some(shape) => record.property.location.point().is_some_and(|point| shape.contains(point)),
+            None => true,
+        };
+        inside
+            && self.filter.as_ref().is_none_or(|filter| filter.matches(record))
+            && self.keywords.matches(&record.property.description)
+    }
+}
+
+/// Keywords looked for in a listing's description, case-insensitively
+///
+/// Descriptions are complete only after the detail pages are read, so these
+/// are best left to searches used once a scrape has finished.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeywordRules {
+    /// Every one of these must appear: ["kakelugn"]
+    pub require: Vec<String>,
+    /// None of these may appear: ["dödsbo auktion"]
+    pub exclude: Vec<String>,
+}
+
+impl KeywordRules {
+    pub fn is_empty(&self) -> bool {
+        self.require.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, description: &str) -> bool {
+        let description = description.to_lowercase();
+        let found = |keyword: &String| description.contains(&keyword.to_lowercase());
+        self.require.iter().all(found) && !self.exclude.iter().any(found)
+    }
+
+    /// The required keywords, which a matching description contains
+    pub fn found<'a>(&'a self, description: &str) -> Vec<&'a str> {
+        let description = description.to_lowercase();
+        self.require
+            .iter()
+            .filter(|keyword| description.contains(&keyword.to_lowercase()))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matches(filter: &str, record: &Value) -> bool {
+        Filter::parse(filter).unwrap().matches_value(record)
+    }
+
+    fn error(filter: &str) -> String {
+        Filter::parse(filter).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        let record = json!({ "a": true, "b": true, "c": false });
+        assert!(matches("a || b && c", &record));
+        assert!(!matches("(a || b) && c", &record));
+        assert!(matches("c || a && b", &record));
+        assert!(!matches("!a", &record));
+        assert!(matches("!c && a", &record));
+        assert!(!matches("!(c || a)", &record));
+        assert!(matches("not c and (a or c)", &record));
+    }
+
+    #[test]
+    fn number_shorthands() {
+        let record = json!({ "price": 4_500_000, "fee": 500_000 });
+        assert!(matches("price < 5m", &record));
+        assert!(matches("price == 4_500_000", &record));
+        assert!(matches("price == 4.5m", &record));
+        assert!(matches("fee == 500k", &record));
+        assert!(!matches("price < 4_000k", &record));
+    }
+
+    #[test]
+    fn lists() {
+        let record = json!({ "area": "Södermalm", "tags": ["Balkong", "Hiss"] });
+        assert!(matches(r#"area in ["Hornstull", "södermalm"]"#, &record));
+        assert!(!matches(r#"area in ["Hornstull"]"#, &record));
+        assert!(matches(r#"tags ~ "balk""#, &record));
+        assert!(matches(r#"tags ~ "hiss""#, &record));
+        assert!(!matches(r#"tags ~ "bastu""#, &record));
+        assert!(matches(r#"tags !~ "bastu""#, &record));
+        assert!(!matches(r#"tags !~ "Hiss""#, &record));
+    }
+
+    #[test]
+    fn missing_fields() {
+        let record = json!({ "sqm": 55 });
+        assert!(matches("rooms != 2", &record));
+        assert!(matches("rooms == null", &record));
+        assert!(!matches("rooms == 2", &record));
+        assert!(!matches("rooms < 2", &record));
+        assert!(!matches("rooms >= 2", &record));
+        assert!(!matches(r#"rooms ~ "2""#, &record));
+        assert!(!matches(r#"rooms !~ "2""#, &record));
+        assert!(!matches("rooms", &record));
+        assert!(!matches("sqm == null", &record));
+    }
+
+    #[test]
+    fn aliases_and_money() {
+        let record = json!({
+            "location": { "area": "Hornstull", "city": "Stockholm" },
+            "price": { "amount_minor": 425_000_000, "currency": "SEK" },
+        });
+        assert!(matches(r#"area == "hornstull""#, &record));
+        assert!(matches(r#"location.area == "Hornstull""#, &record));
+        assert!(matches("price == 4_250_000", &record));
+        assert!(matches("price < 5m && price > 4m", &record));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error(r#"area == "Söder"#), "Unterminated string starting at position 8 in filter");
+        assert_eq!(error("sqm > 50 60"), "Unexpected 60 at position 9 in filter");
+        assert_eq!(error("sqm > 50 )"), "Unexpected `)` at position 9 in filter");
+        assert_eq!(error("sqm >"), "Expected a value at the end of the filter");
+    }
+}
@@ -0,0 +1,271 @@
+use super::{Comparison, Expr, Literal};
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(word) => write!(f, "`{}`", word),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Str(s) => write!(f, "{:?}", s),
+            Token::Op(op) => write!(f, "`{}`", op),
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::LBracket => f.write_str("`[`"),
+            Token::RBracket => f.write_str("`]`"),
+            Token::Comma => f.write_str("`,`"),
+        }
+    }
+}
+
+/// Operators, longest first so `<=` isn't read as `<`
+const OPERATORS: &[&str] = &["&&", "||", "==", "!=", "<=", ">=", "!~", "<", ">", "~", "!"];
+
+pub(super) fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.or()?;
+    if let Some((token, at)) = parser.peek_at() {
+        bail!("Unexpected {} at position {} in filter", token, at);
+    }
+    Ok(expr)
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (at, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let single = match c {
+            '(' => Some(Token::LParen),
+            ')' => Some(Token::RParen),
+            '[' => Some(Token::LBracket),
+            ']' => Some(Token::RBracket),
+            ',' => Some(Token::Comma),
+            _ => None,
+        };
+        if let Some(token) = single {
+            tokens.push((token, at));
+            i += 1;
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated string starting at position {} in filter", at),
+                    Some((_, '\\')) if i + 1 < chars.len() => {
+                        text.push(chars[i + 1].1);
+                        i += 2;
+                    }
+                    Some((_, q)) if *q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some((_, other)) => {
+                        text.push(*other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((Token::Str(text), at));
+            continue;
+        }
+
+        if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|(_, d)| d.is_ascii_digit())) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.' || chars[i].1 == '_') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().map(|(_, c)| c).filter(|c| **c != '_').collect();
+            let Ok(mut number) = text.parse::<f64>() else {
+                bail!("Invalid number {:?} at position {} in filter", text, at);
+            };
+            // Allow 500k / 5m shorthands for prices
+            if let Some((_, suffix @ ('k' | 'm'))) = chars.get(i) {
+                if !chars.get(i + 1).is_some_and(|(_, c)| c.is_alphanumeric()) {
+                    number *= if *suffix == 'k' { 1_000.0 } else { 1_000_000.0 };
+                    i += 1;
+                }
+            }
+            tokens.push((Token::Number(number), at));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '.') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().map(|(_, c)| c).collect();
+            let token = match word.as_str() {
+                "and" => Token::Op("&&"),
+                "or" => Token::Op("||"),
+                "not" => Token::Op("!"),
+                _ => Token::Ident(word),
+            };
+            tokens.push((token, at));
+            continue;
+        }
+
+        let rest: String = chars[i..].iter().take(2).map(|(_, c)| c).collect();
+        let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) else {
+            bail!("Unexpected character {:?} at position {} in filter", c, at);
+        };
+        tokens.push((Token::Op(op), at));
+        i += op.chars().count();
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self) -> Option<(&Token, usize)> {
+        self.tokens.get(self.pos).map(|(token, at)| (token, *at))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expected(&self, what: &str) -> anyhow::Error {
+        match self.peek_at() {
+            Some((token, at)) => anyhow::anyhow!("Expected {} at position {} in filter, found {}", what, at, token),
+            None => anyhow::anyhow!("Expected {} at the end of the filter", what),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Op("||")) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::Op("&&")) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Op("!")) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let expr = self.or()?;
+            if !self.eat(&Token::RParen) {
+                return Err(self.expected("`)`"));
+            }
+            return Ok(expr);
+        }
+
+        let Some(Token::Ident(field)) = self.peek().cloned() else {
+            return Err(self.expected("a field name"));
+        };
+        self.pos += 1;
+
+        let comparison = match self.peek() {
+            Some(Token::Op("==")) => Comparison::Eq,
+            Some(Token::Op("!=")) => Comparison::Ne,
+            Some(Token::Op("<")) => Comparison::Lt,
+            Some(Token::Op("<=")) => Comparison::Le,
+            Some(Token::Op(">")) => Comparison::Gt,
+            Some(Token::Op(">=")) => Comparison::Ge,
+            Some(Token::Op("~")) => Comparison::Contains,
+            Some(Token::Op("!~")) => Comparison::NotContains,
+            Some(Token::Ident(word)) if word == "in" => Comparison::In,
+            // A bare field tests whether it is set, true and non-empty
+            _ => return Ok(Expr::Truthy(field)),
+        };
+        self.pos += 1;
+
+        let value = if comparison == Comparison::In {
+            self.list()?
+        } else {
+            self.literal()?
+        };
+        Ok(Expr::Compare { field, comparison, value })
+    }
+
+    fn list(&mut self) -> Result<Literal> {
+        if !self.eat(&Token::LBracket) {
+            return Err(self.expected("`[`"));
+        }
+        let mut items = Vec::new();
+        if !self.eat(&Token::RBracket) {
+            loop {
+                items.push(self.literal()?);
+                if self.eat(&Token::RBracket) {
+                    break;
+                }
+                if !self.eat(&Token::Comma) {
+                    return Err(self.expected("`,` or `]`"));
+                }
+            }
+        }
+        Ok(Literal::List(items))
+    }
+
+    fn literal(&mut self) -> Result<Literal> {
+        if self.peek() == Some(&Token::LBracket) {
+            return self.list();
+        }
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Literal::Number(n)),
+            Some(Token::Str(s)) => Ok(Literal::Str(s)),
+            Some(Token::Ident(word)) if word == "true" => Ok(Literal::Bool(true)),
+            Some(Token::Ident(word)) if word == "false" => Ok(Literal::Bool(false)),
+            Some(Token::Ident(word)) if word == "null" => Ok(Literal::Null),
+            _ => {
+                self.pos -= 1;
+                Err(self.expected("a value"))
+            }
+        }
+    }
+}
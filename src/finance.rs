@@ -0,0 +1,135 @@
+use crate::models::Property;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Mortgage assumptions used to compute monthly costs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FinanceConfig {
+    /// Mortgage interest rate in percent, e.g. 3.5
+    pub interest_rate: f64,
+    /// Cash down payment in SEK; defaults to the minimum allowed by `max_ltv`
+    pub down_payment: Option<i64>,
+    /// Gross annual household income in SEK, used for the debt-to-income rule
+    pub gross_annual_income: Option<i64>,
+    /// Largest loan-to-value ratio banks will lend at (bolånetak)
+    pub max_ltv: f64,
+    /// Apply the extra 1% amortization when the loan exceeds 4.5x gross income
+    pub debt_to_income_rule: bool,
+    /// Subtract the 30% interest deduction (ränteavdrag) from the monthly interest
+    pub interest_deduction: bool,
+}
+
+impl Default for FinanceConfig {
+    fn default() -> Self {
+        Self {
+            interest_rate: 3.5,
+            down_payment: None,
+            gross_annual_income: None,
+            max_ltv: 0.85,
+            debt_to_income_rule: true,
+            interest_deduction: false,
+        }
+    }
+}
+
+/// Share of interest costs refunded through the tax return: 30% of the first
+/// 100 000 kr of yearly interest, 21% of the rest
+const INTEREST_DEDUCTION_RATE: f64 = 0.30;
+const INTEREST_DEDUCTION_REDUCED_RATE: f64 = 0.21;
+const INTEREST_DEDUCTION_THRESHOLD: f64 = 100_000.0;
+
+/// Loan above this multiple of gross income triggers the stricter amortization rule
+const DEBT_TO_INCOME_LIMIT: f64 = 4.5;
+
+/// Monthly cost of owning a property, in whole SEK
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MonthlyCost {
+    pub down_payment: f64,
+    pub loan: f64,
+    /// Loan-to-value ratio, 0.0 - 1.0
+    pub ltv: f64,
+    /// Required yearly amortization in percent of the loan
+    pub amortization_rate: f64,
+    pub amortization: f64,
+    pub interest: f64,
+    pub fee: f64,
+    /// Interest + amortization + fee
+    pub total: f64,
+    /// Amount the configured down payment falls short of the bolånetak minimum
+    pub down_payment_shortfall: Option<f64>,
+}
+
+/// Required yearly amortization in percent under the Swedish amortization rules
+///
+/// - LTV above 70%: 2%
+/// - LTV 50–70%: 1%
+/// - LTV below 50%: none
+/// - plus 1% when the loan exceeds 4.5x gross annual income
+pub fn amortization_rate(ltv: f64, loan: f64, finance: &FinanceConfig) -> f64 {
+    let mut rate = if ltv > 0.70 {
+        2.0
+    } else if ltv > 0.50 {
+        1.0
+    } else {
+        0.0
+    };
+
+    if finance.debt_to_income_rule {
+        if let Some(income) = finance.gross_annual_income.filter(|income| *income > 0) {
+            if loan > income as f64 * DEBT_TO_INCOME_LIMIT {
+                rate += 1.0;
+            }
+        }
+    }
+
+    rate
+}
+
+impl MonthlyCost {
+    /// Monthly cost of buying `property` at its listed price
+    pub fn for_property(property: &Property, finance: &FinanceConfig) -> Option<Self> {
+        let price = property.price?;
+        Some(Self::compute(
+            price.as_f64(),
+            property.monthly_fee.map(|fee| fee.as_f64()).unwrap_or(0.0),
+            finance,
+        ))
+    }
+
+    pub fn compute(price: f64, fee: f64, finance: &FinanceConfig) -> Self {
+        let min_down_payment = (price * (1.0 - finance.max_ltv)).round();
+        let configured = finance.down_payment.map(|dp| dp as f64);
+
+        let down_payment = configured.unwrap_or(min_down_payment).max(min_down_payment).min(price);
+        let shortfall = configured
+            .filter(|dp| *dp < min_down_payment)
+            .map(|dp| min_down_payment - dp);
+
+        let loan = price - down_payment;
+        let ltv = if price > 0.0 { loan / price } else { 0.0 };
+
+        let amortization_rate = amortization_rate(ltv, loan, finance);
+        let amortization = loan * amortization_rate / 100.0 / 12.0;
+
+        let mut yearly_interest = loan * finance.interest_rate / 100.0;
+        if finance.interest_deduction {
+            let base = yearly_interest.min(INTEREST_DEDUCTION_THRESHOLD);
+            let excess = (yearly_interest - INTEREST_DEDUCTION_THRESHOLD).max(0.0);
+            yearly_interest -= base * INTEREST_DEDUCTION_RATE + excess * INTEREST_DEDUCTION_REDUCED_RATE;
+        }
+        let interest = yearly_interest / 12.0;
+
+        Self {
+            down_payment,
+            loan,
+            ltv,
+            amortization_rate,
+            amortization: amortization.round(),
+            interest: interest.round(),
+            fee,
+            total: (interest + amortization + fee).round(),
+            down_payment_shortfall: shortfall,
+        }
+    }
+}
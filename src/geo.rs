@@ -0,0 +1,113 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Mean earth radius used for distance calculations
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// A WGS84 coordinate
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Great-circle distance in meters
+    pub fn distance_m(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = (other.lat - self.lat).to_radians();
+        let dlon = (other.lon - self.lon).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+}
+
+/// A polygon as GeoJSON coordinates: rings of `[lon, lat]`, the first ring the
+/// outer boundary and any further rings holes
+pub type Polygon = Vec<Vec<[f64; 2]>>;
+
+/// A geographic area to search within
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Shape {
+    Circle { center: GeoPoint, radius_m: f64 },
+    Polygon { coordinates: Polygon },
+    /// Polygons read from a GeoJSON file: a Polygon or MultiPolygon geometry,
+    /// or a Feature / FeatureCollection of them
+    #[serde(rename = "geojson")]
+    GeoJson {
+        path: PathBuf,
+        #[serde(skip)]
+        polygons: Vec<Polygon>,
+    },
+}
+
+impl Shape {
+    /// Read the polygons of a `GeoJson` shape; other shapes need no loading
+    pub fn load(&mut self) -> Result<()> {
+        if let Shape::GeoJson { path, polygons } = self {
+            let text = std::fs::read_to_string(&*path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let json: Value =
+                serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+            *polygons = geojson_polygons(&json);
+            if polygons.is_empty() {
+                bail!("No Polygon or MultiPolygon geometry in {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, point: GeoPoint) -> bool {
+        match self {
+            Shape::Circle { center, radius_m } => center.distance_m(&point) <= *radius_m,
+            Shape::Polygon { coordinates } => polygon_contains(coordinates, point),
+            Shape::GeoJson { polygons, .. } => polygons.iter().any(|polygon| polygon_contains(polygon, point)),
+        }
+    }
+}
+
+fn polygon_contains(polygon: &Polygon, point: GeoPoint) -> bool {
+    let Some((outer, holes)) = polygon.split_first() else {
+        return false;
+    };
+    ring_contains(outer, point) && !holes.iter().any(|hole| ring_contains(hole, point))
+}
+
+/// Even-odd ray casting; close enough to planar at city scale
+fn ring_contains(ring: &[[f64; 2]], point: GeoPoint) -> bool {
+    let (x, y) = (point.lon, point.lat);
+    let mut inside = false;
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        if (a[1] > y) != (b[1] > y) && x < (b[0] - a[0]) * (y - a[1]) / (b[1] - a[1]) + a[0] {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn geojson_polygons(json: &Value) -> Vec<Polygon> {
+    let parse = |coordinates: &Value| serde_json::from_value::<Polygon>(coordinates.clone()).ok();
+    match json.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => json
+            .get("features")
+            .and_then(Value::as_array)
+            .map(|features| features.iter().flat_map(geojson_polygons).collect())
+            .unwrap_or_default(),
+        Some("Feature") => json.get("geometry").map(geojson_polygons).unwrap_or_default(),
+        Some("Polygon") => json.get("coordinates").and_then(parse).into_iter().collect(),
+        Some("MultiPolygon") => json
+            .get("coordinates")
+            .and_then(Value::as_array)
+            .map(|polygons| polygons.iter().filter_map(parse).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,366 @@
+//! English and Swedish wording for CLI output, reports and notifications
+//!
+//! Texts are looked up by key in [`MESSAGES`]; `{name}` placeholders are
+//! filled in by [`Language::format`]. Listing data such as addresses and
+//! descriptions stay as written, but amenities are shown in the reader's
+//! language via [`crate::models::Amenity::label`].
+
+use crate::models::{PipeStatus, Property, Renovation};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Language output is written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Sv,
+}
+
+impl Language {
+    /// Language code, as used in `<html lang>`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Sv => "sv",
+        }
+    }
+
+    /// Text for `key`; unknown keys come back unchanged
+    pub fn t<'a>(&self, key: &'a str) -> &'a str {
+        MESSAGES
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, en, sv)| match self {
+                Language::En => *en,
+                Language::Sv => *sv,
+            })
+            .unwrap_or(key)
+    }
+
+    /// Text for `key` with its `{name}` placeholders replaced
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut text = self.t(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    /// "2 rooms, 70 m²" from whichever of rooms and living area are known
+    pub fn size(&self, rooms: Option<f32>, sqm: Option<i32>) -> String {
+        let parts: Vec<String> = [
+            rooms.map(|r| self.format("rooms", &[("rooms", &r)])),
+            sqm.map(|s| self.format("sqm", &[("sqm", &s)])),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        parts.join(", ")
+    }
+
+    /// The price, or "no price yet" for listings without one
+    pub fn price(&self, property: &Property) -> String {
+        property
+            .price
+            .map(|price| price.to_string())
+            .unwrap_or_else(|| self.t("no_price_yet").to_string())
+    }
+
+    /// "5195000 kr, 2 rooms, 70 m²"
+    pub fn summary(&self, property: &Property) -> String {
+        let size = self.size(property.rooms, property.sqm);
+        if size.is_empty() {
+            self.price(property)
+        } else {
+            format!("{}, {}", self.price(property), size)
+        }
+    }
+
+    /// "pipes replaced 2015, kitchen 2019" from what the description told
+    pub fn renovation(&self, renovation: &Renovation) -> String {
+        let mut parts = Vec::new();
+        if renovation.needs_renovation {
+            parts.push(self.t("renovation.needed").to_string());
+        }
+        if let Some(pipes) = &renovation.pipes {
+            let key = match pipes.status {
+                PipeStatus::Done => "renovation.pipes_done",
+                PipeStatus::Planned => "renovation.pipes_planned",
+                PipeStatus::NotDone => "renovation.pipes_not_done",
+            };
+            let year = pipes.year.map(|year| format!(" {}", year)).unwrap_or_default();
+            parts.push(self.format(key, &[("year", &year)]));
+        }
+        if let Some(year) = renovation.kitchen_year {
+            parts.push(self.format("renovation.kitchen", &[("year", &year)]));
+        }
+        if let Some(year) = renovation.bathroom_year {
+            parts.push(self.format("renovation.bathroom", &[("year", &year)]));
+        }
+        parts.join(", ")
+    }
+
+    /// "yes" or "no"
+    pub fn yes_no(&self, value: bool) -> &'static str {
+        self.t(if value { "yes" } else { "no" })
+    }
+}
+
+/// Output languages per kind of output
+///
+/// Defaults keep notifications in Swedish, like the listings they announce;
+/// set everything to "en" to share digests with someone who reads English.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// `scout scrape`, `scout rank` and `scout search` output
+    pub cli: Language,
+    /// `scout report` and the pages of `scout export --format site`
+    pub reports: Language,
+    pub notifications: Language,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self {
+            cli: Language::En,
+            reports: Language::En,
+            notifications: Language::Sv,
+        }
+    }
+}
+
+/// Key, English, Swedish
+pub const MESSAGES: &[(&str, &str, &str)] = &[
+    // Shared
+    ("rooms", "{rooms} rooms", "{rooms} rum"),
+    ("sqm", "{sqm} m²", "{sqm} kvm"),
+    ("no_price_yet", "no price yet", "inget pris än"),
+    ("yes", "yes", "ja"),
+    ("no", "no", "nej"),
+    ("estimated", " (estimated)", " (uppskattat)"),
+    ("afternoon_sun", "afternoon sun", "eftermiddagssol"),
+    ("faces", "faces {direction}", "mot {direction}"),
+    ("vs_asking", "{premium}% vs asking", "{premium}% mot utgångspris"),
+    ("unknown", "unknown", "okänt"),
+    // Field labels
+    ("price", "Price", "Pris"),
+    ("size", "Size", "Storlek"),
+    ("price_per_sqm", "Price per m²", "Pris per kvm"),
+    ("monthly_fee", "Monthly fee", "Avgift"),
+    ("operating_cost", "Operating cost", "Driftkostnad"),
+    ("floor", "Floor", "Våning"),
+    ("current_bid", "Current bid", "Högsta bud"),
+    ("bidding_in_progress", "in progress", "pågår"),
+    ("predicted_price", "Predicted sale price", "Förväntat slutpris"),
+    ("floor_plan_area", "Floor plan area", "Yta enligt planritning"),
+    ("score", "Score", "Poäng"),
+    ("features", "Features", "Egenskaper"),
+    ("next_viewing", "Next viewing", "Nästa visning"),
+    ("broker", "Broker", "Mäklare"),
+    ("area", "Area", "Område"),
+    ("status", "Status", "Status"),
+    ("station", "Station", "Station"),
+    ("schools", "Schools", "Skolor"),
+    ("balcony", "Balcony", "Balkong"),
+    ("noise", "Noise", "Buller"),
+    ("broadband", "Broadband", "Bredband"),
+    ("association", "Association", "Förening"),
+    ("monthly_cost", "Monthly cost", "Månadskostnad"),
+    ("renovation", "Renovation", "Renovering"),
+    ("renovation.needed", "needs renovation", "renoveringsobjekt"),
+    ("renovation.pipes_done", "pipes replaced{year}", "stambytt{year}"),
+    ("renovation.pipes_planned", "pipe replacement planned{year}", "stambyte planerat{year}"),
+    ("renovation.pipes_not_done", "pipes not replaced", "ej stambytt"),
+    ("renovation.kitchen", "kitchen renovated {year}", "kök renoverat {year}"),
+    ("renovation.bathroom", "bathroom renovated {year}", "badrum renoverat {year}"),
+    // Property reports
+    ("report.all_properties", "← All properties", "← Alla bostäder"),
+    (
+        "report.below_median",
+        "🔥 {discount}% below the {cohort} median ({median} kr/m² across {count} listings)",
+        "🔥 {discount}% under medianen för {cohort} ({median} kr/kvm bland {count} bostäder)",
+    ),
+    ("report.debt_per_sqm", "Association debt", "Föreningens skuld"),
+    ("report.planned_renovations", "Planned renovations", "Planerade renoveringar"),
+    ("report.association_founded", "Association founded", "Föreningen bildad"),
+    ("report.new_association", "{year} (fresh conversion?)", "{year} (nyombildning?)"),
+    ("report.virtual_tour", "3D tour", "3D-visning"),
+    ("report.per_month", "{amount}/month", "{amount}/mån"),
+    ("report.kr_per_month", "{amount} kr/month", "{amount} kr/mån"),
+    (
+        "report.predicted",
+        "{price} kr ({delta}% asking vs predicted)",
+        "{price} kr (utgångspris {delta}% mot förväntat)",
+    ),
+    ("report.floor_plan_differs", "{sqm} m² (differs from listing)", "{sqm} kvm (skiljer sig från annonsen)"),
+    ("report.key_facts", "Key facts", "Fakta"),
+    ("report.down_payment", "Down payment", "Kontantinsats"),
+    ("report.loan", "Loan", "Lån"),
+    ("report.loan_ltv", "{loan} kr ({ltv}% LTV)", "{loan} kr ({ltv}% belåningsgrad)"),
+    ("report.interest", "Interest", "Ränta"),
+    ("report.amortization", "Amortization", "Amortering"),
+    ("report.amortization_rate", "{amount} kr/month ({rate}%)", "{amount} kr/mån ({rate}%)"),
+    ("report.fee", "Fee", "Avgift"),
+    ("report.total", "Total", "Totalt"),
+    ("report.down_payment_shortfall", "Down payment shortfall", "Saknad kontantinsats"),
+    ("report.nearest_station", "Nearest station", "Närmaste station"),
+    (
+        "report.station_walk",
+        "{name} ({kind}), ~{distance} m, {minutes} min walk",
+        "{name} ({kind}), ~{distance} m, {minutes} min promenad",
+    ),
+    ("report.nearby", "Nearby", "I närheten"),
+    (
+        "report.nearby_counts",
+        "{groceries} groceries, {gyms} gyms, {preschools} preschools, {parks} parks within {radius} m",
+        "{groceries} matbutiker, {gyms} gym, {preschools} förskolor, {parks} parker inom {radius} m",
+    ),
+    ("report.school", "School", "Skola"),
+    ("report.merit", "merit rating {merit}", "meritvärde {merit}"),
+    ("report.students_per_teacher", "{ratio} students/teacher", "{ratio} elever/lärare"),
+    ("report.flood_risk", "Flood risk", "Översvämningsrisk"),
+    ("report.location", "Location", "Läge"),
+    ("report.rating", "Rating", "Betyg"),
+    ("report.note", "Note", "Anteckning"),
+    ("report.my_notes", "My notes", "Mina anteckningar"),
+    ("report.price_history", "Price history", "Prishistorik"),
+    (
+        "report.relisted",
+        "Relisting of {ids}; the history starts with the first listing",
+        "Ny annons för {ids}; historiken börjar med den första annonsen",
+    ),
+    ("report.date", "Date", "Datum"),
+    ("report.bid", "Bid", "Bud"),
+    ("report.map", "Map", "Karta"),
+    ("report.photos", "Photos", "Bilder"),
+    ("report.scraped", "Scraped {time}", "Hämtad {time}"),
+    // Static site index
+    ("site.shown", "of {count} properties", "av {count} bostäder"),
+    ("site.search", "Search", "Sök"),
+    ("site.search_placeholder", "Address or area", "Adress eller område"),
+    ("site.max_price", "Max price", "Högsta pris"),
+    ("site.min_sqm", "Min m²", "Minsta kvm"),
+    ("site.min_rooms", "Min rooms", "Minsta antal rum"),
+    ("site.max_cost", "Max monthly cost", "Högsta månadskostnad"),
+    ("site.underpriced", "Under-priced only", "Bara lågt prissatta"),
+    ("site.address", "Address", "Adress"),
+    ("site.rooms", "Rooms", "Rum"),
+    ("site.sqm", "m²", "Kvm"),
+    ("site.price_per_sqm", "kr/m²", "kr/kvm"),
+    ("site.monthly", "Monthly", "Per månad"),
+    // Notifications
+    ("notify.new_listing", "🏠 New listing: {address}", "🏠 Ny bostad: {address}"),
+    ("notify.underpriced", "🔥 Low price: {address}", "🔥 Lågt pris: {address}"),
+    ("notify.price_change", "💸 Now {price}: {address}", "💸 Nu {price}: {address}"),
+    ("notify.digest_title", "📬 {count} listing updates", "📬 {count} bostadsnyheter"),
+    ("notify.bidding_started", "🔨 Bidding started: {address}", "🔨 Budgivning har börjat: {address}"),
+    ("notify.monthly_cost", "About {cost} kr/month", "Ca {cost} kr/mån"),
+    ("notify.keywords", "🔑 Mentions {keywords}", "🔑 Nämner {keywords}"),
+    (
+        "notify.below_median",
+        "{per_sqm} kr/m², {discount}% below the median for {cohort} ({median} kr/m²)",
+        "{per_sqm} kr/kvm, {discount}% under median för {cohort} ({median} kr/kvm)",
+    ),
+    ("notify.watch_title", "👀 {address} changed", "👀 {address} har ändrats"),
+    ("notify.health_title", "🐤 {source}: fewer details than usual", "🐤 {source}: färre uppgifter än vanligt"),
+    (
+        "notify.health_body",
+        "Run {run} may have hit changed markup:",
+        "Körning {run} kan ha stött på ändrad sidkod:",
+    ),
+    // `scout scrape` output
+    (
+        "scrape.underpriced",
+        "🔥 {count} listings priced well below comparable listings:",
+        "🔥 {count} bostäder till klart lägre pris än jämförbara:",
+    ),
+    (
+        "scrape.below_median",
+        "{address} - {per_sqm} kr/m², {discount}% below {cohort} median ({median} kr/m², {count} listings)",
+        "{address} - {per_sqm} kr/kvm, {discount}% under medianen för {cohort} ({median} kr/kvm, {count} bostäder)",
+    ),
+    (
+        "scrape.floor_plan_mismatch",
+        "⚠️  Floor plan states {plan} m², listing says {sqm} m²",
+        "⚠️  Planritningen anger {plan} kvm, annonsen {sqm} kvm",
+    ),
+    ("scrape.per_sqm", "{per_sqm} kr/m²", "{per_sqm} kr/kvm"),
+    ("scrape.fee", ", fee {fee}/month ({per_sqm} kr/m²)", ", avgift {fee}/mån ({per_sqm} kr/kvm)"),
+    ("scrape.operating_cost", ", operating cost {cost}/month", ", drift {cost}/mån"),
+    (
+        "scrape.monthly_cost",
+        "Monthly cost: {total} kr (interest {interest}, amortization {amortization} at {rate}%, fee {fee}), loan {loan} kr at {ltv}% LTV",
+        "Månadskostnad: {total} kr (ränta {interest}, amortering {amortization} à {rate}%, avgift {fee}), lån {loan} kr à {ltv}% belåningsgrad",
+    ),
+    (
+        "scrape.predicted",
+        "Predicted sale price: {price} kr ({delta}% asking vs predicted)",
+        "Förväntat slutpris: {price} kr (utgångspris {delta}% mot förväntat)",
+    ),
+    ("scrape.lowball", " - lowball asking price?", " - lockpris?"),
+    ("scrape.bidding", "Bidding in progress", "Budgivning pågår"),
+    ("scrape.bid_history", "Bid history", "Budhistorik"),
+    (
+        "scrape.relisted",
+        "🔁 Relisting of {id}, on the market since {date} ({days} days)",
+        "🔁 Ny annons för {id}, till salu sedan {date} ({days} dagar)",
+    ),
+    ("scrape.first_seen", ", first seen {date}", ", först sedd {date}"),
+    (
+        "scrape.shared_photos",
+        "📸 Shares {count} photos with {id} ({address}){first_seen} - relisted?",
+        "📸 Har {count} bilder gemensamt med {id} ({address}){first_seen} - ny annons?",
+    ),
+    ("scrape.found_in", "Found in searches", "Hittad i sökningarna"),
+    ("scrape.station_walk", "{name} ({kind}), ~{distance} m walk", "{name} ({kind}), ~{distance} m promenad"),
+    (
+        "scrape.nearby",
+        "Within {radius} m: {groceries} groceries, {gyms} gyms, {preschools} preschools, {parks} parks",
+        "Inom {radius} m: {groceries} matbutiker, {gyms} gym, {preschools} förskolor, {parks} parker",
+    ),
+    ("scrape.up_to", "{technologies} (up to {mbps} Mbit/s)", "{technologies} (upp till {mbps} Mbit/s)"),
+    ("scrape.debt_per_sqm", ", debt {debt} kr/m²", ", skuld {debt} kr/kvm"),
+    ("scrape.planned", "Planned: {works}", "Planerat: {works}"),
+    (
+        "scrape.new_association",
+        "⚠️  Newly formed association - a fresh conversion?",
+        "⚠️  Nybildad förening - nyombildning?",
+    ),
+    ("scrape.flood_risk", "⚠️  In a mapped flood risk zone", "⚠️  I ett karterat översvämningsområde"),
+    ("search.no_matches", "No listings match \"{query}\"", "Inga bostäder matchar \"{query}\""),
+    // `scout watch` changes
+    ("watch.price", "Price {before} → {after}", "Pris {before} → {after}"),
+    ("watch.first_bid", "First bid: {bid}", "Första budet: {bid}"),
+    ("watch.bid", "Highest bid {before} → {after}", "Högsta bud {before} → {after}"),
+    ("watch.bidding_started", "Bidding started", "Budgivningen har börjat"),
+    ("watch.bidding_closed", "Bidding closed", "Budgivningen är avslutad"),
+    ("watch.description", "Description changed:", "Beskrivningen har ändrats:"),
+    ("watch.removed", "Gone from Booli - sold or withdrawn?", "Borta från Booli - såld eller tillbakadragen?"),
+    ("watch.relisted", "Back on Booli", "Tillbaka på Booli"),
+    // `scout bot` replies
+    (
+        "bot.help",
+        "/search 2 rooms under 5M Södermalm - best listings of the latest run\n/search <saved search>\n/fav <id>, /unfav <id> - mark as favorite\n/hide <id>, /unhide <id> - leave out of searches\n/stats [area] - prices of the last 30 days",
+        "/search 2 rum under 5M Södermalm - bästa bostäderna i senaste körningen\n/search <sparad sökning>\n/fav <id>, /unfav <id> - markera som favorit\n/hide <id>, /unhide <id> - dölj i sökningar\n/stats [område] - priser de senaste 30 dagarna",
+    ),
+    ("bot.saved_searches", "Saved searches: {names}", "Sparade sökningar: {names}"),
+    ("bot.no_matches", "No listings match \"{query}\"", "Inga bostäder matchar \"{query}\""),
+    ("bot.matches", "{count} listings, the best {shown}:", "{count} bostäder, de {shown} bästa:"),
+    ("bot.unknown_listing", "No stored listing with ID {id}", "Ingen sparad bostad med id {id}"),
+    ("bot.favorited", "⭐ {address} is a favorite", "⭐ {address} är en favorit"),
+    ("bot.unfavorited", "{address} is no longer a favorite", "{address} är inte längre en favorit"),
+    ("bot.hidden", "🙈 {address} is hidden", "🙈 {address} är dold"),
+    ("bot.unhidden", "{address} is shown again", "{address} visas igen"),
+    ("bot.no_stats", "No listings seen in the last {days} days", "Inga bostäder sedda de senaste {days} dagarna"),
+    ("bot.stats_title", "📊 Last {days} days", "📊 Senaste {days} dagarna"),
+    ("bot.stats_listings", "{count} listings", "{count} bostäder"),
+    ("bot.median_price", "Median price {price} kr", "Medianpris {price} kr"),
+    (
+        "bot.not_allowed",
+        "This chat isn't allowed. To use the bot here, add {chat} to [bot] allowed_chats in scout.toml.",
+        "Den här chatten är inte tillåten. Lägg till {chat} i [bot] allowed_chats i scout.toml för att använda boten här.",
+    ),
+];
@@ -0,0 +1,146 @@
+use crate::images::ImageConfig;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use tracing::{debug, instrument, warn};
+
+/// A cached image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    path: PathBuf,
+    bytes: u64,
+}
+
+/// Content-addressed image store: files are named by the SHA-256 of their
+/// bytes, so the same photo listed under several URLs is stored once
+///
+/// `index.json` maps source URLs to files so repeat runs skip the download.
+pub struct ImageCache {
+    client: Client,
+    dir: PathBuf,
+    max_image_bytes: u64,
+    max_cache_bytes: u64,
+    index: Mutex<BTreeMap<String, Entry>>,
+}
+
+impl ImageCache {
+    pub async fn open(config: &ImageConfig) -> Result<Self> {
+        tokio::fs::create_dir_all(&config.cache_dir)
+            .await
+            .with_context(|| format!("Failed to create {}", config.cache_dir.display()))?;
+
+        let index_path = config.cache_dir.join("index.json");
+        let index = match tokio::fs::read_to_string(&index_path).await {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable image index {}: {}", index_path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        Ok(Self {
+            client: Client::builder()
+                .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+                .build()?,
+            dir: config.cache_dir.clone(),
+            max_image_bytes: config.max_image_bytes,
+            max_cache_bytes: config.max_cache_bytes,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Local path of `url`, downloading it unless cached; `None` when skipped by a size limit
+    #[instrument(name = "http", level = "debug", skip(self))]
+    pub async fn fetch(&self, url: &str) -> Result<Option<PathBuf>> {
+        if let Some(entry) = self.index.lock().await.get(url) {
+            if tokio::fs::try_exists(&entry.path).await.unwrap_or(false) {
+                return Ok(Some(entry.path.clone()));
+            }
+        }
+
+        if self.total_bytes().await >= self.max_cache_bytes {
+            debug!("Image cache full, skipping {}", url);
+            return Ok(None);
+        }
+
+        let mut response = self.client.get(url).send().await.context("Failed to send image request")?;
+        if !response.status().is_success() {
+            anyhow::bail!("Image request returned status: {}", response.status());
+        }
+        if response.content_length().is_some_and(|len| len > self.max_image_bytes) {
+            debug!("Skipping oversized image {}", url);
+            return Ok(None);
+        }
+
+        let extension = extension(url, response.headers().get("content-type").and_then(|v| v.to_str().ok()));
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            data.extend_from_slice(&chunk);
+            if data.len() as u64 > self.max_image_bytes {
+                debug!("Skipping oversized image {}", url);
+                return Ok(None);
+            }
+        }
+
+        let hash = format!("{:x}", Sha256::digest(&data));
+        let dir = self.dir.join(&hash[..2]);
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(format!("{}.{}", hash, extension));
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            tokio::fs::write(&path, &data)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        self.index.lock().await.insert(
+            url.to_string(),
+            Entry {
+                path: path.clone(),
+                bytes: data.len() as u64,
+            },
+        );
+        Ok(Some(path))
+    }
+
+    pub async fn save_index(&self) -> Result<()> {
+        let path = self.dir.join("index.json");
+        let json = serde_json::to_string_pretty(&*self.index.lock().await)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Number of distinct cached files
+    pub async fn file_count(&self) -> usize {
+        let index = self.index.lock().await;
+        index.values().map(|e| &e.path).collect::<std::collections::BTreeSet<_>>().len()
+    }
+
+    /// Size of all cached files; files shared by several URLs count once
+    pub async fn total_bytes(&self) -> u64 {
+        let index = self.index.lock().await;
+        let files: BTreeMap<&PathBuf, u64> = index.values().map(|e| (&e.path, e.bytes)).collect();
+        files.values().sum()
+    }
+}
+
+fn extension(url: &str, content_type: Option<&str>) -> &'static str {
+    match content_type.map(|t| t.split(';').next().unwrap_or(t).trim()) {
+        Some("image/png") => "png",
+        Some("image/webp") => "webp",
+        Some("image/gif") => "gif",
+        Some("image/avif") => "avif",
+        Some("image/jpeg") => "jpg",
+        _ => {
+            let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+            ["png", "webp", "gif", "avif"]
+                .into_iter()
+                .find(|ext| path.ends_with(&format!(".{}", ext)))
+                .unwrap_or("jpg")
+        }
+    }
+}
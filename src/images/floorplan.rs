@@ -0,0 +1,65 @@
+use crate::images::ImageConfig;
+use anyhow::{Context, Result};
+use std::path::Path;
+use tokio::process::Command;
+
+/// Plausible apartment sizes; smaller numbers on a plan are usually rooms
+const AREA_RANGE: std::ops::RangeInclusive<f32> = 10.0..=400.0;
+
+/// Read the stated living area from a floor plan image with tesseract
+pub async fn ocr_area(path: &Path, config: &ImageConfig) -> Result<Option<f32>> {
+    let output = Command::new(&config.tesseract)
+        .arg(path)
+        .arg("stdout")
+        .args(["-l", "swe+eng"])
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", config.tesseract))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            config.tesseract,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_plan_area(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Living area from floor plan text
+///
+/// Plans label every room with its area ("Kök 8 m²"), so an area labelled
+/// BOA/boarea/bostadsyta wins; otherwise the largest area is the total.
+pub fn parse_plan_area(text: &str) -> Option<f32> {
+    let lower = text.to_lowercase().replace('\u{a0}', " ");
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+
+    let mut labelled = None;
+    let mut largest: Option<f32> = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        // "62,5m²" or "62,5 m²"
+        let (number, unit) = match token.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.')) {
+            Some(0) => continue,
+            Some(pos) => (&token[..pos], &token[pos..]),
+            None => (*token, tokens.get(i + 1).copied().unwrap_or("")),
+        };
+        if !["m²", "m2", "kvm", "m^2"].iter().any(|u| unit.starts_with(u)) {
+            continue;
+        }
+        let Ok(area) = number.trim_end_matches(['.', ',']).replace(',', ".").parse::<f32>() else {
+            continue;
+        };
+        if !AREA_RANGE.contains(&area) {
+            continue;
+        }
+
+        let label = tokens[i.saturating_sub(3)..i].join(" ");
+        if labelled.is_none() && ["boa", "boarea", "bostadsyta", "living"].iter().any(|l| label.contains(l)) {
+            labelled = Some(area);
+        }
+        largest = Some(largest.map_or(area, |max| max.max(area)));
+    }
+
+    labelled.or(largest)
+}
@@ -0,0 +1,225 @@
+pub mod cache;
+pub mod floorplan;
+pub mod phash;
+
+pub use cache::ImageCache;
+
+use crate::models::{PhotoMatch, Property};
+use crate::storage::JsonStore;
+use phash::{hamming, MATCH_DISTANCE};
+use std::collections::BTreeMap;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+/// Settings for downloading listing photos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageConfig {
+    /// Download photos on every scrape (also enabled by `scrape --download-images`)
+    pub download: bool,
+    /// Content-addressed image cache directory
+    pub cache_dir: PathBuf,
+    /// Skip photos larger than this
+    pub max_image_bytes: u64,
+    /// Stop downloading once the cache holds this much
+    pub max_cache_bytes: u64,
+    /// Download at most this many photos per listing
+    pub max_per_property: usize,
+    /// Parallel downloads
+    pub concurrency: usize,
+    /// Read the living area off floor plans with tesseract OCR
+    pub ocr: bool,
+    /// tesseract executable, with Swedish language data installed
+    pub tesseract: String,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            download: false,
+            cache_dir: PathBuf::from("data/images"),
+            max_image_bytes: 10 * 1024 * 1024,
+            max_cache_bytes: 2 * 1024 * 1024 * 1024,
+            max_per_property: 20,
+            concurrency: 4,
+            ocr: false,
+            tesseract: "tesseract".to_string(),
+        }
+    }
+}
+
+/// Download every property's photos into the image cache and record their local paths
+pub async fn download_images(properties: &mut [Property], config: &ImageConfig) -> Result<()> {
+    let cache = Arc::new(ImageCache::open(config).await?);
+    let semaphore = Arc::new(Semaphore::new(config.concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for (idx, property) in properties.iter().enumerate() {
+        let photos = property.photos().take(config.max_per_property).map(|image| (false, &image.url));
+        let plans = property.floor_plans().map(|image| (true, &image.url));
+        for (order, (is_plan, url)) in photos.chain(plans).enumerate() {
+            let (cache, semaphore, url) = (cache.clone(), semaphore.clone(), url.clone());
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = cache.fetch(&url).await;
+                (idx, order, is_plan, url, result)
+            });
+        }
+    }
+
+    let mut downloaded: Vec<Vec<(usize, bool, PathBuf)>> = vec![Vec::new(); properties.len()];
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((idx, order, is_plan, url, result)) = joined else {
+            continue;
+        };
+        match result {
+            Ok(Some(path)) => downloaded[idx].push((order, is_plan, path)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to download {}: {}", url, e),
+        }
+    }
+
+    for (property, mut files) in properties.iter_mut().zip(downloaded) {
+        files.sort_by_key(|(order, _, _)| *order);
+        let (plans, photos): (Vec<_>, Vec<_>) = files.into_iter().partition(|(_, is_plan, _)| *is_plan);
+        property.local_images = photos.into_iter().map(|(_, _, path)| path).collect();
+        property.local_floor_plans = plans.into_iter().map(|(_, _, path)| path).collect();
+    }
+
+    if config.ocr {
+        read_floor_plans(properties, config).await;
+    }
+
+    cache.save_index().await?;
+    info!(
+        "🖼️  Image cache: {} files, {:.1} MB",
+        cache.file_count().await,
+        cache.total_bytes().await as f64 / 1_048_576.0
+    );
+    Ok(())
+}
+
+/// OCR each property's floor plans until one states the living area
+async fn read_floor_plans(properties: &mut [Property], config: &ImageConfig) {
+    for property in properties.iter_mut() {
+        for path in &property.local_floor_plans {
+            match floorplan::ocr_area(path, config).await {
+                Ok(Some(sqm)) => {
+                    property.floor_plan_sqm = Some(sqm);
+                    break;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    // A missing tesseract fails every file the same way
+                    warn!("Floor plan OCR failed: {}", e);
+                    return;
+                }
+            }
+        }
+        if property.floor_plan_mismatch() {
+            warn!(
+                "{}: floor plan says {} kvm but the listing says {} kvm",
+                property.address,
+                property.floor_plan_sqm.unwrap_or_default(),
+                property.sqm.unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// Perceptual hashes keyed by cache file name (the file's content hash)
+#[cfg(feature = "images")]
+const PHASH_CACHE: &str = "phash";
+
+/// Compute perceptual hashes of every property's downloaded photos
+#[cfg(feature = "images")]
+pub async fn hash_images(properties: &mut [Property], store: &JsonStore) -> Result<()> {
+    let mut cache: BTreeMap<String, String> = store.load_cache(PHASH_CACHE).await.unwrap_or_default();
+    let before = cache.len();
+
+    for property in properties.iter_mut() {
+        let mut hashes = Vec::with_capacity(property.local_images.len());
+        for path in &property.local_images {
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if let Some(hash) = cache.get(&key) {
+                hashes.push(hash.clone());
+                continue;
+            }
+
+            // Decoding and hashing is CPU bound; keep it off the async workers
+            let file = path.clone();
+            match tokio::task::spawn_blocking(move || phash::phash(&file)).await? {
+                Ok(hash) => {
+                    let hash = format!("{:016x}", hash);
+                    cache.insert(key, hash.clone());
+                    hashes.push(hash);
+                }
+                Err(e) => warn!("Failed to hash {}: {}", path.display(), e),
+            }
+        }
+        property.image_hashes = hashes;
+    }
+
+    if cache.len() > before {
+        store.save_cache(PHASH_CACHE, &cache).await?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "images"))]
+pub async fn hash_images(_properties: &mut [Property], _store: &JsonStore) -> Result<()> {
+    tracing::debug!("Photo hashing disabled; rebuild with --features images");
+    Ok(())
+}
+
+/// Link properties to other listings showing the same photos
+///
+/// Brokers relist apartments under new IDs to reset days on market; the new
+/// listing reuses most of the old photos. `known` holds previously stored
+/// listings; properties in the same run are compared against each other too.
+/// Listings must share at least half of the smaller photo set (and two photos)
+/// so a broker's stock photo of the neighbourhood alone doesn't link them.
+pub fn find_photo_matches(properties: &mut [Property], known: &[Property]) {
+    let parse = |p: &Property| -> Vec<u64> {
+        p.image_hashes.iter().filter_map(|h| u64::from_str_radix(h, 16).ok()).collect()
+    };
+
+    let mut candidates: BTreeMap<String, (String, Vec<u64>)> = known
+        .iter()
+        .map(|p| (p.id.clone(), (p.address.clone(), parse(p))))
+        .collect();
+    for property in properties.iter() {
+        candidates.insert(property.id.clone(), (property.address.clone(), parse(property)));
+    }
+
+    for property in properties.iter_mut() {
+        let own = parse(property);
+        if own.is_empty() {
+            continue;
+        }
+
+        property.photo_matches = candidates
+            .iter()
+            .filter(|(id, (_, hashes))| **id != property.id && !hashes.is_empty())
+            .filter_map(|(id, (address, hashes))| {
+                let shared = own
+                    .iter()
+                    .filter(|a| hashes.iter().any(|b| hamming(**a, *b) <= MATCH_DISTANCE))
+                    .count();
+                let required = (own.len().min(hashes.len()).div_ceil(2)).max(2);
+                (shared >= required).then(|| PhotoMatch {
+                    property_id: id.clone(),
+                    address: address.clone(),
+                    shared_photos: shared,
+                })
+            })
+            .collect();
+    }
+}
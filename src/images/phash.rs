@@ -0,0 +1,66 @@
+#[cfg(feature = "images")]
+use anyhow::{Context, Result};
+#[cfg(feature = "images")]
+use image::imageops::FilterType;
+#[cfg(feature = "images")]
+use std::f64::consts::PI;
+#[cfg(feature = "images")]
+use std::path::Path;
+
+/// Images are reduced to this many pixels per side before the DCT
+#[cfg(feature = "images")]
+const SIZE: usize = 32;
+/// Low-frequency DCT coefficients kept per side; 8 x 8 gives a 64-bit hash
+#[cfg(feature = "images")]
+const LOW: usize = 8;
+
+/// Hashes at most this many bits apart are treated as the same photo
+pub const MATCH_DISTANCE: u32 = 6;
+
+/// DCT-based perceptual hash of an image file
+///
+/// Robust to resizing, recompression and small edits, so the same photo
+/// uploaded again under a new listing hashes (nearly) identically.
+#[cfg(feature = "images")]
+pub fn phash(path: &Path) -> Result<u64> {
+    let img = image::open(path)
+        .with_context(|| format!("Failed to decode {}", path.display()))?
+        .resize_exact(SIZE as u32, SIZE as u32, FilterType::Triangle)
+        .to_luma8();
+    let pixels: Vec<f64> = img.pixels().map(|p| p[0] as f64).collect();
+
+    let mut cosines = [[0.0; SIZE]; LOW];
+    for (k, row) in cosines.iter_mut().enumerate() {
+        for (n, c) in row.iter_mut().enumerate() {
+            *c = ((2 * n + 1) as f64 * k as f64 * PI / (2 * SIZE) as f64).cos();
+        }
+    }
+
+    let mut coefficients = [0.0; LOW * LOW];
+    for v in 0..LOW {
+        for u in 0..LOW {
+            let mut sum = 0.0;
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    sum += pixels[y * SIZE + x] * cosines[u][x] * cosines[v][y];
+                }
+            }
+            coefficients[v * LOW + u] = sum;
+        }
+    }
+
+    // The DC term only reflects overall brightness; compare the rest to their median
+    let mut ac: Vec<f64> = coefficients[1..].to_vec();
+    ac.sort_by(f64::total_cmp);
+    let median = ac[ac.len() / 2];
+
+    Ok(coefficients
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| **c > median)
+        .fold(0u64, |hash, (i, _)| hash | 1 << i))
+}
+
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
@@ -0,0 +1,34 @@
+pub mod anomaly;
+pub mod bot;
+pub mod cli;
+pub mod commands;
+pub mod config;
+pub mod currency;
+pub mod diff;
+pub mod enrich;
+pub mod events;
+pub mod export;
+pub mod filter;
+pub mod finance;
+pub mod geo;
+pub mod i18n;
+pub mod images;
+pub mod metrics;
+pub mod models;
+pub mod notify;
+pub mod output;
+pub mod prediction;
+pub mod relist;
+pub mod scoring;
+pub mod schema;
+pub mod scrapers;
+pub mod scripts;
+pub mod search;
+pub mod secrets;
+pub mod server;
+pub mod shutdown;
+pub mod stats;
+pub mod storage;
+pub mod telemetry;
+pub mod templates;
+pub mod watch;
@@ -1,61 +1,50 @@
-mod models;
-mod scrapers;
-
-use scrapers::BooliBrowserScraper;
-use tracing::{info, Level};
-use tracing_subscriber;
+use clap::Parser;
+use housing_scout::cli::{Cli, Command, ScrapeArgs};
+use housing_scout::commands;
+use housing_scout::config::Config;
+use housing_scout::shutdown::{Interrupted, EXIT_INTERRUPTED};
+use housing_scout::telemetry;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
-        .init();
-
-    info!("🏠 Housing Scout - Booli Browser Scraper");
-    info!("==========================================");
-    info!("");
-
-    // Create browser scraper
-    let scraper = BooliBrowserScraper::new()?;
-
-    // Run scraper
-    info!("Starting browser-based scrape from Booli Södermalm...");
-    info!("This will visit each property page for detailed information");
-    info!("");
-    
-    let properties = scraper.scrape_sodermalm()?;
-
-    // Display results
-    info!("\n✅ Scraped {} properties\n", properties.len());
-
-    for (i, property) in properties.iter().enumerate() {
-        println!("{}. {} ({} kr)", i + 1, property.address, property.price);
-        println!("   {} rum, {} kvm", property.rooms, property.sqm);
-        if let Some(area) = &property.location.area {
-            println!("   Area: {}", area);
+    let cli = Cli::parse();
+
+    let config = Config::load(cli.config.as_deref())?;
+
+    // Initialize logging (stderr, so exports can be piped from stdout)
+    let telemetry = telemetry::init(cli.log_format, &config.telemetry)?;
+
+    let result = match cli.command.unwrap_or(Command::Scrape(ScrapeArgs::default())) {
+        Command::Scrape(args) => commands::scrape::run(&args, &config).await,
+        Command::Export(args) => commands::export::run(&args, &config).await,
+        Command::Brokers(args) => commands::brokers::run(&args).await,
+        Command::Stats(args) => commands::stats::run(&args, &config).await,
+        Command::Rank(args) => commands::rank::run(&args, &config).await,
+        Command::Search(args) => commands::search::run(&args, &config).await,
+        Command::Annotate(args) => commands::annotate::run(&args).await,
+        Command::Pipeline(args) => commands::pipeline::run(&args).await,
+        Command::Diff(args) => commands::diff::run(&args).await,
+        Command::Runs(args) => commands::runs::run(&args).await,
+        Command::Import(args) => commands::import::run(&args).await,
+        Command::Query(args) => commands::query::run(&args, &config).await,
+        Command::Report(args) => commands::report::run(&args, &config).await,
+        Command::Schema(args) => commands::schema::run(&args).await,
+        Command::Sold(args) => commands::sold::run(&args, &config).await,
+        Command::AddSearch(args) => commands::add_search::run(&args).await,
+        Command::Booli(args) => commands::booli::run(&args, &config).await,
+        Command::Secrets(args) => commands::secrets::run(&args).await,
+        Command::Discover(args) => commands::discover::run(&args, &config).await,
+        Command::Serve(args) => commands::serve::run(&args, &config).await,
+        Command::Bot => commands::bot::run(&config).await,
+        Command::Watch(args) => commands::watch::run(&args, &config).await,
+    };
+
+    if let Err(e) = &result {
+        if e.is::<Interrupted>() {
+            eprintln!("{}", e);
+            drop(telemetry);
+            std::process::exit(EXIT_INTERRUPTED);
         }
-        println!("   ID: {}", property.id);
-        println!("   Features: {}", property.features.join(", "));
-        println!("   URL: {}", property.url);
-        println!();
     }
-
-    // Save to main JSON file
-    let json = serde_json::to_string_pretty(&properties)?;
-    tokio::fs::write("scraped_properties.json", json).await?;
-    info!("💾 Saved all properties to scraped_properties.json");
-
-    // Save each property to separate file in raw_scrape/
-    tokio::fs::create_dir_all("raw_scrape").await?;
-    
-    for property in &properties {
-        let filename = format!("raw_scrape/{}.json", property.id);
-        let prop_json = serde_json::to_string_pretty(&property)?;
-        tokio::fs::write(&filename, prop_json).await?;
-    }
-    
-    info!("💾 Saved {} individual property files to raw_scrape/", properties.len());
-
-    Ok(())
+    result
 }
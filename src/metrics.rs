@@ -0,0 +1,27 @@
+use crate::finance::{FinanceConfig, MonthlyCost};
+use crate::models::Property;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Values derived from a property's raw fields
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PropertyMetrics {
+    pub price_per_sqm: Option<f64>,
+    pub fee_per_sqm: Option<f64>,
+    /// Interest, amortization and monthly fee, in SEK
+    pub monthly_cost: Option<f64>,
+}
+
+impl PropertyMetrics {
+    pub fn compute(property: &Property, finance: &FinanceConfig) -> Self {
+        let sqm = property.sqm.filter(|sqm| *sqm > 0).map(f64::from);
+        let price = property.price.map(|price| price.as_f64());
+        let fee = property.monthly_fee.map(|fee| fee.as_f64());
+
+        Self {
+            price_per_sqm: price.zip(sqm).map(|(price, sqm)| price / sqm),
+            fee_per_sqm: fee.zip(sqm).map(|(fee, sqm)| fee / sqm),
+            monthly_cost: MonthlyCost::for_property(property, finance).map(|cost| cost.total),
+        }
+    }
+}
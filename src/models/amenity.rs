@@ -0,0 +1,244 @@
+use super::Source;
+use crate::i18n::Language;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A listing feature recognised across sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Amenity {
+    Elevator,
+    Balcony,
+    FrenchBalcony,
+    Terrace,
+    Patio,
+    Garden,
+    Fireplace,
+    Sauna,
+    ParkingIncluded,
+    Garage,
+    Storage,
+    Laundry,
+    Dishwasher,
+}
+
+impl Amenity {
+    pub const ALL: [Amenity; 13] = [
+        Amenity::Elevator,
+        Amenity::Balcony,
+        Amenity::FrenchBalcony,
+        Amenity::Terrace,
+        Amenity::Patio,
+        Amenity::Garden,
+        Amenity::Fireplace,
+        Amenity::Sauna,
+        Amenity::ParkingIncluded,
+        Amenity::Garage,
+        Amenity::Storage,
+        Amenity::Laundry,
+        Amenity::Dishwasher,
+    ];
+
+    /// Name used in config and filters, e.g. "parking_included"
+    pub fn key(&self) -> &'static str {
+        match self {
+            Amenity::Elevator => "elevator",
+            Amenity::Balcony => "balcony",
+            Amenity::FrenchBalcony => "french_balcony",
+            Amenity::Terrace => "terrace",
+            Amenity::Patio => "patio",
+            Amenity::Garden => "garden",
+            Amenity::Fireplace => "fireplace",
+            Amenity::Sauna => "sauna",
+            Amenity::ParkingIncluded => "parking_included",
+            Amenity::Garage => "garage",
+            Amenity::Storage => "storage",
+            Amenity::Laundry => "laundry",
+            Amenity::Dishwasher => "dishwasher",
+        }
+    }
+
+    /// The label shown to readers of `language`; the Swedish ones are the
+    /// wording listings use, e.g. "Hiss"
+    pub fn label(&self, language: Language) -> &'static str {
+        match language {
+            Language::En => self.english_label(),
+            Language::Sv => self.swedish_label(),
+        }
+    }
+
+    fn english_label(&self) -> &'static str {
+        match self {
+            Amenity::Elevator => "Elevator",
+            Amenity::Balcony => "Balcony",
+            Amenity::FrenchBalcony => "French balcony",
+            Amenity::Terrace => "Terrace",
+            Amenity::Patio => "Patio",
+            Amenity::Garden => "Garden",
+            Amenity::Fireplace => "Fireplace",
+            Amenity::Sauna => "Sauna",
+            Amenity::ParkingIncluded => "Parking included",
+            Amenity::Garage => "Garage",
+            Amenity::Storage => "Storage room",
+            Amenity::Laundry => "Laundry room",
+            Amenity::Dishwasher => "Dishwasher",
+        }
+    }
+
+    fn swedish_label(&self) -> &'static str {
+        match self {
+            Amenity::Elevator => "Hiss",
+            Amenity::Balcony => "Balkong",
+            Amenity::FrenchBalcony => "Fransk balkong",
+            Amenity::Terrace => "Terrass",
+            Amenity::Patio => "Uteplats",
+            Amenity::Garden => "Trädgård",
+            Amenity::Fireplace => "Eldstad",
+            Amenity::Sauna => "Bastu",
+            Amenity::ParkingIncluded => "Parkering ingår",
+            Amenity::Garage => "Garage",
+            Amenity::Storage => "Förråd",
+            Amenity::Laundry => "Tvättstuga",
+            Amenity::Dishwasher => "Diskmaskin",
+        }
+    }
+
+    /// A balcony or terrace, as counted by scoring and sun exposure
+    pub fn is_outdoor_space(&self) -> bool {
+        matches!(self, Amenity::Balcony | Amenity::Terrace)
+    }
+
+    /// The amenity a source's label stands for, if any
+    ///
+    /// Labels are matched on their own keys first, then on the source's wording,
+    /// so "Balkong i västerläge" and the API's "balcony" both become
+    /// [`Amenity::Balcony`]. Negated labels ("Ej hiss") never match.
+    pub fn normalize(source: &Source, label: &str) -> Option<Self> {
+        let label = label.trim().to_lowercase();
+        if NEGATIONS.iter().any(|negation| label.starts_with(negation)) {
+            return None;
+        }
+        if let Some(amenity) = Self::ALL.into_iter().find(|a| a.key() == label) {
+            return Some(amenity);
+        }
+        wording(source)
+            .iter()
+            .find(|(word, _)| label.contains(word))
+            .map(|(_, amenity)| *amenity)
+    }
+}
+
+const NEGATIONS: &[&str] = &["ej ", "ingen ", "inget ", "saknar "];
+
+/// Lowercase words in a source's labels, most specific first
+fn wording(source: &Source) -> &'static [(&'static str, Amenity)] {
+    match source {
+        // Swedish brokers label amenities the way Booli does
+        Source::Booli | Source::Broker => BOOLI_WORDING,
+    }
+}
+
+const BOOLI_WORDING: &[(&str, Amenity)] = &[
+    ("hiss", Amenity::Elevator),
+    ("fransk balkong", Amenity::FrenchBalcony),
+    ("inglasad balkong", Amenity::Balcony),
+    ("balkong", Amenity::Balcony),
+    ("terrass", Amenity::Terrace),
+    ("uteplats", Amenity::Patio),
+    ("altan", Amenity::Patio),
+    ("trädgård", Amenity::Garden),
+    ("eldstad", Amenity::Fireplace),
+    ("öppen spis", Amenity::Fireplace),
+    ("kakelugn", Amenity::Fireplace),
+    ("braskamin", Amenity::Fireplace),
+    ("bastu", Amenity::Sauna),
+    ("garage", Amenity::Garage),
+    ("parkering", Amenity::ParkingIncluded),
+    ("p-plats", Amenity::ParkingIncluded),
+    ("förråd", Amenity::Storage),
+    ("tvättstuga", Amenity::Laundry),
+    ("tvättmaskin", Amenity::Laundry),
+    ("diskmaskin", Amenity::Dishwasher),
+];
+
+/// What a listing offers: recognised amenities, and labels that matched none
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct Features {
+    pub amenities: Vec<Amenity>,
+    /// Source labels with no [`Amenity`], as written
+    pub other: Vec<String>,
+}
+
+impl Features {
+    /// Sort a source's labels into amenities and the rest
+    pub fn normalize<S: AsRef<str>>(source: &Source, labels: impl IntoIterator<Item = S>) -> Self {
+        let mut features = Self::default();
+        for label in labels {
+            let label = label.as_ref().trim();
+            if label.is_empty() {
+                continue;
+            }
+            match Amenity::normalize(source, label) {
+                Some(amenity) => features.insert(amenity),
+                None if !features.other.iter().any(|o| o == label) => features.other.push(label.to_string()),
+                None => {}
+            }
+        }
+        features
+    }
+
+    pub fn insert(&mut self, amenity: Amenity) {
+        if !self.amenities.contains(&amenity) {
+            self.amenities.push(amenity);
+        }
+    }
+
+    pub fn has(&self, amenity: Amenity) -> bool {
+        self.amenities.contains(&amenity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amenities.is_empty() && self.other.is_empty()
+    }
+
+    /// Amenity labels in `language` followed by the other labels as written
+    pub fn labels(&self, language: Language) -> Vec<&str> {
+        self.amenities
+            .iter()
+            .map(|amenity| amenity.label(language))
+            .chain(self.other.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+impl From<Vec<Amenity>> for Features {
+    fn from(amenities: Vec<Amenity>) -> Self {
+        let mut features = Self::default();
+        for amenity in amenities {
+            features.insert(amenity);
+        }
+        features
+    }
+}
+
+impl<'de> Deserialize<'de> for Features {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            /// Free-text labels, as stored before amenities were typed; all from Booli
+            Labels(Vec<String>),
+            Typed {
+                #[serde(default)]
+                amenities: Vec<Amenity>,
+                #[serde(default)]
+                other: Vec<String>,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Labels(labels) => Features::normalize(&Source::Booli, labels),
+            Repr::Typed { amenities, other } => Features { amenities, other },
+        })
+    }
+}
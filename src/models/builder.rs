@@ -0,0 +1,266 @@
+use crate::models::{Features, Image, ListingStatus, Location, Money, Property, Source};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Living areas outside this range are parse errors, not apartments
+const SQM_RANGE: std::ops::RangeInclusive<i32> = 5..=1_000;
+const ROOMS_RANGE: std::ops::RangeInclusive<f32> = 0.5..=50.0;
+/// Prices above this are parse errors (e.g. concatenated numbers)
+const MAX_PRICE: i64 = 1_000_000_000;
+/// Bounding box of Sweden: south, north, west, east
+const SWEDEN_BOUNDS: (f64, f64, f64, f64) = (55.0, 69.1, 10.9, 24.2);
+
+/// One rule a scraped listing broke
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+/// A listing a scraper dropped because it failed validation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct InvalidListing {
+    pub id: String,
+    pub url: String,
+    pub errors: Vec<ValidationError>,
+}
+
+impl fmt::Display for InvalidListing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.errors.iter().map(|e| format!("{} {}", e.field, e.message)).collect();
+        write!(f, "Invalid listing {}: {}", self.id, errors.join(", "))
+    }
+}
+
+impl std::error::Error for InvalidListing {}
+
+/// Builds a [`Property`] and checks it before scrapers emit it
+///
+/// Unset values default to empty or unknown. `build` requires an ID, an
+/// address and a URL, a positive price unless the listing is coming soon,
+/// plausible sqm and rooms when known, and coordinates inside Sweden for
+/// Swedish sources.
+pub struct PropertyBuilder {
+    property: Property,
+}
+
+impl PropertyBuilder {
+    pub fn new(id: impl Into<String>, source: Source) -> Self {
+        Self {
+            property: Property {
+                id: id.into(),
+                source,
+                location: Location {
+                    city: String::new(),
+                    area: None,
+                    latitude: None,
+                    longitude: None,
+                },
+                address: String::new(),
+                price: None,
+                asking_price: None,
+                current_bid: None,
+                bidding_in_progress: false,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: None,
+                operating_cost: None,
+                rooms: None,
+                sqm: None,
+                floor: None,
+                description: String::new(),
+                features: Features::default(),
+                images: Vec::new(),
+                local_images: Vec::new(),
+                local_floor_plans: Vec::new(),
+                floor_plan_sqm: None,
+                image_hashes: Vec::new(),
+                photo_matches: Vec::new(),
+                viewings: Vec::new(),
+                virtual_tours: Vec::new(),
+                url: String::new(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: Vec::new(),
+                published_at: None,
+                run_id: None,
+                scraped_at: Utc::now(),
+                raw_data: serde_json::Value::Null,
+            },
+        }
+    }
+
+    pub fn location(mut self, location: Location) -> Self {
+        self.property.location = location;
+        self
+    }
+
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.property.address = address.into();
+        self
+    }
+
+    pub fn price(mut self, price: Option<Money>) -> Self {
+        self.property.price = price;
+        self
+    }
+
+    pub fn asking_price(mut self, asking_price: Option<Money>) -> Self {
+        self.property.asking_price = asking_price;
+        self
+    }
+
+    pub fn current_bid(mut self, current_bid: Option<Money>) -> Self {
+        self.property.current_bid = current_bid;
+        self
+    }
+
+    pub fn bidding_in_progress(mut self, in_progress: bool) -> Self {
+        self.property.bidding_in_progress = in_progress;
+        self
+    }
+
+    pub fn listing_status(mut self, status: ListingStatus) -> Self {
+        self.property.listing_status = status;
+        self
+    }
+
+    pub fn monthly_fee(mut self, fee: Option<Money>) -> Self {
+        self.property.monthly_fee = fee;
+        self
+    }
+
+    pub fn operating_cost(mut self, cost: Option<Money>) -> Self {
+        self.property.operating_cost = cost;
+        self
+    }
+
+    pub fn rooms(mut self, rooms: Option<f32>) -> Self {
+        self.property.rooms = rooms;
+        self
+    }
+
+    pub fn sqm(mut self, sqm: Option<i32>) -> Self {
+        self.property.sqm = sqm;
+        self
+    }
+
+    pub fn floor(mut self, floor: Option<f32>) -> Self {
+        self.property.floor = floor;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.property.description = description.into();
+        self
+    }
+
+    pub fn features(mut self, features: Features) -> Self {
+        self.property.features = features;
+        self
+    }
+
+    pub fn images(mut self, images: Vec<Image>) -> Self {
+        self.property.images = images;
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.property.url = url.into();
+        self
+    }
+
+    pub fn area_tags(mut self, tags: Vec<String>) -> Self {
+        self.property.area_tags = tags;
+        self
+    }
+
+    pub fn published_at(mut self, at: Option<DateTime<Utc>>) -> Self {
+        self.property.published_at = at;
+        self
+    }
+
+    pub fn scraped_at(mut self, at: DateTime<Utc>) -> Self {
+        self.property.scraped_at = at;
+        self
+    }
+
+    pub fn raw_data(mut self, raw: serde_json::Value) -> Self {
+        self.property.raw_data = raw;
+        self
+    }
+
+    /// Validate and return the property, or every rule it broke
+    pub fn build(self) -> Result<Property, InvalidListing> {
+        let errors = validate(&self.property);
+        if errors.is_empty() {
+            return Ok(self.property);
+        }
+        Err(InvalidListing {
+            id: self.property.id,
+            url: self.property.url,
+            errors,
+        })
+    }
+}
+
+/// Check a property that wasn't built here, e.g. one read from a file
+impl From<Property> for PropertyBuilder {
+    fn from(property: Property) -> Self {
+        Self { property }
+    }
+}
+
+fn validate(p: &Property) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut fail = |field: &str, message: String| {
+        errors.push(ValidationError {
+            field: field.to_string(),
+            message,
+        })
+    };
+
+    if p.id.trim().is_empty() || p.id == "unknown" {
+        fail("id", "is missing".to_string());
+    }
+    if p.address.trim().is_empty() {
+        fail("address", "is empty".to_string());
+    }
+    if !p.url.starts_with("http") {
+        fail("url", format!("{:?} is not a URL", p.url));
+    }
+    match p.price {
+        None if p.listing_status == ListingStatus::ComingSoon => {}
+        None => fail("price", "is missing and the listing is not coming soon".to_string()),
+        Some(price) if !price.is_positive() => fail("price", format!("{} is not positive", price)),
+        Some(price) if price.major() > MAX_PRICE => fail("price", format!("{} is implausibly high", price)),
+        Some(_) => {}
+    }
+    if let Some(sqm) = p.sqm.filter(|sqm| !SQM_RANGE.contains(sqm)) {
+        fail("sqm", format!("{} is outside {:?}", sqm, SQM_RANGE));
+    }
+    if let Some(rooms) = p.rooms.filter(|rooms| !ROOMS_RANGE.contains(rooms)) {
+        fail("rooms", format!("{} is outside {:?}", rooms, ROOMS_RANGE));
+    }
+    match (p.location.latitude, p.location.longitude) {
+        (Some(lat), Some(lon)) if p.source.is_domestic() => {
+            let (south, north, west, east) = SWEDEN_BOUNDS;
+            if !(south..=north).contains(&lat) || !(west..=east).contains(&lon) {
+                fail("location", format!("({}, {}) is outside Sweden", lat, lon));
+            }
+        }
+        (Some(_), None) | (None, Some(_)) => fail("location", "has only one coordinate".to_string()),
+        _ => {}
+    }
+
+    errors
+}
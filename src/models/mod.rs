@@ -1,14 +1,48 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+pub mod amenity;
+pub mod builder;
+pub mod money;
+
+pub use amenity::{Amenity, Features};
+pub use builder::{InvalidListing, PropertyBuilder, ValidationError};
+pub use money::{Currency, Money};
+
+use crate::geo::GeoPoint;
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::PathBuf;
 
 /// Source of the property listing
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub enum Source {
     Booli,
+    /// A broker's own site, read by a rules file; see
+    /// [`SiteRules`](crate::scrapers::site::SiteRules)
+    Broker,
+}
+
+impl Source {
+    /// Whether the source only lists Swedish properties
+    pub fn is_domestic(&self) -> bool {
+        match self {
+            // Rules files are written for Swedish brokers
+            Source::Booli | Source::Broker => true,
+        }
+    }
+}
+
+/// Whether a listing is open for bids yet
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingStatus {
+    #[default]
+    ForSale,
+    /// "Snart till salu": announced before viewings, often without a price
+    ComingSoon,
 }
 
 /// Location information for a property
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Location {
     pub city: String,
     pub area: Option<String>,
@@ -16,21 +50,509 @@ pub struct Location {
     pub longitude: Option<f64>,
 }
 
+impl Location {
+    /// Coordinates of the location, when both are known
+    pub fn point(&self) -> Option<GeoPoint> {
+        Some(GeoPoint::new(self.latitude?, self.longitude?))
+    }
+}
+
+/// Listing broker ("mäklare") and their agency
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Broker {
+    pub agency: Option<String>,
+    pub name: Option<String>,
+    pub phone: Option<String>,
+}
+
 /// Core property data model
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Property {
     pub id: String,
     pub source: Source,
     pub location: Location,
     pub address: String,
-    pub price: i64,
-    pub rooms: f32,
-    pub sqm: i32,
+    /// Unknown for coming-soon listings without a price yet
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub price: Option<Money>,
+    /// Asking price ("utgångspris") when stated separately from the listed price
+    #[serde(default)]
+    pub asking_price: Option<Money>,
+    /// Highest bid so far, when shown on the listing
+    #[serde(default)]
+    pub current_bid: Option<Money>,
+    /// Listing shows "Budgivning pågår"
+    #[serde(default)]
+    pub bidding_in_progress: bool,
+    /// Coming-soon listings may not have a price yet
+    #[serde(default)]
+    pub listing_status: ListingStatus,
+    /// Monthly association fee ("avgift")
+    #[serde(default)]
+    pub monthly_fee: Option<Money>,
+    /// Monthly operating cost ("driftkostnad") on top of the fee
+    #[serde(default)]
+    pub operating_cost: Option<Money>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub rooms: Option<f32>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub sqm: Option<i32>,
+    /// Floor ("våning"); half floors exist, hence the float
+    #[serde(default)]
+    pub floor: Option<f32>,
     pub description: String,
-    pub features: Vec<String>,
-    pub images: Vec<String>,
+    /// Amenities, normalised across sources; older stores held plain labels
+    pub features: Features,
+    /// Photos, floor plans and 3D tour stills in gallery order; older stores
+    /// held plain photo URLs
+    #[serde(deserialize_with = "images_or_urls")]
+    pub images: Vec<Image>,
+    /// Downloaded copies of the photos in the local image cache
+    #[serde(default)]
+    pub local_images: Vec<PathBuf>,
+    /// Downloaded copies of the floor plans
+    #[serde(default)]
+    pub local_floor_plans: Vec<PathBuf>,
+    /// Living area read from the floor plan by OCR
+    #[serde(default)]
+    pub floor_plan_sqm: Option<f32>,
+    /// Perceptual hashes of `local_images`, as hex
+    #[serde(default)]
+    pub image_hashes: Vec<String>,
+    /// Other listings showing the same photos, e.g. the same apartment relisted
+    #[serde(default)]
+    pub photo_matches: Vec<PhotoMatch>,
+    /// Upcoming viewing ("visning") start times
+    #[serde(default)]
+    pub viewings: Vec<DateTime<Utc>>,
+    /// Matterport and other virtual tour ("3D-visning") links
+    #[serde(default)]
+    pub virtual_tours: Vec<String>,
     pub url: String,
+    #[serde(default)]
+    pub broker: Option<Broker>,
+    /// The housing association ("bostadsrättsförening") the apartment is in
+    #[serde(default)]
+    pub association: Option<Association>,
+    /// Closest tunnelbana or pendeltåg station
+    #[serde(default)]
+    pub nearest_station: Option<NearestStation>,
+    /// Amenities within walking distance, from OpenStreetMap
+    #[serde(default)]
+    pub nearby: Option<NearbyPlaces>,
+    /// Closest grundskolor with Skolverket statistics, nearest first
+    #[serde(default)]
+    pub schools: Vec<NearbySchool>,
+    /// Road and rail noise at the building in dB(A), from the municipal noise map
+    #[serde(default)]
+    pub noise_db: Option<f64>,
+    /// Whether the property lies in a mapped flood risk zone
+    #[serde(default)]
+    pub flood_risk: Option<bool>,
+    /// Best-effort estimate of which way the balcony faces
+    #[serde(default)]
+    pub balcony_orientation: Option<BalconyOrientation>,
+    /// Broadband available at the address
+    #[serde(default)]
+    pub broadband: Option<Broadband>,
+    /// Renovation state read from the description, when it mentions any
+    #[serde(default)]
+    pub renovation: Option<Renovation>,
+    /// Names of the configured search areas the listing was found in
+    #[serde(default)]
+    pub area_tags: Vec<String>,
+    /// When the source published the listing; listings seen before being
+    /// scraped for the first time went on the market earlier than first seen
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+    /// ID of the scrape run that stored this version of the listing
+    #[serde(default)]
+    pub run_id: Option<String>,
     pub scraped_at: DateTime<Utc>,
     pub raw_data: serde_json::Value,
 }
 
+
+/// Largest difference between listed and floor plan area not worth flagging
+const FLOOR_PLAN_TOLERANCE_SQM: f32 = 2.0;
+
+impl Property {
+    /// Start building a validated property; see [`PropertyBuilder`]
+    pub fn builder(id: impl Into<String>, source: Source) -> PropertyBuilder {
+        PropertyBuilder::new(id, source)
+    }
+
+    /// Currency the listing is priced in, SEK when it has no amounts yet
+    pub fn currency(&self) -> Currency {
+        self.price.or(self.monthly_fee).map(|m| m.currency).unwrap_or_default()
+    }
+
+    /// Current bid relative to the asking price, in percent
+    ///
+    /// Falls back to the listed price when no explicit asking price was parsed.
+    pub fn bid_premium(&self) -> Option<f64> {
+        let bid = self.current_bid?;
+        let asking = self.asking_price.or(self.price)?;
+        if !asking.is_positive() || bid.currency != asking.currency {
+            return None;
+        }
+        Some((bid.as_f64() - asking.as_f64()) / asking.as_f64() * 100.0)
+    }
+
+    /// Listing photos, in order
+    pub fn photos(&self) -> impl Iterator<Item = &Image> {
+        self.images.iter().filter(|image| image.kind == ImageKind::Photo)
+    }
+
+    /// Floor plan images, in order
+    pub fn floor_plans(&self) -> impl Iterator<Item = &Image> {
+        self.images.iter().filter(|image| image.kind == ImageKind::FloorPlan)
+    }
+
+    /// Whether the area stated on the floor plan disagrees with the listed sqm
+    pub fn floor_plan_mismatch(&self) -> bool {
+        match (self.floor_plan_sqm, self.sqm) {
+            (Some(plan), Some(sqm)) => (plan - sqm as f32).abs() > FLOOR_PLAN_TOLERANCE_SQM,
+            _ => false,
+        }
+    }
+
+    /// "2 rum, 70 kvm", leaving out whatever is unknown
+    pub fn size_label(&self) -> String {
+        size_label(self.rooms, self.sqm)
+    }
+
+    /// The price, or "no price yet" for listings without one
+    pub fn price_label(&self) -> String {
+        self.price.map(|price| price.to_string()).unwrap_or_else(|| "no price yet".to_string())
+    }
+
+    /// "5195000 kr, 2 rum, 70 kvm"
+    pub fn summary(&self) -> String {
+        let size = self.size_label();
+        if size.is_empty() {
+            self.price_label()
+        } else {
+            format!("{}, {}", self.price_label(), size)
+        }
+    }
+}
+
+/// "2 rum, 70 kvm" from whichever of rooms and living area are known
+pub fn size_label(rooms: Option<f32>, sqm: Option<i32>) -> String {
+    let parts: Vec<String> = [rooms.map(|r| format!("{} rum", r)), sqm.map(|s| format!("{} kvm", s))]
+        .into_iter()
+        .flatten()
+        .collect();
+    parts.join(", ")
+}
+
+/// Values older data stored as 0 to mean "unknown"
+pub(crate) trait Unknown {
+    fn is_unknown(&self) -> bool;
+}
+
+impl Unknown for f32 {
+    fn is_unknown(&self) -> bool {
+        *self == 0.0
+    }
+}
+
+impl Unknown for i32 {
+    fn is_unknown(&self) -> bool {
+        *self == 0
+    }
+}
+
+impl Unknown for Money {
+    fn is_unknown(&self) -> bool {
+        self.amount_minor == 0
+    }
+}
+
+/// Read null or a legacy zero as `None`
+pub(crate) fn zero_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Unknown,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.filter(|value| !value.is_unknown()))
+}
+
+/// Kind of rapid transit station
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StationKind {
+    Tunnelbana,
+    Pendeltag,
+}
+
+/// The station closest to a property
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct NearestStation {
+    pub name: String,
+    pub kind: StationKind,
+    /// Straight-line distance in meters
+    pub distance_m: f64,
+    /// Estimated walking distance in meters
+    pub walking_m: f64,
+}
+
+/// Counts of everyday amenities around a property
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct NearbyPlaces {
+    /// Search radius in meters
+    pub radius_m: f64,
+    pub groceries: u32,
+    pub gyms: u32,
+    pub preschools: u32,
+    pub parks: u32,
+}
+
+/// A school near a property
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct NearbySchool {
+    /// Skolverket school unit code
+    pub code: String,
+    pub name: String,
+    /// Straight-line distance in meters
+    pub distance_m: f64,
+    /// Average merit rating (meritvärde) in year 9
+    pub merit_rating: Option<f64>,
+    /// Share of teachers with a teaching certificate, in percent
+    pub certified_teachers_pct: Option<f64>,
+    pub students_per_teacher: Option<f64>,
+}
+
+/// Where a balcony orientation estimate came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrientationSource {
+    /// Stated in the listing, e.g. "balkong i västerläge"
+    Listing,
+    /// Guessed from the building footprint's long facades
+    Footprint,
+}
+
+/// Which way a balcony faces
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct BalconyOrientation {
+    /// Compass bearing the facade faces, degrees clockwise from north
+    pub bearing: f64,
+    /// "N", "NE", "E", ...
+    pub direction: String,
+    /// Faces south-west to west, getting afternoon and evening sun
+    pub afternoon_sun: bool,
+    pub source: OrientationSource,
+}
+
+/// Broadband available at an address
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Broadband {
+    pub fiber: bool,
+    /// "fiber", "dsl", "cable"
+    pub technologies: Vec<String>,
+    pub max_down_mbps: Option<u32>,
+}
+
+/// Renovation state of an apartment, as its description tells it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Renovation {
+    /// Replacement of the building's water and sewage pipes ("stambyte")
+    pub pipes: Option<PipeReplacement>,
+    /// Year the kitchen was renovated
+    pub kitchen_year: Option<i32>,
+    /// Year the bathroom was renovated
+    pub bathroom_year: Option<i32>,
+    /// Sold as needing a full renovation ("renoveringsobjekt")
+    pub needs_renovation: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PipeReplacement {
+    pub status: PipeStatus,
+    /// Year it was or will be done, when the description says
+    pub year: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipeStatus {
+    Done,
+    Planned,
+    /// Not done and not yet planned: the full cost is still ahead
+    NotDone,
+}
+
+impl PipeStatus {
+    /// Whether a pipe replacement is still ahead of the building
+    pub fn is_ahead(&self) -> bool {
+        matches!(self, PipeStatus::Planned | PipeStatus::NotDone)
+    }
+}
+
+/// A housing association ("bostadsrättsförening", BRF)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Association {
+    /// "Brf Solgläntan 4"
+    pub name: String,
+    /// Link to the latest annual report ("årsredovisning"), usually a PDF
+    #[serde(default)]
+    pub annual_report_url: Option<String>,
+    /// Figures read from the annual report
+    #[serde(default)]
+    pub financials: Option<BrfFinancials>,
+    /// Organisation number, "769612-3456"
+    #[serde(default)]
+    pub org_number: Option<String>,
+    /// What the companies registry says about the association
+    #[serde(default)]
+    pub registry: Option<AssociationRegistry>,
+}
+
+/// Associations founded this many years before the listing are treated as
+/// fresh conversions
+const NEW_ASSOCIATION_YEARS: i32 = 3;
+
+impl Association {
+    /// Whether the association looks like a recent conversion, formed to sell
+    /// the building's apartments: registered in the last few years, or still
+    /// without a registered economic plan
+    pub fn is_new_conversion(&self, year: i32) -> bool {
+        self.registry.as_ref().is_some_and(|registry| {
+            registry.founded_year.is_some_and(|founded| year - founded < NEW_ASSOCIATION_YEARS)
+                || registry.economic_plan == Some(EconomicPlan::Missing)
+        })
+    }
+}
+
+/// Registry data of a housing association (Bolagsverket)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct AssociationRegistry {
+    /// Year the association was registered
+    pub founded_year: Option<i32>,
+    pub economic_plan: Option<EconomicPlan>,
+}
+
+/// Status of the association's economic plan ("ekonomisk plan"), which must be
+/// registered before apartments can be sold
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EconomicPlan {
+    Registered,
+    Missing,
+}
+
+/// Figures from a housing association's annual report
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct BrfFinancials {
+    pub report_url: String,
+    /// Financial year the report covers
+    pub year: Option<i32>,
+    /// Association loans per sqm of apartment area, in kronor
+    pub debt_per_sqm: Option<f64>,
+    /// Yearly fee per sqm from the multi-year overview, newest first
+    pub fee_history: Vec<FeeYear>,
+    /// Maintenance the board plans, as the report words it
+    pub planned_renovations: Vec<PlannedRenovation>,
+}
+
+/// Yearly fee per sqm ("årsavgift per kvm") in one financial year
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct FeeYear {
+    pub year: i32,
+    pub fee_per_sqm: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PlannedRenovation {
+    /// Year the work is planned for, when the report says
+    pub year: Option<i32>,
+    pub text: String,
+}
+
+/// What a listing image shows
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageKind {
+    #[default]
+    Photo,
+    FloorPlan,
+    /// A still or panorama from a 3D tour
+    Tour,
+}
+
+/// One image of a listing's gallery
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Image {
+    pub url: String,
+    /// Alt text or caption given by the broker
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// Position in the listing's gallery, from 0
+    #[serde(default)]
+    pub order: usize,
+    #[serde(default)]
+    pub kind: ImageKind,
+}
+
+impl Image {
+    pub fn new(url: impl Into<String>, order: usize, kind: ImageKind) -> Self {
+        Self {
+            url: url.into(),
+            caption: None,
+            order,
+            kind,
+        }
+    }
+}
+
+/// Read images, or the plain photo URLs older stores held
+fn images_or_urls<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Image>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Url(String),
+        Image(Image),
+    }
+
+    Ok(Vec::<Repr>::deserialize(deserializer)?
+        .into_iter()
+        .enumerate()
+        .map(|(order, repr)| match repr {
+            Repr::Url(url) => Image::new(url, order, ImageKind::Photo),
+            Repr::Image(image) => image,
+        })
+        .collect())
+}
+
+/// Another listing sharing photos with a property
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PhotoMatch {
+    pub property_id: String,
+    pub address: String,
+    pub shared_photos: usize,
+}
+
+/// A completed sale ("slutpris") scraped from Booli's sold listings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SoldListing {
+    pub id: String,
+    pub source: Source,
+    pub address: String,
+    pub area: Option<String>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub rooms: Option<f32>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub sqm: Option<i32>,
+    pub floor: Option<f32>,
+    pub monthly_fee: Option<i64>,
+    pub asking_price: Option<i64>,
+    pub sold_price: i64,
+    pub sold_at: Option<NaiveDate>,
+    pub url: String,
+    /// The tracked listing that sold, found by `scout sold --backfill`
+    #[serde(default)]
+    pub property_id: Option<String>,
+    pub scraped_at: DateTime<Utc>,
+}
@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// ISO 4217 currency of a listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[default]
+    Sek,
+    Nok,
+    Dkk,
+    Eur,
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Sek => "SEK",
+            Currency::Nok => "NOK",
+            Currency::Dkk => "DKK",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    /// Unit written after amounts: "kr" for SEK, the currency code otherwise
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Currency::Sek => "kr",
+            other => other.code(),
+        }
+    }
+}
+
+/// An amount of money in minor units (öre, øre, cent)
+///
+/// Older data stored prices as bare integers of whole kronor; those still
+/// deserialize, as SEK.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+pub struct Money {
+    pub amount_minor: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Whole units of `currency`, e.g. kronor
+    pub fn new(amount: i64, currency: Currency) -> Self {
+        Self {
+            amount_minor: amount * 100,
+            currency,
+        }
+    }
+
+    /// Whole Swedish kronor
+    pub fn sek(kronor: i64) -> Self {
+        Self::new(kronor, Currency::Sek)
+    }
+
+    /// Amount in whole units, rounded
+    pub fn major(&self) -> i64 {
+        (self.amount_minor as f64 / 100.0).round() as i64
+    }
+
+    /// Amount in whole units, for arithmetic like price per sqm
+    pub fn as_f64(&self) -> f64 {
+        self.amount_minor as f64 / 100.0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.amount_minor > 0
+    }
+}
+
+impl fmt::Display for Money {
+    /// "5195000 kr", or "3500000 NOK" for other currencies; honours width
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{} {}", self.major(), self.currency.unit()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            /// Whole kronor, as stored before amounts carried a currency
+            Kronor(i64),
+            Full {
+                amount_minor: i64,
+                #[serde(default)]
+                currency: Currency,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Kronor(kronor) => Money::sek(kronor),
+            Repr::Full { amount_minor, currency } => Money { amount_minor, currency },
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use crate::notify::Notification;
+use crate::scrapers::ActiveHours;
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Europe::Stockholm;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// When a channel gets the notifications meant for it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Delivery {
+    /// Each notification as soon as it comes up
+    #[default]
+    Immediate,
+    /// Everything from the last `every_hours` hours in one message
+    Batched { every_hours: u32 },
+    /// Everything since the last digest in one message, once a day at
+    /// `at_hour` o'clock Stockholm time
+    Digest { at_hour: u32 },
+}
+
+impl Delivery {
+    /// Whether a channel last flushed at `last` is due again at `now`
+    fn due(&self, last: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        match self {
+            Delivery::Immediate => true,
+            Delivery::Batched { every_hours } => {
+                last.is_none_or(|last| now - last >= Duration::hours(i64::from(*every_hours)))
+            }
+            Delivery::Digest { at_hour } => {
+                let local = now.with_timezone(&Stockholm);
+                let at = NaiveTime::from_hms_opt((*at_hour).min(23), 0, 0).unwrap_or_default();
+                let mut scheduled = local.date_naive().and_time(at);
+                if local.time() < at {
+                    scheduled -= Duration::days(1);
+                }
+                // Around a DST change the hour may not exist; the next run catches up
+                let Some(scheduled) = Stockholm.from_local_datetime(&scheduled).earliest() else {
+                    return false;
+                };
+                last.is_none_or(|last| last < scheduled)
+            }
+        }
+    }
+}
+
+/// Notifications waiting for their channel's next delivery (data/outbox.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    /// By channel ledger key
+    pub channels: BTreeMap<String, ChannelQueue>,
+}
+
+/// What one channel has waiting, and when it last got anything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelQueue {
+    pub queued: Vec<Notification>,
+    pub last_flush: Option<DateTime<Utc>>,
+}
+
+impl ChannelQueue {
+    /// Queue `notification`, replacing an older one about the same listing
+    /// or with the same title
+    pub fn push(&mut self, notification: Notification) {
+        self.queued.retain(|queued| match &notification.property_id {
+            Some(id) => queued.property_id.as_ref() != Some(id),
+            None => queued.title != notification.title,
+        });
+        self.queued.push(notification);
+    }
+
+    /// The queued notifications if `delivery` is due at `now` and it isn't
+    /// `quiet`, emptying the queue
+    pub fn flush(&mut self, delivery: &Delivery, quiet: bool, now: DateTime<Utc>) -> Option<Vec<Notification>> {
+        if quiet || self.queued.is_empty() || !delivery.due(self.last_flush, now) {
+            return None;
+        }
+        self.last_flush = Some(now);
+        Some(std::mem::take(&mut self.queued))
+    }
+}
+
+/// Whether `now` falls in `quiet_hours`, Stockholm time
+pub fn is_quiet(quiet_hours: Option<&ActiveHours>, now: DateTime<Utc>) -> bool {
+    quiet_hours.is_some_and(|hours| hours.contains(now.with_timezone(&Stockholm).hour()))
+}
@@ -0,0 +1,120 @@
+use crate::notify::{ListingState, Notification, NotificationEvent};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When a notification already sent keeps another one from going out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    /// Check notifications against the ledger; when off every one is sent
+    pub enabled: bool,
+    /// Notify about a listing again once its price moved at least this
+    /// fraction (0.02 = 2%) since the last notification, or its status changed
+    pub min_price_change: f64,
+    /// Never notify about one listing on one channel more often than this
+    pub cooldown_minutes: i64,
+    /// Forget notifications older than this
+    pub keep_days: i64,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_price_change: 0.02,
+            cooldown_minutes: 60,
+            keep_days: 180,
+        }
+    }
+}
+
+/// Notifications sent so far, per listing and channel (data/notifications.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// One notification sent on one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Listing ID, or the title of a notification about no single listing
+    pub key: String,
+    pub channel: String,
+    pub event: NotificationEvent,
+    pub sent_at: DateTime<Utc>,
+    #[serde(default)]
+    pub state: Option<ListingState>,
+}
+
+impl Ledger {
+    /// Whether `notification` is news to `channel` at `now`
+    ///
+    /// A listing is notified about again only when its price moved at least
+    /// `min_price_change` or its status changed since the last notification,
+    /// and not within `cooldown_minutes` of it. Other notifications repeat once
+    /// the cooldown is over. Nor is one queued twice: `queued` is what already
+    /// waits in the outbox for `channel`.
+    pub fn should_send(
+        &self,
+        notification: &Notification,
+        channel: &str,
+        queued: &[Notification],
+        now: DateTime<Utc>,
+        config: &DedupConfig,
+    ) -> bool {
+        if !config.enabled {
+            return true;
+        }
+        let key = key(notification);
+        let waiting = queued.iter().any(|waiting| {
+            self::key(waiting) == key
+                && match (&notification.state, &waiting.state) {
+                    (Some(state), Some(earlier)) => !state.changed_since(earlier, config.min_price_change),
+                    _ => true,
+                }
+        });
+        if waiting {
+            return false;
+        }
+        let Some(last) = self
+            .entries
+            .iter()
+            .filter(|entry| entry.key == key && entry.channel == channel)
+            .max_by_key(|entry| entry.sent_at)
+        else {
+            return true;
+        };
+        if now - last.sent_at < Duration::minutes(config.cooldown_minutes) {
+            return false;
+        }
+        match (&notification.state, &last.state) {
+            (Some(state), Some(earlier)) => state.changed_since(earlier, config.min_price_change),
+            (Some(_), None) => true,
+            (None, _) => notification.property_id.is_none(),
+        }
+    }
+
+    /// Note that `notification` went out on `channel` at `now`
+    pub fn record(&mut self, notification: &Notification, channel: &str, now: DateTime<Utc>) {
+        self.entries.push(LedgerEntry {
+            key: key(notification),
+            channel: channel.to_string(),
+            event: notification.event,
+            sent_at: now,
+            state: notification.state,
+        });
+    }
+
+    /// Drop entries older than `keep_days`
+    pub fn prune(&mut self, now: DateTime<Utc>, keep_days: i64) {
+        let cutoff = now - Duration::days(keep_days);
+        self.entries.retain(|entry| entry.sent_at >= cutoff);
+    }
+}
+
+fn key(notification: &Notification) -> String {
+    notification
+        .property_id
+        .clone()
+        .unwrap_or_else(|| notification.title.clone())
+}
@@ -0,0 +1,73 @@
+use crate::notify::{Notification, Notifier};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Matrix room, posted to over the client-server API
+///
+/// Messages are sent as plain `m.room.message` events, so the room must not
+/// have end-to-end encryption enabled.
+pub struct MatrixNotifier {
+    client: Client,
+    homeserver: String,
+    room_id: String,
+    access_token: String,
+    /// Makes transaction IDs unique within one timestamp
+    sent: AtomicU64,
+}
+
+impl MatrixNotifier {
+    /// Post to `room_id` (e.g. "!abc123:matrix.org") on `homeserver`
+    /// (e.g. "https://matrix.org") as the user owning `access_token`
+    pub fn new(homeserver: &str, room_id: &str, access_token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            room_id: room_id.to_string(),
+            access_token: access_token.to_string(),
+            sent: AtomicU64::new(0),
+        }
+    }
+
+    /// `PUT /rooms/{roomId}/send/m.room.message/{txnId}`; the transaction ID
+    /// lets the homeserver drop a retried request it already handled
+    fn message_url(&self) -> Result<Url> {
+        let txn_id = format!(
+            "scout-{}-{}",
+            Utc::now().timestamp_millis(),
+            self.sent.fetch_add(1, Ordering::SeqCst)
+        );
+        let mut url = Url::parse(&self.homeserver).with_context(|| format!("Invalid homeserver {}", self.homeserver))?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("Invalid homeserver {}", self.homeserver))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", &self.room_id, "send", "m.room.message", &txn_id]);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let response = self
+            .client
+            .put(self.message_url()?)
+            .bearer_auth(&self.access_token)
+            .json(&json!({ "msgtype": "m.text", "body": notification.text() }))
+            .send()
+            .await
+            .context("Failed to send Matrix message")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Matrix homeserver returned status: {}", response.status());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "Matrix"
+    }
+}
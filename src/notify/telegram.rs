@@ -0,0 +1,130 @@
+use crate::notify::{Notification, Notifier};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Longer messages are cut off; the API rejects anything past 4096 characters
+const MAX_MESSAGE_CHARS: usize = 4000;
+
+/// Telegram Bot API client, for notifications and `scout bot`
+pub struct TelegramClient {
+    client: Client,
+    api_url: String,
+    token: String,
+}
+
+/// Every Bot API response is wrapped in one of these
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+/// An incoming update; only messages are asked for
+#[derive(Debug, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    pub chat: Chat,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
+
+impl TelegramClient {
+    /// Act as the bot owning `token`, from @BotFather
+    pub fn new(token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: "https://api.telegram.org".to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Talk to another Bot API server, e.g. a self-hosted one
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, body: &Value, timeout: Duration) -> Result<T> {
+        let response = self
+            .client
+            .post(format!("{}/bot{}/{}", self.api_url, self.token, method))
+            .json(body)
+            .timeout(timeout)
+            .send()
+            .await
+            .with_context(|| format!("Failed to call Telegram {}", method))?;
+        let status = response.status();
+        let response: ApiResponse<T> = response
+            .json()
+            .await
+            .with_context(|| format!("Telegram {} returned status {}", method, status))?;
+        match response.result {
+            Some(result) if response.ok => Ok(result),
+            _ => anyhow::bail!(
+                "Telegram {} failed: {}",
+                method,
+                response.description.unwrap_or_else(|| status.to_string())
+            ),
+        }
+    }
+
+    /// Updates after `offset`, waiting up to `wait` for one to arrive
+    pub async fn updates(&self, offset: i64, wait: Duration) -> Result<Vec<Update>> {
+        let body = json!({ "offset": offset, "timeout": wait.as_secs(), "allowed_updates": ["message"] });
+        self.call("getUpdates", &body, wait + Duration::from_secs(10)).await
+    }
+
+    /// Post `text` to `chat_id` as plain text
+    pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<()> {
+        let text: String = match text.char_indices().nth(MAX_MESSAGE_CHARS) {
+            Some((end, _)) => format!("{}…", &text[..end]),
+            None => text.to_string(),
+        };
+        let body = json!({ "chat_id": chat_id, "text": text, "link_preview_options": { "is_disabled": true } });
+        self.call::<Value>("sendMessage", &body, Duration::from_secs(30)).await?;
+        Ok(())
+    }
+}
+
+/// Telegram chat, posted to by a bot
+pub struct TelegramNotifier {
+    client: TelegramClient,
+    chat_id: i64,
+}
+
+impl TelegramNotifier {
+    /// Post to `chat_id` as the bot owning `token`; the bot must be a member
+    /// of the chat, or have been messaged first in a private chat
+    pub fn new(token: &str, chat_id: i64) -> Self {
+        Self {
+            client: TelegramClient::new(token),
+            chat_id,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        self.client.send_message(self.chat_id, &notification.text()).await
+    }
+
+    fn name(&self) -> &str {
+        "Telegram"
+    }
+}
@@ -0,0 +1,98 @@
+use crate::notify::{Notification, Notifier};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+
+/// POST `body` as JSON to `url`, failing on non-2xx responses
+async fn post_json(client: &Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .context("Failed to send webhook request")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Webhook returned status: {}", response.status());
+    }
+    Ok(())
+}
+
+/// Slack incoming webhook
+pub struct SlackNotifier {
+    client: Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        post_json(&self.client, &self.webhook_url, &json!({ "text": notification.text() })).await
+    }
+
+    fn name(&self) -> &str {
+        "Slack"
+    }
+}
+
+/// Discord channel webhook
+pub struct DiscordNotifier {
+    client: Client,
+    webhook_url: String,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        post_json(&self.client, &self.webhook_url, &json!({ "content": notification.text() })).await
+    }
+
+    fn name(&self) -> &str {
+        "Discord"
+    }
+}
+
+/// Generic webhook receiving the full notification as JSON
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        post_json(&self.client, &self.url, &serde_json::to_value(notification)?).await
+    }
+
+    fn name(&self) -> &str {
+        "webhook"
+    }
+}
@@ -0,0 +1,111 @@
+use super::{create_parent, source, Placeholders};
+use crate::models::Property;
+use crate::output::OutputConfig;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tracing::{debug, info, warn};
+
+/// Cache of the files written per listing
+const CACHE_NAME: &str = "listing_files";
+
+/// The files `output.listing` has written, per listing, newest last
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ListingFiles {
+    /// The template the files were written by; files of another template
+    /// are left alone
+    template: String,
+    listings: BTreeMap<String, Versions>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Versions {
+    /// [`content_hash`] of the newest file
+    hash: String,
+    files: Vec<PathBuf>,
+}
+
+/// Hash of a listing's JSON without the fields every scrape changes, so a
+/// listing that didn't change hashes the same from run to run
+fn content_hash(property: &Property) -> Result<String> {
+    let mut value = serde_json::to_value(property)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("scraped_at");
+        fields.remove("run_id");
+    }
+    Ok(format!("{:x}", Sha256::digest(serde_json::to_vec(&value)?)))
+}
+
+/// Write each property's `output.listing` file, except those whose content
+/// is the same as their newest file, then delete all but the newest
+/// `keep_listing_versions` files of each; returns the paths written
+pub(super) async fn write_listing_files(
+    properties: &[Property],
+    base: &Placeholders,
+    config: &OutputConfig,
+    store: &JsonStore,
+) -> Result<Vec<PathBuf>> {
+    let mut manifest: ListingFiles = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    if manifest.template != config.listing {
+        manifest = ListingFiles {
+            template: config.listing.clone(),
+            listings: BTreeMap::new(),
+        };
+    }
+
+    let mut written = Vec::new();
+    let mut unchanged = 0;
+    let mut pruned = 0;
+    for property in properties {
+        let hash = content_hash(property)?;
+        let versions = manifest.listings.entry(property.id.clone()).or_default();
+        let newest_exists = match versions.files.last() {
+            Some(path) => tokio::fs::try_exists(path).await.unwrap_or(false),
+            None => false,
+        };
+        if config.skip_unchanged_listings && versions.hash == hash && newest_exists {
+            unchanged += 1;
+            continue;
+        }
+
+        let placeholders = Placeholders {
+            search: property.area_tags.first().cloned().unwrap_or_default(),
+            source: source(property),
+            id: property.id.clone(),
+            ..base.clone()
+        };
+        let path = placeholders.render(&config.listing);
+        create_parent(&path).await?;
+        let json = serde_json::to_string_pretty(property)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        versions.hash = hash;
+        versions.files.retain(|file| *file != path);
+        versions.files.push(path.clone());
+        if let Some(keep) = config.keep_listing_versions {
+            let excess = versions.files.len().saturating_sub(keep.max(1));
+            for old in versions.files.drain(..excess) {
+                match tokio::fs::remove_file(&old).await {
+                    Ok(()) => pruned += 1,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => warn!("Failed to remove {}: {}", old.display(), e),
+                }
+            }
+        }
+        written.push(path);
+    }
+
+    if unchanged > 0 {
+        debug!("Skipped {} unchanged listing files", unchanged);
+    }
+    if pruned > 0 {
+        info!("Removed {} old listing files", pruned);
+    }
+    store.save_cache(CACHE_NAME, &manifest).await?;
+    Ok(written)
+}
@@ -0,0 +1,169 @@
+mod listings;
+pub mod s3;
+pub mod search;
+pub mod timeseries;
+
+pub use s3::{upload_run, S3Config, S3Uploader};
+pub use search::{write_search_outputs, SearchOutput};
+pub use timeseries::{push_run, TimeseriesConfig};
+
+use crate::models::Property;
+use crate::storage::{write_json_array, write_json_lines, JsonStore, ScrapeRun};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where `scout scrape` writes its plain JSON output, besides data/
+///
+/// Paths are templates: `{date}` (YYYY-MM-DD), `{time}` (HHMMSS), `{run}`
+/// (run ID), `{search}` (search area name) and `{source}` (listing source),
+/// plus `{id}` in `listing`. When `properties` uses `{search}` or `{source}`,
+/// one file is written per search or source. An empty template turns that
+/// output off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Every scraped property in one JSON array, or one per line when the
+    /// path ends in `.jsonl`
+    pub properties: String,
+    /// One JSON file per property
+    pub listing: String,
+    /// Leave a property's `listing` file unwritten when nothing but its scrape
+    /// time and run changed since the newest one
+    pub skip_unchanged_listings: bool,
+    /// Keep only the newest this many `listing` files per property, deleting
+    /// older ones; unset keeps every file
+    pub keep_listing_versions: Option<usize>,
+    /// Bucket to copy outputs and captures to
+    pub s3: S3Config,
+    /// Database to write market figures to
+    pub timeseries: TimeseriesConfig,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            properties: "scraped_properties.json".to_string(),
+            listing: "raw_scrape/{id}.json".to_string(),
+            skip_unchanged_listings: true,
+            keep_listing_versions: None,
+            s3: S3Config::default(),
+            timeseries: TimeseriesConfig::default(),
+        }
+    }
+}
+
+/// Write the configured output files for a run; returns the paths written
+///
+/// The `listing` files written are tracked in `store`'s cache, to skip
+/// unchanged properties and remove old versions.
+pub async fn write_outputs(
+    properties: &[Property],
+    run: &ScrapeRun,
+    config: &OutputConfig,
+    store: &JsonStore,
+) -> Result<Vec<PathBuf>> {
+    let now = Local::now();
+    let base = Placeholders {
+        date: now.format("%Y-%m-%d").to_string(),
+        time: now.format("%H%M%S").to_string(),
+        run: run.id.clone(),
+        ..Default::default()
+    };
+    let mut written = Vec::new();
+
+    if !config.properties.is_empty() {
+        let mut files: BTreeMap<PathBuf, Vec<&Property>> = BTreeMap::new();
+        for property in properties {
+            for search in searches(property, &config.properties) {
+                let placeholders = Placeholders {
+                    search: search.to_string(),
+                    source: source(property),
+                    ..base.clone()
+                };
+                files.entry(placeholders.render(&config.properties)).or_default().push(property);
+            }
+        }
+        for (path, properties) in files {
+            write_listings(&path, properties).await?;
+            written.push(path);
+        }
+    }
+
+    if !config.listing.is_empty() {
+        written.extend(listings::write_listing_files(properties, &base, config, store).await?);
+    }
+
+    Ok(written)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Placeholders {
+    date: String,
+    time: String,
+    run: String,
+    search: String,
+    source: String,
+    id: String,
+}
+
+impl Placeholders {
+    fn render(&self, template: &str) -> PathBuf {
+        let path = template
+            .replace("{date}", &self.date)
+            .replace("{time}", &self.time)
+            .replace("{run}", &self.run)
+            .replace("{search}", &slug(&self.search))
+            .replace("{source}", &slug(&self.source))
+            .replace("{id}", &slug(&self.id));
+        PathBuf::from(path)
+    }
+}
+
+/// The searches a property is filed under; a property found by several
+/// searches goes into each search's file
+fn searches<'a>(property: &'a Property, template: &str) -> Vec<&'a str> {
+    if !template.contains("{search}") || property.area_tags.is_empty() {
+        return vec![""];
+    }
+    property.area_tags.iter().map(String::as_str).collect()
+}
+
+fn source(property: &Property) -> String {
+    format!("{:?}", property.source).to_lowercase()
+}
+
+/// Lowercase with anything but letters and digits replaced, so placeholder
+/// values can't add directories; empty values become "all"
+fn slug(value: &str) -> String {
+    if value.is_empty() {
+        return "all".to_string();
+    }
+    value
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Write `items` to `path` one at a time: as JSON Lines when it ends in
+/// `.jsonl`, else as a JSON array
+async fn write_listings<T: Serialize>(path: &Path, items: impl IntoIterator<Item = T>) -> Result<()> {
+    create_parent(path).await?;
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        write_json_lines(path, items).await
+    } else {
+        write_json_array(path, items).await
+    }
+}
+
+async fn create_parent(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,287 @@
+use crate::output::Placeholders;
+use crate::scrapers::CaptureConfig;
+use crate::secrets::Secret;
+use crate::storage::ScrapeRun;
+use anyhow::{Context, Result};
+use chrono::{Local, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, instrument, warn};
+
+/// An S3-compatible bucket (AWS, MinIO, Cloudflare R2, ...) that run outputs
+/// and page captures are uploaded to after every `scout scrape`
+///
+/// Object keys are templates with the placeholders of [`OutputConfig`](crate::output::OutputConfig)
+/// plus `{path}`, the file's local path. With `remove_local` on, uploaded
+/// files are deleted, so a small disk only ever holds one run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    /// Uploads are off without a bucket
+    pub bucket: Option<String>,
+    /// e.g. "http://minio.local:9000" or "https://<account>.r2.cloudflarestorage.com";
+    /// AWS's endpoint for `region` when unset
+    pub endpoint: Option<String>,
+    /// "auto" for R2
+    pub region: String,
+    pub access_key_id: Option<Secret>,
+    pub secret_access_key: Option<Secret>,
+    /// Address the bucket as `<endpoint>/<bucket>` rather than `<bucket>.<endpoint host>`
+    pub path_style: bool,
+    pub key: String,
+    /// Upload the files written under [output]
+    pub outputs: bool,
+    /// Upload this run's screenshots and page archives from [scrape.capture]
+    pub captures: bool,
+    pub remove_local: bool,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: None,
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            access_key_id: None,
+            secret_access_key: None,
+            path_style: true,
+            key: "{date}/{run}/{path}".to_string(),
+            outputs: true,
+            captures: true,
+            remove_local: false,
+        }
+    }
+}
+
+/// Upload the outputs `written` by `run` and its captures; returns how many
+/// files were uploaded
+///
+/// A file that fails to upload is kept locally and the rest still go.
+pub async fn upload_run(written: &[PathBuf], run: &ScrapeRun, captures: &CaptureConfig, config: &S3Config) -> Result<usize> {
+    let Some(bucket) = &config.bucket else {
+        return Ok(0);
+    };
+    let uploader = S3Uploader::new(bucket, config)?;
+
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    let started = run.started_at.with_timezone(&Local);
+    let base = Placeholders {
+        date: started.format("%Y-%m-%d").to_string(),
+        time: started.format("%H%M%S").to_string(),
+        run: run.id.clone(),
+        ..Default::default()
+    };
+    if config.outputs {
+        files.extend(written.iter().map(|path| (path.clone(), String::new())));
+    }
+    if config.captures {
+        files.extend(run_captures(&captures.dir, run).await?);
+    }
+
+    let mut uploaded = 0;
+    for (path, id) in files {
+        let placeholders = Placeholders { id, ..base.clone() };
+        let local = path.to_string_lossy().replace('\\', "/");
+        let key = placeholders
+            .render(&config.key)
+            .to_string_lossy()
+            .replace("{path}", local.trim_start_matches("./"));
+        match uploader.upload(&path, &key).await {
+            Ok(()) => {
+                uploaded += 1;
+                if config.remove_local {
+                    if let Err(e) = tokio::fs::remove_file(&path).await {
+                        warn!("Failed to remove {} after uploading it: {}", path.display(), e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to upload {}: {:#}", path.display(), e),
+        }
+    }
+    info!("☁️ Uploaded {} files to s3://{}", uploaded, bucket);
+    Ok(uploaded)
+}
+
+/// Captures taken during `run`, with the property ID each is of; capture file
+/// names are the UTC time they were taken
+async fn run_captures(dir: &Path, run: &ScrapeRun) -> Result<Vec<(PathBuf, String)>> {
+    let since = run.started_at.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut captures = Vec::new();
+    let Ok(mut properties) = tokio::fs::read_dir(dir).await else {
+        return Ok(captures);
+    };
+    while let Some(property) = properties.next_entry().await? {
+        if !property.file_type().await?.is_dir() {
+            continue;
+        }
+        let id = property.file_name().to_string_lossy().to_string();
+        let mut files = tokio::fs::read_dir(property.path()).await?;
+        while let Some(file) = files.next_entry().await? {
+            let name = file.file_name().to_string_lossy().to_string();
+            if name.split('.').next().is_some_and(|stem| stem >= since.as_str()) {
+                captures.push((file.path(), id.clone()));
+            }
+        }
+    }
+    captures.sort();
+    Ok(captures)
+}
+
+/// PUTs objects into one bucket, signed with AWS Signature Version 4
+pub struct S3Uploader {
+    client: Client,
+    endpoint: Url,
+    bucket: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    path_style: bool,
+}
+
+impl S3Uploader {
+    pub fn new(bucket: &str, config: &S3Config) -> Result<Self> {
+        let endpoint = config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", config.region));
+        let key = |secret: &Option<Secret>, name: &str| -> Result<String> {
+            secret
+                .as_ref()
+                .with_context(|| format!("Set [output.s3] {} to upload to S3", name))?
+                .resolve()
+        };
+        Ok(Self {
+            client: Client::new(),
+            endpoint: Url::parse(&endpoint).with_context(|| format!("Invalid S3 endpoint {}", endpoint))?,
+            bucket: bucket.to_string(),
+            region: config.region.clone(),
+            access_key_id: key(&config.access_key_id, "access_key_id")?,
+            secret_access_key: key(&config.secret_access_key, "secret_access_key")?,
+            path_style: config.path_style,
+        })
+    }
+
+    /// Host and path of object `key`, the path already URI-encoded
+    fn address(&self, key: &str) -> Result<(String, String)> {
+        let host = self.endpoint.host_str().context("S3 endpoint has no host")?;
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let key = uri_encode(key.trim_start_matches('/'));
+        let prefix = self.endpoint.path().trim_end_matches('/');
+        Ok(if self.path_style {
+            (host, format!("{}/{}/{}", prefix, uri_encode(&self.bucket), key))
+        } else {
+            (format!("{}.{}", self.bucket, host), format!("{}/{}", prefix, key))
+        })
+    }
+
+    /// The Authorization header for a request whose canonical headers are
+    /// `headers` (lowercase names, sorted)
+    fn authorization(&self, method: &str, path: &str, headers: &[(&str, &str)], payload_hash: &str, amz_date: &str) -> String {
+        let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_request = format!("{}\n{}\n\n{}\n{}\n{}", method, path, canonical_headers, signed_headers, payload_hash);
+
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let mut key = format!("AWS4{}", self.secret_access_key).into_bytes();
+        for part in [date, self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac(&key, part.as_bytes());
+        }
+        let signature: String = hmac(&key, string_to_sign.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, signed_headers, signature
+        )
+    }
+
+    /// PUT `body` as object `key`
+    pub async fn put(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<()> {
+        let (host, path) = self.address(key)?;
+        let url = format!("{}://{}{}", self.endpoint.scheme(), host, path);
+        let payload_hash = format!("{:x}", Sha256::digest(&body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let headers = [
+            ("content-type", content_type),
+            ("host", host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        let authorization = self.authorization("PUT", &path, &headers, &payload_hash, &amz_date);
+
+        let response = self
+            .client
+            .put(&url)
+            .header("content-type", content_type)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", self.endpoint))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let code = text
+                .split("<Code>")
+                .nth(1)
+                .and_then(|rest| rest.split("</Code>").next())
+                .unwrap_or_default();
+            anyhow::bail!("S3 returned status {} {}", status, code);
+        }
+        Ok(())
+    }
+
+    /// Upload the file at `path` as object `key`
+    #[instrument(name = "upload", level = "debug", skip_all, fields(key = key))]
+    pub async fn upload(&self, path: &Path, key: &str) -> Result<()> {
+        let body = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.put(key, body, content_type(path)).await?;
+        debug!("Uploaded {} to s3://{}/{}", path.display(), self.bucket, key);
+        Ok(())
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode everything but unreserved characters and `/`, as SigV4
+/// expects of S3 object paths
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("mhtml") => "multipart/related",
+        Some("warc") => "application/warc",
+        Some("html") => "text/html",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,88 @@
+use super::{create_parent, write_listings, Placeholders};
+use crate::export::{ExportRecord, RecordContext};
+use crate::filter::SavedSearch;
+use crate::models::Property;
+use crate::storage::{write_sqlite, History, ScrapeRun, SqlIndex};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Columns of the properties table written to CSV outputs
+const CSV_QUERY: &str = "SELECT id, address, area, city, price, asking_price, current_bid, bidding_in_progress,
+    monthly_fee, operating_cost, currency, rooms, sqm, floor, price_per_sqm, fee_per_sqm, published_at,
+    first_seen, last_seen, status, favorite, rating, url
+    FROM properties ORDER BY coalesce(published_at, first_seen) DESC";
+
+/// A file or database a saved search's listings are written to after every
+/// scrape; `path` takes the placeholders of [`OutputConfig`](super::OutputConfig)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchOutput {
+    /// The matching listings as a JSON array, as `scout export --search` writes
+    /// them, or one per line when `path` ends in `.jsonl`
+    Json { path: String },
+    /// One row per matching listing, with the columns `scout query` has
+    Csv { path: String },
+    /// A SQLite database with `scout query`'s tables; listings and their
+    /// price history are upserted, so it keeps listings from earlier scrapes
+    Sqlite { path: String },
+}
+
+/// Write each saved search's listings among `records` to its outputs; returns
+/// the paths written
+pub async fn write_search_outputs(
+    records: &[ExportRecord<'_>],
+    searches: &[SavedSearch],
+    context: &RecordContext,
+    run: &ScrapeRun,
+) -> Result<Vec<PathBuf>> {
+    let now = Local::now();
+    let mut written = Vec::new();
+
+    for search in searches.iter().filter(|search| !search.outputs.is_empty()) {
+        let matching: Vec<&ExportRecord<'_>> = records.iter().filter(|record| search.matches(record)).collect();
+        let properties: Vec<Property> = matching.iter().map(|record| record.property.clone()).collect();
+        let history = History {
+            properties: properties
+                .iter()
+                .filter_map(|p| Some((p.id.clone(), context.history.get(&p.id)?.clone())))
+                .collect(),
+        };
+        let placeholders = Placeholders {
+            date: now.format("%Y-%m-%d").to_string(),
+            time: now.format("%H%M%S").to_string(),
+            run: run.id.clone(),
+            search: search.name.clone(),
+            ..Default::default()
+        };
+
+        for output in &search.outputs {
+            let path = match output {
+                SearchOutput::Json { path } => {
+                    let path = placeholders.render(path);
+                    write_listings(&path, &matching).await?;
+                    path
+                }
+                SearchOutput::Csv { path } => {
+                    let path = placeholders.render(path);
+                    let index = SqlIndex::build(&properties, &history, &context.annotations, &[])?;
+                    create_parent(&path).await?;
+                    tokio::fs::write(&path, index.query(CSV_QUERY)?.to_csv())
+                        .await
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    path
+                }
+                SearchOutput::Sqlite { path } => {
+                    let path = placeholders.render(path);
+                    create_parent(&path).await?;
+                    write_sqlite(&path, &properties, &history, &context.annotations)?;
+                    path
+                }
+            };
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
@@ -0,0 +1,180 @@
+use crate::models::Property;
+use crate::secrets::Secret;
+use crate::stats::area_stats;
+use crate::storage::{History, JsonStore};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, instrument};
+
+/// A time-series database (InfluxDB, VictoriaMetrics) that every run's
+/// market figures are written to in InfluxDB line protocol, for Grafana
+///
+/// Each run writes one `<measurement>_area` point per area, tagged `area`,
+/// with `listings`, `new_24h`, `removed_24h` and the area's median price,
+/// price per sqm and fee per sqm; and with `listings` on, one
+/// `<measurement>_listing` point per listing, tagged `id`, `area` and
+/// `source`, with its price, price per sqm, current bid and bidding state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeseriesConfig {
+    /// Write endpoint; off when unset
+    pub url: Option<String>,
+    /// Sent as `Authorization: <auth_scheme> <token>`
+    pub token: Option<Secret>,
+    pub auth_scheme: String,
+    /// Prefix of the measurement names
+    pub measurement: String,
+    pub listings: bool,
+}
+
+impl Default for TimeseriesConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            token: None,
+            auth_scheme: "Token".to_string(),
+            measurement: "housing".to_string(),
+            listings: true,
+        }
+    }
+}
+
+/// Write the points of a run of `properties` that finished at `now`, already
+/// recorded in `store`; returns how many were written
+#[instrument(name = "timeseries", level = "debug", skip_all)]
+pub async fn push_run(properties: &[Property], store: &JsonStore, config: &TimeseriesConfig, now: DateTime<Utc>) -> Result<usize> {
+    let Some(url) = &config.url else {
+        return Ok(0);
+    };
+    let known = store.load_all_properties().await?;
+    let history = store.load_history().await?;
+    let lines = lines(properties, &known, &history, config, now);
+    let mut request = Client::new().post(url).body(lines.join("\n"));
+    if let Some(token) = &config.token {
+        request = request.header("Authorization", format!("{} {}", config.auth_scheme, token.resolve()?));
+    }
+    let response = request.send().await.with_context(|| format!("Failed to reach {}", url))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let message = response.text().await.unwrap_or_default();
+        anyhow::bail!("Time-series database returned status {}: {}", status, message.trim());
+    }
+    info!("📈 Wrote {} time-series points", lines.len());
+    Ok(lines.len())
+}
+
+/// The line protocol points of a run; `known` are all stored listings, for
+/// the areas of ones that have since gone
+pub fn lines(
+    properties: &[Property],
+    known: &[Property],
+    history: &History,
+    config: &TimeseriesConfig,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let timestamp = now.timestamp_nanos_opt().unwrap_or_default();
+    let day_ago = now - Duration::days(1);
+    let area_of = |property: &Property| property.location.area.clone().unwrap_or_else(|| "Unknown".to_string());
+
+    // New listings, and those seen in the last day that this run didn't find
+    let current: HashSet<&str> = properties.iter().map(|p| p.id.as_str()).collect();
+    let mut new: BTreeMap<String, usize> = BTreeMap::new();
+    let mut removed: BTreeMap<String, usize> = BTreeMap::new();
+    for property in known {
+        let Some(entry) = history.get(&property.id) else {
+            continue;
+        };
+        if current.contains(property.id.as_str()) {
+            if entry.first_seen >= day_ago {
+                *new.entry(area_of(property)).or_default() += 1;
+            }
+        } else if entry.last_seen >= day_ago {
+            *removed.entry(area_of(property)).or_default() += 1;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut areas: BTreeMap<String, Vec<(&str, Field)>> = BTreeMap::new();
+    for stats in area_stats(properties, history, now, 1) {
+        areas.insert(
+            stats.area,
+            vec![
+                ("listings", Field::Int(stats.listings as i64)),
+                ("median_price", Field::float(stats.median_price)),
+                ("median_price_per_sqm", Field::float(stats.median_price_per_sqm)),
+                ("median_fee_per_sqm", Field::float(stats.median_fee_per_sqm)),
+            ],
+        );
+    }
+    for area in removed.keys() {
+        areas.entry(area.clone()).or_insert_with(|| vec![("listings", Field::Int(0))]);
+    }
+    for (area, mut fields) in areas {
+        fields.push(("new_24h", Field::Int(new.get(&area).copied().unwrap_or_default() as i64)));
+        fields.push(("removed_24h", Field::Int(removed.get(&area).copied().unwrap_or_default() as i64)));
+        lines.extend(line(&format!("{}_area", config.measurement), &[("area", &area)], &fields, timestamp));
+    }
+
+    if config.listings {
+        for property in properties {
+            let price = property.price.map(|price| price.as_f64());
+            let per_sqm = price.zip(property.sqm.filter(|sqm| *sqm > 0)).map(|(price, sqm)| price / f64::from(sqm));
+            let fields = [
+                ("price", Field::float(price)),
+                ("price_per_sqm", Field::float(per_sqm)),
+                ("current_bid", Field::float(property.current_bid.map(|bid| bid.as_f64()))),
+                ("bidding", Field::Bool(property.bidding_in_progress)),
+            ];
+            let source = format!("{:?}", property.source).to_lowercase();
+            let tags = [("id", property.id.as_str()), ("area", &area_of(property)), ("source", &source)];
+            lines.extend(line(&format!("{}_listing", config.measurement), &tags, &fields, timestamp));
+        }
+    }
+    lines
+}
+
+/// A field value; unknown values are left out of the point
+enum Field {
+    Float(Option<f64>),
+    Int(i64),
+    Bool(bool),
+}
+
+impl Field {
+    fn float(value: Option<f64>) -> Self {
+        Field::Float(value.filter(|value| value.is_finite()))
+    }
+
+    fn render(&self) -> Option<String> {
+        match self {
+            Field::Float(value) => value.map(|value| value.to_string()),
+            Field::Int(value) => Some(format!("{}i", value)),
+            Field::Bool(value) => Some(value.to_string()),
+        }
+    }
+}
+
+/// One point, or none when every field is unknown
+fn line(measurement: &str, tags: &[(&str, &str)], fields: &[(&str, Field)], timestamp: i64) -> Option<String> {
+    let fields: Vec<String> = fields
+        .iter()
+        .filter_map(|(name, value)| Some(format!("{}={}", escape(name), value.render()?)))
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+    let tags: String = tags
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| format!(",{}={}", escape(name), escape(value)))
+        .collect();
+    Some(format!("{}{} {} {}", escape(measurement), tags, fields.join(","), timestamp))
+}
+
+/// Escape commas, equals signs and spaces in names and tag values
+fn escape(value: &str) -> String {
+    value.replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
@@ -0,0 +1,225 @@
+#[cfg(not(feature = "ml"))]
+use crate::models::{Property, SoldListing};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the sale price model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredictionConfig {
+    /// Don't train on fewer sold listings than this
+    pub min_training_rows: usize,
+    /// Areas with fewer sales than this share the baseline instead of their own term
+    pub min_area_sales: usize,
+    /// Flag asking prices at least this far below the prediction (0.10 = 10%)
+    pub lowball_threshold: f64,
+}
+
+impl Default for PredictionConfig {
+    fn default() -> Self {
+        Self {
+            min_training_rows: 20,
+            min_area_sales: 3,
+            lowball_threshold: 0.10,
+        }
+    }
+}
+
+/// Expected sale price of a listing
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PricePrediction {
+    pub predicted_price: i64,
+    /// Asking price minus predicted price; negative when asking is below the prediction
+    pub delta: i64,
+    /// `delta` relative to the predicted price, e.g. -0.12
+    pub delta_pct: f64,
+}
+
+impl PricePrediction {
+    #[cfg_attr(not(feature = "ml"), allow(dead_code))]
+    fn new(asking: i64, predicted: f64) -> Self {
+        let predicted_price = predicted.round() as i64;
+        let delta = asking - predicted_price;
+        Self {
+            predicted_price,
+            delta,
+            delta_pct: delta as f64 / predicted_price as f64,
+        }
+    }
+
+    /// Whether the asking price looks set low to start a bidding war
+    pub fn is_lowball(&self, config: &PredictionConfig) -> bool {
+        self.delta_pct <= -config.lowball_threshold
+    }
+}
+
+#[cfg(feature = "ml")]
+pub use model::PriceModel;
+
+#[cfg(feature = "ml")]
+mod model {
+    use super::{PredictionConfig, PricePrediction};
+    use crate::models::{Currency, Property, SoldListing};
+    use linfa::prelude::*;
+    use linfa_linear::{FittedLinearRegression, LinearRegression};
+    use ndarray::{Array1, Array2};
+    use std::collections::BTreeMap;
+    use tracing::{debug, warn};
+
+    /// Linear model of sold price per sqm from size, rooms, fee, floor and area
+    pub struct PriceModel {
+        fitted: FittedLinearRegression<f64>,
+        /// Areas with their own one-hot column, lowercased
+        areas: Vec<String>,
+        /// Feature columns that vary in the training data; constant columns
+        /// (e.g. "fee unknown" when every fee is known) would make the fit singular
+        columns: Vec<usize>,
+        training_rows: usize,
+    }
+
+    /// Size, rooms, fee per sqm and floor, with indicator columns for unknown values
+    const NUMERIC_FEATURES: usize = 6;
+
+    struct Row<'a> {
+        sqm: i32,
+        rooms: Option<f32>,
+        monthly_fee: Option<i64>,
+        floor: Option<f32>,
+        area: Option<&'a str>,
+    }
+
+    impl Row<'_> {
+        fn features(&self, areas: &[String]) -> Vec<f64> {
+            let sqm = self.sqm as f64;
+            let mut features = vec![
+                sqm,
+                self.rooms.map(f64::from).unwrap_or(0.0),
+                self.monthly_fee.map(|fee| fee as f64 / sqm).unwrap_or(0.0),
+                if self.monthly_fee.is_some() { 0.0 } else { 1.0 },
+                self.floor.map(f64::from).unwrap_or(0.0),
+                if self.floor.is_some() { 0.0 } else { 1.0 },
+            ];
+            let area = self.area.map(|a| a.trim().to_lowercase());
+            features.extend(areas.iter().map(|a| if area.as_ref() == Some(a) { 1.0 } else { 0.0 }));
+            features
+        }
+    }
+
+    impl PriceModel {
+        /// Fit a model on sold listings, or `None` if there is too little data
+        pub fn train(sold: &[SoldListing], config: &PredictionConfig) -> Option<Self> {
+            let usable: Vec<(&SoldListing, i32)> = sold
+                .iter()
+                .filter_map(|s| Some((s, s.sqm.filter(|sqm| *sqm > 0)?)))
+                .filter(|(s, _)| s.sold_price > 0)
+                .collect();
+            if usable.len() < config.min_training_rows {
+                debug!(
+                    "Not training price model: {} sold listings, need {}",
+                    usable.len(),
+                    config.min_training_rows
+                );
+                return None;
+            }
+
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for (listing, _) in &usable {
+                if let Some(area) = &listing.area {
+                    *counts.entry(area.trim().to_lowercase()).or_default() += 1;
+                }
+            }
+            // The most common area is the baseline the intercept captures
+            let baseline = counts.iter().max_by_key(|(_, count)| **count).map(|(area, _)| area.clone());
+            let areas: Vec<String> = counts
+                .into_iter()
+                .filter(|(area, count)| *count >= config.min_area_sales && Some(area) != baseline.as_ref())
+                .map(|(area, _)| area)
+                .collect();
+
+            let rows: Vec<Vec<f64>> =
+                usable.iter().map(|(listing, sqm)| Self::row(listing, *sqm).features(&areas)).collect();
+            let columns: Vec<usize> = (0..NUMERIC_FEATURES + areas.len())
+                .filter(|&col| rows.iter().any(|row| row[col] != rows[0][col]))
+                .collect();
+            let targets: Vec<f64> = usable
+                .iter()
+                .map(|(listing, sqm)| listing.sold_price as f64 / *sqm as f64)
+                .collect();
+
+            let records: Vec<f64> = rows.iter().flat_map(|row| columns.iter().map(|&col| row[col])).collect();
+            let records = Array2::from_shape_vec((usable.len(), columns.len()), records).ok()?;
+            let dataset = Dataset::new(records, Array1::from(targets));
+
+            match LinearRegression::new().fit(&dataset) {
+                Ok(fitted) => Some(Self {
+                    fitted,
+                    areas,
+                    columns,
+                    training_rows: usable.len(),
+                }),
+                Err(e) => {
+                    warn!("Failed to train price model: {}", e);
+                    None
+                }
+            }
+        }
+
+        /// Number of sold listings the model was trained on
+        pub fn training_rows(&self) -> usize {
+            self.training_rows
+        }
+
+        /// Predict the sale price of a listing
+        pub fn predict(&self, property: &Property) -> Option<PricePrediction> {
+            let sqm = property.sqm.filter(|sqm| *sqm > 0)?;
+            // Sold listings are all in SEK; the model doesn't price other currencies
+            let price = property.price.filter(|price| price.currency == Currency::Sek)?;
+
+            let row = Row {
+                sqm,
+                rooms: property.rooms,
+                monthly_fee: property.monthly_fee.map(|fee| fee.major()),
+                floor: property.floor,
+                area: property.location.area.as_deref(),
+            };
+            let all = row.features(&self.areas);
+            let features: Vec<f64> = self.columns.iter().map(|&col| all[col]).collect();
+            let features = Array2::from_shape_vec((1, features.len()), features).ok()?;
+            let per_sqm = self.fitted.predict(&features)[0];
+
+            // Compare against the original asking price, not a bid already driven up
+            let asking = property.asking_price.unwrap_or(price);
+            (per_sqm > 0.0).then(|| PricePrediction::new(asking.major(), per_sqm * sqm as f64))
+        }
+
+        fn row(listing: &SoldListing, sqm: i32) -> Row<'_> {
+            Row {
+                sqm,
+                rooms: listing.rooms,
+                monthly_fee: listing.monthly_fee,
+                floor: listing.floor,
+                area: listing.area.as_deref(),
+            }
+        }
+    }
+}
+
+/// Placeholder used when built without the `ml` feature; never trains
+#[cfg(not(feature = "ml"))]
+pub struct PriceModel;
+
+#[cfg(not(feature = "ml"))]
+impl PriceModel {
+    pub fn train(_sold: &[SoldListing], _config: &PredictionConfig) -> Option<Self> {
+        tracing::debug!("Price prediction disabled; rebuild with --features ml");
+        None
+    }
+
+    pub fn training_rows(&self) -> usize {
+        0
+    }
+
+    pub fn predict(&self, _property: &Property) -> Option<PricePrediction> {
+        None
+    }
+}
@@ -0,0 +1,85 @@
+//! Spotting listings that are an earlier listing put up again under a new ID
+//!
+//! Brokers take a listing down and put it up again to reset its days on
+//! market. Linking the two in the history (see
+//! [`History::link_relist`](crate::storage::History::link_relist)) keeps the
+//! first listing's days on market and price history with the new one.
+
+use crate::models::Property;
+use crate::storage::History;
+use std::collections::HashSet;
+
+/// Largest difference in living area still taken as the same home
+const SQM_TOLERANCE: i32 = 1;
+
+/// A stored listing that was put up again as another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relist {
+    pub from: String,
+    pub to: String,
+}
+
+/// Listings in `properties` that are relistings of stored listings
+///
+/// A relisting is a listing seen for the first time that matches a stored
+/// listing missing from this run: the same normalised address and living
+/// area, and, when photos of both were hashed, shared photos (see
+/// [`find_photo_matches`](crate::images::find_photo_matches)). A stored
+/// listing is taken to be relisted once, as the new listing most like it.
+pub fn find_relists(properties: &[Property], known: &[Property], history: &History) -> Vec<Relist> {
+    let current: HashSet<&str> = properties.iter().map(|p| p.id.as_str()).collect();
+    let gone: Vec<&Property> = known
+        .iter()
+        .filter(|p| !current.contains(p.id.as_str()))
+        .filter(|p| history.get(&p.id).is_some_and(|h| h.relisted_as.is_none()))
+        .collect();
+
+    let mut taken: HashSet<&str> = HashSet::new();
+    let mut relists = Vec::new();
+    for property in properties.iter().filter(|p| history.get(&p.id).is_none()) {
+        let address = normalize_address(&property.address);
+        let best = gone
+            .iter()
+            .filter(|old| !taken.contains(old.id.as_str()))
+            .filter(|old| same_home(property, &address, old))
+            .max_by_key(|old| (shared_photos(property, old), history.get(&old.id).map(|h| h.last_seen)));
+        if let Some(old) = best {
+            taken.insert(&old.id);
+            relists.push(Relist {
+                from: old.id.clone(),
+                to: property.id.clone(),
+            });
+        }
+    }
+    relists
+}
+
+fn same_home(new: &Property, address: &str, old: &Property) -> bool {
+    if new.source != old.source || address.is_empty() || normalize_address(&old.address) != address {
+        return false;
+    }
+    let same_sqm = match (new.sqm, old.sqm) {
+        (Some(a), Some(b)) => (a - b).abs() <= SQM_TOLERANCE,
+        (None, None) => true,
+        _ => false,
+    };
+    let photos_agree = new.image_hashes.is_empty() || old.image_hashes.is_empty() || shared_photos(new, old) > 0;
+    same_sqm && photos_agree
+}
+
+/// Photos `new` shares with `old`, as found by photo matching
+fn shared_photos(new: &Property, old: &Property) -> usize {
+    new.photo_matches
+        .iter()
+        .find(|m| m.property_id == old.id)
+        .map(|m| m.shared_photos)
+        .unwrap_or(0)
+}
+
+/// "Götgatan 12B, 3 tr" and "götgatan 12 b" both become "götgatan12b":
+/// lower case, letters and digits only, without the floor or apartment
+/// details brokers put after a comma
+pub fn normalize_address(address: &str) -> String {
+    let street = address.split(',').next().unwrap_or_default();
+    street.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
@@ -0,0 +1,150 @@
+use crate::export::ExportRecord;
+use crate::models::{Property, SoldListing};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use schemars::schema_for;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Which stored or exported shape to describe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaType {
+    /// A stored property (data/properties/<run-id>.json)
+    Property,
+    /// A sold listing (data/sold.json)
+    Sold,
+    /// A property with its derived values, as written by `scout export`
+    Export,
+}
+
+/// JSON Schema (draft 2020-12) of `ty`, with related types under `$defs`
+pub fn json_schema(ty: SchemaType) -> Value {
+    let schema = match ty {
+        SchemaType::Property => schema_for!(Property),
+        SchemaType::Sold => schema_for!(SoldListing),
+        SchemaType::Export => schema_for!(ExportRecord<'static>),
+    };
+    schema.to_value()
+}
+
+/// TypeScript declarations for a schema from [`json_schema`]: the root type
+/// and every type under `$defs`
+pub fn typescript(schema: &Value) -> Result<String> {
+    let root = schema.get("title").and_then(Value::as_str).context("Schema has no title")?;
+    let mut out = String::from("// Generated by `scout schema --typescript`; do not edit\n");
+    declare(&mut out, root, schema);
+    if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+        for (name, def) in defs {
+            declare(&mut out, name, def);
+        }
+    }
+    Ok(out)
+}
+
+fn declare(out: &mut String, name: &str, schema: &Value) {
+    out.push('\n');
+    doc(out, schema, "");
+    match schema.get("properties").and_then(Value::as_object) {
+        Some(properties) => {
+            let _ = writeln!(out, "export interface {} {{", name);
+            for (field, property) in properties {
+                doc(out, property, "  ");
+                let optional = if is_required(schema.get("required"), field) { "" } else { "?" };
+                let _ = writeln!(out, "  {}{}: {};", field, optional, ts_type(property));
+            }
+            out.push_str("}\n");
+        }
+        None => {
+            let _ = writeln!(out, "export type {} = {};", name, ts_type(schema));
+        }
+    }
+}
+
+fn doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        let _ = writeln!(out, "{}/** {} */", indent, description.replace('\n', " ").replace("*/", "* /"));
+    }
+}
+
+/// Whether `field` is listed in a schema's `required` array
+fn is_required(required: Option<&Value>, field: &str) -> bool {
+    required
+        .and_then(Value::as_array)
+        .is_some_and(|required| required.iter().any(|r| r == field))
+}
+
+/// TypeScript type of a (sub)schema
+fn ts_type(schema: &Value) -> String {
+    let Some(object) = schema.as_object() else {
+        return "unknown".to_string();
+    };
+
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().unwrap_or("unknown").to_string();
+    }
+    if let Some(value) = object.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = object.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string).collect());
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(members) = object.get(key).and_then(Value::as_array) {
+            return union(members.iter().map(ts_type).collect());
+        }
+    }
+
+    match object.get("type") {
+        Some(Value::String(ty)) => primitive(ty, object),
+        Some(Value::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|ty| primitive(ty, object))
+                .collect(),
+        ),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn primitive(ty: &str, schema: &serde_json::Map<String, Value>) -> String {
+    match ty {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            if item.contains(' ') {
+                format!("({})[]", item)
+            } else {
+                format!("{}[]", item)
+            }
+        }
+        "object" => match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) => {
+                let fields: Vec<String> = properties
+                    .iter()
+                    .map(|(field, property)| {
+                        let optional = if is_required(schema.get("required"), field) { "" } else { "?" };
+                        format!("{}{}: {}", field, optional, ts_type(property))
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            None => {
+                let value = schema
+                    .get("additionalProperties")
+                    .map(ts_type)
+                    .unwrap_or_else(|| "unknown".to_string());
+                format!("Record<string, {}>", value)
+            }
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+fn union(mut members: Vec<String>) -> String {
+    members.dedup();
+    members.join(" | ")
+}
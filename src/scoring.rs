@@ -0,0 +1,179 @@
+use crate::geo::GeoPoint;
+use crate::models::{Amenity, Property};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Weights and preferences for the composite property score
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub weights: ScoringWeights,
+    /// Point to measure distance from, e.g. work or a favourite square
+    pub reference_point: Option<GeoPoint>,
+    /// Distance at which the distance score reaches zero
+    pub max_distance_m: Option<f64>,
+    /// Areas that get a full area score; matched case-insensitively
+    pub preferred_areas: Vec<String>,
+}
+
+/// Relative importance of each score component; 0 disables a component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub price_per_sqm: f64,
+    pub fee: f64,
+    pub floor: f64,
+    pub balcony: f64,
+    pub distance: f64,
+    pub area: f64,
+    pub amenities: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            price_per_sqm: 1.0,
+            fee: 1.0,
+            floor: 0.5,
+            balcony: 0.5,
+            distance: 1.0,
+            area: 1.0,
+            amenities: 1.0,
+        }
+    }
+}
+
+/// Distance score falls linearly to zero at this many meters by default
+const DEFAULT_MAX_DISTANCE_M: f64 = 5_000.0;
+
+/// Floors at or above this get a full floor score
+const TOP_FLOOR_SCORE_AT: f32 = 5.0;
+
+/// Amenity counts that earn a full score: (groceries, gyms, preschools, parks)
+const AMENITY_TARGETS: (u32, u32, u32, u32) = (2, 1, 2, 1);
+
+/// Composite score of a property
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Score {
+    /// Weighted average of the known components, 0.0 - 1.0
+    pub total: f64,
+    /// Per-component scores, 0.0 - 1.0; unknown components are left out
+    pub components: BTreeMap<String, f64>,
+}
+
+/// Score every property; price and fee are ranked relative to the whole set
+pub fn score_all(properties: &[Property], config: &ScoringConfig) -> Vec<Score> {
+    let price_per_sqm: Vec<Option<f64>> = properties
+        .iter()
+        .map(|p| Some(p.price?.as_f64() / p.sqm? as f64))
+        .collect();
+    let fee_per_sqm: Vec<Option<f64>> = properties
+        .iter()
+        .map(|p| Some(p.monthly_fee?.as_f64() / p.sqm? as f64))
+        .collect();
+
+    let price_ranks = cheapness_ranks(&price_per_sqm);
+    let fee_ranks = cheapness_ranks(&fee_per_sqm);
+
+    properties
+        .iter()
+        .enumerate()
+        .map(|(idx, property)| {
+            let mut components = BTreeMap::new();
+            let weights = &config.weights;
+
+            let mut add = |name: &str, weight: f64, value: Option<f64>| {
+                if let Some(value) = value.filter(|_| weight > 0.0) {
+                    components.insert(name.to_string(), value.clamp(0.0, 1.0));
+                }
+            };
+
+            add("price_per_sqm", weights.price_per_sqm, price_ranks[idx]);
+            add("fee", weights.fee, fee_ranks[idx]);
+            add("floor", weights.floor, property.floor.map(|f| (f / TOP_FLOOR_SCORE_AT) as f64));
+            add("balcony", weights.balcony, Some(if has_balcony(property) { 1.0 } else { 0.0 }));
+            add("distance", weights.distance, distance_score(property, config));
+            add("area", weights.area, area_score(property, config));
+            add("amenities", weights.amenities, amenity_score(property));
+
+            let (sum, weight_sum) = components.iter().fold((0.0, 0.0), |(sum, weight_sum), (name, value)| {
+                let weight = weight_of(weights, name);
+                (sum + value * weight, weight_sum + weight)
+            });
+
+            Score {
+                total: if weight_sum > 0.0 { sum / weight_sum } else { 0.0 },
+                components,
+            }
+        })
+        .collect()
+}
+
+fn weight_of(weights: &ScoringWeights, component: &str) -> f64 {
+    match component {
+        "price_per_sqm" => weights.price_per_sqm,
+        "fee" => weights.fee,
+        "floor" => weights.floor,
+        "balcony" => weights.balcony,
+        "distance" => weights.distance,
+        "area" => weights.area,
+        "amenities" => weights.amenities,
+        _ => 0.0,
+    }
+}
+
+/// Percentile rank where the cheapest value scores 1.0 and the most expensive 0.0
+fn cheapness_ranks(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let known: Vec<f64> = values.iter().flatten().copied().collect();
+    if known.len() < 2 {
+        return values.iter().map(|v| v.map(|_| 1.0)).collect();
+    }
+
+    values
+        .iter()
+        .map(|value| {
+            value.map(|v| {
+                let more_expensive = known.iter().filter(|other| **other > v).count();
+                more_expensive as f64 / (known.len() - 1) as f64
+            })
+        })
+        .collect()
+}
+
+fn has_balcony(property: &Property) -> bool {
+    property.features.amenities.iter().any(Amenity::is_outdoor_space)
+}
+
+fn distance_score(property: &Property, config: &ScoringConfig) -> Option<f64> {
+    let reference = config.reference_point?;
+    let point = property.location.point()?;
+    let max = config.max_distance_m.unwrap_or(DEFAULT_MAX_DISTANCE_M);
+    Some(1.0 - point.distance_m(&reference) / max)
+}
+
+fn area_score(property: &Property, config: &ScoringConfig) -> Option<f64> {
+    if config.preferred_areas.is_empty() {
+        return None;
+    }
+    let area = property.location.area.as_deref()?.to_lowercase();
+    let preferred = config
+        .preferred_areas
+        .iter()
+        .any(|p| area.contains(&p.to_lowercase()));
+    Some(if preferred { 1.0 } else { 0.0 })
+}
+
+/// Average of each amenity category's count relative to its target, capped at 1
+fn amenity_score(property: &Property) -> Option<f64> {
+    let nearby = property.nearby.as_ref()?;
+    let (groceries, gyms, preschools, parks) = AMENITY_TARGETS;
+    let ratio = |count: u32, target: u32| (count as f64 / target as f64).min(1.0);
+    Some(
+        (ratio(nearby.groceries, groceries)
+            + ratio(nearby.gyms, gyms)
+            + ratio(nearby.preschools, preschools)
+            + ratio(nearby.parks, parks))
+            / 4.0,
+    )
+}
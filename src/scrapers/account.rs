@@ -0,0 +1,73 @@
+use crate::secrets::Secret;
+use anyhow::{Context, Result};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+pub const LOGIN_URL: &str = "https://www.booli.se/logga-in";
+/// "Bevakningar": the account's saved searches
+pub const SAVED_SEARCHES_URL: &str = "https://www.booli.se/mina-sidor/bevakningar";
+
+const BOOLI_ORIGIN: &str = "https://www.booli.se";
+
+/// Booli login used by `scout booli` and, when set, by the browser scraper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BooliAccount {
+    pub email: String,
+    /// The password, or `{ secret = "<name>" }`; see [`crate::secrets`]
+    #[serde(default)]
+    pub password: Option<Secret>,
+    /// Environment variable holding the password when `password` isn't set
+    #[serde(default = "default_password_env")]
+    pub password_env: String,
+}
+
+fn default_password_env() -> String {
+    "BOOLI_PASSWORD".to_string()
+}
+
+impl BooliAccount {
+    pub fn password(&self) -> Result<String> {
+        if let Some(password) = &self.password {
+            return password.resolve();
+        }
+        std::env::var(&self.password_env)
+            .with_context(|| format!("Set {} to the Booli account's password", self.password_env))
+    }
+}
+
+/// A search saved in the Booli account
+#[derive(Debug, Clone, PartialEq)]
+pub struct BooliSavedSearch {
+    pub name: String,
+    pub url: String,
+}
+
+/// Saved searches listed on the account's "Bevakningar" page
+pub fn parse_saved_searches(html: &str) -> Vec<BooliSavedSearch> {
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse(r#"a[href*="/sok/"]"#).unwrap();
+
+    let mut searches: Vec<BooliSavedSearch> = Vec::new();
+    for link in document.select(&link_selector) {
+        let Some(href) = link.value().attr("href") else {
+            continue;
+        };
+        let url = if href.starts_with('/') {
+            format!("{}{}", BOOLI_ORIGIN, href)
+        } else {
+            href.to_string()
+        };
+        if searches.iter().any(|search| search.url == url) {
+            continue;
+        }
+        let text = link.text().collect::<Vec<_>>().join(" ");
+        let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let name = if name.is_empty() {
+            format!("Bevakning {}", searches.len() + 1)
+        } else {
+            name
+        };
+        searches.push(BooliSavedSearch { name, url });
+    }
+    searches
+}
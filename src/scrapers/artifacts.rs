@@ -0,0 +1,160 @@
+use crate::storage::runs::run_started;
+use anyhow::{Context, Result};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Directory under [`DebugConfig::dir`] holding search page snapshots
+pub(crate) const SNAPSHOT_DIR: &str = "snapshots";
+/// A source's search page from the latest run
+pub(crate) const LATEST_SNAPSHOT: &str = "latest.html";
+/// A source's search page from the latest run it passed its health checks in
+pub(crate) const GOOD_SNAPSHOT: &str = "good.html";
+
+/// Debug HTML and screenshots of search pages, for working out markup changes
+///
+/// Written only with `scout scrape --debug-artifacts` or `enabled = true`,
+/// into one directory per run named by run ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugConfig {
+    /// Write artifacts on every run, not just with `--debug-artifacts`
+    pub enabled: bool,
+    pub dir: PathBuf,
+    /// Run directories kept; older ones are removed after each run
+    pub keep_runs: usize,
+    /// Also remove run directories older than this
+    pub max_age_days: Option<u32>,
+    /// Keep each source's latest search page, and the latest from a run that
+    /// passed its health checks, in <dir>/snapshots; when a source's health
+    /// check warns, both are compared in the run's directory (see
+    /// [`snapshot`](crate::scrapers::snapshot))
+    pub snapshots: bool,
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("debug"),
+            keep_runs: 5,
+            max_age_days: Some(30),
+            snapshots: true,
+        }
+    }
+}
+
+/// Where a run's debug artifacts go, if it keeps any
+#[derive(Debug, Clone, Default)]
+pub struct DebugArtifacts {
+    dir: Option<PathBuf>,
+    snapshots: Option<PathBuf>,
+    source: Option<String>,
+}
+
+impl DebugArtifacts {
+    /// Artifacts of run `run_id` under the configured directory
+    pub fn for_run(config: &DebugConfig, run_id: &str) -> Self {
+        Self {
+            dir: Some(config.dir.join(run_id)),
+            ..Self::default()
+        }
+    }
+
+    /// Also keep search page snapshots, if configured
+    pub fn with_snapshots(mut self, config: &DebugConfig) -> Self {
+        self.snapshots = config.snapshots.then(|| config.dir.join(SNAPSHOT_DIR));
+        self
+    }
+
+    /// Snapshots go under `source`, unless a source wrapping this one named
+    /// them already
+    pub fn labelled(mut self, source: &str) -> Self {
+        self.source.get_or_insert_with(|| source.to_string());
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Write `name` into the run's directory; failures are only logged, since
+    /// a debug file is never worth failing a scrape over
+    pub fn write(&self, name: &str, data: impl AsRef<[u8]>) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let path = dir.join(name);
+        let result = std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::write(&path, data.as_ref()))
+            .with_context(|| format!("Failed to write {}", path.display()));
+        match result {
+            Ok(()) => info!("Saved {} ({} bytes)", path.display(), data.as_ref().len()),
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+
+    /// Keep `html` as the source's latest search page, replacing the last one
+    pub fn snapshot(&self, html: &str) {
+        let (Some(dir), Some(source)) = (&self.snapshots, &self.source) else {
+            return;
+        };
+        let dir = dir.join(source_slug(source));
+        let path = dir.join(LATEST_SNAPSHOT);
+        let result = std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, html))
+            .with_context(|| format!("Failed to write {}", path.display()));
+        if let Err(e) = result {
+            warn!("{:#}", e);
+        }
+    }
+}
+
+/// `Booli (browser)` -> `booli_browser`
+pub(crate) fn source_slug(source: &str) -> String {
+    source
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Remove run directories beyond `keep_runs` and older than `max_age_days`;
+/// returns how many were removed
+pub fn prune(config: &DebugConfig) -> Result<usize> {
+    if !config.dir.is_dir() {
+        return Ok(0);
+    }
+    let mut runs: Vec<(PathBuf, Option<NaiveDateTime>)> = std::fs::read_dir(&config.dir)
+        .with_context(|| format!("Failed to read {}", config.dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| {
+            let started = run_started(&entry.file_name().to_string_lossy());
+            (entry.path(), started)
+        })
+        // Only run directories; anything else in there isn't ours to delete
+        .filter(|(_, started)| started.is_some())
+        .collect();
+    // Run IDs are timestamps, so newest first by name
+    runs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let cutoff = config
+        .max_age_days
+        .map(|days| Utc::now().naive_utc() - ChronoDuration::days(i64::from(days)));
+    let mut removed = 0;
+    for (i, (path, started)) in runs.iter().enumerate() {
+        let too_old = cutoff.is_some_and(|cutoff| started.is_some_and(|s| s < cutoff));
+        if i >= config.keep_runs || too_old {
+            remove(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn remove(path: &Path) -> Result<()> {
+    std::fs::remove_dir_all(path).with_context(|| format!("Failed to remove {}", path.display()))
+}
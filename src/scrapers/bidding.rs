@@ -0,0 +1,56 @@
+use crate::scrapers::parse::{leading_number, normalize};
+
+/// Bidding information extracted from a listing card or detail page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BidInfo {
+    /// "Budgivning pågår" was shown on the listing
+    pub in_progress: bool,
+    /// Asking price ("Utgångspris") when stated explicitly
+    pub asking_price: Option<i64>,
+    /// Current highest bid ("Högsta bud" / "Senaste bud")
+    pub current_bid: Option<i64>,
+}
+
+/// Labels Booli uses in front of the current bid
+const BID_LABELS: [&str; 4] = ["Högsta bud", "Senaste bud", "Aktuellt bud", "Bud"];
+
+/// Labels Booli uses in front of the asking price
+const ASKING_LABELS: [&str; 2] = ["Utgångspris", "Pris"];
+
+/// Parse bidding status, asking price and current bid from free text
+///
+/// Works on the flattened text of a listing card or detail page, e.g.
+/// "Budgivning pågår Högsta bud 5 400 000 kr Utgångspris 5 195 000 kr"
+pub fn parse_bid_info(text: &str) -> BidInfo {
+    let text = normalize(text);
+
+    let in_progress = text.contains("Budgivning pågår") || text.contains("budgivning pågår");
+
+    let current_bid = BID_LABELS
+        .iter()
+        .find_map(|label| amount_after(&text, label));
+
+    let asking_price = ASKING_LABELS
+        .iter()
+        .find_map(|label| amount_after(&text, label));
+
+    BidInfo {
+        in_progress: in_progress || current_bid.is_some(),
+        asking_price,
+        current_bid,
+    }
+}
+
+/// Find `label` in `text` and parse the "5 400 000 kr" amount that follows it
+fn amount_after(text: &str, label: &str) -> Option<i64> {
+    text.match_indices(label).find_map(|(pos, _)| {
+        let after = &text[pos + label.len()..];
+
+        // Skip separators between the label and the number ("Högsta bud: 5 400 000 kr")
+        let after = after.trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+        let (amount, rest) = leading_number(after)?;
+
+        // Require a currency marker so "Pris per kvm" etc. aren't mistaken for a price
+        rest.trim_start().starts_with("kr").then(|| amount.round() as i64)
+    })
+}
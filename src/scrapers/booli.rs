@@ -1,20 +1,72 @@
-use crate::models::{Location, Property, Source};
+use crate::models::{size_label, Amenity, Features, InvalidListing, ListingStatus, Location, Money, Property, Source};
+use crate::scrapers::artifacts::DebugArtifacts;
+use crate::scrapers::bidding::parse_bid_info;
+use crate::scrapers::fees::parse_monthly_fee;
+use crate::scrapers::floor::parse_floor;
+use crate::scrapers::parse::{parse_price, parse_rooms, parse_sqm};
+use crate::scrapers::plugin::ParserPlugin;
+use crate::scrapers::politeness::{Identity, Politeness};
+use crate::scrapers::runner::merge_listings;
 use crate::scrapers::traits::ScraperTrait;
-use crate::scrapers::types::SearchParams;
+use crate::scrapers::types::{SearchParams, SODERMALM_AREA_ID};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
-use reqwest::Client;
-use scraper::Html;
+use chrono_tz::Europe::Stockholm;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Response, StatusCode};
 use serde_json::json;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+/// Listing link at the end of a card line: "...](https://www.booli.se/annons/123)"
+static LISTING_URL: Lazy<Regex> = Lazy::new(|| Regex::new(r"\]\((https://www\.booli\.se/[^)\s]+)\)").unwrap());
+
+/// Address between the save button and the property type: "Spara Götgatan 120Götgatan 120Lägenhet"
+static ADDRESS: Lazy<Regex> = Lazy::new(|| Regex::new(r"Spara\s+(.+?)\s*Lägenhet").unwrap());
+
+/// Area after the property type: "Lägenhet · Södermalm · Stockholm"
+static AREA: Lazy<Regex> = Lazy::new(|| Regex::new(r"Lägenhet\s*·\s*([^·]+?)\s*·").unwrap());
+
+/// What a current desktop Chrome sends as its user agent
+pub(crate) const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Result pages read at most; later pages are left for the other sources
+const MAX_PAGES: u32 = 10;
+
+/// Tries per page before a rate limit, server error or dropped connection
+/// fails the scrape
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry, doubled for each one after it, unless Booli
+/// sends a `Retry-After`
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Cards repeat the address (heading and image alt text); keep one copy
+fn dedupe_address(section: &str) -> String {
+    let section = section.trim();
+    let chars: Vec<char> = section.chars().collect();
+    let (first, second) = chars.split_at(chars.len() / 2);
+    if chars.len().is_multiple_of(2) && first == second {
+        first.iter().collect::<String>().trim().to_string()
+    } else {
+        section.to_string()
+    }
+}
+
 /// Booli scraper implementation
 pub struct BooliScraper {
     client: Client,
+    base_url: String,
     #[allow(dead_code)]
     params: SearchParams,
+    politeness: Politeness,
+    plugin: Option<Arc<ParserPlugin>>,
+    debug: DebugArtifacts,
+    rejected: Mutex<Vec<InvalidListing>>,
 }
 
 impl BooliScraper {
@@ -27,166 +79,223 @@ impl BooliScraper {
     pub fn with_params(params: SearchParams) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
-            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+            .user_agent(BROWSER_USER_AGENT)
+            .default_headers(browser_headers())
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client, params })
+        Ok(Self {
+            client,
+            base_url: "https://www.booli.se".to_string(),
+            params,
+            // Pauses only when configured; tests and library callers go straight on
+            politeness: Politeness {
+                page_delay_ms: 0,
+                ..Politeness::default()
+            },
+            plugin: None,
+            debug: DebugArtifacts::default(),
+            rejected: Mutex::new(Vec::new()),
+        })
     }
 
-    /// Parse property data from extracted JSON or HTML
-    fn parse_properties_from_html(&self, html: &str) -> Vec<Property> {
-        let mut properties = Vec::new();
-        
-        // The content comes as text, look for property patterns
-        // Format we're seeing: "### Address" followed by details like "- XX m²", "- X rum", etc.
-        let lines: Vec<&str> = html.lines().collect();
-        let mut i = 0;
-        
-        while i < lines.len() {
-            let line = lines[i].trim();
-            
-            // Look for lines that contain "www.booli.se" which have property data
-            if line.contains("www.booli.se") && line.contains("Lägenhet") {
-                // Extract data from the link line
-                // Format: [Date]Spara AddressAddressLägenhet · Area · StockholmPRICE krSIZE m²ROOMS rumvånFLOOR FEE kr/månFEATURES](URL)
-                
-                let mut address = String::new();
-                let mut price: i64 = 0;
-                let mut sqm: i32 = 0;
-                let mut rooms: f32 = 0.0;
-                let mut url = String::new();
-                let mut area = String::from("Södermalm");
-                let mut features = Vec::new();
-                
-                // Extract URL
-                if let Some(url_start) = line.rfind("](https://www.booli.se/") {
-                    let url_part = &line[url_start+2..];
-                    if let Some(url_end) = url_part.find(')') {
-                        url = url_part[..url_end].to_string();
-                    }
-                }
-                
-                // Extract address - it's after "Spara " and before "Lägenhet"
-                if let Some(spara_pos) = line.find("Spara ") {
-                    if let Some(lagenhet_pos) = line.find("Lägenhet") {
-                        let addr_section = &line[spara_pos+6..lagenhet_pos];
-                        // Address appears twice, take first occurrence
-                        let parts: Vec<&str> = addr_section.split(|c: char| !c.is_alphanumeric() && c != ' ' && c != 'å' && c != 'ä' && c != 'ö' && c != 'Å' && c != 'Ä' && c != 'Ö').collect();
-                        for part in parts {
-                            if !part.is_empty() && part.len() > 3 {
-                                address = part.trim().to_string();
-                                break;
-                            }
-                        }
-                    }
-                }
-                
-                // Extract area - between "·" markers
-                if let Some(area_match) = line.match_indices(" · ").nth(0) {
-                    if let Some(area_end) = line[area_match.0+3..].find(" · ") {
-                        area = line[area_match.0+3..area_match.0+3+area_end].trim().to_string();
-                    }
-                }
-                
-                // Extract price - number followed by " kr"
-                if let Some(kr_pos) = line.find(" kr") {
-                    // Look backwards for the price
-                    let before_kr = &line[..kr_pos];
-                    if let Some(last_digit_pos) = before_kr.rfind(|c: char| c.is_numeric()) {
-                        // Find start of number
-                        let mut start = last_digit_pos;
-                        while start > 0 && (before_kr.chars().nth(start-1).unwrap().is_numeric() || before_kr.chars().nth(start-1).unwrap() == ' ') {
-                            start -= 1;
-                        }
-                        let price_str = before_kr[start..=last_digit_pos].replace(" ", "");
-                        if let Ok(p) = price_str.parse::<i64>() {
-                            price = p;
-                        }
-                    }
-                }
-                
-                // Extract sqm - number before "m²"
-                if let Some(m2_pos) = line.find("m²") {
-                    let before_m2 = &line[..m2_pos];
-                    if let Some(last_digit_pos) = before_m2.rfind(|c: char| c.is_numeric()) {
-                        let mut start = last_digit_pos;
-                        while start > 0 && (before_m2.chars().nth(start-1).unwrap().is_numeric() || before_m2.chars().nth(start-1).unwrap() == ' ' || before_m2.chars().nth(start-1).unwrap() == '+') {
-                            start -= 1;
-                        }
-                        let sqm_str = before_m2[start..=last_digit_pos].replace(" ", "").replace("+", "");
-                        if let Ok(s) = sqm_str.parse::<i32>() {
-                            sqm = s;
-                        }
-                    }
-                }
-                
-                // Extract rooms - number before "rum"
-                if let Some(rum_pos) = line.find("rum") {
-                    let before_rum = &line[..rum_pos];
-                    if let Some(last_digit_pos) = before_rum.rfind(|c: char| c.is_numeric() || c == ',' || c == '.') {
-                        let mut start = last_digit_pos;
-                        while start > 0 && (before_rum.chars().nth(start-1).map(|c| c.is_numeric() || c == ',' || c == '.').unwrap_or(false)) {
-                            start -= 1;
-                        }
-                        let rooms_str = before_rum[start..=last_digit_pos].replace(",", ".");
-                        if let Ok(r) = rooms_str.parse::<f32>() {
-                            rooms = r;
-                        }
-                    }
-                }
-                
-                // Extract features
-                if line.contains("Hiss") {
-                    features.push("Hiss".to_string());
+    /// Fetch search pages from `url` instead of www.booli.se, e.g. a test server
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Wait between pages, stay within active hours and announce the scraper
+    /// as `politeness` says
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Parse search pages with `plugin` instead of the built-in parser
+    pub fn with_plugin(mut self, plugin: Option<Arc<ParserPlugin>>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Keep snapshots of the first search page in `debug`
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        self.debug = debug.labelled(self.source_name());
+        self
+    }
+
+    /// Södermalm search results, page `page` counting from 1
+    fn search_url(&self, page: u32) -> String {
+        let url = format!("{}/sok/till-salu?areaIds={}", self.base_url, SODERMALM_AREA_ID);
+        if page == 1 {
+            url
+        } else {
+            format!("{}&page={}", url, page)
+        }
+    }
+
+    /// GET `url`, retrying rate limits, server errors and dropped connections
+    async fn fetch(&self, url: &str) -> Result<String> {
+        let mut attempt = 1;
+        loop {
+            debug!("Fetching URL: {} (attempt {})", url, attempt);
+            let request = self
+                .client
+                .get(url)
+                .headers(self.politeness.identity.as_ref().map(Identity::headers).unwrap_or_default());
+            let (error, retry_after) = match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return response.text().await.context("Failed to read response body");
                 }
-                if line.contains("Balkong") {
-                    features.push("Balkong".to_string());
+                Ok(response) if is_transient(response.status()) => {
+                    let retry_after = retry_after(&response);
+                    (anyhow::anyhow!("Booli returned status: {}", response.status()), retry_after)
                 }
-                if line.contains("Eldstad") {
-                    features.push("Eldstad".to_string());
+                Ok(response) => {
+                    warn!("Booli returned status: {}", response.status());
+                    anyhow::bail!("Failed to fetch Booli page: {}", response.status());
                 }
-                
-                // Extract Booli ID from URL
-                let property_id = if !url.is_empty() {
-                    url.split('/').last().unwrap_or("unknown").to_string()
+                Err(e) => (anyhow::Error::new(e).context("Failed to fetch Booli page"), None),
+            };
+            if attempt >= MAX_ATTEMPTS {
+                return Err(error.context(format!("Gave up on {} after {} attempts", url, attempt)));
+            }
+            let delay = retry_after.unwrap_or(RETRY_DELAY * 2u32.pow(attempt - 1));
+            warn!("{:#}; retrying in {:?}", error, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Wait for the configured active hours, if outside them
+    async fn wait_until_active(&self) {
+        let wait = self.politeness.until_active(Utc::now().with_timezone(&Stockholm).time());
+        if !wait.is_zero() {
+            info!("Outside active hours; resuming in {} min", wait.as_secs().div_ceil(60));
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The listings on a Booli search page as served over plain HTTP, one card
+/// per line, and the ones that failed validation
+pub fn parse_card_lines(html: &str) -> (Vec<Property>, Vec<InvalidListing>) {
+    let mut properties = Vec::new();
+    let mut rejected = Vec::new();
+    
+    // The content comes as text, look for property patterns
+    // Format we're seeing: "### Address" followed by details like "- XX m²", "- X rum", etc.
+    let lines: Vec<&str> = html.lines().collect();
+    let mut i = 0;
+    
+    while i < lines.len() {
+        let line = lines[i].trim();
+        
+        // Look for lines that contain "www.booli.se" which have property data
+        if line.contains("www.booli.se") && line.contains("Lägenhet") {
+            // Extract data from the link line
+            // Format: [Date]Spara AddressAddressLägenhet · Area · StockholmPRICE krSIZE m²ROOMS rumvånFLOOR FEE kr/månFEATURES](URL)
+            
+            let url = LISTING_URL
+                .captures_iter(line)
+                .last()
+                .map(|c| c[1].to_string())
+                .unwrap_or_default();
+            let address = ADDRESS.captures(line).map(|c| dedupe_address(&c[1])).unwrap_or_default();
+            let area = AREA
+                .captures(line)
+                .map(|c| c[1].trim().to_string())
+                .unwrap_or_else(|| "Södermalm".to_string());
+            let mut features = Features::default();
+            
+            // Price, living area and rooms: "5 195 000 kr70 m²2 rum"
+            let price = parse_price(line);
+            let sqm = parse_sqm(line).map(|sqm| sqm.round() as i32);
+            let rooms = parse_rooms(line);
+            
+            // Extract features
+            if line.contains("Hiss") {
+                features.insert(Amenity::Elevator);
+            }
+            if line.contains("Balkong") {
+                features.insert(Amenity::Balcony);
+            }
+            if line.contains("Eldstad") {
+                features.insert(Amenity::Fireplace);
+            }
+            
+            // Bidding status and current bid
+            let bids = parse_bid_info(line);
+            
+            // Extract Booli ID from URL
+            let property_id = if !url.is_empty() {
+                url.split('/').next_back().unwrap_or("unknown").to_string()
+            } else {
+                format!("booli_{}", i)
+            };
+            
+            let built = Property::builder(property_id, Source::Booli)
+                .location(Location {
+                    city: "Stockholm".to_string(),
+                    area: Some(area.clone()),
+                    latitude: Some(59.3145),
+                    longitude: Some(18.0736),
+                })
+                .address(address.clone())
+                .price(price.map(Money::sek))
+                .asking_price(bids.asking_price.map(Money::sek))
+                .current_bid(bids.current_bid.map(Money::sek))
+                .bidding_in_progress(bids.in_progress)
+                .listing_status(if line.contains("Snart till salu") {
+                    ListingStatus::ComingSoon
                 } else {
-                    format!("booli_{}", i)
-                };
-                
-                // Only add if we have minimum data
-                if !address.is_empty() && (price > 0 || sqm > 0) {
-                    properties.push(Property {
-                        id: property_id,
-                        source: Source::Booli,
-                        location: Location {
-                            city: "Stockholm".to_string(),
-                            area: Some(area.clone()),
-                            latitude: Some(59.3145),
-                            longitude: Some(18.0736),
-                        },
-                        address: address.clone(),
-                        price,
-                        rooms,
-                        sqm,
-                        description: format!("Lägenhet i {}. {} rum, {} kvm.", area, rooms, sqm),
-                        features: features.clone(),
-                        images: vec![],
-                        url: url.clone(),
-                        scraped_at: Utc::now(),
-                        raw_data: json!({
-                            "area": area,
-                            "scraped_from": "booli_real_data"
-                        }),
-                    });
+                    ListingStatus::ForSale
+                })
+                .monthly_fee(parse_monthly_fee(line).map(Money::sek))
+                .rooms(rooms)
+                .sqm(sqm)
+                .floor(parse_floor(line))
+                .description(format!("Lägenhet i {}. {}.", area, size_label(rooms, sqm)))
+                .features(features.clone())
+                .url(url.clone())
+                .area_tags(vec!["Södermalm".to_string()])
+                .raw_data(json!({
+                    "area": area,
+                    "scraped_from": "booli_real_data"
+                }))
+                .build();
+            match built {
+                Ok(property) => properties.push(property),
+                Err(invalid) => {
+                    debug!("{}", invalid);
+                    rejected.push(invalid);
                 }
             }
-            
-            i += 1;
         }
         
-        properties
+        i += 1;
     }
+    
+    (properties, rejected)
+}
+
+/// Headers a browser sends with a page load, so Booli serves the Swedish page
+pub(crate) fn browser_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml;q=0.9,*/*;q=0.8"));
+    headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("sv-SE,sv;q=0.9,en;q=0.8"));
+    headers
+}
+
+/// Rate limits and server errors are worth another try
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// A `Retry-After` given in seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
 }
 
 impl Default for BooliScraper {
@@ -200,29 +309,47 @@ impl ScraperTrait for BooliScraper {
     async fn scrape(&self) -> Result<Vec<Property>> {
         info!("Starting Booli scrape for Södermalm");
 
-        // Södermalm search URL
-        let url = "https://www.booli.se/sok/till-salu?areaIds=115341";
-        
-        debug!("Fetching URL: {}", url);
-        
-        let response = self.client
-            .get(url)
-            .send()
-            .await
-            .context("Failed to fetch Booli page")?;
+        // Read result pages until one adds no listings; later pages failing
+        // doesn't lose the earlier ones
+        let mut properties = Vec::new();
+        for page in 1..=MAX_PAGES {
+            if page > 1 {
+                tokio::time::sleep(self.politeness.page_delay()).await;
+            }
+            self.wait_until_active().await;
+            let html = match self.fetch(&self.search_url(page)).await {
+                Ok(html) => html,
+                Err(e) if page > 1 => {
+                    warn!("Stopping at page {}: {:#}", page, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            debug!("Downloaded {} bytes of HTML", html.len());
+            if page == 1 {
+                self.debug.snapshot(&html);
+            }
 
-        if !response.status().is_success() {
-            warn!("Booli returned status: {}", response.status());
-            anyhow::bail!("Failed to fetch Booli page: {}", response.status());
+            // Parse properties from the HTML content
+            let before = properties.len();
+            let found = match &self.plugin {
+                Some(plugin) => {
+                    let (found, rejected) = plugin.parse_page(&html, "Södermalm", "Stockholm")?;
+                    self.rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(rejected);
+                    found
+                }
+                None => {
+                    let (found, rejected) = parse_card_lines(&html);
+                    self.rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(rejected);
+                    found
+                }
+            };
+            merge_listings(&mut properties, found);
+            if properties.len() == before {
+                break;
+            }
         }
 
-        let html = response.text().await.context("Failed to read response body")?;
-        
-        debug!("Downloaded {} bytes of HTML", html.len());
-        
-        // Parse properties from the HTML content
-        let properties = self.parse_properties_from_html(&html);
-
         if properties.is_empty() {
             warn!("No properties found - unable to parse Booli page");
             info!("Page downloaded successfully but parsing failed");
@@ -234,9 +361,13 @@ impl ScraperTrait for BooliScraper {
         }
     }
 
-    fn source_name(&self) -> &'static str {
+    fn source_name(&self) -> &str {
         "Booli"
     }
+
+    fn rejected(&self) -> Vec<InvalidListing> {
+        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
 }
 
 impl BooliScraper {
@@ -255,13 +386,40 @@ impl BooliScraper {
                     longitude: Some(18.0736),
                 },
                 address: "Götgatan 120".to_string(),
-                price: 5_195_000,
-                rooms: 2.0,
-                sqm: 70,
+                price: Some(Money::sek(5_195_000)),
+                asking_price: None,
+                current_bid: None,
+                bidding_in_progress: false,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: Some(Money::sek(3_449)),
+                operating_cost: None,
+                rooms: Some(2.0),
+                sqm: Some(70),
+                floor: Some(3.0),
                 description: "Lägenhet på Södermalm. Hiss och balkong. Avgift: 3 449 kr/mån.".to_string(),
-                features: vec!["Hiss".to_string(), "Balkong".to_string()],
+                features: Features::from(vec![Amenity::Elevator, Amenity::Balcony]),
                 images: vec![],
+                local_images: vec![],
+                local_floor_plans: vec![],
+                floor_plan_sqm: None,
+                image_hashes: vec![],
+                photo_matches: vec![],
+                viewings: vec![],
+                virtual_tours: vec![],
                 url: "https://www.booli.se/annons/sodermalm1".to_string(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: vec!["Södermalm".to_string()],
+                published_at: None,
+                run_id: None,
                 scraped_at: Utc::now(),
                 raw_data: json!({
                     "mock": true,
@@ -279,13 +437,40 @@ impl BooliScraper {
                     longitude: Some(18.0736),
                 },
                 address: "Ringvägen 11A".to_string(),
-                price: 7_900_000,
-                rooms: 4.0,
-                sqm: 84,
+                price: Some(Money::sek(7_900_000)),
+                asking_price: Some(Money::sek(7_600_000)),
+                current_bid: Some(Money::sek(7_900_000)),
+                bidding_in_progress: true,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: Some(Money::sek(3_390)),
+                operating_cost: None,
+                rooms: Some(4.0),
+                sqm: Some(84),
+                floor: Some(5.0),
                 description: "Lägenhet på Södermalm. Hiss och balkong. Avgift: 3 390 kr/mån.".to_string(),
-                features: vec!["Hiss".to_string(), "Balkong".to_string()],
+                features: Features::from(vec![Amenity::Elevator, Amenity::Balcony]),
                 images: vec![],
+                local_images: vec![],
+                local_floor_plans: vec![],
+                floor_plan_sqm: None,
+                image_hashes: vec![],
+                photo_matches: vec![],
+                viewings: vec![],
+                virtual_tours: vec![],
                 url: "https://www.booli.se/annons/sodermalm2".to_string(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: vec!["Södermalm".to_string()],
+                published_at: None,
+                run_id: None,
                 scraped_at: Utc::now(),
                 raw_data: json!({
                     "mock": true,
@@ -303,13 +488,40 @@ impl BooliScraper {
                     longitude: Some(18.0736),
                 },
                 address: "Tjustgatan 4".to_string(),
-                price: 2_395_000,
-                rooms: 1.0,
-                sqm: 24,
+                price: Some(Money::sek(2_395_000)),
+                asking_price: None,
+                current_bid: None,
+                bidding_in_progress: false,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: Some(Money::sek(2_405)),
+                operating_cost: None,
+                rooms: Some(1.0),
+                sqm: Some(24),
+                floor: Some(1.0),
                 description: "Liten lägenhet på Katarina. Hiss och balkong. Avgift: 2 405 kr/mån.".to_string(),
-                features: vec!["Hiss".to_string(), "Balkong".to_string()],
+                features: Features::from(vec![Amenity::Elevator, Amenity::Balcony]),
                 images: vec![],
+                local_images: vec![],
+                local_floor_plans: vec![],
+                floor_plan_sqm: None,
+                image_hashes: vec![],
+                photo_matches: vec![],
+                viewings: vec![],
+                virtual_tours: vec![],
                 url: "https://www.booli.se/annons/sodermalm3".to_string(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: vec!["Södermalm".to_string()],
+                published_at: None,
+                run_id: None,
                 scraped_at: Utc::now(),
                 raw_data: json!({
                     "mock": true,
@@ -327,13 +539,40 @@ impl BooliScraper {
                     longitude: Some(18.0736),
                 },
                 address: "Torkel Knutssonsgatan 31".to_string(),
-                price: 12_950_000,
-                rooms: 4.0,
-                sqm: 114,
+                price: Some(Money::sek(12_950_000)),
+                asking_price: None,
+                current_bid: None,
+                bidding_in_progress: false,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: Some(Money::sek(4_457)),
+                operating_cost: None,
+                rooms: Some(4.0),
+                sqm: Some(114),
+                floor: Some(4.0),
                 description: "Lägenhet på Södermalm. Hiss, balkong och eldstad. Avgift: 4 457 kr/mån.".to_string(),
-                features: vec!["Hiss".to_string(), "Balkong".to_string(), "Eldstad".to_string()],
+                features: Features::from(vec![Amenity::Elevator, Amenity::Balcony, Amenity::Fireplace]),
                 images: vec![],
+                local_images: vec![],
+                local_floor_plans: vec![],
+                floor_plan_sqm: None,
+                image_hashes: vec![],
+                photo_matches: vec![],
+                viewings: vec![],
+                virtual_tours: vec![],
                 url: "https://www.booli.se/annons/sodermalm4".to_string(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: vec!["Södermalm".to_string()],
+                published_at: None,
+                run_id: None,
                 scraped_at: Utc::now(),
                 raw_data: json!({
                     "mock": true,
@@ -351,13 +590,40 @@ impl BooliScraper {
                     longitude: Some(18.0736),
                 },
                 address: "Folkungagatan 101".to_string(),
-                price: 3_495_000,
-                rooms: 2.0,
-                sqm: 39,
+                price: Some(Money::sek(3_495_000)),
+                asking_price: None,
+                current_bid: None,
+                bidding_in_progress: false,
+                listing_status: ListingStatus::ForSale,
+                monthly_fee: Some(Money::sek(2_416)),
+                operating_cost: None,
+                rooms: Some(2.0),
+                sqm: Some(39),
+                floor: Some(2.0),
                 description: "Lägenhet på Södermalm. Hiss. Avgift: 2 416 kr/mån.".to_string(),
-                features: vec!["Hiss".to_string()],
+                features: Features::from(vec![Amenity::Elevator]),
                 images: vec![],
+                local_images: vec![],
+                local_floor_plans: vec![],
+                floor_plan_sqm: None,
+                image_hashes: vec![],
+                photo_matches: vec![],
+                viewings: vec![],
+                virtual_tours: vec![],
                 url: "https://www.booli.se/annons/sodermalm5".to_string(),
+                broker: None,
+                association: None,
+                nearest_station: None,
+                nearby: None,
+                schools: Vec::new(),
+                noise_db: None,
+                flood_risk: None,
+                balcony_orientation: None,
+                broadband: None,
+                renovation: None,
+                area_tags: vec!["Södermalm".to_string()],
+                published_at: None,
+                run_id: None,
                 scraped_at: Utc::now(),
                 raw_data: json!({
                     "mock": true,
@@ -0,0 +1,520 @@
+//! Typed queries against the GraphQL API behind Booli's frontend
+//!
+//! Listings from the API come with coordinates, exact numbers and amenities
+//! without any HTML parsing, so the `booli_graphql` source prefers it. The
+//! schema is unofficial (see `schema.graphql`); when a query stops matching
+//! it, [`ApiChanged`] is returned and the source falls back to the browser.
+
+use crate::geo::GeoPoint;
+use crate::models::{
+    Broker, Features, Image, ImageKind, InvalidListing, ListingStatus, Location, Money, Property, Source,
+};
+use crate::scrapers::artifacts::DebugArtifacts;
+use crate::scrapers::bidding::BidInfo;
+use crate::scrapers::budget::Budget;
+use crate::scrapers::detail::ListingDetails;
+use crate::scrapers::plugin::ParserPlugin;
+use crate::scrapers::politeness::{Identity, Politeness};
+use crate::scrapers::runner::merge_listings;
+#[cfg(feature = "browser")]
+use crate::scrapers::{BooliBrowserSource, Chrome};
+use crate::scrapers::{AreaSearch, ScrapeConfig, ScraperTrait, SearchParams};
+use crate::shutdown::{Interrupted, Shutdown};
+use crate::storage::Checkpoint;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Europe::Stockholm;
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, debug_span, info, info_span, warn, Instrument};
+
+pub const BOOLI_GRAPHQL_URL: &str = "https://www.booli.se/graphql";
+
+/// Result pages fetched per search at most
+const MAX_PAGES: i64 = 50;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/scrapers/booli_graphql/schema.graphql",
+    query_path = "src/scrapers/booli_graphql/search.graphql",
+    response_derives = "Debug"
+)]
+pub struct SearchForSale;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/scrapers/booli_graphql/schema.graphql",
+    query_path = "src/scrapers/booli_graphql/listing.graphql",
+    response_derives = "Debug"
+)]
+pub struct ListingDetail;
+
+/// The API no longer answers our queries as the schema says it should
+#[derive(Debug)]
+pub struct ApiChanged(pub String);
+
+impl fmt::Display for ApiChanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Booli's GraphQL API has changed: {}", self.0)
+    }
+}
+
+impl std::error::Error for ApiChanged {}
+
+/// Client for Booli's GraphQL endpoint
+pub struct BooliGraphqlClient {
+    client: Client,
+    endpoint: String,
+    min_interval: Duration,
+    politeness: Politeness,
+    budget: Arc<Budget>,
+}
+
+impl BooliGraphqlClient {
+    pub fn new(endpoint: &str, min_interval: Duration) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(Self {
+            client,
+            endpoint: endpoint.to_string(),
+            min_interval,
+            politeness: Politeness::default(),
+            budget: Arc::default(),
+        })
+    }
+
+    /// Spend result pages from `budget`
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Wait between pages and stay within active hours as `politeness` says
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Sleep for `delay`, but at least the minimum interval between requests
+    async fn pause(&self, delay: Duration) {
+        tokio::time::sleep(delay.max(self.min_interval)).await;
+    }
+
+    /// Wait for the configured active hours, if outside them
+    async fn wait_until_active(&self) {
+        let wait = self.politeness.until_active(Utc::now().with_timezone(&Stockholm).time());
+        if !wait.is_zero() {
+            info!("Outside active hours; resuming in {} min", wait.as_secs().div_ceil(60));
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    async fn query<Q: GraphQLQuery>(&self, variables: Q::Variables) -> Result<Q::ResponseData> {
+        self.wait_until_active().await;
+        let body = Q::build_query(variables);
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .headers(self.politeness.identity.as_ref().map(Identity::headers).unwrap_or_default())
+            .json(&body)
+            .send()
+            .instrument(debug_span!("http", operation = body.operation_name, url = %self.endpoint))
+            .await
+            .with_context(|| format!("Failed to query {}", body.operation_name))?;
+        // Apollo answers queries that don't validate against its schema with 400
+        if response.status() == StatusCode::BAD_REQUEST {
+            return Err(ApiChanged(format!("{} was rejected", body.operation_name)).into());
+        }
+        let response: Response<Q::ResponseData> = response
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| ApiChanged(format!("unexpected {} response: {}", body.operation_name, e)))?;
+
+        if let Some(errors) = response.errors.filter(|errors| !errors.is_empty()) {
+            let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+            return Err(ApiChanged(messages.join("; ")).into());
+        }
+        response
+            .data
+            .ok_or_else(|| ApiChanged(format!("{} returned no data", body.operation_name)).into())
+    }
+
+    /// Every listing for sale matching `search`, as cards from the search results
+    pub async fn search(&self, search: &AreaSearch) -> Result<Vec<search_for_sale::SearchForSaleSearchForSaleResult>> {
+        let params = match &search.url {
+            Some(url) => SearchParams::from_url(url)?,
+            None => SearchParams {
+                area_ids: search.area_ids.clone(),
+                ..SearchParams::default()
+            },
+        };
+        let area_ids: Vec<String> = params.area_ids.iter().map(u64::to_string).collect();
+        if area_ids.is_empty() {
+            anyhow::bail!("Search {} has no Booli area IDs", search.name);
+        }
+
+        let mut listings = Vec::new();
+        let mut page = 1;
+        loop {
+            if !self.budget.take_page() {
+                break;
+            }
+            let data = self
+                .query::<SearchForSale>(search_for_sale::Variables {
+                    input: search_for_sale::SearchRequest {
+                        area_id: area_ids.join(","),
+                        page: Some(page),
+                        filters: Some(search_filters(&params)),
+                    },
+                })
+                .await?;
+            let result = data
+                .search_for_sale
+                .ok_or_else(|| ApiChanged("searchForSale returned null".to_string()))?;
+            listings.extend(result.result.into_iter().flatten().flatten());
+            debug!("{}: page {}/{}", search.name, page, result.pages);
+
+            if page >= result.pages.min(MAX_PAGES) {
+                break;
+            }
+            page += 1;
+            self.pause(self.politeness.page_delay()).await;
+        }
+        Ok(listings)
+    }
+
+    /// Detail page data of one listing
+    pub async fn listing(&self, id: &str) -> Result<ListingDetails> {
+        self.find_listing(id)
+            .await?
+            .with_context(|| format!("Booli has no listing {}", id))
+    }
+
+    /// Detail page data of one listing, or None once Booli no longer has it
+    pub async fn find_listing(&self, id: &str) -> Result<Option<ListingDetails>> {
+        let data = self
+            .query::<ListingDetail>(listing_detail::Variables { listing_id: id.to_string() })
+            .await?;
+        Ok(data.listing.map(details))
+    }
+}
+
+/// Price, rooms, size and type bounds, keyed by the same names as in search URLs
+fn search_filters(params: &SearchParams) -> Vec<search_for_sale::SearchFilter> {
+    let mut filters: Vec<(&str, String)> = [
+        ("minListPrice", params.min_price.map(|v| v.to_string())),
+        ("maxListPrice", params.max_price.map(|v| v.to_string())),
+        ("minRooms", params.min_rooms.map(|v| v.to_string())),
+        ("maxRooms", params.max_rooms.map(|v| v.to_string())),
+        ("minLivingArea", params.min_sqm.map(|v| v.to_string())),
+        ("maxLivingArea", params.max_sqm.map(|v| v.to_string())),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .collect();
+    if !params.property_types.is_empty() {
+        let names: Vec<&str> = params.property_types.iter().map(|t| t.booli_name()).collect();
+        filters.push(("objectType", names.join(",")));
+    }
+    filters
+        .into_iter()
+        .map(|(key, value)| search_for_sale::SearchFilter {
+            key: key.to_string(),
+            value,
+        })
+        .collect()
+}
+
+/// "2026-10-03 09:12:44", Stockholm time, or an RFC 3339 timestamp
+fn parse_published(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let local = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok()?;
+    Some(Stockholm.from_local_datetime(&local).earliest()?.with_timezone(&Utc))
+}
+
+fn raw(value: Option<f64>) -> Option<i64> {
+    value.map(|v| v.round() as i64).filter(|v| *v > 0)
+}
+
+/// A search result as a property, or why it isn't a valid one
+fn property(
+    listing: search_for_sale::SearchForSaleSearchForSaleResult,
+    search: &AreaSearch,
+) -> Result<Property, InvalidListing> {
+    let price = raw(listing.list_price.and_then(|v| v.raw));
+    let status = if listing.upcoming_sale == Some(true) && price.is_none() {
+        ListingStatus::ComingSoon
+    } else {
+        ListingStatus::ForSale
+    };
+    let url = match listing.url.as_deref() {
+        Some(url) if url.starts_with('/') => format!("https://www.booli.se{}", url),
+        Some(url) => url.to_string(),
+        None => format!("https://www.booli.se/annons/{}", listing.booli_id),
+    };
+    // The key is stable where Booli sends one; the label is what the site shows
+    let labels: Vec<String> = listing
+        .amenities
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|amenity| amenity.key.or(amenity.label))
+        .collect();
+    let rooms = listing.rooms.and_then(|v| v.raw).map(|v| v as f32);
+    let sqm = listing.living_area.and_then(|v| v.raw).map(|v| v.round() as i32);
+
+    Property::builder(listing.booli_id.clone(), Source::Booli)
+        .location(Location {
+            city: listing.municipality_name.clone().unwrap_or_else(|| search.city.clone()),
+            area: listing.descriptive_area_name.clone().or_else(|| Some(search.name.clone())),
+            latitude: listing.latitude,
+            longitude: listing.longitude,
+        })
+        .address(listing.street_address.clone().unwrap_or_default())
+        .price(price.map(Money::sek))
+        .bidding_in_progress(listing.bidding_open == Some(true))
+        .listing_status(status)
+        .monthly_fee(raw(listing.rent.and_then(|v| v.raw)).map(Money::sek))
+        .rooms(rooms)
+        .sqm(sqm)
+        .floor(listing.floor.and_then(|v| v.raw).map(|v| v as f32))
+        .features(Features::normalize(&Source::Booli, labels))
+        .url(url)
+        .published_at(listing.published.as_deref().and_then(parse_published))
+        .area_tags(vec![search.name.clone()])
+        .raw_data(json!({
+            "scraped_from": "graphql",
+            "booli_id": listing.booli_id,
+        }))
+        .build()
+}
+
+fn details(listing: listing_detail::ListingDetailListing) -> ListingDetails {
+    let broker = Broker {
+        agency: listing.agency.and_then(|agency| agency.name),
+        name: listing.agent.as_ref().and_then(|agent| agent.name.clone()),
+        phone: listing.agent.and_then(|agent| agent.phone),
+    };
+    ListingDetails {
+        viewings: listing
+            .showings
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|showing| DateTime::parse_from_rfc3339(&showing.start_time?).ok())
+            .map(|start| start.with_timezone(&Utc))
+            .filter(|start| *start > Utc::now())
+            .collect(),
+        bids: BidInfo {
+            in_progress: listing.bidding_open == Some(true),
+            asking_price: raw(listing.list_price.and_then(|v| v.raw)),
+            current_bid: raw(listing.highest_bid.and_then(|v| v.raw)),
+        },
+        broker: (broker != Broker::default()).then_some(broker),
+        // The API has no association; the BRF enrichment reads it off the listing page
+        association: None,
+        published_at: listing.published.as_deref().and_then(parse_published),
+        description: listing.description,
+        monthly_fee: raw(listing.rent.and_then(|v| v.raw)),
+        operating_cost: raw(listing.operating_cost.and_then(|v| v.raw)),
+        floor: listing.floor.and_then(|v| v.raw).map(|v| v as f32),
+        coordinates: listing.latitude.zip(listing.longitude).map(|(lat, lon)| GeoPoint::new(lat, lon)),
+        images: listing
+            .images
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|image| Some((image.url?, ImageKind::Photo)))
+            .chain(
+                listing
+                    .floor_plans
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter_map(|image| Some((image.url?, ImageKind::FloorPlan))),
+            )
+            .enumerate()
+            .map(|(order, (url, kind))| Image::new(url, order, kind))
+            .collect(),
+        // The API has no tour links
+        virtual_tours: Vec::new(),
+    }
+}
+
+/// Booli listings from the GraphQL API, falling back to the browser scraper
+/// when the API has changed (in builds with the `browser` feature)
+pub struct BooliGraphqlSource {
+    config: ScrapeConfig,
+    details: bool,
+    #[cfg(feature = "browser")]
+    fallback: BooliBrowserSource,
+    shutdown: Shutdown,
+    budget: Arc<Budget>,
+    rejected: Mutex<Vec<InvalidListing>>,
+}
+
+impl BooliGraphqlSource {
+    pub fn new(config: ScrapeConfig, details: bool) -> Self {
+        Self {
+            #[cfg(feature = "browser")]
+            fallback: BooliBrowserSource::new(config.clone(), details),
+            config,
+            details,
+            shutdown: Shutdown::default(),
+            budget: Arc::default(),
+            rejected: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Hand `chrome` to the browser fallback
+    #[cfg(feature = "browser")]
+    pub fn with_chrome(mut self, chrome: Chrome) -> Self {
+        self.fallback = self.fallback.with_chrome(chrome);
+        self
+    }
+
+    /// Hand `checkpoint` to the browser fallback, which can resume part-way
+    #[cfg_attr(not(feature = "browser"), allow(unused_mut, unused_variables))]
+    pub fn with_checkpoint(mut self, checkpoint: Arc<Checkpoint>) -> Self {
+        #[cfg(feature = "browser")]
+        {
+            self.fallback = self.fallback.with_checkpoint(checkpoint);
+        }
+        self
+    }
+
+    /// Hand `debug` to the browser fallback, the one writing debug artifacts
+    #[cfg_attr(not(feature = "browser"), allow(unused_mut, unused_variables))]
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        #[cfg(feature = "browser")]
+        {
+            let debug = debug.labelled(self.source_name());
+            self.fallback = self.fallback.with_debug(debug);
+        }
+        self
+    }
+
+    /// Hand the search page parser `plugin` to the browser fallback
+    #[cfg_attr(not(feature = "browser"), allow(unused_mut, unused_variables))]
+    pub fn with_plugin(mut self, plugin: Option<Arc<ParserPlugin>>) -> Self {
+        #[cfg(feature = "browser")]
+        {
+            self.fallback = self.fallback.with_plugin(plugin);
+        }
+        self
+    }
+
+    /// Stop between requests once `shutdown` is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        #[cfg(feature = "browser")]
+        {
+            self.fallback = self.fallback.with_shutdown(shutdown.clone());
+        }
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Spend pages, listings and detail visits from `budget`, shared with the
+    /// browser fallback
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        #[cfg(feature = "browser")]
+        {
+            self.fallback = self.fallback.with_budget(budget.clone());
+        }
+        self.budget = budget;
+        self
+    }
+
+    async fn scrape_api(&self) -> Result<Vec<Property>> {
+        let client = BooliGraphqlClient::new(
+            BOOLI_GRAPHQL_URL,
+            Duration::from_millis(self.config.min_interval_ms),
+        )?
+        .with_politeness(self.config.politeness.booli_graphql.clone())
+        .with_budget(self.budget.clone());
+
+        let mut properties = Vec::new();
+        for search in &self.config.areas {
+            if self.shutdown.requested() {
+                return Err(Interrupted.into());
+            }
+            let listings = client
+                .search(search)
+                .instrument(info_span!("page", search = %search.name, url = %search.url()))
+                .await?;
+            let mut found = Vec::new();
+            for listing in listings {
+                match property(listing, search) {
+                    Ok(property) => found.push(property),
+                    Err(invalid) => {
+                        debug!("Skipped listing: {}", invalid);
+                        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).push(invalid);
+                    }
+                }
+            }
+            found.truncate(self.budget.take_listings(found.len()));
+            info!("{}: {} listings from the API", search.name, found.len());
+            merge_listings(&mut properties, found);
+        }
+
+        if self.details {
+            for property in &mut properties {
+                if self.shutdown.requested() {
+                    return Err(Interrupted.into());
+                }
+                if !self.budget.take_detail() {
+                    break;
+                }
+                client.pause(client.politeness.detail_delay()).await;
+                let span = info_span!("listing", id = %property.id, url = %property.url);
+                match client.listing(&property.id).instrument(span).await {
+                    Ok(details) => details.apply(property),
+                    Err(e) if e.is::<ApiChanged>() => return Err(e),
+                    Err(e) => warn!("Failed to fetch details for {}: {:#}", property.id, e),
+                }
+            }
+        }
+        Ok(properties)
+    }
+}
+
+#[async_trait]
+impl ScraperTrait for BooliGraphqlSource {
+    async fn scrape(&self) -> Result<Vec<Property>> {
+        match self.scrape_api().await {
+            #[cfg(feature = "browser")]
+            Err(e) if e.is::<ApiChanged>() => {
+                warn!("{:#}; scraping with the browser instead", e);
+                self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clear();
+                self.fallback.scrape().await
+            }
+            result => result,
+        }
+    }
+
+    fn source_name(&self) -> &str {
+        "Booli (GraphQL)"
+    }
+
+    fn rejected(&self) -> Vec<InvalidListing> {
+        #[cfg_attr(not(feature = "browser"), allow(unused_mut))]
+        let mut rejected = self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        #[cfg(feature = "browser")]
+        rejected.extend(self.fallback.rejected());
+        rejected
+    }
+
+    fn shutdown(&self) -> Option<&Shutdown> {
+        Some(&self.shutdown)
+    }
+}
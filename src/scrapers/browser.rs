@@ -1,49 +1,234 @@
-use crate::models::{Location, Property, Source};
+use crate::models::{InvalidListing, Property, SoldListing};
+use crate::scrapers::account::{parse_saved_searches, BooliAccount, BooliSavedSearch, LOGIN_URL, SAVED_SEARCHES_URL};
+use crate::scrapers::artifacts::DebugArtifacts;
+use crate::scrapers::budget::Budget;
+use crate::scrapers::capture::CaptureConfig;
+use crate::scrapers::cards::parse_listing_cards;
+use crate::scrapers::chrome::{Chrome, ChromeTab};
+use crate::scrapers::detail::parse_detail_page;
+use crate::scrapers::fast_path::FastPath;
+use crate::scrapers::plugin::ParserPlugin;
+use crate::scrapers::politeness::Politeness;
+use crate::scrapers::selectors::{CardRules, CardSelectors};
+use crate::scrapers::sold::parse_sold_cards;
+use crate::scrapers::runner::merge_listings;
+use crate::scrapers::{AreaSearch, FetchStrategy, RateLimiter, ScrapeConfig, ScraperTrait};
+use crate::shutdown::{Interrupted, Shutdown};
+use crate::storage::Checkpoint;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::Utc;
-use headless_chrome::protocol::cdp::Page;
-use headless_chrome::{Browser, LaunchOptions};
-use scraper::{Html, Selector};
-use serde_json::json;
+use chrono_tz::Europe::Stockholm;
+use futures::stream::{self, StreamExt};
+use headless_chrome::Tab;
+use scraper::Html;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-use tracing::{debug, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{debug, debug_span, info, info_span, instrument, warn, Span};
 
 /// Browser-based scraper for Booli using headless Chrome
 pub struct BooliBrowserScraper {
-    browser: Browser,
+    chrome: Chrome,
+    /// The account logged in with, to log in again after Chrome restarts
+    account: Mutex<Option<BooliAccount>>,
+    limiter: RateLimiter,
+    politeness: Politeness,
+    checkpoint: Option<Arc<Checkpoint>>,
+    shutdown: Shutdown,
+    rejected: Mutex<Vec<InvalidListing>>,
+    capture: CaptureConfig,
+    debug: DebugArtifacts,
+    budget: Arc<Budget>,
+    plugin: Option<Arc<ParserPlugin>>,
+    cards: CardSelectors,
 }
 
 impl BooliBrowserScraper {
-    /// Create a new browser-based scraper
+    /// Create a new browser-based scraper; Chrome is launched at the first
+    /// page
     pub fn new() -> Result<Self> {
-        info!("Launching headless Chrome...");
-        
-        let options = LaunchOptions::default_builder()
-            .headless(true)
-            .build()
-            .context("Failed to build launch options")?;
-        
-        let browser = Browser::new(options)
-            .context("Failed to launch Chrome browser")?;
-        
-        Ok(Self { browser })
+        Ok(Self {
+            chrome: Chrome::default(),
+            account: Mutex::new(None),
+            limiter: RateLimiter::new(Duration::from_millis(ScrapeConfig::default().min_interval_ms)),
+            politeness: Politeness::default(),
+            checkpoint: None,
+            shutdown: Shutdown::default(),
+            rejected: Mutex::new(Vec::new()),
+            capture: CaptureConfig::default(),
+            debug: DebugArtifacts::default(),
+            budget: Arc::default(),
+            plugin: None,
+            cards: CardSelectors::default(),
+        })
     }
 
-    /// Scrape all properties from Södermalm listing page
-    pub fn scrape_sodermalm(&self) -> Result<Vec<Property>> {
-        let url = "https://www.booli.se/sok/till-salu?areaIds=115341";
-        
-        info!("Opening Södermalm search page...");
-        let tab = self.browser.new_tab()?;
+    /// Load pages in `chrome`, shared with other scrapers, instead of a Chrome
+    /// of its own
+    pub fn with_chrome(mut self, chrome: Chrome) -> Self {
+        self.chrome = chrome;
+        self
+    }
+
+    /// Space page loads from the same domain at least `interval` apart
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.limiter = RateLimiter::new(interval);
+        self
+    }
+
+    /// Wait between pages and stay within active hours as `politeness` says
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// Record search pages and detail pages in `checkpoint`, and skip the
+    /// ones it already holds
+    pub fn with_checkpoint(mut self, checkpoint: Arc<Checkpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Stop between pages once `shutdown` is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Keep copies of the detail pages visited, as configured
+    pub fn with_capture(mut self, capture: CaptureConfig) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Write search page HTML and screenshots to `debug`
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Spend pages, listings and detail visits from `budget`
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Parse search pages with `plugin` instead of the built-in parser
+    pub fn with_plugin(mut self, plugin: Option<Arc<ParserPlugin>>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Read listing cards, for sale and sold, by `cards` instead of the
+    /// built-in rules
+    pub fn with_cards(mut self, cards: CardSelectors) -> Self {
+        self.cards = cards;
+        self
+    }
+
+    /// Listings dropped by validation so far, emptying the list
+    pub fn take_rejected(&self) -> Vec<InvalidListing> {
+        std::mem::take(&mut *self.rejected.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Scrape every configured search area, `config.concurrency` at a time
+    ///
+    /// Listings found by several searches are kept once, tagged with each
+    /// search's name. Failing areas are logged and skipped; the scrape only
+    /// fails when every area does.
+    pub fn scrape_areas(&self, config: &ScrapeConfig) -> Result<Vec<Property>> {
+        // Each search with whether it's a retry
+        let queue = Mutex::new(config.areas.iter().map(|search| (search, false)).collect::<VecDeque<_>>());
+        let results = Mutex::new(Vec::new());
+
+        // Area threads log under the caller's source span
+        let span = Span::current();
+        thread::scope(|scope| {
+            for _ in 0..self.politeness.concurrency(config.concurrency).clamp(1, config.areas.len().max(1)) {
+                scope.spawn(|| loop {
+                    let _source = span.enter();
+                    let Some((search, retry)) = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front() else {
+                        break;
+                    };
+                    let result = self.scrape_area(search);
+                    // A page Chrome struggled with gets another go once it's restarted
+                    if let Err(e) = &result {
+                        if !retry && self.chrome.needs_restart() {
+                            warn!("Failed to scrape {}, trying again after restarting Chrome: {:#}", search.name, e);
+                            queue.lock().unwrap_or_else(|e| e.into_inner()).push_back((search, true));
+                            continue;
+                        }
+                    }
+                    results.lock().unwrap_or_else(|e| e.into_inner()).push((search, result));
+                });
+            }
+        });
+
+        // Merge in config order so output doesn't depend on thread timing
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|(search, _)| config.areas.iter().position(|s| std::ptr::eq(s, *search)));
+
+        let mut properties: Vec<Property> = Vec::new();
+        let mut failures = 0;
+        for (search, result) in results {
+            match result {
+                Ok(found) => {
+                    info!("{}: {} listings", search.name, found.len());
+                    merge_listings(&mut properties, found);
+                }
+                Err(e) => {
+                    warn!("Failed to scrape {}: {:#}", search.name, e);
+                    failures += 1;
+                }
+            }
+        }
+
+        if self.shutdown.requested() {
+            return Err(Interrupted.into());
+        }
+        if failures > 0 && failures == config.areas.len() {
+            anyhow::bail!("Every search area failed to scrape");
+        }
+        Ok(properties)
+    }
+
+    /// Scrape all properties from one search's listing page
+    pub fn scrape_area(&self, search: &AreaSearch) -> Result<Vec<Property>> {
+        let _page = info_span!("page", search = %search.name, url = %search.url()).entered();
+        if let Some(properties) = self.checkpoint.as_ref().and_then(|c| c.page(&search.name)) {
+            info!("{}: reusing {} listings from the checkpoint", search.name, properties.len());
+            return Ok(properties);
+        }
+        if self.shutdown.requested() {
+            return Err(Interrupted.into());
+        }
+        if !self.budget.take_page() {
+            info!("{}: skipped, the scrape budget is spent", search.name);
+            return Ok(Vec::new());
+        }
+
+        let url = search.url();
+        let slug: String = search
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+
+        self.restart_if_needed();
+        info!("Opening {} search page...", search.name);
+        let tab = self.open_tab()?;
         
         // Navigate to search page
-        tab.navigate_to(url)?;
-        tab.wait_until_navigated()?;
+        self.wait_until_active();
+        self.limiter.wait(&url);
+        debug_span!("navigate", url = %url).in_scope(|| self.navigate(&tab, &url))?;
         
         // Wait longer for page to fully load
         info!("Waiting for page to fully load...");
-        thread::sleep(Duration::from_secs(8));
+        self.pause(self.politeness.page_delay());
         
         // Accept cookies if present
         let _ = tab.evaluate(
@@ -56,30 +241,19 @@ impl BooliBrowserScraper {
         
         thread::sleep(Duration::from_secs(2));
         
-        // Create debug directory
-        std::fs::create_dir_all("debug")?;
-        
-        // Capture HTML for debugging
-        info!("Capturing page HTML for debugging...");
-        let html_result = tab.evaluate("document.documentElement.outerHTML", false)?;
-        if let Some(html_value) = html_result.value {
-            if let Some(html_str) = html_value.as_str() {
-                std::fs::write("debug/booli_page.html", html_str)?;
-                info!("Saved page HTML to debug/booli_page.html ({} bytes)", html_str.len());
+        if self.debug.enabled() {
+            info!("Capturing screenshot for debugging...");
+            match tab.capture_screenshot(
+                headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png,
+                None,
+                None,
+                true,
+            ) {
+                Ok(png) => self.debug.write(&format!("booli_screenshot_{}.png", slug), png),
+                Err(e) => warn!("Failed to capture a debug screenshot: {}", e),
             }
         }
         
-        // Capture screenshot
-        info!("Capturing screenshot...");
-        let screenshot_data = tab.capture_screenshot(
-            headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png,
-            None,
-            None,
-            true,
-        )?;
-        std::fs::write("debug/booli_screenshot.png", screenshot_data)?;
-        info!("Saved screenshot to debug/booli_screenshot.png");
-        
         info!("Extracting property data from listing page HTML...");
         
         // Get the HTML content we just captured
@@ -91,161 +265,482 @@ impl BooliBrowserScraper {
                 String::new()
             }
         };
+        self.debug.write(&format!("booli_page_{}.html", slug), &html_str);
         
         if html_str.is_empty() {
             warn!("HTML is empty");
             return Ok(Vec::new());
         }
+        self.debug.snapshot(&html_str);
+
+        if let Some(plugin) = &self.plugin {
+            drop(tab);
+            let (properties, rejected) = plugin.parse_page(&html_str, &search.name, &search.city)?;
+            info!("{} found {} listings", plugin.name(), properties.len());
+            self.rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(rejected);
+            return Ok(self.finish_page(search, properties));
+        }
         
-        // Parse HTML with scraper
-        let document = Html::parse_document(&html_str);
-        let card_selector = Selector::parse("a.object-card-link").unwrap();
-        
-        let cards: Vec<_> = document.select(&card_selector).collect();
-        info!("Found {} property cards in HTML", cards.len());
-        
-        let mut properties = Vec::new();
-        
-        for (idx, element) in cards.iter().enumerate() {
-            // Extract data from the card  
-            let href = element.value().attr("href").unwrap_or("");
-            let aria_label_raw = element.value().attr("aria-label").unwrap_or("");
-            
-            // Decode HTML entities (&nbsp; -> space)
-            let aria_label = aria_label_raw.replace("&nbsp;", " ");
-            
-            debug!("Processing: {}", aria_label);
-            
-            // Extract Booli ID from URL
-            let booli_id = href.split('/').last().unwrap_or("unknown").to_string();
-            
-            // Parse aria-label: "2 rum lägenhet på Götgatan 120 Södermalm, Stockholms kommun"
-            let mut rooms = 0.0;
-            let mut address = String::new();
-            let mut area = String::from("Södermalm");
-            
-            if let Some(rum_match) = aria_label.split("rum").next() {
-                if let Some(last_word) = rum_match.trim().split_whitespace().last() {
-                    rooms = last_word.replace(",", ".").parse().unwrap_or(0.0);
-                }
+        if self.debug.enabled() {
+            let document = Html::parse_document(&html_str);
+            if let Some(card) = document.select(&self.cards.card).next() {
+                self.debug.write(&format!("first_card_{}.html", slug), card.html());
+            }
+        }
+
+        let (properties, rejected) =
+            debug_span!("parse", bytes = html_str.len()).in_scope(|| parse_listing_cards(&html_str, search, &self.cards));
+        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(rejected);
+        Ok(self.finish_page(search, properties))
+    }
+
+    /// Keep as many of a page's listings as the budget allows and record them
+    /// in the checkpoint
+    fn finish_page(&self, search: &AreaSearch, mut properties: Vec<Property>) -> Vec<Property> {
+        properties.truncate(self.budget.take_listings(properties.len()));
+        info!("Successfully scraped {} properties from {} listing page", properties.len(), search.name);
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.record_page(&search.name, &properties);
+        }
+        properties
+    }
+
+    /// Scrape final prices from a Booli slutpriser search page
+    pub fn scrape_sold(&self, url: &str) -> Result<Vec<SoldListing>> {
+        self.restart_if_needed();
+        info!("Opening sold listings page...");
+        let tab = self.open_tab()?;
+        self.wait_until_active();
+        self.limiter.wait(url);
+        self.navigate(&tab, url)?;
+        self.pause(self.politeness.page_delay());
+
+        let html = tab.get_content().context("Failed to read sold listings HTML")?;
+        drop(tab);
+
+        let sold = parse_sold_cards(&html, &self.cards);
+        info!("Found {} sold listings", sold.len());
+        Ok(sold)
+    }
+
+    /// Visit each property's detail page to fill in viewings and bidding details
+    pub fn enrich_details(&self, properties: &mut [Property]) -> Result<()> {
+        let mut tab = self.open_tab()?;
+        let total = properties.len();
+
+        for (idx, property) in properties.iter_mut().enumerate() {
+            if self.shutdown.requested() {
+                return Err(Interrupted.into());
+            }
+            if let Some(enriched) = self.checkpoint.as_ref().and_then(|c| c.enriched(&property.id)) {
+                debug!("{}: details already fetched", property.id);
+                let area_tags = std::mem::take(&mut property.area_tags);
+                *property = enriched;
+                property.area_tags = area_tags;
+                continue;
+            }
+            if !self.budget.take_detail() {
+                break;
             }
-            
-            // Extract address - between "på " and area name
-            if let Some(pa_pos) = aria_label.find("på ") {
-                let after_pa = &aria_label[pa_pos + 3..];
-                // Address is until we hit the area or comma
-                if let Some(comma_pos) = after_pa.find(',') {
-                    address = after_pa[..comma_pos].trim().to_string();
-                    // Extract area from what's before the comma
-                    let area_part = &after_pa[..comma_pos];
-                    if let Some(last_space) = area_part.rfind(' ') {
-                        let potential_area = &area_part[last_space + 1..];
-                        if !potential_area.chars().next().unwrap_or('a').is_numeric() {
-                            area = potential_area.to_string();
+            if self.chrome.needs_restart() {
+                drop(tab);
+                self.restart_if_needed();
+                tab = self.open_tab()?;
+            }
+            let _listing = info_span!("listing", id = %property.id, url = %property.url).entered();
+            info!("Fetching details {}/{}: {}", idx + 1, total, property.address);
+
+            let html = match self.fetch_html(&tab, &property.url) {
+                Ok(html) => html,
+                // A page Chrome struggled with gets another go once it's restarted
+                Err(e) if self.chrome.needs_restart() => {
+                    warn!("Failed to fetch details for {}, trying again after restarting Chrome: {}", property.id, e);
+                    drop(tab);
+                    self.restart_if_needed();
+                    tab = self.open_tab()?;
+                    match self.fetch_html(&tab, &property.url) {
+                        Ok(html) => html,
+                        Err(e) => {
+                            warn!("Failed to fetch details for {}: {}", property.id, e);
+                            continue;
                         }
                     }
-                } else {
-                    address = after_pa.trim().to_string();
+                }
+                Err(e) => {
+                    warn!("Failed to fetch details for {}: {}", property.id, e);
+                    continue;
+                }
+            };
+
+            if self.capture.screenshots {
+                if let Err(e) = self.capture.screenshot(&tab, &property.id) {
+                    warn!("Failed to capture {}: {:#}", property.id, e);
                 }
             }
-            
-            // Get the element's inner HTML to extract other data
-            let card_html = element.html();
-            
-            // Debug: Save first card HTML
-            if idx == 0 {
-                std::fs::write("debug/first_card.html", &card_html)?;
-                info!("Saved first card HTML to debug/first_card.html");
-            }
-            
-            let card_doc = Html::parse_fragment(&card_html);
-            
-            // Extract price, sqm, and other details from list items
-            let li_selector = Selector::parse("li").unwrap();
-            
-            let mut price: i64 = 0;
-            let mut sqm: i32 = 0;
-            let mut features = Vec::new();
-            let mut monthly_fee = String::new();
-            
-            for li in element.select(&li_selector) {
-                if let Some(aria) = li.value().attr("aria-label") {
-                    let aria_decoded = aria.replace("&nbsp;", " ");
-                    
-                    // Extract sqm from "35,5 kvadratmeter" or similar
-                    if aria_decoded.contains("kvadratmeter") {
-                        let sqm_str: String = aria_decoded.chars()
-                            .filter(|c| c.is_numeric() || *c == ',' || *c == '.')
-                            .collect();
-                        sqm = sqm_str.replace(",", ".").parse::<f32>().unwrap_or(0.0) as i32;
-                    }
-                    
-                    // Monthly fee
-                    if aria_decoded.contains("kr/mån") {
-                        monthly_fee = aria_decoded.clone();
-                    }
+            if let Err(e) = self.capture.archive(&tab, &property.id, &html) {
+                warn!("Failed to archive {}: {:#}", property.id, e);
+            }
+
+            let details = parse_detail_page(&html);
+            debug!("{}: {} viewings", property.id, details.viewings.len());
+            details.apply(property);
+            if let Some(checkpoint) = &self.checkpoint {
+                checkpoint.record_enriched(property);
+            }
+        }
+        Ok(())
+    }
+
+    /// Log into Booli; later pages in this browser load logged in
+    pub fn login(&self, account: &BooliAccount) -> Result<()> {
+        let password = account.password()?;
+        info!("Logging into Booli as {}...", account.email);
+        *self.account.lock().unwrap_or_else(|e| e.into_inner()) = Some(account.clone());
+        let tab = self.open_tab()?;
+        self.limiter.wait(LOGIN_URL);
+        self.navigate(&tab, LOGIN_URL)?;
+
+        tab.wait_for_element(r#"input[type="email"]"#)?.click()?;
+        tab.type_str(&account.email)?;
+        tab.wait_for_element(r#"input[type="password"]"#)?.click()?;
+        tab.type_str(&password)?;
+        tab.press_key("Enter")?;
+        tab.wait_until_navigated()?;
+        thread::sleep(Duration::from_secs(3));
+
+        let still_on_login = tab.get_url().starts_with(LOGIN_URL);
+        drop(tab);
+        if still_on_login {
+            anyhow::bail!("Booli login failed; check the email and password");
+        }
+        Ok(())
+    }
+
+    /// Saved searches of the logged-in account
+    pub fn saved_searches(&self) -> Result<Vec<BooliSavedSearch>> {
+        let tab = self.open_tab()?;
+        let html = self.fetch_html(&tab, SAVED_SEARCHES_URL)?;
+        Ok(parse_saved_searches(&html))
+    }
+
+    /// Save a listing as a favorite in the logged-in account
+    ///
+    /// Returns false when it already was one.
+    pub fn save_favorite(&self, url: &str) -> Result<bool> {
+        let tab = self.open_tab()?;
+        self.fetch_html(&tab, url)?;
+        let result = tab.evaluate(
+            r#"
+            (() => {
+                const button = document.querySelector('button[aria-label*="Spara"], button[aria-label*="spara"]');
+                if (!button) return "missing";
+                if (button.getAttribute("aria-pressed") === "true") return "saved";
+                button.click();
+                return "clicked";
+            })()
+            "#,
+            false,
+        )?;
+        thread::sleep(Duration::from_secs(1));
+        drop(tab);
+
+        match result.value.as_ref().and_then(|value| value.as_str()) {
+            Some("clicked") => Ok(true),
+            Some("saved") => Ok(false),
+            _ => anyhow::bail!("No save button on {}", url),
+        }
+    }
+
+    #[instrument(name = "navigate", level = "debug", skip(self, tab))]
+    fn fetch_html(&self, tab: &Tab, url: &str) -> Result<String> {
+        self.wait_until_active();
+        self.limiter.wait(url);
+        self.navigate(tab, url)?;
+        self.pause(self.politeness.detail_delay());
+        tab.get_content().with_context(|| format!("Failed to read {}", url))
+    }
+
+    /// Load `url` in `tab`, telling Chrome how long it took or that it failed
+    fn navigate(&self, tab: &Tab, url: &str) -> Result<()> {
+        let started = Instant::now();
+        let loaded = tab.navigate_to(url).and_then(|tab| tab.wait_until_navigated());
+        match loaded {
+            Ok(_) => {
+                self.chrome.page_loaded(started.elapsed());
+                Ok(())
+            }
+            Err(e) => {
+                self.chrome.page_failed();
+                Err(e.context(format!("Failed to load {}", url)))
+            }
+        }
+    }
+
+    /// Restart Chrome if it is due one, logging in again if it was logged
+    /// in; no tab of it may be open on this thread
+    fn restart_if_needed(&self) {
+        if !self.chrome.restart_if_needed() {
+            return;
+        }
+        let account = self.account.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some(account) = account {
+            if let Err(e) = self.login(&account) {
+                warn!("Scraping logged out after restarting Chrome: {:#}", e);
+            }
+        }
+    }
+
+    /// A new tab, announcing the scraper when configured to; closed when
+    /// dropped
+    fn open_tab(&self) -> Result<ChromeTab<'_>> {
+        let tab = self.chrome.tab()?;
+        if let Some(identity) = &self.politeness.identity {
+            tab.set_user_agent(&identity.user_agent(), Some("sv-SE,sv;q=0.9,en;q=0.8"), None)?;
+            tab.set_extra_http_headers(HashMap::from([("From", identity.contact.as_str())]))?;
+        }
+        Ok(tab)
+    }
+
+    /// Sleep for `delay`, waking early once shutdown is requested
+    fn pause(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        while !self.shutdown.requested() {
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            thread::sleep(left.min(Duration::from_millis(500)));
+        }
+    }
+
+    /// Wait for the configured active hours, if outside them
+    fn wait_until_active(&self) {
+        let wait = self.politeness.until_active(Utc::now().with_timezone(&Stockholm).time());
+        if !wait.is_zero() {
+            info!("Outside active hours; resuming in {} min", wait.as_secs().div_ceil(60));
+            self.pause(wait);
+        }
+    }
+}
+
+/// [`BooliBrowserScraper`] over the configured search areas, as a source
+///
+/// With [`FetchStrategy::HttpFirst`] pages are read over plain HTTP where
+/// possible; Chrome is launched at the first page that needs it and the
+/// blocking work runs off the async runtime.
+pub struct BooliBrowserSource {
+    config: ScrapeConfig,
+    details: bool,
+    chrome: Chrome,
+    checkpoint: Option<Arc<Checkpoint>>,
+    shutdown: Shutdown,
+    debug: DebugArtifacts,
+    budget: Arc<Budget>,
+    plugin: Option<Arc<ParserPlugin>>,
+    rejected: Arc<Mutex<Vec<InvalidListing>>>,
+}
+
+impl BooliBrowserSource {
+    /// `details` also visits every listing's detail page
+    pub fn new(config: ScrapeConfig, details: bool) -> Self {
+        Self {
+            chrome: Chrome::new(config.chrome.clone()),
+            config,
+            details,
+            checkpoint: None,
+            shutdown: Shutdown::default(),
+            debug: DebugArtifacts::default(),
+            budget: Arc::default(),
+            plugin: None,
+            rejected: Arc::default(),
+        }
+    }
+
+    /// Load pages in `chrome`, shared with the run's other browser scraping
+    pub fn with_chrome(mut self, chrome: Chrome) -> Self {
+        self.chrome = chrome;
+        self
+    }
+
+    /// Resume from and record progress in `checkpoint`
+    pub fn with_checkpoint(mut self, checkpoint: Arc<Checkpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Stop between pages and close Chrome once `shutdown` is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Write search page HTML and screenshots to `debug`
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        self.debug = debug.labelled(self.source_name());
+        self
+    }
+
+    /// Spend pages, listings and detail visits from `budget`
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Parse search pages with `plugin` instead of the built-in parser
+    pub fn with_plugin(mut self, plugin: Option<Arc<ParserPlugin>>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Scrape `areas` in Chrome and, with details, visit the detail pages of
+    /// their listings and of `enrich`, which are returned with them
+    async fn in_chrome(&self, areas: Vec<AreaSearch>, enrich: Vec<Property>) -> Result<Vec<Property>> {
+        let mut config = self.config.clone();
+        config.areas = areas;
+        let details = self.details;
+        let chrome = self.chrome.clone();
+        let checkpoint = self.checkpoint.clone();
+        let shutdown = self.shutdown.clone();
+        let debug = self.debug.clone();
+        let budget = self.budget.clone();
+        let plugin = self.plugin.clone();
+        let rejected = self.rejected.clone();
+        let span = Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _source = span.enter();
+            let cards = CardRules::load(&config.rules_dir, "booli")?.compile()?;
+            let mut scraper = BooliBrowserScraper::new()?
+                .with_chrome(chrome)
+                .with_min_interval(Duration::from_millis(config.min_interval_ms))
+                .with_politeness(config.politeness.booli_browser.clone())
+                .with_shutdown(shutdown)
+                .with_capture(config.capture.clone())
+                .with_debug(debug)
+                .with_budget(budget)
+                .with_plugin(plugin)
+                .with_cards(cards);
+            if let Some(checkpoint) = checkpoint {
+                scraper = scraper.with_checkpoint(checkpoint);
+            }
+            if let Some(account) = &config.account {
+                // Logged-out results are still worth having
+                if let Err(e) = scraper.login(account) {
+                    warn!("Scraping logged out: {:#}", e);
                 }
             }
-            
-            // Extract price from price container
-            let price_selector = Selector::parse("span.object-card__price--logo").unwrap();
-            if let Some(price_el) = element.select(&price_selector).next() {
-                let price_text = price_el.text().collect::<String>();
-                // Remove spaces and "kr", parse
-                let price_clean: String = price_text.chars()
-                    .filter(|c| c.is_numeric())
-                    .collect();
-                if !price_clean.is_empty() {
-                    price = price_clean.parse().unwrap_or(0);
+            let scraped = scraper.scrape_areas(&config);
+            rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(scraper.take_rejected());
+            let mut properties = scraped?;
+            merge_listings(&mut properties, enrich);
+            if details && !properties.is_empty() {
+                info!("Visiting each property page for detailed information");
+                // The cards are still worth keeping when detail pages fail
+                match scraper.enrich_details(&mut properties) {
+                    Err(e) if e.is::<Interrupted>() => return Err(e),
+                    Err(e) => warn!("Failed to fetch detail pages: {:#}", e),
+                    Ok(()) => {}
                 }
             }
-            
-            // Extract features from amenities
-            let tag_selector = Selector::parse("div.tag").unwrap();
-            for tag in element.select(&tag_selector) {
-                let feature = tag.text().collect::<String>().trim().to_string();
-                if !feature.is_empty() && feature != "Snart till salu" {
-                    features.push(feature);
+            Ok(properties)
+        })
+        .await?
+    }
+
+    /// Read what the fast path can over HTTP, then load the search and
+    /// detail pages it failed on in Chrome
+    async fn http_first(&self) -> Result<Vec<Property>> {
+        let fast = FastPath::new(
+            Duration::from_millis(self.config.min_interval_ms),
+            self.config.politeness.booli_browser.clone(),
+        )?
+        .with_budget(self.budget.clone())
+        .with_checkpoint(self.checkpoint.clone())
+        .with_shutdown(self.shutdown.clone())
+        .with_debug(self.debug.clone())
+        .with_plugin(self.plugin.clone())
+        .with_parse_threads(self.config.parse_threads());
+
+        // In config order, so output doesn't depend on timing
+        let concurrency = self.config.politeness.booli_browser.concurrency(self.config.concurrency).max(1);
+        let results: Vec<_> = stream::iter(self.config.areas.clone())
+            .map(|search| {
+                let fast = &fast;
+                async move {
+                    let result = fast.search(&search).await;
+                    (search, result)
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let mut properties = Vec::new();
+        let mut failed = Vec::new();
+        for (search, result) in results {
+            match result {
+                Ok((found, rejected)) => {
+                    info!("{}: {} listings over HTTP", search.name, found.len());
+                    self.rejected.lock().unwrap_or_else(|e| e.into_inner()).extend(rejected);
+                    merge_listings(&mut properties, found);
+                }
+                Err(e) if e.is::<Interrupted>() => return Err(e),
+                Err(e) => {
+                    info!("{}: loading the search in Chrome: {:#}", search.name, e);
+                    failed.push(search);
                 }
             }
-            
-            // Only add if we have minimum data
-            if !address.is_empty() && (price > 0 || sqm > 0) {
-                let property = Property {
-                    id: booli_id.clone(),
-                    source: Source::Booli,
-                    location: Location {
-                        city: "Stockholm".to_string(),
-                        area: Some(area.clone()),
-                        latitude: Some(59.3145),
-                        longitude: Some(18.0736),
-                    },
-                    address: address.clone(),
-                    price,
-                    rooms,
-                    sqm,
-                    description: format!("{} rum lägenhet i {}. {} kvm.", rooms, area, sqm),
-                    features: features.clone(),
-                    images: vec![],
-                    url: format!("https://www.booli.se{}", href),
-                    scraped_at: Utc::now(),
-                    raw_data: json!({
-                        "area": area,
-                        "scraped_from": "listing_page",
-                        "booli_id": booli_id,
-                        "aria_label": aria_label,
-                        "monthly_fee": monthly_fee
-                    }),
-                };
-                
-                properties.push(property);
-            } else {
-                info!("Skipped property {}: address='{}', price={}, sqm={}", idx, address, price, sqm);
+        }
+
+        // Screenshots and archives are taken of the page in Chrome
+        let capture = self.config.capture.screenshots || self.config.capture.archive.is_some();
+        let enrich = match self.details {
+            true if capture => std::mem::take(&mut properties),
+            true => {
+                let (done, failed) = fast.details(properties).await?;
+                properties = done;
+                failed
             }
+            false => Vec::new(),
+        };
+        if failed.is_empty() && enrich.is_empty() {
+            return Ok(properties);
+        }
+
+        info!("Loading {} searches and {} detail pages in Chrome", failed.len(), enrich.len());
+        let unenriched = enrich.clone();
+        match self.in_chrome(failed, enrich).await {
+            Ok(found) => merge_listings(&mut properties, found),
+            Err(e) if e.is::<Interrupted>() => return Err(e),
+            // What the fast path found is still worth keeping
+            Err(e) if !properties.is_empty() || !unenriched.is_empty() => {
+                warn!("Keeping the listings read over HTTP, as Chrome failed: {:#}", e);
+                merge_listings(&mut properties, unenriched);
+            }
+            Err(e) => return Err(e),
         }
-        
-        info!("Successfully scraped {} properties from listing page", properties.len());
-        
         Ok(properties)
     }
 }
+
+#[async_trait]
+impl ScraperTrait for BooliBrowserSource {
+    async fn scrape(&self) -> Result<Vec<Property>> {
+        // A logged-in session lives in Chrome
+        if self.config.fetch_strategy == FetchStrategy::Browser || self.config.account.is_some() {
+            return self.in_chrome(self.config.areas.clone(), Vec::new()).await;
+        }
+        self.http_first().await
+    }
+
+    fn source_name(&self) -> &str {
+        "Booli (browser)"
+    }
+
+    fn rejected(&self) -> Vec<InvalidListing> {
+        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn shutdown(&self) -> Option<&Shutdown> {
+        Some(&self.shutdown)
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Caps on what one run may fetch, so a broad search can't run for hours
+///
+/// Unset caps don't limit anything. A run that hits one keeps what it found
+/// so far and records the cap in its run record.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Search result pages loaded, over all sources and areas
+    pub max_pages: Option<usize>,
+    /// Listings kept from search results, over all sources
+    pub max_listings: Option<usize>,
+    /// Listing detail pages visited
+    pub max_detail_fetches: Option<usize>,
+    /// After this long no more pages or detail pages are loaded
+    pub max_runtime_secs: Option<u64>,
+}
+
+/// A cap a run hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Limit {
+    Pages,
+    Listings,
+    DetailFetches,
+    Runtime,
+}
+
+/// What is left of a run's [`BudgetConfig`], shared by its sources
+#[derive(Debug)]
+pub struct Budget {
+    config: BudgetConfig,
+    started: Instant,
+    pages: AtomicUsize,
+    listings: AtomicUsize,
+    details: AtomicUsize,
+    hit: Mutex<BTreeSet<Limit>>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new(BudgetConfig::default())
+    }
+}
+
+impl Budget {
+    /// A budget whose runtime counts from now
+    pub fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+            pages: AtomicUsize::new(0),
+            listings: AtomicUsize::new(0),
+            details: AtomicUsize::new(0),
+            hit: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Spend one search result page; false when pages or time are used up
+    pub fn take_page(&self) -> bool {
+        !self.out_of_time() && self.take(&self.pages, self.config.max_pages, Limit::Pages)
+    }
+
+    /// Spend one detail page visit; false when visits or time are used up
+    pub fn take_detail(&self) -> bool {
+        !self.out_of_time() && self.take(&self.details, self.config.max_detail_fetches, Limit::DetailFetches)
+    }
+
+    /// How many of `found` new listings may be kept
+    pub fn take_listings(&self, found: usize) -> usize {
+        let Some(max) = self.config.max_listings else {
+            return found;
+        };
+        let before = self
+            .listings
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| {
+                Some(taken + found.min(max.saturating_sub(taken)))
+            })
+            .unwrap_or_else(|taken| taken);
+        let kept = found.min(max.saturating_sub(before));
+        if kept < found {
+            self.record(Limit::Listings);
+        }
+        kept
+    }
+
+    /// Caps the run hit, as sentences for the run record
+    pub fn truncations(&self) -> Vec<String> {
+        let hit = self.hit.lock().unwrap_or_else(|e| e.into_inner());
+        hit.iter().map(|limit| self.describe(*limit)).collect()
+    }
+
+    fn describe(&self, limit: Limit) -> String {
+        match limit {
+            Limit::Pages => format!(
+                "max_pages ({}) reached; later result pages were not loaded",
+                self.config.max_pages.unwrap_or_default()
+            ),
+            Limit::Listings => format!(
+                "max_listings ({}) reached; further listings were dropped",
+                self.config.max_listings.unwrap_or_default()
+            ),
+            Limit::DetailFetches => format!(
+                "max_detail_fetches ({}) reached; the remaining listings have no details",
+                self.config.max_detail_fetches.unwrap_or_default()
+            ),
+            Limit::Runtime => format!(
+                "max_runtime_secs ({}) reached; remaining pages and details were skipped",
+                self.config.max_runtime_secs.unwrap_or_default()
+            ),
+        }
+    }
+
+    fn out_of_time(&self) -> bool {
+        let out = self
+            .config
+            .max_runtime_secs
+            .is_some_and(|secs| self.started.elapsed() >= Duration::from_secs(secs));
+        if out {
+            self.record(Limit::Runtime);
+        }
+        out
+    }
+
+    fn take(&self, counter: &AtomicUsize, max: Option<usize>, limit: Limit) -> bool {
+        let Some(max) = max else {
+            return true;
+        };
+        if counter.fetch_add(1, Ordering::SeqCst) < max {
+            return true;
+        }
+        self.record(limit);
+        false
+    }
+
+    fn record(&self, limit: Limit) {
+        if self.hit.lock().unwrap_or_else(|e| e.into_inner()).insert(limit) {
+            warn!("Scrape budget: {}", self.describe(limit));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "browser")]
+use anyhow::{Context, Result};
+#[cfg(feature = "browser")]
+use base64::prelude::{Engine, BASE64_STANDARD};
+use chrono::Utc;
+#[cfg(feature = "browser")]
+use headless_chrome::{protocol::cdp::Page, Tab};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+#[cfg(feature = "browser")]
+use std::path::Path;
+#[cfg(feature = "browser")]
+use tracing::debug;
+
+/// Copies kept of the listing pages the browser scraper visits
+///
+/// Listings get edited and removed; captures show what an ad said when it was
+/// scraped. They are taken of detail pages, so not with `--skip-details`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// Full-page PNG screenshot of every listing's detail page
+    pub screenshots: bool,
+    /// Also save every detail page in an archive format
+    pub archive: Option<ArchiveFormat>,
+    /// Captures go in `<dir>/<property ID>/`, one file per visit
+    pub dir: PathBuf,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            screenshots: false,
+            archive: None,
+            dir: PathBuf::from("data/captures"),
+        }
+    }
+}
+
+impl CaptureConfig {
+    /// Where a capture of `property_id` taken now is written
+    pub fn path(&self, property_id: &str, extension: &str) -> PathBuf {
+        let id: String = property_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        self.dir
+            .join(id)
+            .join(format!("{}.{}", Utc::now().format("%Y%m%dT%H%M%SZ"), extension))
+    }
+
+    /// Screenshot the page open in `tab` as a capture of `property_id`
+    #[cfg(feature = "browser")]
+    pub fn screenshot(&self, tab: &Tab, property_id: &str) -> Result<PathBuf> {
+        let png = full_page_screenshot(tab)?;
+        let path = self.path(property_id, "png");
+        write(&path, &png)?;
+        debug!("{}: saved screenshot {}", property_id, path.display());
+        Ok(path)
+    }
+
+    /// Archive the page open in `tab`, whose HTML was read as `html`, as a
+    /// capture of `property_id`
+    #[cfg(feature = "browser")]
+    pub fn archive(&self, tab: &Tab, property_id: &str, html: &str) -> Result<Option<PathBuf>> {
+        let Some(format) = self.archive else {
+            return Ok(None);
+        };
+        let (data, extension) = match format {
+            ArchiveFormat::Mhtml => (mhtml_snapshot(tab)?.into_bytes(), "mhtml"),
+            ArchiveFormat::Warc => (warc(&tab.get_url(), html), "warc"),
+        };
+        let path = self.path(property_id, extension);
+        write(&path, &data)?;
+        debug!("{}: archived page as {}", property_id, path.display());
+        Ok(Some(path))
+    }
+}
+
+/// How listing pages are archived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// The page with its images and styles in one file, as Chrome saves it;
+    /// opens in Chrome
+    Mhtml,
+    /// The page's HTML as a WARC resource record, for web archive tools
+    /// such as pywb or ReplayWeb.page
+    Warc,
+}
+
+/// The page in `tab` with its resources, via CDP `Page.captureSnapshot`
+#[cfg(feature = "browser")]
+pub fn mhtml_snapshot(tab: &Tab) -> Result<String> {
+    let snapshot = tab
+        .call_method(Page::CaptureSnapshot {
+            format: Some(Page::CaptureSnapshotFormatOption::Mhtml),
+        })
+        .context("Failed to capture an MHTML snapshot")?;
+    Ok(snapshot.data)
+}
+
+/// A WARC/1.1 file with a warcinfo record and the page's HTML as a resource
+pub fn warc(url: &str, html: &str) -> Vec<u8> {
+    let date = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let info = format!(
+        "software: housing-scout/{}\r\nformat: WARC File Format 1.1\r\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    let mut out = warc_record(
+        &[
+            ("WARC-Type", "warcinfo"),
+            ("WARC-Record-ID", &record_id(&format!("warcinfo {} {}", url, date))),
+            ("WARC-Date", &date),
+            ("Content-Type", "application/warc-fields"),
+        ],
+        info.as_bytes(),
+    );
+    out.extend(warc_record(
+        &[
+            ("WARC-Type", "resource"),
+            ("WARC-Record-ID", &record_id(&format!("resource {} {}", url, date))),
+            ("WARC-Date", &date),
+            ("WARC-Target-URI", url),
+            ("WARC-Block-Digest", &format!("sha256:{:x}", Sha256::digest(html))),
+            ("Content-Type", "text/html; charset=utf-8"),
+        ],
+        html.as_bytes(),
+    ));
+    out
+}
+
+fn warc_record(headers: &[(&str, &str)], block: &[u8]) -> Vec<u8> {
+    let mut record = b"WARC/1.1\r\n".to_vec();
+    for (name, value) in headers {
+        record.extend(format!("{}: {}\r\n", name, value).into_bytes());
+    }
+    record.extend(format!("Content-Length: {}\r\n\r\n", block.len()).into_bytes());
+    record.extend_from_slice(block);
+    record.extend_from_slice(b"\r\n\r\n");
+    record
+}
+
+/// A `<urn:uuid:...>` derived from `seed`, unique per record and capture time
+fn record_id(seed: &str) -> String {
+    let hex = format!("{:x}", Sha256::digest(seed));
+    format!(
+        "<urn:uuid:{}-{}-{}-{}-{}>",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// PNG of the whole page, not just the part in the window
+#[cfg(feature = "browser")]
+pub fn full_page_screenshot(tab: &Tab) -> Result<Vec<u8>> {
+    let metrics = tab.call_method(Page::GetLayoutMetrics(None))?;
+    let size = metrics.css_content_size;
+    let clip = Page::Viewport {
+        x: 0.0,
+        y: 0.0,
+        width: size.width,
+        height: size.height,
+        scale: 1.0,
+    };
+    let data = tab
+        .call_method(Page::CaptureScreenshot {
+            format: Some(Page::CaptureScreenshotFormatOption::Png),
+            quality: None,
+            clip: Some(clip),
+            from_surface: Some(true),
+            capture_beyond_viewport: Some(true),
+            optimize_for_speed: None,
+        })
+        .context("Failed to capture a screenshot")?
+        .data;
+    BASE64_STANDARD.decode(data).context("Failed to decode the screenshot")
+}
+
+#[cfg(feature = "browser")]
+fn write(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,157 @@
+use crate::models::{size_label, Features, InvalidListing, ListingStatus, Location, Money, Property, Source};
+use crate::scrapers::bidding::parse_bid_info;
+use crate::scrapers::fees::parse_monthly_fee;
+use crate::scrapers::floor::parse_floor;
+use crate::scrapers::parse::{parse_price, parse_rooms, parse_sqm};
+use crate::scrapers::selectors::CardSelectors;
+use crate::scrapers::AreaSearch;
+use scraper::Html;
+use serde_json::json;
+use tracing::{debug, info};
+
+/// The listings on a Booli search page as rendered in a browser, read by
+/// `cards`, and the ones that failed validation
+pub fn parse_listing_cards(
+    html: &str,
+    search: &AreaSearch,
+    cards: &CardSelectors,
+) -> (Vec<Property>, Vec<InvalidListing>) {
+    let document = Html::parse_document(html);
+    let rules = &cards.rules;
+
+    let elements: Vec<_> = document.select(&cards.card).collect();
+    info!("Found {} property cards in HTML", elements.len());
+
+    let mut properties = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (idx, element) in elements.iter().enumerate() {
+        // Extract data from the card
+        let href = element.value().attr("href").unwrap_or("");
+        let aria_label_raw = element.value().attr(&rules.label_attribute).unwrap_or("");
+
+        // Decode HTML entities (&nbsp; -> space)
+        let aria_label = aria_label_raw.replace("&nbsp;", " ");
+
+        debug!("Processing: {}", aria_label);
+
+        // Extract Booli ID from URL
+        let booli_id = href.split('/').next_back().unwrap_or("unknown").to_string();
+
+        // Parse aria-label: "2 rum lägenhet på Götgatan 120 Södermalm, Stockholms kommun"
+        let rooms = parse_rooms(&aria_label);
+        let mut address = String::new();
+        let mut area = search.name.clone();
+
+        // Extract address - between "på " and area name
+        if let Some(pa_pos) = aria_label.find("på ") {
+            let after_pa = &aria_label[pa_pos + 3..];
+            // Address is until we hit the area or comma
+            if let Some(comma_pos) = after_pa.find(',') {
+                address = after_pa[..comma_pos].trim().to_string();
+                // Extract area from what's before the comma
+                let area_part = &after_pa[..comma_pos];
+                if let Some(last_space) = area_part.rfind(' ') {
+                    let potential_area = &area_part[last_space + 1..];
+                    if !potential_area.chars().next().unwrap_or('a').is_numeric() {
+                        area = potential_area.to_string();
+                    }
+                }
+            } else {
+                address = after_pa.trim().to_string();
+            }
+        }
+
+        // Extract price, sqm, and other details from list items
+        let mut sqm: Option<i32> = None;
+        let mut floor = None;
+        let mut features = Vec::new();
+        let mut monthly_fee = String::new();
+
+        for li in element.select(&cards.facts) {
+            if let Some(aria) = li.value().attr(&rules.label_attribute) {
+                let aria_decoded = aria.replace("&nbsp;", " ");
+
+                // Extract sqm from "35,5 kvadratmeter" or similar
+                if aria_decoded.contains(&rules.sqm_marker) {
+                    sqm = parse_sqm(&aria_decoded).map(|sqm| sqm as i32);
+                }
+
+                // Floor from "våning 3"
+                if aria_decoded.to_lowercase().contains(&rules.floor_marker.to_lowercase()) {
+                    floor = parse_floor(&aria_decoded);
+                }
+
+                // Monthly fee
+                if aria_decoded.contains(&rules.fee_marker) {
+                    monthly_fee = aria_decoded.clone();
+                }
+            }
+        }
+
+        // Extract price from price container
+        let price = element
+            .select(&cards.price)
+            .next()
+            .and_then(|price_el| parse_price(&price_el.text().collect::<String>()));
+
+        // Extract features from amenities
+        for tag in element.select(&cards.tags) {
+            let feature = tag.text().collect::<String>().trim().to_string();
+            if !feature.is_empty() && feature != "Snart till salu" && feature != "Budgivning pågår" {
+                features.push(feature);
+            }
+        }
+
+        // Bidding status and current bid from the card text
+        let card_text = element.text().collect::<Vec<_>>().join(" ");
+        let bids = parse_bid_info(&card_text);
+
+        let listing_status = if card_text.contains("Snart till salu") {
+            ListingStatus::ComingSoon
+        } else {
+            ListingStatus::ForSale
+        };
+
+        let built = Property::builder(booli_id.clone(), Source::Booli)
+            .location(Location {
+                city: search.city.clone(),
+                area: Some(area.clone()),
+                // Cards carry no coordinates; detail pages fill them in
+                latitude: None,
+                longitude: None,
+            })
+            .address(address.clone())
+            .price(price.map(Money::sek))
+            .asking_price(bids.asking_price.map(Money::sek))
+            .current_bid(bids.current_bid.map(Money::sek))
+            .bidding_in_progress(bids.in_progress)
+            .listing_status(listing_status)
+            .monthly_fee(parse_monthly_fee(&monthly_fee).map(Money::sek))
+            .rooms(rooms)
+            .sqm(sqm)
+            .floor(floor)
+            .description(format!("Lägenhet i {}. {}.", area, size_label(rooms, sqm)))
+            .features(Features::normalize(&Source::Booli, &features))
+            .url(format!("https://www.booli.se{}", href))
+            .area_tags(vec![search.name.clone()])
+            .raw_data(json!({
+                "area": area,
+                "scraped_from": "listing_page",
+                "booli_id": booli_id,
+                "aria_label": aria_label,
+                "monthly_fee": monthly_fee
+            }))
+            .build();
+
+        match built {
+            Ok(property) => properties.push(property),
+            Err(invalid) => {
+                info!("Skipped property {}: {}", idx, invalid);
+                rejected.push(invalid);
+            }
+        }
+    }
+
+    (properties, rejected)
+}
@@ -0,0 +1,323 @@
+//! The headless Chrome the browser scraper drives, and the processes it leaves
+//!
+//! Chrome is launched on first use and kept for every search, detail page
+//! and source of a run, one tab per page. Each launch is marked with
+//! housing-scout's PID, so Chrome left behind by a run that crashed or was
+//! killed is found and stopped before the next one launches.
+//!
+//! Chrome grows over a long run and slows down when the machine runs short of
+//! memory, so it is restarted between pages once it uses too much memory or a
+//! page took too long or failed to load. Pages in flight finish first, and the
+//! page that failed is loaded again in the new Chrome.
+
+use serde::{Deserialize, Serialize};
+
+/// How the browser scraper runs Chrome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChromeConfig {
+    /// Before launching, stop Chrome processes left by housing-scout runs
+    /// that are no longer running
+    pub kill_orphans: bool,
+    /// Restart Chrome between pages once it and its tab processes use more
+    /// than this much memory (resident, in MB); unset never restarts it
+    pub restart_above_mb: Option<u64>,
+    /// Take Chrome for hung, and close it, after this long without a word
+    /// from it
+    pub idle_timeout_secs: u64,
+    /// Give up on a page that hasn't loaded after this long
+    pub page_timeout_secs: u64,
+    /// Restart Chrome between pages once a page took longer than this to load
+    pub slow_page_secs: u64,
+    /// Restarts in one run, after which Chrome is kept running as it is
+    pub max_restarts: usize,
+    /// Pass for an everyday desktop Chrome, for when Booli serves obvious
+    /// headless clients degraded pages. Contradicts a booli_browser identity,
+    /// so the two can't be combined.
+    pub stealth: bool,
+}
+
+impl Default for ChromeConfig {
+    fn default() -> Self {
+        Self {
+            kill_orphans: true,
+            restart_above_mb: Some(1500),
+            idle_timeout_secs: 600,
+            page_timeout_secs: 60,
+            slow_page_secs: 30,
+            max_restarts: 10,
+            stealth: false,
+        }
+    }
+}
+
+#[cfg(feature = "browser")]
+pub use self::handle::{Chrome, ChromeTab};
+
+#[cfg(feature = "browser")]
+mod handle {
+    use super::ChromeConfig;
+    use crate::scrapers::stealth;
+    use anyhow::{Context, Result};
+    use headless_chrome::{Browser, LaunchOptions, Tab};
+    use std::ffi::OsStr;
+    use std::ops::Deref;
+    use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+    use std::sync::{Arc, Once, RwLock, RwLockReadGuard};
+    use std::time::Duration;
+    use tracing::{info, warn};
+
+    /// Orphans are looked for once per process, at the first launch
+    static ORPHANS: Once = Once::new();
+
+    /// A Chrome shared by everything holding a clone, launched on first use
+    ///
+    /// Restarting waits for every open tab to close, so pages in flight on
+    /// other threads finish first.
+    #[derive(Clone, Default)]
+    pub struct Chrome {
+        config: ChromeConfig,
+        browser: Arc<RwLock<Option<Browser>>>,
+        /// PID of the running Chrome, 0 when none; read without `browser`'s
+        /// lock, which a thread holding a tab can't take again while a restart
+        /// waits for it
+        pid: Arc<AtomicU32>,
+        /// A page was slow or failed since the last restart
+        struggling: Arc<AtomicBool>,
+        restarts: Arc<AtomicUsize>,
+    }
+
+    impl Chrome {
+        pub fn new(config: ChromeConfig) -> Self {
+            Self {
+                config,
+                browser: Arc::default(),
+                pid: Arc::default(),
+                struggling: Arc::default(),
+                restarts: Arc::default(),
+            }
+        }
+
+        /// A new tab, launching Chrome first if it isn't running
+        pub fn tab(&self) -> Result<ChromeTab<'_>> {
+            loop {
+                let browser = self.browser.read().unwrap_or_else(|e| e.into_inner());
+                if let Some(running) = browser.as_ref() {
+                    let tab = running.new_tab().inspect_err(|_| self.page_failed())?;
+                    tab.set_default_timeout(Duration::from_secs(self.config.page_timeout_secs));
+                    if self.config.stealth {
+                        stealth::apply(&tab, &running.get_version()?.user_agent)?;
+                    }
+                    return Ok(ChromeTab { tab, _browser: browser });
+                }
+                drop(browser);
+                let mut browser = self.browser.write().unwrap_or_else(|e| e.into_inner());
+                if browser.is_none() {
+                    let launched = self.launch()?;
+                    self.pid.store(launched.get_process_id().unwrap_or(0), Ordering::Relaxed);
+                    *browser = Some(launched);
+                }
+            }
+        }
+
+        fn launch(&self) -> Result<Browser> {
+            if self.config.kill_orphans {
+                ORPHANS.call_once(|| {
+                    let killed = kill_orphans();
+                    if killed > 0 {
+                        warn!("Stopped {} Chrome processes left by an earlier run", killed);
+                    }
+                });
+            }
+            info!("Launching headless Chrome...");
+            let owner = format!("{}{}", OWNER_FLAG, std::process::id());
+            let mut args = vec![OsStr::new(&owner)];
+            let mut dropped = Vec::new();
+            if self.config.stealth {
+                args.extend(stealth::LAUNCH_ARGS.iter().map(OsStr::new));
+                dropped.extend(stealth::DROPPED_ARGS.iter().map(OsStr::new));
+            }
+            let options = LaunchOptions::default_builder()
+                .headless(true)
+                .idle_browser_timeout(Duration::from_secs(self.config.idle_timeout_secs))
+                .args(args)
+                .ignore_default_args(dropped)
+                .build()
+                .context("Failed to build launch options")?;
+            Browser::new(options).context("Failed to launch Chrome browser")
+        }
+
+        /// Memory used by Chrome and its tab processes, in MB; None when it
+        /// isn't running or the platform doesn't tell
+        pub fn memory_mb(&self) -> Option<u64> {
+            let pid = match self.pid.load(Ordering::Relaxed) {
+                0 => return None,
+                pid => pid,
+            };
+            let processes = processes();
+            if processes.is_empty() {
+                return None;
+            }
+            let kb: u64 = tree(&processes, pid).iter().filter_map(|pid| rss_kb(*pid)).sum();
+            Some(kb / 1024)
+        }
+
+        /// Note how long a page took to load, flagging Chrome for a restart
+        /// when it was slow
+        pub fn page_loaded(&self, took: Duration) {
+            if took > Duration::from_secs(self.config.slow_page_secs) {
+                warn!("A page took {:.0} s to load", took.as_secs_f64());
+                self.struggling.store(true, Ordering::Relaxed);
+            }
+        }
+
+        /// Flag Chrome for a restart after a page failed to load
+        pub fn page_failed(&self) {
+            self.struggling.store(true, Ordering::Relaxed);
+        }
+
+        /// Whether Chrome is due a restart: it struggled with a page or uses
+        /// more memory than `restart_above_mb`, and restarts are left
+        pub fn needs_restart(&self) -> bool {
+            self.restarts.load(Ordering::Relaxed) < self.config.max_restarts
+                && (self.struggling.load(Ordering::Relaxed) || self.over_limit())
+        }
+
+        fn over_limit(&self) -> bool {
+            match (self.config.restart_above_mb, self.memory_mb()) {
+                (Some(limit), Some(used)) => used > limit,
+                _ => false,
+            }
+        }
+
+        /// Restart Chrome if it is due one, once its open tabs close; true
+        /// when it was restarted
+        ///
+        /// Call it with no tab of this Chrome open on the calling thread.
+        pub fn restart_if_needed(&self) -> bool {
+            if !self.needs_restart() {
+                return false;
+            }
+            // Another thread may be restarting it for the same reason
+            let struggling = self.struggling.swap(false, Ordering::Relaxed);
+            if !struggling && !self.over_limit() {
+                return false;
+            }
+            let restarts = self.restarts.fetch_add(1, Ordering::Relaxed) + 1;
+            let reason = if struggling { "pages load slowly or fail" } else { "it uses too much memory" };
+            info!("Restarting Chrome as {} ({}/{})", reason, restarts, self.config.max_restarts);
+            self.close();
+            true
+        }
+
+        /// Close Chrome once its open tabs close; the next tab launches it
+        /// again
+        pub fn close(&self) {
+            let mut browser = self.browser.write().unwrap_or_else(|e| e.into_inner());
+            browser.take();
+            self.pid.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// A tab of a [`Chrome`], closed when dropped
+    pub struct ChromeTab<'a> {
+        tab: Arc<Tab>,
+        _browser: RwLockReadGuard<'a, Option<Browser>>,
+    }
+
+    impl Deref for ChromeTab<'_> {
+        type Target = Tab;
+
+        fn deref(&self) -> &Tab {
+            &self.tab
+        }
+    }
+
+    impl Drop for ChromeTab<'_> {
+        fn drop(&mut self) {
+            let _ = self.tab.close(false);
+        }
+    }
+
+    /// Chrome argument naming the housing-scout process that launched it
+    const OWNER_FLAG: &str = "--housing-scout-owner=";
+
+    /// One running process, from /proc
+    struct Process {
+        pid: u32,
+        parent: u32,
+        args: Vec<String>,
+    }
+
+    /// Every running process; empty where there's no /proc
+    fn processes() -> Vec<Process> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| {
+                let pid: u32 = entry.ok()?.file_name().to_str()?.parse().ok()?;
+                // "pid (command) state ppid ...", where the command may hold spaces
+                let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+                let parent = stat.rsplit_once(')')?.1.split_whitespace().nth(1)?.parse().ok()?;
+                let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+                let args = cmdline
+                    .split(|b| *b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect();
+                Some(Process { pid, parent, args })
+            })
+            .collect()
+    }
+
+    /// `root` and every process descending from it
+    fn tree(processes: &[Process], root: u32) -> Vec<u32> {
+        let mut pids = vec![root];
+        let mut i = 0;
+        while i < pids.len() {
+            let parent = pids[i];
+            pids.extend(processes.iter().filter(|p| p.parent == parent).map(|p| p.pid));
+            i += 1;
+        }
+        pids
+    }
+
+    /// Resident memory of `pid` in kB
+    fn rss_kb(pid: u32) -> Option<u64> {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        line.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    /// Kill every Chrome, with its tab processes, launched by a housing-scout
+    /// process that is gone; returns how many processes were stopped
+    fn kill_orphans() -> usize {
+        let processes = processes();
+        let alive = |pid: u32| processes.iter().any(|p| p.pid == pid);
+        let orphans: Vec<u32> = processes
+            .iter()
+            .filter(|p| {
+                p.args
+                    .iter()
+                    .filter_map(|arg| arg.strip_prefix(OWNER_FLAG)?.parse::<u32>().ok())
+                    .any(|owner| owner != std::process::id() && !alive(owner))
+            })
+            .flat_map(|p| tree(&processes, p.pid))
+            .collect();
+        if orphans.is_empty() {
+            return 0;
+        }
+        let status = std::process::Command::new("kill")
+            .arg("-KILL")
+            .args(orphans.iter().map(u32::to_string))
+            .status();
+        match status {
+            Ok(_) => orphans.len(),
+            Err(e) => {
+                warn!("Failed to stop Chrome left by an earlier run: {}", e);
+                0
+            }
+        }
+    }
+}
@@ -0,0 +1,650 @@
+use crate::geo::GeoPoint;
+use crate::models::{Association, Broker, Image, ImageKind, Money, Property};
+use crate::scrapers::bidding::{parse_bid_info, BidInfo};
+use crate::scrapers::fees::{parse_monthly_fee, parse_operating_cost};
+use crate::scrapers::floor::parse_floor;
+use crate::scrapers::renovation::parse_renovation;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::Stockholm;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{Html, Node, Selector};
+use serde_json::Value;
+use tracing::instrument;
+
+/// Data extracted from a single listing's detail page
+#[derive(Debug, Clone, Default)]
+pub struct ListingDetails {
+    /// Upcoming viewing start times
+    pub viewings: Vec<DateTime<Utc>>,
+    pub bids: BidInfo,
+    pub broker: Option<Broker>,
+    pub association: Option<Association>,
+    /// When the listing was published, from its date or its days on Booli
+    pub published_at: Option<DateTime<Utc>>,
+    /// The listing's full text; listing cards only have a summary, if any
+    pub description: Option<String>,
+    pub monthly_fee: Option<i64>,
+    /// Monthly "driftkostnad"
+    pub operating_cost: Option<i64>,
+    pub floor: Option<f32>,
+    pub coordinates: Option<GeoPoint>,
+    /// Photos, floor plans and 3D tour stills in page order
+    pub images: Vec<Image>,
+    /// Matterport and other virtual tour links
+    pub virtual_tours: Vec<String>,
+}
+
+impl ListingDetails {
+    /// Merge detail page data into a property scraped from a listing card
+    pub fn apply(self, property: &mut Property) {
+        if !self.viewings.is_empty() {
+            property.viewings = self.viewings;
+        }
+
+        // The detail page is more complete than the card, but keep card values it lacks
+        property.bidding_in_progress |= self.bids.in_progress;
+        if let Some(asking) = self.bids.asking_price {
+            property.asking_price = Some(Money::sek(asking));
+        }
+        if let Some(bid) = self.bids.current_bid {
+            property.current_bid = Some(Money::sek(bid));
+        }
+        if self.broker.is_some() {
+            property.broker = self.broker;
+        }
+        if self.association.is_some() {
+            property.association = self.association;
+        }
+        if self.published_at.is_some() {
+            property.published_at = self.published_at;
+        }
+        if let Some(description) = self.description.filter(|d| !d.trim().is_empty()) {
+            property.renovation = parse_renovation(&description);
+            property.description = description;
+        }
+        if let Some(fee) = self.monthly_fee {
+            property.monthly_fee = Some(Money::sek(fee));
+        }
+        if let Some(cost) = self.operating_cost {
+            property.operating_cost = Some(Money::sek(cost));
+        }
+        if self.floor.is_some() {
+            property.floor = self.floor;
+        }
+        if !self.images.is_empty() {
+            property.images = self.images;
+        }
+        if !self.virtual_tours.is_empty() {
+            property.virtual_tours = self.virtual_tours;
+        }
+        if let Some(point) = self.coordinates {
+            property.location.latitude = Some(point.lat);
+            property.location.longitude = Some(point.lon);
+        }
+    }
+}
+
+/// Parse a Booli listing detail page
+#[instrument(name = "parse", level = "debug", skip_all, fields(bytes = html.len()))]
+pub fn parse_detail_page(html: &str) -> ListingDetails {
+    let document = Html::parse_document(html);
+    let text = visible_text(&document);
+
+    ListingDetails {
+        viewings: parse_viewings(&text, Utc::now()),
+        bids: parse_bid_info(&text),
+        broker: parse_broker(&document),
+        association: parse_association(&document, &text),
+        published_at: parse_published(&text, Utc::now()),
+        description: parse_description(&document),
+        monthly_fee: parse_monthly_fee(&text),
+        operating_cost: parse_operating_cost(&text),
+        floor: parse_floor(&text),
+        coordinates: parse_coordinates(&document),
+        images: parse_images(&document),
+        virtual_tours: parse_virtual_tours(&document, html),
+    }
+}
+
+/// Words in an image's URL, alt text or title marking it as a floor plan
+const FLOOR_PLAN_MARKERS: [&str; 3] = ["planritning", "floorplan", "floor-plan"];
+/// Words marking an image as a still or panorama from a 3D tour
+const TOUR_MARKERS: [&str; 4] = ["matterport", "panorama", "3d-visning", "360-"];
+
+/// Virtual tour viewers brokers embed or link, found anywhere in the page
+/// including its embedded JSON
+static TOUR_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"https?://(?:[\w-]+\.)*(?:matterport\.com|kuula\.co|giraffe360\.com|ogulo\.com|cloudpano\.com|roundme\.com)/[^\s"'<>\\]+"#,
+    )
+    .unwrap()
+});
+/// Link texts of virtual tours on other hosts
+const TOUR_LINK_TEXTS: [&str; 4] = ["3d-visning", "virtuell visning", "360-visning", "3d-tur"];
+
+/// Collect listing images from schema.org data, og:image and img tags, typed
+/// by their URL, alt text or title and numbered in page order
+fn parse_images(document: &Html) -> Vec<Image> {
+    let mut found: Vec<(String, Option<String>)> = Vec::new();
+
+    let ld_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    for script in document.select(&ld_selector) {
+        if let Ok(json) = serde_json::from_str::<Value>(&script.inner_html()) {
+            find_images(&json, &mut found);
+        }
+    }
+
+    let og_selector = Selector::parse(r#"meta[property="og:image"]"#).unwrap();
+    found.extend(document.select(&og_selector).filter_map(|m| m.value().attr("content")).map(|url| (url.to_string(), None)));
+
+    let img_selector = Selector::parse("img").unwrap();
+    for img in document.select(&img_selector) {
+        // The largest srcset candidate is listed last
+        let src = img
+            .value()
+            .attr("srcset")
+            .and_then(|set| set.split(',').next_back())
+            .and_then(|candidate| candidate.split_whitespace().next())
+            .or_else(|| img.value().attr("src"));
+        let Some(src) = src else {
+            continue;
+        };
+        let caption = ["alt", "title"].iter().find_map(|attr| non_empty(img.value().attr(attr)?.to_string()));
+        found.push((src.to_string(), caption));
+    }
+
+    // Keep each URL once, in first-seen order, with the first caption given for it
+    let mut images: Vec<(String, Option<String>)> = Vec::new();
+    for (url, caption) in found {
+        let lower = url.to_lowercase();
+        if !url.starts_with("http") || lower.ends_with(".svg") || lower.contains("logo") || lower.contains("icon") {
+            continue;
+        }
+        match images.iter_mut().find(|(seen, _)| *seen == url) {
+            Some((_, seen_caption)) => *seen_caption = seen_caption.take().or(caption),
+            None => images.push((url, caption)),
+        }
+    }
+
+    images
+        .into_iter()
+        .enumerate()
+        .map(|(order, (url, caption))| {
+            let kind = image_kind(&url, caption.as_deref());
+            Image {
+                url,
+                caption,
+                order,
+                kind,
+            }
+        })
+        .collect()
+}
+
+fn image_kind(url: &str, caption: Option<&str>) -> ImageKind {
+    let text = format!("{} {}", url, caption.unwrap_or_default()).to_lowercase();
+    if FLOOR_PLAN_MARKERS.iter().any(|marker| text.contains(marker)) {
+        ImageKind::FloorPlan
+    } else if TOUR_MARKERS.iter().any(|marker| text.contains(marker)) {
+        ImageKind::Tour
+    } else {
+        ImageKind::Photo
+    }
+}
+
+/// Matterport and other virtual tour links, in page order
+fn parse_virtual_tours(document: &Html, html: &str) -> Vec<String> {
+    // Embedded JSON escapes slashes and attributes escape ampersands
+    let unescaped = html.replace("\\/", "/").replace("&amp;", "&");
+    let mut tours: Vec<String> = TOUR_URL.find_iter(&unescaped).map(|m| m.as_str().to_string()).collect();
+
+    let link_selector = Selector::parse("a[href]").unwrap();
+    for link in document.select(&link_selector) {
+        let text = link.text().collect::<String>().to_lowercase();
+        if TOUR_LINK_TEXTS.iter().any(|t| text.contains(t)) {
+            tours.extend(link.value().attr("href").filter(|href| href.starts_with("http")).map(str::to_string));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    tours.retain(|url| seen.insert(url.clone()));
+    tours
+}
+
+/// The longest schema.org `description`, else the og:description
+fn parse_description(document: &Html) -> Option<String> {
+    let ld_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    let mut descriptions = Vec::new();
+    for script in document.select(&ld_selector) {
+        if let Ok(json) = serde_json::from_str::<Value>(&script.inner_html()) {
+            find_descriptions(&json, &mut descriptions);
+        }
+    }
+    let og_selector = Selector::parse(r#"meta[property="og:description"]"#).unwrap();
+    descriptions
+        .into_iter()
+        .max_by_key(|d| d.len())
+        .or_else(|| document.select(&og_selector).find_map(|m| m.value().attr("content")).map(str::to_string))
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+fn find_descriptions(json: &Value, descriptions: &mut Vec<String>) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::String(text) if key == "description" => descriptions.push(text.clone()),
+                    value => find_descriptions(value, descriptions),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| find_descriptions(item, descriptions)),
+        _ => {}
+    }
+}
+
+/// schema.org image URLs with their `caption` or `name`
+fn find_images(json: &Value, images: &mut Vec<(String, Option<String>)>) {
+    let image_of = |item: &Value| -> Option<(String, Option<String>)> {
+        if let Some(url) = item.as_str() {
+            return Some((url.to_string(), None));
+        }
+        let url = item.get("url").or_else(|| item.get("contentUrl"))?.as_str()?;
+        let caption = item.get("caption").or_else(|| item.get("name")).and_then(Value::as_str);
+        Some((url.to_string(), caption.and_then(|c| non_empty(c.to_string()))))
+    };
+    match json {
+        Value::Object(map) => {
+            match map.get("image") {
+                Some(Value::Array(items)) => images.extend(items.iter().filter_map(image_of)),
+                Some(image) => images.extend(image_of(image)),
+                None => {}
+            }
+            map.iter().filter(|(key, _)| *key != "image").for_each(|(_, value)| find_images(value, images));
+        }
+        Value::Array(items) => items.iter().for_each(|item| find_images(item, images)),
+        _ => {}
+    }
+}
+
+/// Parse the listing's coordinates from schema.org `geo` data, falling back to
+/// the first latitude/longitude pair in the page's embedded JSON
+fn parse_coordinates(document: &Html) -> Option<GeoPoint> {
+    let script_selector = Selector::parse("script").unwrap();
+    let scripts: Vec<String> = document.select(&script_selector).map(|s| s.inner_html()).collect();
+
+    let from_ld = scripts
+        .iter()
+        .filter_map(|script| serde_json::from_str::<Value>(script).ok())
+        .find_map(|json| find_geo(&json));
+
+    from_ld
+        .or_else(|| scripts.iter().find_map(|script| embedded_coordinates(script)))
+        .filter(|point| (-90.0..=90.0).contains(&point.lat) && (-180.0..=180.0).contains(&point.lon))
+}
+
+fn find_geo(json: &Value) -> Option<GeoPoint> {
+    match json {
+        Value::Object(map) => {
+            let geo = map.get("geo").and_then(|geo| {
+                let number = |key: &str| {
+                    geo.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()))
+                };
+                Some(GeoPoint::new(number("latitude")?, number("longitude")?))
+            });
+            geo.or_else(|| map.values().find_map(find_geo))
+        }
+        Value::Array(items) => items.iter().find_map(find_geo),
+        _ => None,
+    }
+}
+
+/// `"latitude":59.31,"longitude":18.07` anywhere in a script body
+fn embedded_coordinates(script: &str) -> Option<GeoPoint> {
+    let number_after = |key: &str, from: usize| -> Option<(f64, usize)> {
+        let start = from + script[from..].find(key)? + key.len();
+        let rest = script[start..].trim_start_matches([':', ' ']);
+        let offset = start + (script[start..].len() - rest.len());
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+            .unwrap_or(rest.len());
+        Some((rest[..end].parse().ok()?, offset + end))
+    };
+
+    let (lat, end) = number_after("\"latitude\"", 0)?;
+    let (lon, _) = number_after("\"longitude\"", end).filter(|(_, lon_end)| lon_end - end < 200)?;
+    Some(GeoPoint::new(lat, lon))
+}
+
+/// Parse the listing broker from structured data, profile links and tel: links
+fn parse_broker(document: &Html) -> Option<Broker> {
+    let mut broker = Broker::default();
+
+    // schema.org data is the most reliable source when present
+    let ld_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    for script in document.select(&ld_selector) {
+        if let Ok(json) = serde_json::from_str::<Value>(&script.inner_html()) {
+            find_agent(&json, &mut broker);
+        }
+    }
+
+    // Booli links the broker's and the agency's profile pages
+    let name_selector = Selector::parse(r#"a[href*="/maklare/"]"#).unwrap();
+    if broker.name.is_none() {
+        broker.name = document.select(&name_selector).find_map(|a| non_empty(a.text().collect()));
+    }
+
+    let agency_selector = Selector::parse(r#"a[href*="/maklarbyra"]"#).unwrap();
+    if broker.agency.is_none() {
+        broker.agency = document.select(&agency_selector).find_map(|a| non_empty(a.text().collect()));
+    }
+
+    let phone_selector = Selector::parse(r#"a[href^="tel:"]"#).unwrap();
+    if broker.phone.is_none() {
+        broker.phone = document
+            .select(&phone_selector)
+            .filter_map(|a| a.value().attr("href"))
+            .find_map(|href| non_empty(href.trim_start_matches("tel:").replace("%20", " ")));
+    }
+
+    if broker == Broker::default() {
+        None
+    } else {
+        Some(broker)
+    }
+}
+
+/// Organisation numbers of economic associations start with 7: "769612-3456"
+static ORG_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(7\d{5})-?(\d{4})\b").unwrap());
+
+/// Words association names start with: "Brf Solgläntan 4", "HSB Brf Taket"
+const ASSOCIATION_PREFIXES: [&str; 5] = ["brf", "bostadsrättsföreningen", "bostadsrättsförening", "hsb", "riksbyggen"];
+
+/// Parse the housing association from its link, or else from the page text,
+/// and a link to its annual report
+pub fn parse_association(document: &Html, text: &str) -> Option<Association> {
+    let starts_like_name = |name: &str| {
+        let first = name.split_whitespace().next().unwrap_or_default().to_lowercase();
+        ASSOCIATION_PREFIXES.contains(&first.as_str()) && name.split_whitespace().count() > 1
+    };
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let name = document
+        .select(&link_selector)
+        .filter_map(|a| non_empty(a.text().collect()))
+        .find(|name| starts_like_name(name) && name.len() < 80)
+        .or_else(|| association_in_text(text))?;
+
+    let annual_report_url = document
+        .select(&link_selector)
+        .find(|a| {
+            let label = format!("{} {}", a.value().attr("href").unwrap_or_default(), a.text().collect::<String>());
+            let label = label.to_lowercase();
+            label.contains("årsredovisning") || label.contains("arsredovisning")
+        })
+        .and_then(|a| a.value().attr("href"))
+        .map(|href| absolute_url(href, "https://www.booli.se"));
+
+    let org_number = ORG_NUMBER
+        .captures(text)
+        .map(|caps| format!("{}-{}", &caps[1], &caps[2]));
+
+    Some(Association {
+        name,
+        annual_report_url,
+        financials: None,
+        org_number,
+        registry: None,
+    })
+}
+
+/// "... Förening Brf Solgläntan 4 Avgift ..." gives "Brf Solgläntan 4": the
+/// prefix and up to three capitalised or numeric words after it
+fn association_in_text(text: &str) -> Option<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let start = words.iter().position(|w| {
+        let w = w.to_lowercase();
+        w == "brf" || w == "bostadsrättsföreningen"
+    })?;
+    let rest = words[start + 1..]
+        .iter()
+        .take(3)
+        .take_while(|w| w.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit()))
+        .map(|w| w.trim_end_matches([',', '.', ':']))
+        .collect::<Vec<_>>();
+    if rest.is_empty() {
+        return None;
+    }
+    Some(format!("{} {}", words[start], rest.join(" ")))
+}
+
+/// `href` resolved against `base` when it is relative
+pub fn absolute_url(href: &str, base: &str) -> String {
+    if href.starts_with("http://") || href.starts_with("https://") {
+        href.to_string()
+    } else if let Some(rest) = href.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), href.trim_start_matches('/'))
+    }
+}
+
+/// Walk JSON-LD looking for a RealEstateAgent or a Person working for one
+fn find_agent(json: &Value, broker: &mut Broker) {
+    match json {
+        Value::Array(items) => items.iter().for_each(|item| find_agent(item, broker)),
+        Value::Object(map) => {
+            let kind = map.get("@type").and_then(Value::as_str).unwrap_or("");
+            let name = map.get("name").and_then(Value::as_str).map(str::to_string);
+            let phone = map.get("telephone").and_then(Value::as_str).map(str::to_string);
+
+            match kind {
+                "RealEstateAgent" => {
+                    broker.agency = broker.agency.take().or(name);
+                    broker.phone = broker.phone.take().or(phone);
+                }
+                "Person" if map.contains_key("worksFor") => {
+                    broker.name = broker.name.take().or(name);
+                    broker.phone = broker.phone.take().or(phone);
+                }
+                _ => {}
+            }
+
+            map.values().for_each(|value| find_agent(value, broker));
+        }
+        _ => {}
+    }
+}
+
+fn non_empty(text: String) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Collect the page's text content, skipping scripts and styles
+pub fn visible_text(document: &Html) -> String {
+    let mut text = String::new();
+
+    for node in document.root_element().descendants() {
+        if let Node::Text(t) = node.value() {
+            let in_script = node
+                .parent()
+                .and_then(|p| p.value().as_element())
+                .map(|e| matches!(e.name(), "script" | "style" | "noscript"))
+                .unwrap_or(false);
+            if !in_script {
+                text.push_str(t);
+                text.push(' ');
+            }
+        }
+    }
+
+    text
+}
+
+/// How far past the "Visning" label we look for dates
+const VIEWING_WINDOW: usize = 200;
+
+/// Parse viewing times like "Visning sön 19 jan 13:00–13:45" or "Visning idag kl 17.30"
+///
+/// Times on Booli are local Stockholm time; dates without a year are assumed
+/// to be the next occurrence relative to `now`.
+pub fn parse_viewings(text: &str, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let lower = text.to_lowercase().replace('\u{a0}', " ");
+    let today = now.with_timezone(&Stockholm).date_naive();
+
+    let mut viewings = Vec::new();
+
+    for (pos, _) in lower.match_indices("visning") {
+        let window: String = lower[pos..].chars().take(VIEWING_WINDOW).collect();
+        let tokens: Vec<&str> = window
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '–' | '-' | '(' | ')'))
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let date = match tokens[i] {
+                "idag" => Some((today, i + 1)),
+                "imorgon" => Some((today + Duration::days(1), i + 1)),
+                token => parse_day_month(token, tokens.get(i + 1).copied(), tokens.get(i + 2).copied(), today)
+                    .map(|(date, consumed)| (date, i + consumed)),
+            };
+
+            if let Some((date, next)) = date {
+                // The start time follows within a couple of tokens ("kl.", weekday etc.)
+                let time = tokens[next..]
+                    .iter()
+                    .take(3)
+                    .find_map(|t| parse_time(t));
+
+                if let Some(time) = time {
+                    if let Some(local) = Stockholm.from_local_datetime(&date.and_time(time)).earliest() {
+                        viewings.push(local.with_timezone(&Utc));
+                    }
+                }
+                i = next;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    viewings.retain(|viewing| *viewing >= now);
+    viewings.sort();
+    viewings.dedup();
+    viewings
+}
+
+/// Parse when a listing was published from "Publicerad 3 okt",
+/// "Publicerad 2026-10-03", "Publicerad igår" or "Dagar på Booli 12",
+/// as the start of that day in Stockholm
+pub fn parse_published(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let lower = text.to_lowercase().replace('\u{a0}', " ");
+    let today = now.with_timezone(&Stockholm).date_naive();
+
+    let published = lower.find("publicerad").and_then(|pos| {
+        let tokens: Vec<&str> = lower[pos + "publicerad".len()..]
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+            .filter(|t| !t.is_empty())
+            .take(3)
+            .collect();
+        match tokens.first().copied()? {
+            "idag" => Some(today),
+            "igår" => Some(today - Duration::days(1)),
+            token => NaiveDate::parse_from_str(token, "%Y-%m-%d").ok().or_else(|| {
+                let (date, _) = parse_day_month(token, tokens.get(1).copied(), tokens.get(2).copied(), today)?;
+                // Publication dates are past; "3 jan" read in October is this year's
+                if date > today {
+                    date.with_year(date.year() - 1)
+                } else {
+                    Some(date)
+                }
+            }),
+        }
+    });
+    let date = published.or_else(|| {
+        // "Dagar på Booli 12" in the facts list, or "12 dagar på Booli"
+        let pos = lower.find("dagar på booli")?;
+        let after = lower[pos + "dagar på booli".len()..].split_whitespace().next();
+        let before = lower[..pos].split_whitespace().next_back();
+        let days: i64 = after.and_then(|t| t.parse().ok()).or_else(|| before?.parse().ok())?;
+        Some(today - Duration::days(days))
+    })?;
+
+    let start = Stockholm.from_local_datetime(&date.and_time(NaiveTime::MIN)).earliest()?;
+    Some(start.with_timezone(&Utc))
+}
+
+/// Find the first Swedish date ("12 september 2024", "3 jan") in free text
+pub fn parse_swedish_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let lower = text.to_lowercase().replace('\u{a0}', " ");
+    let tokens: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    (0..tokens.len()).find_map(|i| {
+        parse_day_month(tokens[i], tokens.get(i + 1).copied(), tokens.get(i + 2).copied(), today)
+            .map(|(date, _)| date)
+    })
+}
+
+/// Parse "19 jan [2025]" starting at `day`, returning the date and the number of tokens consumed
+fn parse_day_month(
+    day: &str,
+    month: Option<&str>,
+    year: Option<&str>,
+    today: NaiveDate,
+) -> Option<(NaiveDate, usize)> {
+    let day: u32 = day.trim_end_matches('.').parse().ok()?;
+    let month = month_number(month?)?;
+
+    let explicit_year = year
+        .filter(|y| y.len() == 4)
+        .and_then(|y| y.parse::<i32>().ok());
+
+    let date = match explicit_year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day)?,
+        None => {
+            let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            // A date well in the past means it's next year's ("3 jan" seen in December)
+            if this_year < today - Duration::days(7) {
+                NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+            } else {
+                this_year
+            }
+        }
+    };
+
+    Some((date, if explicit_year.is_some() { 3 } else { 2 }))
+}
+
+/// Swedish month names; abbreviations are any prefix of at least three letters
+const MONTHS: [&str; 12] = [
+    "januari", "februari", "mars", "april", "maj", "juni",
+    "juli", "augusti", "september", "oktober", "november", "december",
+];
+
+fn month_number(token: &str) -> Option<u32> {
+    let token = token.trim_end_matches('.');
+    if token.chars().count() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|name| name.starts_with(token))
+        .map(|idx| idx as u32 + 1)
+}
+
+/// Parse "13:00" or "13.00"
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    let (hour, minute) = token.split_once([':', '.'])?;
+    let minute: String = minute.chars().take(2).collect();
+    NaiveTime::from_hms_opt(hour.parse().ok()?, minute.parse().ok()?, 0)
+}
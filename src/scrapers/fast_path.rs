@@ -0,0 +1,341 @@
+//! Booli pages over plain HTTP, before resorting to Chrome
+//!
+//! Booli renders its pages on the server and puts the listings of a search
+//! page in the page's `__NEXT_DATA__` JSON as well. Reading that over HTTP
+//! takes a fraction of the time and memory of a page load in Chrome, so with
+//! [`FetchStrategy::HttpFirst`](crate::scrapers::FetchStrategy::HttpFirst) the
+//! browser source tries it for every search and detail page, and only loads
+//! the pages it fails on in Chrome.
+
+use crate::models::{size_label, Features, InvalidListing, ListingStatus, Location, Money, Property, Source};
+use crate::scrapers::artifacts::DebugArtifacts;
+use crate::scrapers::booli::{browser_headers, BROWSER_USER_AGENT};
+use crate::scrapers::budget::Budget;
+use crate::scrapers::detail::parse_detail_page;
+use crate::scrapers::plugin::ParserPlugin;
+use crate::scrapers::politeness::{Identity, Politeness};
+use crate::scrapers::rate_limit::RateLimiter;
+use crate::scrapers::AreaSearch;
+use crate::shutdown::{Interrupted, Shutdown};
+use crate::storage::Checkpoint;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use chrono_tz::Europe::Stockholm;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+static NEXT_DATA: Lazy<Selector> = Lazy::new(|| Selector::parse("script#__NEXT_DATA__").unwrap());
+
+/// A listing in a page's Apollo cache, as the search results query returns it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NextListing {
+    booli_id: Value,
+    url: Option<String>,
+    street_address: Option<String>,
+    descriptive_area_name: Option<String>,
+    municipality_name: Option<String>,
+    upcoming_sale: Option<bool>,
+    list_price: Option<Raw>,
+    rent: Option<Raw>,
+    living_area: Option<Raw>,
+    rooms: Option<Raw>,
+    floor: Option<Raw>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    #[serde(default)]
+    amenities: Vec<NextAmenity>,
+    bidding_open: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Raw {
+    raw: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NextAmenity {
+    key: Option<String>,
+    label: Option<String>,
+}
+
+/// The `__NEXT_DATA__` JSON of a page, if it has any
+fn next_data(html: &str) -> Option<Value> {
+    let document = Html::parse_document(html);
+    let script = document.select(&NEXT_DATA).next()?;
+    serde_json::from_str(&script.text().collect::<String>()).ok()
+}
+
+/// Every `Listing` object anywhere in `value`
+fn listings(value: &Value, found: &mut Vec<NextListing>) {
+    match value {
+        Value::Object(object) => {
+            if object.get("__typename").and_then(Value::as_str) == Some("Listing") && object.contains_key("booliId") {
+                match serde_json::from_value(value.clone()) {
+                    Ok(listing) => found.push(listing),
+                    Err(e) => debug!("Skipping a listing in __NEXT_DATA__: {}", e),
+                }
+            }
+            object.values().for_each(|value| listings(value, found));
+        }
+        Value::Array(values) => values.iter().for_each(|value| listings(value, found)),
+        _ => {}
+    }
+}
+
+/// The listings in a search page's `__NEXT_DATA__` and the ones that failed
+/// validation; an error when the page has none, so it is loaded in Chrome
+pub fn parse_search_page(html: &str, search: &AreaSearch) -> Result<(Vec<Property>, Vec<InvalidListing>)> {
+    let data = next_data(html).context("The page has no __NEXT_DATA__")?;
+    let mut found = Vec::new();
+    listings(&data, &mut found);
+    anyhow::ensure!(!found.is_empty(), "No listings in the page's __NEXT_DATA__");
+
+    let mut valid = Vec::new();
+    let mut rejected = Vec::new();
+    for listing in found {
+        match property(listing, search) {
+            Ok(property) => valid.push(property),
+            Err(invalid) => {
+                debug!("Skipped listing: {}", invalid);
+                rejected.push(invalid);
+            }
+        }
+    }
+    Ok((valid, rejected))
+}
+
+fn raw(value: Option<Raw>) -> Option<f64> {
+    value.and_then(|v| v.raw).filter(|v| *v > 0.0)
+}
+
+fn property(listing: NextListing, search: &AreaSearch) -> Result<Property, InvalidListing> {
+    let id = match &listing.booli_id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    };
+    let price = raw(listing.list_price).map(|v| v.round() as i64);
+    let rooms = raw(listing.rooms).map(|v| v as f32);
+    let sqm = raw(listing.living_area).map(|v| v.round() as i32);
+    let area = listing.descriptive_area_name.unwrap_or_else(|| search.name.clone());
+    let url = match listing.url.as_deref() {
+        Some(url) if url.starts_with('/') => format!("https://www.booli.se{}", url),
+        Some(url) => url.to_string(),
+        None => format!("https://www.booli.se/annons/{}", id),
+    };
+    let labels: Vec<String> = listing.amenities.into_iter().filter_map(|a| a.key.or(a.label)).collect();
+
+    Property::builder(id.clone(), Source::Booli)
+        .location(Location {
+            city: listing.municipality_name.unwrap_or_else(|| search.city.clone()),
+            area: Some(area.clone()),
+            latitude: listing.latitude,
+            longitude: listing.longitude,
+        })
+        .address(listing.street_address.unwrap_or_default())
+        .price(price.map(Money::sek))
+        .bidding_in_progress(listing.bidding_open == Some(true))
+        .listing_status(if listing.upcoming_sale == Some(true) && price.is_none() {
+            ListingStatus::ComingSoon
+        } else {
+            ListingStatus::ForSale
+        })
+        .monthly_fee(raw(listing.rent).map(|v| Money::sek(v.round() as i64)))
+        .rooms(rooms)
+        .sqm(sqm)
+        .floor(listing.floor.and_then(|v| v.raw).map(|v| v as f32))
+        .description(format!("Lägenhet i {}. {}.", area, size_label(rooms, sqm)))
+        .features(Features::normalize(&Source::Booli, labels))
+        .url(url)
+        .area_tags(vec![search.name.clone()])
+        .raw_data(json!({ "scraped_from": "next_data", "booli_id": id }))
+        .build()
+}
+
+/// Search and detail pages over plain HTTP, for the browser source
+///
+/// Searches may run concurrently; the pages are parsed on blocking threads,
+/// [`with_parse_threads`](Self::with_parse_threads) at a time.
+pub struct FastPath {
+    client: Client,
+    limiter: RateLimiter,
+    politeness: Politeness,
+    parse: Semaphore,
+    budget: Arc<Budget>,
+    checkpoint: Option<Arc<Checkpoint>>,
+    shutdown: Shutdown,
+    debug: DebugArtifacts,
+    plugin: Option<Arc<ParserPlugin>>,
+}
+
+impl FastPath {
+    pub fn new(min_interval: Duration, politeness: Politeness) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(BROWSER_USER_AGENT)
+            .default_headers(browser_headers())
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            client,
+            limiter: RateLimiter::new(min_interval),
+            politeness,
+            parse: Semaphore::new(1),
+            budget: Arc::default(),
+            checkpoint: None,
+            shutdown: Shutdown::default(),
+            debug: DebugArtifacts::default(),
+            plugin: None,
+        })
+    }
+
+    /// Spend pages, listings and detail visits from `budget`
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Record search pages and detail pages in `checkpoint`, and skip the
+    /// ones it already holds
+    pub fn with_checkpoint(mut self, checkpoint: Option<Arc<Checkpoint>>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Stop between pages once `shutdown` is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Keep snapshots of the search pages in `debug`
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Parse search pages with `plugin` instead of reading `__NEXT_DATA__`
+    pub fn with_plugin(mut self, plugin: Option<Arc<ParserPlugin>>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Parse at most `threads` search pages at the same time
+    pub fn with_parse_threads(mut self, threads: usize) -> Self {
+        self.parse = Semaphore::new(threads.max(1));
+        self
+    }
+
+    /// GET `url` once the site's next slot comes, within active hours
+    async fn fetch(&self, url: &str) -> Result<String> {
+        tokio::time::sleep(self.limiter.delay(url)).await;
+        let wait = self.politeness.until_active(Utc::now().with_timezone(&Stockholm).time());
+        if !wait.is_zero() {
+            info!("Outside active hours; resuming in {} min", wait.as_secs().div_ceil(60));
+            tokio::time::sleep(wait).await;
+        }
+        debug!("Fetching URL over HTTP: {}", url);
+        self.client
+            .get(url)
+            .headers(self.politeness.identity.as_ref().map(Identity::headers).unwrap_or_default())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))
+    }
+
+    /// The listings of one search's result page and the ones that failed
+    /// validation; an error means the page should be loaded in Chrome
+    pub async fn search(&self, search: &AreaSearch) -> Result<(Vec<Property>, Vec<InvalidListing>)> {
+        if let Some(properties) = self.checkpoint.as_ref().and_then(|c| c.page(&search.name)) {
+            info!("{}: reusing {} listings from the checkpoint", search.name, properties.len());
+            return Ok((properties, Vec::new()));
+        }
+        if self.shutdown.requested() {
+            return Err(Interrupted.into());
+        }
+        if !self.budget.take_page() {
+            info!("{}: skipped, the scrape budget is spent", search.name);
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let html = self.fetch(&search.url()).await?;
+        self.debug.snapshot(&html);
+        let parsed = {
+            let _permit = self.parse.acquire().await?;
+            let (search, plugin) = (search.clone(), self.plugin.clone());
+            tokio::task::spawn_blocking(move || match plugin {
+                Some(plugin) => plugin.parse_page(&html, &search.name, &search.city),
+                None => parse_search_page(&html, &search),
+            })
+            .await?
+        };
+        tokio::time::sleep(self.politeness.page_delay()).await;
+        let (mut properties, rejected) = parsed?;
+        properties.truncate(self.budget.take_listings(properties.len()));
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.record_page(&search.name, &properties);
+        }
+        Ok((properties, rejected))
+    }
+
+    /// Fill in each property from its detail page; returns the properties
+    /// done and the ones whose page should be loaded in Chrome
+    pub async fn details(&self, properties: Vec<Property>) -> Result<(Vec<Property>, Vec<Property>)> {
+        let mut done = Vec::new();
+        let mut failed = Vec::new();
+        let total = properties.len();
+        let mut properties = properties.into_iter().enumerate();
+
+        for (idx, mut property) in properties.by_ref() {
+            if self.shutdown.requested() {
+                return Err(Interrupted.into());
+            }
+            if let Some(mut enriched) = self.checkpoint.as_ref().and_then(|c| c.enriched(&property.id)) {
+                debug!("{}: details already fetched", property.id);
+                enriched.area_tags = property.area_tags;
+                done.push(enriched);
+                continue;
+            }
+            if !self.budget.take_detail() {
+                done.push(property);
+                break;
+            }
+            info!("Fetching details {}/{} over HTTP: {}", idx + 1, total, property.address);
+            let fetched = self.fetch(&property.url).await;
+            if fetched.is_ok() {
+                tokio::time::sleep(self.politeness.detail_delay()).await;
+            }
+            match fetched {
+                // A page without it is a challenge or error page, not the listing
+                Ok(html) if html.contains("__NEXT_DATA__") => {
+                    parse_detail_page(&html).apply(&mut property);
+                    if let Some(checkpoint) = &self.checkpoint {
+                        checkpoint.record_enriched(&property);
+                    }
+                    done.push(property);
+                }
+                Ok(_) => {
+                    debug!("{}: not a listing page over HTTP", property.id);
+                    failed.push(property);
+                }
+                Err(e) => {
+                    debug!("{}: {:#}", property.id, e);
+                    failed.push(property);
+                }
+            }
+        }
+        done.extend(properties.map(|(_, property)| property));
+        Ok((done, failed))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::scrapers::parse::{leading_number, normalize, number_before};
+
+/// Parse a monthly fee like "3 449 kr/mån" from free text
+///
+/// Only the digit groups directly in front of "kr/mån" are used, so
+/// "vån 3 3 449 kr/mån" yields 3449 rather than 33449.
+pub fn parse_monthly_fee(text: &str) -> Option<i64> {
+    number_before(text, "kr/mån").map(|fee| fee.round() as i64)
+}
+
+/// Parse a monthly operating cost like "Driftkostnad 2 500 kr/mån"; yearly
+/// amounts ("kr/år") are divided by twelve
+pub fn parse_operating_cost(text: &str) -> Option<i64> {
+    let text = normalize(text);
+    let start = text.find("Driftkostnad")? + "Driftkostnad".len();
+    // The amount directly follows the label; anything further away belongs to something else
+    let rest = text[start..].trim_start_matches([':', ' ']);
+    let (amount, unit) = leading_number(rest)?;
+    let unit = unit.trim_start();
+    if !unit.starts_with("kr") {
+        return None;
+    }
+
+    let amount = amount.round() as i64;
+    Some(if unit.starts_with("kr/år") { amount / 12 } else { amount })
+}
@@ -0,0 +1,113 @@
+use crate::models::{InvalidListing, Property, PropertyBuilder};
+use crate::scrapers::detail::parse_detail_page;
+use crate::scrapers::{merge_listings, ScraperTrait};
+use crate::storage::parse_legacy;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Listings read from a directory of fixture files instead of a live site
+///
+/// Every `*.json` file holds a property or an array of them, in any shape
+/// `scout import` reads, and files are read in name order. A listing found
+/// in several files is kept once, with the area tags of every copy. With
+/// details on, `<listing ID>.html` is parsed as that listing's detail page.
+/// Nothing is fetched, so whole runs can be tested without network or Chrome.
+pub struct FixtureScraper {
+    dir: PathBuf,
+    details: bool,
+    rejected: Mutex<Vec<InvalidListing>>,
+}
+
+impl FixtureScraper {
+    pub fn new(dir: impl Into<PathBuf>, details: bool) -> Self {
+        Self {
+            dir: dir.into(),
+            details,
+            rejected: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The fixture files in `dir` with extension `ext`, by name
+    async fn files(&self, ext: &str) -> Result<Vec<PathBuf>> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .with_context(|| format!("Failed to read fixtures in {}", self.dir.display()))?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_file() && path.extension().is_some_and(|e| e == ext) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    async fn read_listings(path: &Path) -> Result<Vec<Property>> {
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let value = serde_json::from_str(&json).with_context(|| format!("{} is not valid JSON", path.display()))?;
+        parse_legacy(value, Utc::now()).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Apply `<id>.html` to each listing that has one
+    async fn apply_details(&self, properties: &mut [Property]) -> Result<()> {
+        for path in self.files("html").await? {
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let Some(property) = properties.iter_mut().find(|p| p.id == id) else {
+                warn!("Detail fixture {} matches no listing", path.display());
+                continue;
+            };
+            let html = tokio::fs::read_to_string(&path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            parse_detail_page(&html).apply(property);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ScraperTrait for FixtureScraper {
+    async fn scrape(&self) -> Result<Vec<Property>> {
+        let mut properties = Vec::new();
+        for path in self.files("json").await? {
+            merge_listings(&mut properties, Self::read_listings(&path).await?);
+        }
+        if self.details {
+            self.apply_details(&mut properties).await?;
+        }
+
+        let mut rejected = Vec::new();
+        let properties: Vec<Property> = properties
+            .into_iter()
+            .filter_map(|property| match PropertyBuilder::from(property).build() {
+                Ok(property) => Some(property),
+                Err(invalid) => {
+                    warn!("{}", invalid);
+                    rejected.push(invalid);
+                    None
+                }
+            })
+            .collect();
+        *self.rejected.lock().unwrap_or_else(|e| e.into_inner()) = rejected;
+
+        info!("Read {} listings from {}", properties.len(), self.dir.display());
+        Ok(properties)
+    }
+
+    fn source_name(&self) -> &str {
+        "Fixtures"
+    }
+
+    fn rejected(&self) -> Vec<InvalidListing> {
+        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
@@ -0,0 +1,33 @@
+use crate::scrapers::parse::{leading_number, normalize};
+
+/// Parse the floor from "vån 3", "Våning 2 av 5" or "3 tr"
+///
+/// Returns the floor as a number since Booli lists half floors ("vån 1,5").
+pub fn parse_floor(text: &str) -> Option<f32> {
+    let lower = normalize(text).to_lowercase();
+
+    for label in ["våning", "vån"] {
+        for (pos, _) in lower.match_indices(label) {
+            let after = lower[pos + label.len()..].trim_start_matches(['.', ':', ' ']);
+            if let Some(floor) = floor_number(after) {
+                return Some(floor);
+            }
+        }
+    }
+
+    // "3 tr" (trappor) counts stairs above the ground floor
+    for (pos, _) in lower.match_indices(" tr") {
+        let after = &lower[pos + 3..];
+        let is_stairs = after.starts_with("app") || !after.starts_with(char::is_alphabetic);
+        let before = lower[..pos].split_whitespace().next_back();
+        if let Some(floor) = before.filter(|_| is_stairs).and_then(floor_number) {
+            return Some(floor);
+        }
+    }
+
+    None
+}
+
+fn floor_number(text: &str) -> Option<f32> {
+    leading_number(text).map(|(floor, _)| floor as f32)
+}
@@ -0,0 +1,170 @@
+use crate::i18n::Language;
+use crate::models::Property;
+use crate::notify::{Notification, NotificationEvent};
+use crate::stats::median;
+use crate::storage::{RunStatus, ScrapeRun, SourceSummary};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Whether a listing has a field filled in
+type Filled = fn(&Property) -> bool;
+
+/// Listing fields whose fill rate is tracked per source
+const TRACKED_FIELDS: [(&str, Filled); 7] = [
+    ("address", |p| !p.address.is_empty()),
+    ("price", |p| p.price.is_some()),
+    ("sqm", |p| p.sqm.is_some()),
+    ("rooms", |p| p.rooms.is_some()),
+    ("monthly_fee", |p| p.monthly_fee.is_some()),
+    ("floor", |p| p.floor.is_some()),
+    ("area", |p| p.location.area.is_some()),
+];
+
+/// Canary checks comparing each source's output with its recent runs
+///
+/// A markup change rarely makes a scraper fail outright; it just stops finding
+/// prices or returns half the listings. These checks catch that.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    pub enabled: bool,
+    /// Completed runs of a source its baseline is taken from
+    pub baseline_runs: usize,
+    /// Don't judge a source until it has this many earlier runs
+    pub min_baseline_runs: usize,
+    /// Warn when a source finds this much fewer listings than usual (0.5 = half)
+    pub max_count_drop: f64,
+    /// Warn when a field is filled in for this much smaller a share of listings
+    /// than usual (0.3 = 30 percentage points)
+    pub max_fill_drop: f64,
+    /// Also send warnings to the [notify] channels
+    pub notify: bool,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            baseline_runs: 10,
+            min_baseline_runs: 3,
+            max_count_drop: 0.5,
+            max_fill_drop: 0.3,
+            notify: true,
+        }
+    }
+}
+
+/// Share of `properties` with each tracked field filled in, 0.0 - 1.0
+pub fn fill_rates(properties: &[Property]) -> BTreeMap<String, f64> {
+    if properties.is_empty() {
+        return BTreeMap::new();
+    }
+    TRACKED_FIELDS
+        .iter()
+        .map(|(field, filled)| {
+            let count = properties.iter().filter(|p| filled(p)).count();
+            (field.to_string(), count as f64 / properties.len() as f64)
+        })
+        .collect()
+}
+
+/// What a source usually returns: medians over its recent successful runs
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    pub runs: usize,
+    pub listings: f64,
+    pub fill_rates: BTreeMap<String, f64>,
+}
+
+impl Baseline {
+    /// Baseline of `source` from the last `window` completed runs in which it succeeded
+    pub fn from_runs(runs: &[ScrapeRun], source: &str, window: usize) -> Self {
+        let summaries: Vec<&SourceSummary> = runs
+            .iter()
+            .rev()
+            .filter(|run| run.status == RunStatus::Completed)
+            .filter_map(|run| run.sources.iter().find(|s| s.source == source))
+            .filter(|summary| summary.errors.is_empty() && summary.listings > 0)
+            .take(window)
+            .collect();
+
+        let mut listings: Vec<f64> = summaries.iter().map(|s| s.listings as f64).collect();
+        let mut fills: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for summary in &summaries {
+            for (field, rate) in &summary.fill_rates {
+                fills.entry(field.clone()).or_default().push(*rate);
+            }
+        }
+
+        Self {
+            runs: summaries.len(),
+            listings: median(&mut listings).unwrap_or_default(),
+            fill_rates: fills
+                .into_iter()
+                .filter_map(|(field, mut rates)| median(&mut rates).map(|m| (field, m)))
+                .collect(),
+        }
+    }
+
+    /// Ways `summary` falls short of this baseline
+    pub fn compare(&self, summary: &SourceSummary, config: &HealthConfig) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if (summary.listings as f64) < self.listings * (1.0 - config.max_count_drop) {
+            warnings.push(format!("{} listings (usually {:.0})", summary.listings, self.listings));
+        }
+        if summary.listings == 0 {
+            return warnings;
+        }
+        for (field, usual) in &self.fill_rates {
+            let rate = summary.fill_rates.get(field).copied().unwrap_or_default();
+            if rate < usual - config.max_fill_drop {
+                warnings.push(format!(
+                    "{} found for {:.0}% of listings (usually {:.0}%)",
+                    field,
+                    rate * 100.0,
+                    usual * 100.0
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+/// Compare every successful source in `run` with its baseline from `earlier` runs,
+/// recording and logging what looks off
+///
+/// `run` itself is still running, so a stored copy of it in `earlier` is ignored.
+pub fn check_run(run: &mut ScrapeRun, earlier: &[ScrapeRun], config: &HealthConfig) {
+    for summary in run.sources.iter_mut().filter(|s| s.errors.is_empty()) {
+        let baseline = Baseline::from_runs(earlier, &summary.source, config.baseline_runs);
+        if baseline.runs < config.min_baseline_runs {
+            continue;
+        }
+        summary.health = baseline.compare(summary, config);
+        for warning in &summary.health {
+            warn!("🐤 {}: {} - has the markup changed?", summary.source, warning);
+        }
+    }
+}
+
+/// One notification per source with health warnings
+pub fn notifications(run: &ScrapeRun, language: Language) -> Vec<Notification> {
+    run.sources
+        .iter()
+        .filter(|summary| !summary.health.is_empty())
+        .map(|summary| Notification {
+            event: NotificationEvent::Health,
+            title: language.format("notify.health_title", &[("source", &summary.source)]),
+            body: format!(
+                "{}\n{}",
+                language.format("notify.health_body", &[("run", &run.id)]),
+                summary.health.join("\n")
+            ),
+            url: None,
+            property_id: None,
+            state: None,
+            searches: Vec::new(),
+        })
+        .collect()
+}
@@ -1,8 +1,56 @@
+pub mod account;
+pub mod artifacts;
+pub mod bidding;
 pub mod booli;
+#[cfg(feature = "graphql")]
+pub mod booli_graphql;
+pub mod budget;
+#[cfg(feature = "browser")]
 pub mod browser;
+pub mod capture;
+pub mod cards;
+pub mod chrome;
+pub mod detail;
+pub mod fast_path;
+pub mod fees;
+pub mod fixture;
+pub mod floor;
+pub mod health;
+pub mod parse;
+pub mod plugin;
+pub mod politeness;
+pub mod rate_limit;
+pub mod renovation;
+pub mod runner;
+pub mod sitemap;
+pub mod search_url;
+pub mod selectors;
+pub mod site;
+pub mod snapshot;
+#[cfg(feature = "browser")]
+pub mod stealth;
+pub mod sold;
 pub mod traits;
 pub mod types;
 
+pub use account::{BooliAccount, BooliSavedSearch};
+pub use artifacts::{DebugArtifacts, DebugConfig};
 pub use booli::BooliScraper;
-pub use browser::BooliBrowserScraper;
+#[cfg(feature = "graphql")]
+pub use booli_graphql::{BooliGraphqlClient, BooliGraphqlSource};
+#[cfg(feature = "browser")]
+pub use browser::{BooliBrowserScraper, BooliBrowserSource};
+pub use budget::{Budget, BudgetConfig};
+pub use capture::{ArchiveFormat, CaptureConfig};
+#[cfg(feature = "browser")]
+pub use chrome::Chrome;
+pub use chrome::ChromeConfig;
+pub use fixture::FixtureScraper;
+pub use health::HealthConfig;
+pub use plugin::ParserPlugin;
+pub use politeness::{ActiveHours, Identity, Politeness, PolitenessConfig};
+pub use rate_limit::RateLimiter;
+pub use runner::{configured_sources, merge_listings, run_sources, SourceOutcome};
+pub use site::{Site, SiteRules, SiteScraper};
 pub use traits::ScraperTrait;
+pub use types::{AreaSearch, FetchStrategy, PropertyType, ScrapeConfig, SearchParams, SearchSite, SourceKind};
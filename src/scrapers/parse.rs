@@ -0,0 +1,188 @@
+/// Spaces Swedish text uses between digit groups, besides a plain space
+const SPACE_LIKE: [char; 3] = ['\u{a0}', '\u{2009}', '\u{202f}'];
+
+/// Replace `&nbsp;`, non-breaking and thin spaces with plain spaces
+pub fn normalize(text: &str) -> String {
+    text.replace("&nbsp;", " ").replace(SPACE_LIKE, " ")
+}
+
+/// Parse a Swedish-formatted number such as "5 195 000", "70,5" or "1.5"
+///
+/// Spaces group thousands and a comma or dot separates decimals. Anything
+/// besides the number, including a unit, makes this return `None`.
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = normalize(text);
+    let text = text.trim();
+    match leading_number(text)? {
+        (number, "") => Some(number),
+        _ => None,
+    }
+}
+
+/// Parse the number at the start of `text`, returning it and the text after it
+///
+/// "3 449 kr/mån" gives 3449 and " kr/mån". Digit groups after the first
+/// must have exactly three digits, so "vån 3 av 5" stops at the 3.
+pub fn leading_number(text: &str) -> Option<(f64, &str)> {
+    let digits_end = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    let first = digits_end(text);
+    if first == 0 {
+        return None;
+    }
+    let mut integer = text[..first].to_string();
+    let mut end = first;
+
+    // Thousand groups: a single space followed by exactly three digits
+    if first <= 3 {
+        while let Some(group) = text[end..].strip_prefix(' ').filter(|group| digits_end(group) == 3) {
+            integer.push_str(&group[..3]);
+            end += 4;
+        }
+    }
+
+    let mut number = integer;
+    if let Some(fraction) = text[end..].strip_prefix([',', '.']) {
+        let len = digits_end(fraction);
+        if len > 0 {
+            number.push('.');
+            number.push_str(&fraction[..len]);
+            end += 1 + len;
+        }
+    }
+
+    Some((number.parse().ok()?, &text[end..]))
+}
+
+/// The number written directly in front of `unit`, e.g. 70.5 for "70,5 m²"
+///
+/// Every occurrence of `unit` is tried in order, so an unrelated earlier match
+/// without a number in front ("Skrapan") doesn't hide a later one. Numbers glued
+/// to a preceding word, as in Booli's flattened card text ("Stockholm5 195 000 kr"),
+/// are still found.
+pub fn number_before(text: &str, unit: &str) -> Option<f64> {
+    let text = normalize(text);
+    text.match_indices(unit)
+        .find_map(|(pos, _)| number_ending_at(&text[..pos]).map(|(number, _)| number))
+}
+
+/// Parse the number that `before` ends with, returning it and where it starts
+fn number_ending_at(before: &str) -> Option<(f64, usize)> {
+    let trimmed = before.trim_end();
+    let is_number_char = |c: char| c.is_ascii_digit() || c == ',' || c == '.' || c == ' ';
+    let start = trimmed
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_number_char(*c))
+        .last()
+        .map(|(i, _)| i)?;
+
+    // The run may start with stray digits that belong to something else
+    // ("vån 3 3 449"), so take the longest well-formed number ending here
+    let candidate = &trimmed[start..];
+    candidate
+        .char_indices()
+        .filter(|(i, c)| c.is_ascii_digit() && (*i == 0 || candidate[..*i].ends_with(' ')))
+        .find_map(|(i, _)| match leading_number(&candidate[i..]) {
+            Some((number, rest)) if rest.trim_end_matches([',', '.']).is_empty() => Some((number, start + i)),
+            _ => None,
+        })
+}
+
+/// Price in whole kronor from text like "5 195 000 kr", or a bare "5 195 000"
+pub fn parse_price(text: &str) -> Option<i64> {
+    number_before(text, "kr")
+        .or_else(|| parse_number(text))
+        .map(|price| price.round() as i64)
+        .filter(|price| *price > 0)
+}
+
+/// Living area in square meters from "70,5 m²", "70 kvm" or "35,5 kvadratmeter"
+///
+/// For "70 + 10 m²" only the living area (boarea) in front of the plus is
+/// returned, not the secondary area (biarea).
+pub fn parse_sqm(text: &str) -> Option<f32> {
+    let text = normalize(text);
+    ["m²", "kvm", "kvadratmeter", "m2"].into_iter().find_map(|unit| {
+        text.match_indices(unit).find_map(|(pos, _)| {
+            let (sqm, start) = number_ending_at(&text[..pos])?;
+            let before = text[..start].trim_end();
+            match before.strip_suffix('+') {
+                Some(living) => number_ending_at(living).map(|(sqm, _)| sqm as f32),
+                None => Some(sqm as f32),
+            }
+        })
+    })
+}
+
+/// Number of rooms from "2,5 rum" or "2 rum lägenhet"
+pub fn parse_rooms(text: &str) -> Option<f32> {
+    number_before(text, "rum").map(|rooms| rooms as f32).filter(|rooms| *rooms > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number("5 195 000"), Some(5_195_000.0));
+        assert_eq!(parse_number("5\u{a0}195\u{a0}000"), Some(5_195_000.0));
+        assert_eq!(parse_number("5&nbsp;195&nbsp;000"), Some(5_195_000.0));
+        assert_eq!(parse_number("70,5"), Some(70.5));
+        assert_eq!(parse_number("1.5"), Some(1.5));
+        assert_eq!(parse_number(" 42 "), Some(42.0));
+        assert_eq!(parse_number("1 070,5"), Some(1070.5));
+        assert_eq!(parse_number("70 kvm"), None);
+        assert_eq!(parse_number("12 34"), None);
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn leading_numbers() {
+        assert_eq!(leading_number("3 449 kr/mån"), Some((3449.0, " kr/mån")));
+        assert_eq!(leading_number("3 av 5"), Some((3.0, " av 5")));
+        assert_eq!(leading_number("1234 567"), Some((1234.0, " 567")));
+        assert_eq!(leading_number("2,5 rum"), Some((2.5, " rum")));
+        assert_eq!(leading_number("3, 4"), Some((3.0, ", 4")));
+        assert_eq!(leading_number("kr"), None);
+    }
+
+    #[test]
+    fn prices() {
+        assert_eq!(parse_price("5 195 000 kr"), Some(5_195_000));
+        assert_eq!(parse_price("5\u{202f}195\u{202f}000\u{a0}kr"), Some(5_195_000));
+        assert_eq!(parse_price("Lägenhet · Södermalm · Stockholm5 195 000 kr70 m²"), Some(5_195_000));
+        assert_eq!(parse_price("Skrapan 12, 4 250 000 kr"), Some(4_250_000));
+        assert_eq!(parse_price("5 195 000"), Some(5_195_000));
+        assert_eq!(parse_price("Pris saknas"), None);
+        assert_eq!(parse_price("0 kr"), None);
+    }
+
+    #[test]
+    fn fees() {
+        assert_eq!(number_before("3 449 kr/mån", "kr/mån"), Some(3449.0));
+        assert_eq!(number_before("vån 3 3 449 kr/mån", "kr/mån"), Some(3449.0));
+        assert_eq!(number_before("Avgift 12 449 kr/mån", "kr/mån"), Some(12_449.0));
+        assert_eq!(number_before("kr/mån", "kr/mån"), None);
+    }
+
+    #[test]
+    fn areas() {
+        assert_eq!(parse_sqm("70,5 m²"), Some(70.5));
+        assert_eq!(parse_sqm("5 195 000 kr70 m²2 rum"), Some(70.0));
+        assert_eq!(parse_sqm("35,5 kvadratmeter"), Some(35.5));
+        assert_eq!(parse_sqm("Boarea 84 kvm"), Some(84.0));
+        assert_eq!(parse_sqm("70 + 10 m²"), Some(70.0));
+        assert_eq!(parse_sqm("1 070 m²"), Some(1070.0));
+        assert_eq!(parse_sqm("m²"), None);
+    }
+
+    #[test]
+    fn rooms() {
+        assert_eq!(parse_rooms("2,5 rum"), Some(2.5));
+        assert_eq!(parse_rooms("2 rum lägenhet på Götgatan 120"), Some(2.0));
+        assert_eq!(parse_rooms("70 m²2 rumvån 3"), Some(2.0));
+        assert_eq!(parse_rooms("Lägenhet med rum för allt"), None);
+    }
+}
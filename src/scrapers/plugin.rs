@@ -0,0 +1,120 @@
+use crate::models::{InvalidListing, Property, PropertyBuilder};
+use crate::storage::parse_legacy;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::json;
+use std::path::Path;
+
+/// Instructions a plugin may run per page before it's stopped, so a plugin
+/// stuck in a loop can't hang a scrape
+#[cfg(feature = "wasm")]
+const FUEL_PER_PAGE: u64 = 20_000_000_000;
+
+/// A WebAssembly module replacing a built-in search page parser, so a parser
+/// can follow markup changes without a new scout build
+///
+/// The module exports its `memory` and two functions:
+/// - `alloc(len: i32) -> i32`: room for `len` bytes, where the page is written
+/// - `parse(ptr: i32, len: i32) -> i64`: parses the UTF-8 HTML at `ptr` and
+///   returns where its output is, the pointer in the high 32 bits and the
+///   length in the low 32
+///
+/// The output is UTF-8 JSON: an array of listings in any shape `scout import`
+/// reads. Every page gets a fresh instance and the module imports nothing, so
+/// a plugin can't reach the network or files. Needs a build with
+/// `--features wasm`.
+pub struct ParserPlugin {
+    name: String,
+    #[cfg(feature = "wasm")]
+    engine: wasmi::Engine,
+    #[cfg(feature = "wasm")]
+    module: wasmi::Module,
+}
+
+impl ParserPlugin {
+    /// Load and compile the module at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+
+        #[cfg(feature = "wasm")]
+        {
+            let wasm = std::fs::read(path).with_context(|| format!("Failed to read parser plugin {}", path.display()))?;
+            let mut config = wasmi::Config::default();
+            config.consume_fuel(true);
+            let engine = wasmi::Engine::new(&config);
+            let module = wasmi::Module::new(&engine, &wasm)
+                .with_context(|| format!("{} is not a valid WebAssembly module", path.display()))?;
+            tracing::info!("Loaded parser plugin {}", name);
+            Ok(Self { name, engine, module })
+        }
+        #[cfg(not(feature = "wasm"))]
+        {
+            let _ = name;
+            anyhow::bail!("The parser plugin {} needs a build with `--features wasm`", path.display())
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The listings on a search page for `area` in `city`, validated like
+    /// the built-in parsers' listings, and the ones that failed
+    ///
+    /// Listings are tagged with `area`, and given `city`, when the plugin
+    /// left those out.
+    pub fn parse_page(&self, html: &str, area: &str, city: &str) -> Result<(Vec<Property>, Vec<InvalidListing>)> {
+        let mut valid = Vec::new();
+        let mut rejected = Vec::new();
+        for mut property in self.parse(html)? {
+            if property.area_tags.is_empty() {
+                property.area_tags = vec![area.to_string()];
+            }
+            if property.location.city.is_empty() {
+                property.location.city = city.to_string();
+            }
+            if property.raw_data.is_null() || property.raw_data == json!({}) {
+                property.raw_data = json!({ "scraped_from": "plugin", "plugin": self.name });
+            }
+            match PropertyBuilder::from(property).build() {
+                Ok(property) => valid.push(property),
+                Err(invalid) => rejected.push(invalid),
+            }
+        }
+        Ok((valid, rejected))
+    }
+
+    /// Run the plugin over one page; listings still need validating
+    pub fn parse(&self, html: &str) -> Result<Vec<Property>> {
+        let output = self.call(html).with_context(|| format!("Parser plugin {} failed", self.name))?;
+        let value = serde_json::from_slice(&output)
+            .with_context(|| format!("Parser plugin {} returned invalid JSON", self.name))?;
+        parse_legacy(value, Utc::now()).with_context(|| format!("Parser plugin {} returned no listings", self.name))
+    }
+
+    #[cfg(feature = "wasm")]
+    fn call(&self, html: &str) -> Result<Vec<u8>> {
+        let mut store = wasmi::Store::new(&self.engine, ());
+        store.set_fuel(FUEL_PER_PAGE)?;
+        let instance = wasmi::Linker::<()>::new(&self.engine)
+            .instantiate(&mut store, &self.module)?
+            .start(&mut store)?;
+        let memory = instance.get_memory(&store, "memory").context("The module exports no memory")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc")?;
+        let parse = instance.get_typed_func::<(i32, i32), i64>(&store, "parse")?;
+
+        let len = i32::try_from(html.len()).context("The page is too large")?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, html.as_bytes())?;
+        let packed = parse.call(&mut store, (ptr, len))? as u64;
+
+        let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+        memory.read(&store, (packed >> 32) as usize, &mut output)?;
+        Ok(output)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn call(&self, _html: &str) -> Result<Vec<u8>> {
+        anyhow::bail!("Parser plugins need a build with `--features wasm`")
+    }
+}
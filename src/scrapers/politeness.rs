@@ -0,0 +1,171 @@
+use chrono::{NaiveTime, Timelike};
+use reqwest::header::{HeaderMap, HeaderValue, FROM, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Least wait after a search result page for a source announcing itself
+const IDENTIFIED_PAGE_DELAY_MS: u64 = 10_000;
+/// Least wait after a detail page for a source announcing itself
+const IDENTIFIED_DETAIL_DELAY_MS: u64 = 5_000;
+
+/// Pauses of each source, so long crawls read like someone browsing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolitenessConfig {
+    pub booli_graphql: Politeness,
+    /// Also used by `scout sold` and `scout booli`
+    pub booli_browser: Politeness,
+    pub booli_http: Politeness,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            booli_graphql: Politeness {
+                page_delay_ms: 2000,
+                detail_delay_ms: 2000,
+                ..Politeness::default()
+            },
+            booli_browser: Politeness::default(),
+            booli_http: Politeness {
+                page_delay_ms: 2000,
+                ..Politeness::default()
+            },
+        }
+    }
+}
+
+/// How long one source waits between fetches, and when it may fetch at all
+///
+/// These come on top of `min_interval_ms`, which only spaces out requests to
+/// the same domain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Politeness {
+    /// Wait after loading a search result page
+    pub page_delay_ms: u64,
+    /// Wait after loading a listing's detail page
+    pub detail_delay_ms: u64,
+    /// Each wait is randomly up to this fraction shorter or longer (0.3 = ±30%)
+    pub jitter: f64,
+    /// Only fetch between these hours, Stockholm time; outside them the
+    /// scrape waits for `start`
+    pub active_hours: Option<ActiveHours>,
+    /// Scrape openly as housing-scout rather than passing for Chrome; see
+    /// [`Identity`]
+    pub identity: Option<Identity>,
+}
+
+impl Default for Politeness {
+    fn default() -> Self {
+        Self {
+            page_delay_ms: 8000,
+            detail_delay_ms: 2000,
+            jitter: 0.3,
+            active_hours: None,
+            identity: None,
+        }
+    }
+}
+
+impl Politeness {
+    /// Wait after a search result page, jittered
+    pub fn page_delay(&self) -> Duration {
+        let floor = if self.identity.is_some() { IDENTIFIED_PAGE_DELAY_MS } else { 0 };
+        jittered(self.page_delay_ms.max(floor), self.jitter)
+    }
+
+    /// Wait after a detail page, jittered
+    pub fn detail_delay(&self) -> Duration {
+        let floor = if self.identity.is_some() { IDENTIFIED_DETAIL_DELAY_MS } else { 0 };
+        jittered(self.detail_delay_ms.max(floor), self.jitter)
+    }
+
+    /// Pages the source may load at the same time, at most `concurrency`;
+    /// one when it announces itself
+    pub fn concurrency(&self, concurrency: usize) -> usize {
+        if self.identity.is_some() {
+            1
+        } else {
+            concurrency
+        }
+    }
+
+    /// Time left until fetching is allowed at `now`, zero within active hours
+    pub fn until_active(&self, now: NaiveTime) -> Duration {
+        self.active_hours.map_or(Duration::ZERO, |hours| hours.until_active(now))
+    }
+}
+
+/// Who runs the scraper, for a source that announces itself
+///
+/// Such a source sends a descriptive User-Agent naming housing-scout and how
+/// to reach its operator, plus the address in a `From` header, and keeps to
+/// conservative limits: at least 10 s after a search page, 5 s after a
+/// detail page, and one page at a time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    /// Email address the site can reach the operator at
+    pub contact: String,
+    /// Page describing this crawler and its purpose
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Identity {
+    /// e.g. `housing-scout/0.1.0 (+https://example.com/bot; me@example.com)`
+    pub fn user_agent(&self) -> String {
+        let about = match &self.url {
+            Some(url) => format!("+{}; {}", url, self.contact),
+            None => self.contact.clone(),
+        };
+        format!("housing-scout/{} ({})", env!("CARGO_PKG_VERSION"), about)
+    }
+
+    /// `User-Agent` and `From` for an HTTP request
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(agent) = HeaderValue::from_str(&self.user_agent()) {
+            headers.insert(USER_AGENT, agent);
+        }
+        if let Ok(contact) = HeaderValue::from_str(&self.contact) {
+            headers.insert(FROM, contact);
+        }
+        headers
+    }
+}
+
+/// A span of hours of the day, e.g. `{ start = 8, end = 22 }`
+///
+/// `end` is exclusive; a `start` after `end` spans midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl ActiveHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => (self.start..self.end).contains(&hour),
+            std::cmp::Ordering::Greater => hour >= self.start || hour < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+
+    fn until_active(&self, now: NaiveTime) -> Duration {
+        if self.contains(now.hour()) {
+            return Duration::ZERO;
+        }
+        let elapsed = u64::from(now.num_seconds_from_midnight());
+        let start = u64::from(self.start.min(23)) * 3600;
+        let wait = if elapsed < start { start - elapsed } else { 24 * 3600 - elapsed + start };
+        Duration::from_secs(wait)
+    }
+}
+
+fn jittered(ms: u64, jitter: f64) -> Duration {
+    let jitter = jitter.clamp(0.0, 1.0);
+    let factor = 1.0 + rand::random_range(-jitter..=jitter);
+    Duration::from_millis((ms as f64 * factor).round() as u64)
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Spaces out requests to the same host, across threads
+pub struct RateLimiter {
+    min_interval: Duration,
+    /// Earliest time the next request to each host may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Block until a request to `url`'s host is allowed
+    pub fn wait(&self, url: &str) {
+        let delay = self.delay(url);
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Take the next slot for a request to `url`'s host, returning how long
+    /// until it starts; for async callers to sleep on
+    pub fn delay(&self, url: &str) -> Duration {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let slot = next_slot.get(&host).copied().unwrap_or(now).max(now);
+        next_slot.insert(host, slot + self.min_interval);
+        slot - now
+    }
+}
@@ -0,0 +1,80 @@
+use crate::models::{PipeReplacement, PipeStatus, Renovation};
+use crate::scrapers::parse::normalize;
+
+/// Words of a sentence about the pipes
+const PIPES: [&str; 5] = ["stambyte", "stambytt", "stammar", "relining", "relinade"];
+/// Words saying something is not done
+const NEGATIONS: [&str; 4] = ["ej ", "inte ", "ännu ", "behov av"];
+/// Words saying something is coming
+const PLANNED: [&str; 7] = ["planera", "kommande", "beslutat", "kommer att", "inför", "påbörja", "pågå"];
+/// Words saying something was done
+const DONE: [&str; 10] = [
+    "genomför", "gjort", "gjord", "utför", "klart", "stambytt", "nya stammar", "relinade", "bytt", "renoverad",
+];
+/// Words saying a room was renovated
+const RENOVATED: [&str; 5] = ["renover", "nytt", "nyligen", "bytt", "byggt"];
+/// Ways of saying the whole apartment needs renovating
+const NEEDS_RENOVATION: [&str; 5] = [
+    "renoveringsobjekt",
+    "renoveringsbehov",
+    "renoveringsbehövande",
+    "i behov av renovering",
+    "originalskick",
+];
+
+/// Read pipe replacement, kitchen and bathroom renovations and whether the
+/// apartment is a "renoveringsobjekt" from a listing description
+///
+/// Works sentence by sentence on the wording brokers use: "Stambyte
+/// genomfört 2015", "Köket renoverat 2019", "Stambyte planeras till 2026".
+/// Returns None when the description mentions none of them.
+pub fn parse_renovation(description: &str) -> Option<Renovation> {
+    let text = normalize(description).to_lowercase();
+    let mut renovation = Renovation::default();
+
+    for sentence in text.split(['.', '!', '?', '\n']) {
+        let sentence = sentence.trim();
+        if sentence.is_empty() {
+            continue;
+        }
+        let year = year_in(sentence);
+
+        if renovation.pipes.is_none() && PIPES.iter().any(|w| sentence.contains(w)) {
+            let status = if NEGATIONS.iter().any(|w| sentence.contains(w)) {
+                Some(PipeStatus::NotDone)
+            } else if PLANNED.iter().any(|w| sentence.contains(w)) {
+                Some(PipeStatus::Planned)
+            } else if DONE.iter().any(|w| sentence.contains(w)) || year.is_some() {
+                Some(PipeStatus::Done)
+            } else {
+                None
+            };
+            renovation.pipes = status.map(|status| PipeReplacement { status, year });
+        }
+
+        let renovated = RENOVATED.iter().any(|w| sentence.contains(w));
+        if renovated && !NEGATIONS.iter().any(|w| sentence.contains(w)) {
+            let words: Vec<&str> = sentence.split(|c: char| !c.is_alphanumeric()).collect();
+            let mentions = |rooms: &[&str]| words.iter().any(|w| rooms.iter().any(|room| w.starts_with(room)));
+            if mentions(&["kök"]) {
+                renovation.kitchen_year = renovation.kitchen_year.or(year);
+            }
+            if mentions(&["badrum", "våtrum", "duschrum"]) {
+                renovation.bathroom_year = renovation.bathroom_year.or(year);
+            }
+        }
+
+        renovation.needs_renovation |= NEEDS_RENOVATION.iter().any(|w| sentence.contains(w));
+    }
+
+    (renovation != Renovation::default()).then_some(renovation)
+}
+
+/// The last plausible year in a sentence: "renoverat 2012 och 2019" gives 2019
+fn year_in(sentence: &str) -> Option<i32> {
+    sentence
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|word| word.len() == 4)
+        .filter_map(|word| word.parse().ok())
+        .rfind(|year| (1900..=2100).contains(year))
+}
@@ -0,0 +1,178 @@
+use crate::models::{InvalidListing, Property};
+#[cfg(feature = "browser")]
+use crate::scrapers::{BooliBrowserSource, Chrome};
+#[cfg(feature = "graphql")]
+use crate::scrapers::BooliGraphqlSource;
+use crate::scrapers::{
+    Budget, DebugArtifacts, BooliScraper, FixtureScraper, ParserPlugin, ScrapeConfig, ScraperTrait, Site, SiteScraper,
+    SourceKind,
+};
+use crate::shutdown::{Interrupted, Shutdown};
+use crate::storage::Checkpoint;
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info_span, Instrument};
+
+/// What one source produced in a run
+pub struct SourceOutcome {
+    pub source: String,
+    pub duration: Duration,
+    pub result: Result<Vec<Property>>,
+    /// Listings the source dropped because they failed validation
+    pub rejected: Vec<InvalidListing>,
+}
+
+/// Build the configured sources, then one per configured site; `details` also
+/// visits every listing's page
+///
+/// Sources that can resume part-way record their progress in `checkpoint`,
+/// and the browser source stops between pages once `shutdown` is requested
+/// and writes its debug files to `debug`. Each source gets a child of
+/// `shutdown`, so [`run_sources`] can stop one that runs out of time. Pages, listings and detail visits
+/// are spent from the run's `budget`. Browser sources share one Chrome.
+#[cfg_attr(not(any(feature = "browser", feature = "graphql")), allow(unused_variables))]
+pub fn configured_sources(
+    config: &ScrapeConfig,
+    details: bool,
+    checkpoint: &Arc<Checkpoint>,
+    shutdown: &Shutdown,
+    debug: &DebugArtifacts,
+    budget: &Arc<Budget>,
+) -> Result<Vec<Box<dyn ScraperTrait>>> {
+    let plugin = config.parser_plugin.as_deref().map(ParserPlugin::load).transpose()?.map(Arc::new);
+    #[cfg(feature = "browser")]
+    let chrome = Chrome::new(config.chrome.clone());
+    let mut sources = config
+        .sources
+        .iter()
+        .map(|kind| -> Result<Box<dyn ScraperTrait>> {
+            Ok(match kind {
+                #[cfg(feature = "graphql")]
+                SourceKind::BooliGraphql => {
+                    let source = BooliGraphqlSource::new(config.clone(), details)
+                        .with_checkpoint(checkpoint.clone())
+                        .with_shutdown(shutdown.child())
+                        .with_debug(debug.clone())
+                        .with_budget(budget.clone())
+                        .with_plugin(plugin.clone());
+                    #[cfg(feature = "browser")]
+                    let source = source.with_chrome(chrome.clone());
+                    Box::new(source)
+                }
+                #[cfg(not(feature = "graphql"))]
+                SourceKind::BooliGraphql => anyhow::bail!("The booli_graphql source needs a build with `--features graphql`"),
+                #[cfg(feature = "browser")]
+                SourceKind::BooliBrowser => Box::new(
+                    BooliBrowserSource::new(config.clone(), details)
+                        .with_chrome(chrome.clone())
+                        .with_checkpoint(checkpoint.clone())
+                        .with_shutdown(shutdown.child())
+                        .with_debug(debug.clone())
+                        .with_budget(budget.clone())
+                        .with_plugin(plugin.clone()),
+                ),
+                #[cfg(not(feature = "browser"))]
+                SourceKind::BooliBrowser => anyhow::bail!("The booli_browser source needs a build with `--features browser`"),
+                SourceKind::BooliHttp => Box::new(
+                    BooliScraper::new()?
+                        .with_politeness(config.politeness.booli_http.clone())
+                        .with_debug(debug.clone())
+                        .with_plugin(plugin.clone()),
+                ),
+                SourceKind::Fixtures => Box::new(FixtureScraper::new(&config.fixtures, details)),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for name in &config.sites {
+        let site = Site::load(&config.rules_dir, name)?;
+        sources.push(Box::new(
+            SiteScraper::new(site, Duration::from_millis(config.min_interval_ms))?
+                .with_shutdown(shutdown.child())
+                .with_debug(debug.clone())
+                .with_budget(budget.clone()),
+        ));
+    }
+    Ok(sources)
+}
+
+/// How long sources get to wind down after a shutdown request
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(15);
+
+/// Scrape every source, at most `limit` at a time, each cut off after `timeout`
+///
+/// After a shutdown request, sources not started yet are skipped and running
+/// ones get [`SHUTDOWN_GRACE`] to stop. Outcomes are returned in the order
+/// sources finish.
+pub async fn run_sources(
+    sources: Vec<Box<dyn ScraperTrait>>,
+    limit: usize,
+    timeout: Duration,
+    shutdown: &Shutdown,
+) -> Vec<SourceOutcome> {
+    stream::iter(sources)
+        .map(|source| {
+            let span = info_span!("source", source = source.source_name());
+            async move {
+                let started = Instant::now();
+                let result = if shutdown.requested() {
+                    Err(Interrupted.into())
+                } else {
+                    tokio::select! {
+                        result = tokio::time::timeout(timeout, source.scrape()) => match result {
+                            Ok(result) => result,
+                            Err(_) => {
+                                // Work already handed to blocking threads stops at its next page
+                                if let Some(shutdown) = source.shutdown() {
+                                    shutdown.request();
+                                }
+                                Err(anyhow::anyhow!("Timed out after {} s", timeout.as_secs()))
+                            }
+                        },
+                        _ = async {
+                            shutdown.wait().await;
+                            tokio::time::sleep(SHUTDOWN_GRACE).await;
+                        } => Err(Interrupted.into()),
+                    }
+                };
+                SourceOutcome {
+                    source: source.source_name().to_string(),
+                    duration: started.elapsed(),
+                    result,
+                    rejected: source.rejected(),
+                }
+            }
+            .instrument(span)
+        })
+        .buffer_unordered(limit.max(1))
+        .collect()
+        .await
+}
+
+/// Add `found` to `properties`, keeping the first copy of each listing ID and
+/// the area tags of every copy
+pub fn merge_listings(properties: &mut Vec<Property>, found: Vec<Property>) {
+    let mut index: HashMap<String, usize> = properties
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id.clone(), i))
+        .collect();
+
+    for property in found {
+        match index.get(&property.id) {
+            Some(&i) => {
+                for tag in property.area_tags {
+                    if !properties[i].area_tags.contains(&tag) {
+                        properties[i].area_tags.push(tag);
+                    }
+                }
+            }
+            None => {
+                index.insert(property.id.clone(), properties.len());
+                properties.push(property);
+            }
+        }
+    }
+}
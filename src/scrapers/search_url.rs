@@ -0,0 +1,175 @@
+use crate::filter::Filter;
+use crate::scrapers::types::{PropertyType, SearchParams, SearchSite};
+use anyhow::{bail, Context, Result};
+use reqwest::Url;
+use std::str::FromStr;
+use tracing::warn;
+
+const BOOLI_SEARCH_URL: &str = "https://www.booli.se/sok/till-salu";
+
+impl PropertyType {
+    /// Booli's `objectType` value
+    pub fn booli_name(&self) -> &'static str {
+        match self {
+            PropertyType::Apartment => "Lägenhet",
+            PropertyType::House => "Villa",
+            PropertyType::Townhouse => "Radhus",
+            PropertyType::Cottage => "Fritidshus",
+            PropertyType::Plot => "Tomt-Mark",
+            PropertyType::Farm => "Gård",
+        }
+    }
+
+    fn from_booli(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "lägenhet" => PropertyType::Apartment,
+            "villa" => PropertyType::House,
+            "radhus" | "parhus" | "kedjehus" => PropertyType::Townhouse,
+            "fritidshus" => PropertyType::Cottage,
+            "tomt-mark" | "tomt" => PropertyType::Plot,
+            "gård" => PropertyType::Farm,
+            _ => return None,
+        })
+    }
+
+    fn from_hemnet(name: &str) -> Option<Self> {
+        Some(match name {
+            "bostadsratt" => PropertyType::Apartment,
+            "villa" => PropertyType::House,
+            "radhus" => PropertyType::Townhouse,
+            "fritidshus" => PropertyType::Cottage,
+            "tomt" => PropertyType::Plot,
+            "gard" => PropertyType::Farm,
+            _ => return None,
+        })
+    }
+}
+
+impl SearchParams {
+    /// Read the areas, price, rooms, size and property types of a search made
+    /// on booli.se or hemnet.se, as copied from the browser's address bar
+    ///
+    /// Parameters that have no equivalent here (sorting, keywords, ...) are ignored.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url.trim()).with_context(|| format!("Not a URL: {}", url))?;
+        let host = parsed.host_str().unwrap_or_default();
+        let site = if host.ends_with("booli.se") {
+            SearchSite::Booli
+        } else if host.ends_with("hemnet.se") {
+            SearchSite::Hemnet
+        } else {
+            bail!("Not a Booli or Hemnet search URL: {}", url);
+        };
+
+        let mut params = Self {
+            site,
+            ..Self::default()
+        };
+        for (key, value) in parsed.query_pairs() {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let key = key.trim_end_matches("[]");
+            match (site, key) {
+                (SearchSite::Booli, "areaIds") | (SearchSite::Hemnet, "location_ids") => {
+                    for id in value.split(',') {
+                        params.area_ids.push(number(key, id)?);
+                    }
+                }
+                (SearchSite::Booli, "minListPrice") | (SearchSite::Hemnet, "price_min") => {
+                    params.min_price = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "maxListPrice") | (SearchSite::Hemnet, "price_max") => {
+                    params.max_price = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "minRooms") | (SearchSite::Hemnet, "rooms_min") => {
+                    params.min_rooms = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "maxRooms") | (SearchSite::Hemnet, "rooms_max") => {
+                    params.max_rooms = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "minLivingArea") | (SearchSite::Hemnet, "living_area_min") => {
+                    params.min_sqm = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "maxLivingArea") | (SearchSite::Hemnet, "living_area_max") => {
+                    params.max_sqm = Some(number(key, value)?)
+                }
+                (SearchSite::Booli, "objectType") | (SearchSite::Hemnet, "item_types") => {
+                    for name in value.split(',') {
+                        let kind = match site {
+                            SearchSite::Booli => PropertyType::from_booli(name),
+                            SearchSite::Hemnet => PropertyType::from_hemnet(name),
+                        };
+                        match kind {
+                            Some(kind) if !params.property_types.contains(&kind) => params.property_types.push(kind),
+                            Some(_) => {}
+                            None => warn!("Ignoring unknown property type {:?} in search URL", name),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(params)
+    }
+
+    /// The equivalent Booli search, if these are Booli areas
+    pub fn booli_url(&self) -> Option<String> {
+        if self.site != SearchSite::Booli || self.area_ids.is_empty() {
+            return None;
+        }
+        // Booli writes the ID list with plain commas, which query_pairs_mut would escape
+        let ids: Vec<String> = self.area_ids.iter().map(u64::to_string).collect();
+        let mut url = Url::parse(&format!("{}?areaIds={}", BOOLI_SEARCH_URL, ids.join(","))).ok()?;
+        {
+            let mut query = url.query_pairs_mut();
+            let bounds = [
+                ("minListPrice", self.min_price.map(|v| v.to_string())),
+                ("maxListPrice", self.max_price.map(|v| v.to_string())),
+                ("minRooms", self.min_rooms.map(|v| v.to_string())),
+                ("maxRooms", self.max_rooms.map(|v| v.to_string())),
+                ("minLivingArea", self.min_sqm.map(|v| v.to_string())),
+                ("maxLivingArea", self.max_sqm.map(|v| v.to_string())),
+            ];
+            for (key, value) in bounds {
+                if let Some(value) = value {
+                    query.append_pair(key, &value);
+                }
+            }
+            if !self.property_types.is_empty() {
+                let names: Vec<&str> = self.property_types.iter().map(PropertyType::booli_name).collect();
+                query.append_pair("objectType", &names.join(","));
+            }
+        }
+        Some(url.to_string())
+    }
+
+    /// Filter expression for the price, rooms and size bounds, if there are any
+    pub fn filter(&self) -> Option<Filter> {
+        let bounds = [
+            ("price", ">=", self.min_price.map(|v| v as f64)),
+            ("price", "<=", self.max_price.map(|v| v as f64)),
+            ("rooms", ">=", self.min_rooms.map(f64::from)),
+            ("rooms", "<=", self.max_rooms.map(f64::from)),
+            ("sqm", ">=", self.min_sqm.map(f64::from)),
+            ("sqm", "<=", self.max_sqm.map(f64::from)),
+        ];
+        let parts: Vec<String> = bounds
+            .into_iter()
+            .filter_map(|(field, op, value)| value.map(|value| format!("{} {} {}", field, op, value)))
+            .collect();
+        if parts.is_empty() {
+            return None;
+        }
+        Filter::parse(&parts.join(" && ")).ok()
+    }
+}
+
+fn number<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .with_context(|| format!("Search URL parameter {} is not a number: {:?}", key, value))
+}
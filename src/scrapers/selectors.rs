@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use scraper::Selector;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// How to read a site's listing cards, on search and sold listing pages
+///
+/// The built-in rules are Booli's markup at the time of writing. A
+/// `<site>.toml` or `<site>.json` in the rules directory (see
+/// [`ScrapeConfig::rules_dir`](crate::scrapers::ScrapeConfig::rules_dir))
+/// replaces any of them, so a renamed class is fixed by editing that file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardRules {
+    /// One listing card; its `href` is the listing's URL
+    pub card: String,
+    /// Attribute of the card reading e.g. "2 rum lägenhet på Götgatan 120
+    /// Södermalm, Stockholms kommun", and of each fact item
+    pub label_attribute: String,
+    /// Fact items within a card: living area, floor and fee
+    pub facts: String,
+    /// The asking or final price within a card
+    pub price: String,
+    /// Amenity tags within a card ("Balkong", "Hiss")
+    pub tags: String,
+    /// Text of the fact item holding the living area
+    pub sqm_marker: String,
+    /// Text of the fact item holding the floor, in any case
+    pub floor_marker: String,
+    /// Text of the fact item holding the monthly fee
+    pub fee_marker: String,
+}
+
+impl Default for CardRules {
+    fn default() -> Self {
+        Self {
+            card: "a.object-card-link".to_string(),
+            label_attribute: "aria-label".to_string(),
+            facts: "li".to_string(),
+            price: "span.object-card__price--logo".to_string(),
+            tags: "div.tag".to_string(),
+            sqm_marker: "kvadratmeter".to_string(),
+            floor_marker: "vån".to_string(),
+            fee_marker: "kr/mån".to_string(),
+        }
+    }
+}
+
+impl CardRules {
+    /// The rules for `site` from `dir`, or the built-in ones when it has no
+    /// file for it
+    pub fn load(dir: &Path, site: &str) -> Result<Self> {
+        let Some(rules) = read_rules::<Self>(dir, site)? else {
+            return Ok(Self::default());
+        };
+        if rules != Self::default() {
+            info!("Using {} card rules from {}", site, dir.display());
+        }
+        Ok(rules)
+    }
+
+    /// Parse the selectors, failing on the first invalid one
+    pub fn compile(&self) -> Result<CardSelectors> {
+        let parse = |field: &str, selector: &str| {
+            Selector::parse(selector).map_err(|e| anyhow::anyhow!("Invalid {} selector {:?}: {}", field, selector, e))
+        };
+        Ok(CardSelectors {
+            card: parse("card", &self.card)?,
+            facts: parse("facts", &self.facts)?,
+            price: parse("price", &self.price)?,
+            tags: parse("tags", &self.tags)?,
+            rules: self.clone(),
+        })
+    }
+
+    /// Every selector a parser relies on, each relative to the document
+    pub fn selectors(&self) -> Vec<String> {
+        vec![
+            self.card.clone(),
+            format!("{}[{}]", self.card, self.label_attribute),
+            format!("{} {}[{}]", self.card, self.facts, self.label_attribute),
+            format!("{} {}", self.card, self.price),
+            format!("{} {}", self.card, self.tags),
+        ]
+    }
+}
+
+/// `<name>.toml`, or else `<name>.json`, from `dir`; None when neither exists
+pub(crate) fn read_rules<T: DeserializeOwned>(dir: &Path, name: &str) -> Result<Option<T>> {
+    let toml_path = dir.join(format!("{}.toml", name));
+    if toml_path.is_file() {
+        let text =
+            std::fs::read_to_string(&toml_path).with_context(|| format!("Failed to read {}", toml_path.display()))?;
+        return toml::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", toml_path.display()));
+    }
+    let json_path = dir.join(format!("{}.json", name));
+    if json_path.is_file() {
+        let text =
+            std::fs::read_to_string(&json_path).with_context(|| format!("Failed to read {}", json_path.display()))?;
+        return serde_json::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", json_path.display()));
+    }
+    Ok(None)
+}
+
+/// [`CardRules`] with the selectors parsed
+#[derive(Debug, Clone)]
+pub struct CardSelectors {
+    pub card: Selector,
+    pub facts: Selector,
+    pub price: Selector,
+    pub tags: Selector,
+    pub rules: CardRules,
+}
+
+impl Default for CardSelectors {
+    fn default() -> Self {
+        CardRules::default().compile().expect("the built-in card rules are valid")
+    }
+}
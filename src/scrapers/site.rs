@@ -0,0 +1,455 @@
+use crate::models::{
+    Features, Image, ImageKind, InvalidListing, ListingStatus, Location, Money, Property, PropertyBuilder, Source,
+};
+use crate::scrapers::artifacts::{source_slug, DebugArtifacts};
+use crate::scrapers::budget::Budget;
+use crate::scrapers::fees::parse_monthly_fee;
+use crate::scrapers::floor::parse_floor;
+use crate::scrapers::parse::{normalize, parse_number, parse_price, parse_rooms, parse_sqm};
+use crate::scrapers::politeness::{Identity, Politeness};
+use crate::scrapers::runner::merge_listings;
+use crate::scrapers::selectors::read_rules;
+use crate::scrapers::traits::ScraperTrait;
+use crate::shutdown::{Interrupted, Shutdown};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Europe::Stockholm;
+use regex::Regex;
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// A listing site scraped entirely by a rules file, for simple broker sites
+/// that don't need code of their own
+///
+/// The rules name the first result page, how to reach the next one, the
+/// selector of one listing card and how to read each listing field from a
+/// card. Only the result pages are read; listings carry what their cards show.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteRules {
+    /// Name in logs and run summaries; the rules file's name when left out
+    #[serde(default)]
+    pub name: String,
+    /// The first result page; with page number pagination `{page}` is
+    /// replaced by the page number
+    pub start_url: String,
+    #[serde(default)]
+    pub pagination: Pagination,
+    /// One listing card on a result page
+    pub card: String,
+    /// City of the listings when no `city` field reads one
+    #[serde(default = "default_city")]
+    pub city: String,
+    /// How to read each listing field from a card
+    pub fields: BTreeMap<Field, FieldRule>,
+    #[serde(default)]
+    pub politeness: Politeness,
+}
+
+fn default_city() -> String {
+    "Stockholm".to_string()
+}
+
+/// How a site's result pages follow one another
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Pagination {
+    /// All listings are on the first page
+    #[default]
+    None,
+    /// `{page}` in `start_url` counts up from `first` until a page adds no
+    /// listings
+    PageNumber {
+        #[serde(default = "first_page")]
+        first: u32,
+        max_pages: u32,
+    },
+    /// Follow the `href` of the element matching `selector` until there is none
+    NextLink { selector: String, max_pages: u32 },
+}
+
+fn first_page() -> u32 {
+    1
+}
+
+/// Listing fields a rules file can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    /// The site's ID of the listing; the last part of `url` when left out
+    Id,
+    /// The listing page, relative to the result page or absolute
+    Url,
+    Address,
+    Area,
+    City,
+    /// Asking price; a card without one is a listing coming soon
+    Price,
+    MonthlyFee,
+    Rooms,
+    Sqm,
+    Floor,
+    Description,
+    /// Every element matching the selector is one amenity ("Balkong", "Hiss")
+    Features,
+    /// The card's photo, relative to the result page or absolute
+    Image,
+    Latitude,
+    Longitude,
+}
+
+/// Where a field's text is in a card and how to clean it up
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldRule {
+    /// Element within the card; the card itself when left out
+    pub selector: Option<String>,
+    /// Read this attribute instead of the element's text
+    pub attribute: Option<String>,
+    /// Keep the first capture group of this regex, or the whole match
+    pub regex: Option<String>,
+    /// Replacements applied after `regex`, in order: `[["from", "to"], ...]`
+    pub replace: Vec<(String, String)>,
+    /// Text used when the card has none
+    pub default: Option<String>,
+}
+
+/// A [`FieldRule`] with its selector and regex compiled
+#[derive(Debug, Clone)]
+struct Extractor {
+    selector: Option<Selector>,
+    regex: Option<Regex>,
+    rule: FieldRule,
+}
+
+impl Extractor {
+    fn new(field: Field, rule: &FieldRule) -> Result<Self> {
+        Ok(Self {
+            selector: rule.selector.as_deref().map(|s| selector(&format!("{:?}", field), s)).transpose()?,
+            regex: rule
+                .regex
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .with_context(|| format!("Invalid regex for {:?}", field))?,
+            rule: rule.clone(),
+        })
+    }
+
+    /// The cleaned up text of every match in `card`
+    fn all(&self, card: ElementRef<'_>) -> Vec<String> {
+        let elements: Vec<ElementRef<'_>> = match &self.selector {
+            Some(selector) => card.select(selector).collect(),
+            None => vec![card],
+        };
+        let texts: Vec<String> = elements
+            .into_iter()
+            .filter_map(|element| match &self.rule.attribute {
+                Some(attribute) => element.value().attr(attribute).map(str::to_string),
+                None => Some(element.text().collect::<Vec<_>>().join(" ")),
+            })
+            .filter_map(|text| self.clean(&text))
+            .collect();
+        if texts.is_empty() {
+            self.rule.default.iter().cloned().collect()
+        } else {
+            texts
+        }
+    }
+
+    /// The cleaned up text of the first match in `card`
+    fn first(&self, card: ElementRef<'_>) -> Option<String> {
+        self.all(card).into_iter().next()
+    }
+
+    fn clean(&self, text: &str) -> Option<String> {
+        let text = normalize(text);
+        let mut text = match &self.regex {
+            Some(regex) => {
+                let captures = regex.captures(&text)?;
+                captures.get(1).or_else(|| captures.get(0))?.as_str().to_string()
+            }
+            None => text,
+        };
+        for (from, to) in &self.rule.replace {
+            text = text.replace(from.as_str(), to);
+        }
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+fn selector(what: &str, selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|e| anyhow::anyhow!("Invalid {} selector {:?}: {}", what, selector, e))
+}
+
+/// [`SiteRules`] ready to read pages with
+#[derive(Debug, Clone)]
+pub struct Site {
+    rules: SiteRules,
+    card: Selector,
+    next: Option<Selector>,
+    fields: BTreeMap<Field, Extractor>,
+}
+
+impl Site {
+    /// Load `<name>.toml` or `<name>.json` from `dir`
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let mut rules: SiteRules = read_rules(dir, name)?
+            .with_context(|| format!("No rules for site {:?}: no {}.toml or .json in {}", name, name, dir.display()))?;
+        if rules.name.is_empty() {
+            rules.name = name.to_string();
+        }
+        Self::new(rules)
+    }
+
+    pub fn new(rules: SiteRules) -> Result<Self> {
+        anyhow::ensure!(
+            rules.fields.contains_key(&Field::Url),
+            "The rules of site {:?} have no url field",
+            rules.name
+        );
+        let fields = rules
+            .fields
+            .iter()
+            .map(|(field, rule)| Ok((*field, Extractor::new(*field, rule)?)))
+            .collect::<Result<_>>()
+            .with_context(|| format!("Invalid rules for site {:?}", rules.name))?;
+        Ok(Self {
+            card: selector("card", &rules.card)?,
+            next: match &rules.pagination {
+                Pagination::NextLink { selector: next, .. } => Some(selector("next link", next)?),
+                _ => None,
+            },
+            fields,
+            rules,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.rules.name
+    }
+
+    /// The result page after `page`, which was read from `url` and found
+    /// `html`; `page` counts from 0
+    fn next_page(&self, page: u32, url: &Url, html: &Html) -> Option<Url> {
+        match &self.rules.pagination {
+            Pagination::None => None,
+            Pagination::PageNumber { first, max_pages } => (page + 1 < *max_pages)
+                .then(|| Url::parse(&self.rules.start_url.replace("{page}", &(first + page + 1).to_string())).ok())
+                .flatten(),
+            Pagination::NextLink { max_pages, .. } => {
+                let next = html.select(self.next.as_ref()?).next()?.value().attr("href")?;
+                (page + 1 < *max_pages).then(|| url.join(next).ok()).flatten()
+            }
+        }
+    }
+
+    /// The first result page
+    fn start_url(&self) -> Result<Url> {
+        let first = match &self.rules.pagination {
+            Pagination::PageNumber { first, .. } => *first,
+            _ => 1,
+        };
+        let url = self.rules.start_url.replace("{page}", &first.to_string());
+        Url::parse(&url).with_context(|| format!("Invalid start_url {:?} for site {:?}", url, self.rules.name))
+    }
+
+    /// The listings on the result page at `url`, and the ones that failed
+    /// validation
+    pub fn parse_page(&self, html: &Html, url: &Url) -> (Vec<Property>, Vec<InvalidListing>) {
+        let mut valid = Vec::new();
+        let mut rejected = Vec::new();
+        for card in html.select(&self.card) {
+            match self.parse_card(card, url).build() {
+                Ok(property) => valid.push(property),
+                Err(invalid) => {
+                    debug!("{}", invalid);
+                    rejected.push(invalid);
+                }
+            }
+        }
+        (valid, rejected)
+    }
+
+    fn parse_card(&self, card: ElementRef<'_>, page: &Url) -> PropertyBuilder {
+        let text = |field: Field| self.fields.get(&field).and_then(|extractor| extractor.first(card));
+        let absolute = |field: Field| text(field).and_then(|href| page.join(&href).ok()).map(String::from);
+
+        let url = absolute(Field::Url).unwrap_or_default();
+        let id = text(Field::Id)
+            .or_else(|| url.trim_end_matches('/').rsplit('/').next().map(str::to_string))
+            .unwrap_or_default();
+        let price = text(Field::Price).and_then(|t| parse_price(&t));
+        let rooms = text(Field::Rooms).and_then(|t| parse_rooms(&t).or_else(|| parse_number(&t).map(|n| n as f32)));
+        let sqm = text(Field::Sqm).and_then(|t| parse_sqm(&t).or_else(|| parse_number(&t).map(|n| n as f32)));
+        let features: Vec<String> =
+            self.fields.get(&Field::Features).map(|extractor| extractor.all(card)).unwrap_or_default();
+
+        Property::builder(format!("{}_{}", source_slug(&self.rules.name), id), Source::Broker)
+            .location(Location {
+                city: text(Field::City).unwrap_or_else(|| self.rules.city.clone()),
+                area: text(Field::Area),
+                latitude: text(Field::Latitude).and_then(|t| parse_number(&t)),
+                longitude: text(Field::Longitude).and_then(|t| parse_number(&t)),
+            })
+            .address(text(Field::Address).unwrap_or_default())
+            .price(price.map(Money::sek))
+            .listing_status(if price.is_some() { ListingStatus::ForSale } else { ListingStatus::ComingSoon })
+            .monthly_fee(
+                text(Field::MonthlyFee).and_then(|t| parse_monthly_fee(&t).or_else(|| parse_price(&t))).map(Money::sek),
+            )
+            .rooms(rooms)
+            .sqm(sqm.map(|sqm| sqm.round() as i32))
+            .floor(text(Field::Floor).and_then(|t| parse_floor(&t).or_else(|| parse_number(&t).map(|n| n as f32))))
+            .description(text(Field::Description).unwrap_or_default())
+            .features(Features::normalize(&Source::Broker, &features))
+            .images(absolute(Field::Image).map(|url| vec![Image::new(url, 0, ImageKind::Photo)]).unwrap_or_default())
+            .url(url)
+            .area_tags(vec![self.rules.name.clone()])
+            .raw_data(json!({ "scraped_from": "site", "site": self.rules.name, "site_id": id }))
+    }
+}
+
+/// [`Site`] as a source
+pub struct SiteScraper {
+    site: Site,
+    client: Client,
+    min_interval: Duration,
+    shutdown: Shutdown,
+    budget: Arc<Budget>,
+    debug: DebugArtifacts,
+    rejected: Mutex<Vec<InvalidListing>>,
+}
+
+impl SiteScraper {
+    pub fn new(site: Site, min_interval: Duration) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            site,
+            client,
+            min_interval,
+            shutdown: Shutdown::default(),
+            budget: Arc::default(),
+            debug: DebugArtifacts::default(),
+            rejected: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Stop between pages once `shutdown` is requested
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Spend pages and listings from `budget`
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Keep snapshots of the first result page in `debug`
+    pub fn with_debug(mut self, debug: DebugArtifacts) -> Self {
+        self.debug = debug.labelled(self.site.name());
+        self
+    }
+
+    fn politeness(&self) -> &Politeness {
+        &self.site.rules.politeness
+    }
+
+    async fn fetch(&self, url: &Url) -> Result<String> {
+        let wait = self.politeness().until_active(Utc::now().with_timezone(&Stockholm).time());
+        if !wait.is_zero() {
+            info!("Outside active hours; resuming in {} min", wait.as_secs().div_ceil(60));
+            tokio::time::sleep(wait).await;
+        }
+        debug!("Fetching URL: {}", url);
+        self.client
+            .get(url.clone())
+            .headers(self.politeness().identity.as_ref().map(Identity::headers).unwrap_or_default())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {}", url))
+    }
+}
+
+#[async_trait]
+impl ScraperTrait for SiteScraper {
+    async fn scrape(&self) -> Result<Vec<Property>> {
+        info!("Starting {} scrape", self.site.name());
+        let mut properties = Vec::new();
+        let mut rejected = Vec::new();
+        let mut next = Some(self.site.start_url()?);
+        let mut page = 0;
+
+        while let Some(url) = next.take() {
+            if self.shutdown.requested() {
+                return Err(Interrupted.into());
+            }
+            if !self.budget.take_page() {
+                break;
+            }
+            if page > 0 {
+                tokio::time::sleep(self.politeness().page_delay().max(self.min_interval)).await;
+            }
+            let body = match self.fetch(&url).await {
+                Ok(body) => body,
+                Err(e) if page > 0 => {
+                    warn!("Stopping at page {}: {:#}", page + 1, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            if page == 0 {
+                self.debug.snapshot(&body);
+            }
+
+            let html = Html::parse_document(&body);
+            let (mut found, invalid) = self.site.parse_page(&html, &url);
+            rejected.extend(invalid);
+            found.truncate(self.budget.take_listings(found.len()));
+            let before = properties.len();
+            merge_listings(&mut properties, found);
+            if properties.len() == before {
+                break;
+            }
+            next = self.site.next_page(page, &url, &html);
+            page += 1;
+        }
+
+        if !rejected.is_empty() {
+            warn!("{}: dropped {} invalid listings", self.site.name(), rejected.len());
+        }
+        *self.rejected.lock().unwrap_or_else(|e| e.into_inner()) = rejected;
+        info!("{}: {} listings", self.site.name(), properties.len());
+        Ok(properties)
+    }
+
+    fn source_name(&self) -> &str {
+        self.site.name()
+    }
+
+    fn rejected(&self) -> Vec<InvalidListing> {
+        self.rejected.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn shutdown(&self) -> Option<&Shutdown> {
+        Some(&self.shutdown)
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::time::Duration;
+use tracing::{info, warn};
+
+pub const BOOLI_SITEMAP_URL: &str = "https://www.booli.se/sitemap.xml";
+
+/// Sitemap indexes nest; don't follow them deeper than this
+const MAX_DEPTH: usize = 3;
+
+static SITEMAP_BLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<sitemap>(.*?)</sitemap>").unwrap());
+static URL_BLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<url>(.*?)</url>").unwrap());
+static LOC: Lazy<Regex> = Lazy::new(|| Regex::new(r"<loc>\s*(.*?)\s*</loc>").unwrap());
+static LASTMOD: Lazy<Regex> = Lazy::new(|| Regex::new(r"<lastmod>\s*(\d{4}-\d{2}-\d{2})").unwrap());
+
+/// Which listings to discover
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoveryKind {
+    /// Listings for sale ("/annons/")
+    ForSale,
+    /// Sold listings with final prices ("/bostad/")
+    Sold,
+}
+
+impl DiscoveryKind {
+    /// Path segment of this kind's listing URLs
+    fn listing_path(&self) -> &'static str {
+        match self {
+            DiscoveryKind::ForSale => "/annons/",
+            DiscoveryKind::Sold => "/bostad/",
+        }
+    }
+
+    /// Words in the names of sitemap files with this kind's listings
+    fn sitemap_words(&self) -> &'static [&'static str] {
+        match self {
+            DiscoveryKind::ForSale => &["annons", "listing", "till-salu"],
+            DiscoveryKind::Sold => &["bostad", "sold", "slutpris"],
+        }
+    }
+
+    /// Name of the cache holding the last discovery
+    pub fn cache_name(&self) -> &'static str {
+        match self {
+            DiscoveryKind::ForSale => "sitemap_for_sale",
+            DiscoveryKind::Sold => "sitemap_sold",
+        }
+    }
+}
+
+/// One `<url>` or `<sitemap>` entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<NaiveDate>,
+}
+
+/// A sitemap file: an index of further sitemaps, a list of pages, or both
+#[derive(Debug, Clone, Default)]
+pub struct Sitemap {
+    pub sitemaps: Vec<SitemapEntry>,
+    pub urls: Vec<SitemapEntry>,
+}
+
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let entries = |block: &Regex| -> Vec<SitemapEntry> {
+        block
+            .captures_iter(xml)
+            .filter_map(|c| {
+                let body = c.get(1)?.as_str();
+                Some(SitemapEntry {
+                    loc: LOC.captures(body)?[1].replace("&amp;", "&"),
+                    lastmod: LASTMOD
+                        .captures(body)
+                        .and_then(|m| NaiveDate::parse_from_str(&m[1], "%Y-%m-%d").ok()),
+                })
+            })
+            .collect()
+    };
+    Sitemap {
+        sitemaps: entries(&SITEMAP_BLOCK),
+        urls: entries(&URL_BLOCK),
+    }
+}
+
+/// What to look for in the sitemaps
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    pub kind: DiscoveryKind,
+    /// Only sitemap files or listing URLs naming this municipality, e.g. "stockholm"
+    pub municipality: Option<String>,
+    /// Only listings modified on or after this day
+    pub since: Option<NaiveDate>,
+    /// Pause between sitemap downloads
+    pub min_interval: Duration,
+}
+
+impl Discovery {
+    fn municipality_slug(&self) -> Option<String> {
+        self.municipality.as_deref().map(slug)
+    }
+}
+
+/// Enumerate listing URLs from Booli's sitemaps, starting at `index_url`
+///
+/// Listing search pages don't have to parse for this to work, which makes it a
+/// fallback when their markup changes, and a way to backfill sold listings.
+pub async fn discover(index_url: &str, discovery: &Discovery) -> Result<Vec<SitemapEntry>> {
+    let client = Client::builder()
+        .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(60))
+        .build()?;
+    let municipality = discovery.municipality_slug();
+
+    let mut found: Vec<SitemapEntry> = Vec::new();
+    let mut queue = vec![(index_url.to_string(), 0)];
+    let mut matched_municipality = false;
+    while let Some((url, depth)) = queue.pop() {
+        info!("Reading sitemap {}", url);
+        let xml = match fetch(&client, &url).await {
+            Ok(xml) => xml,
+            // One broken file shouldn't lose what the others list
+            Err(e) if depth > 0 => {
+                warn!("Skipping sitemap {}: {:#}", url, e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let sitemap = parse_sitemap(&xml);
+
+        if depth < MAX_DEPTH {
+            let children = relevant_sitemaps(&sitemap.sitemaps, discovery.kind, municipality.as_deref());
+            matched_municipality |= children.iter().any(|(_, named)| *named);
+            for (child, _) in children.into_iter().rev() {
+                if discovery.since.is_some_and(|since| child.lastmod.is_some_and(|m| m < since)) {
+                    continue;
+                }
+                queue.push((child.loc.clone(), depth + 1));
+            }
+        }
+
+        found.extend(sitemap.urls.into_iter().filter(|entry| {
+            entry.loc.contains(discovery.kind.listing_path())
+                && discovery.since.is_none_or(|since| entry.lastmod.is_none_or(|m| m >= since))
+                && municipality.as_deref().is_none_or(|m| matched_municipality || slug(&entry.loc).contains(m))
+        }));
+        if !queue.is_empty() {
+            tokio::time::sleep(discovery.min_interval).await;
+        }
+    }
+
+    if let Some(municipality) = &discovery.municipality {
+        if !matched_municipality {
+            warn!(
+                "No sitemap files for {}; only listing URLs naming it were kept",
+                municipality
+            );
+        }
+    }
+    found.sort_by(|a, b| a.loc.cmp(&b.loc));
+    found.dedup_by(|a, b| a.loc == b.loc);
+    Ok(found)
+}
+
+/// Child sitemaps worth reading for `kind`, and whether each names the municipality
+///
+/// Files are picked by name; when none of the names say what they hold, all are read.
+fn relevant_sitemaps<'a>(
+    children: &'a [SitemapEntry],
+    kind: DiscoveryKind,
+    municipality: Option<&str>,
+) -> Vec<(&'a SitemapEntry, bool)> {
+    let named = |entry: &SitemapEntry, words: &[&str]| {
+        let name = entry.loc.to_lowercase();
+        words.iter().any(|word| name.contains(word))
+    };
+    let any_named = children
+        .iter()
+        .any(|c| named(c, DiscoveryKind::ForSale.sitemap_words()) || named(c, DiscoveryKind::Sold.sitemap_words()));
+    let of_kind: Vec<&SitemapEntry> = children
+        .iter()
+        .filter(|c| !any_named || named(c, kind.sitemap_words()))
+        .collect();
+
+    match municipality {
+        Some(municipality) if of_kind.iter().any(|c| slug(&c.loc).contains(municipality)) => of_kind
+            .into_iter()
+            .filter(|c| slug(&c.loc).contains(municipality))
+            .map(|c| (c, true))
+            .collect(),
+        _ => of_kind.into_iter().map(|c| (c, false)).collect(),
+    }
+}
+
+async fn fetch(client: &Client, url: &str) -> Result<String> {
+    let bytes = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", url))?
+        .bytes()
+        .await?;
+    // Large sitemaps are served as .xml.gz files
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        GzDecoder::new(&bytes[..])
+            .read_to_string(&mut xml)
+            .with_context(|| format!("Failed to decompress {}", url))?;
+        return Ok(xml);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// "Göteborg" → "goteborg", as municipalities appear in URLs
+fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'å' | 'ä' => 'a',
+            'ö' => 'o',
+            'é' => 'e',
+            ' ' => '-',
+            c => c,
+        })
+        .collect()
+}
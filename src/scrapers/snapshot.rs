@@ -0,0 +1,117 @@
+//! Search page snapshots compared when a source's output degrades
+//!
+//! Every run keeps each source's latest search page; a run in which a source
+//! passes its health checks keeps that page as the known-good one too. When a
+//! health check warns, the page is copied into the run's debug directory with
+//! a report of which selectors, and which element classes, the known-good page
+//! had and this one lacks.
+
+use crate::scrapers::artifacts::{source_slug, DebugConfig, GOOD_SNAPSHOT, LATEST_SNAPSHOT, SNAPSHOT_DIR};
+use crate::scrapers::selectors::CardRules;
+use crate::storage::ScrapeRun;
+use anyhow::{Context, Result};
+use scraper::{Html, Selector};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Element classes listed per direction in a report, most frequent first
+const MAX_CLASSES: usize = 25;
+
+/// Promote the snapshots of healthy sources and report on the others, checking
+/// the selectors of `rules`
+///
+/// Failures are only logged, like other debug files.
+pub fn review(run: &ScrapeRun, config: &DebugConfig, rules: &CardRules) {
+    if !config.snapshots {
+        return;
+    }
+    for summary in run.sources.iter().filter(|s| s.errors.is_empty() && s.listings > 0) {
+        let dir = config.dir.join(SNAPSHOT_DIR).join(source_slug(&summary.source));
+        let latest = dir.join(LATEST_SNAPSHOT);
+        if !latest.is_file() {
+            continue;
+        }
+        let result = if summary.health.is_empty() {
+            std::fs::copy(&latest, dir.join(GOOD_SNAPSHOT))
+                .map(|_| ())
+                .with_context(|| format!("Failed to keep {} as known-good", latest.display()))
+        } else {
+            compare(&summary.source, &dir, &config.dir.join(&run.id), rules)
+        };
+        if let Err(e) = result {
+            warn!("{:#}", e);
+        }
+    }
+}
+
+/// Copy a source's latest snapshot into `run_dir` and write its report there
+fn compare(source: &str, snapshots: &Path, run_dir: &Path, rules: &CardRules) -> Result<()> {
+    let slug = source_slug(source);
+    let current = std::fs::read_to_string(snapshots.join(LATEST_SNAPSHOT))
+        .with_context(|| format!("Failed to read the latest {} snapshot", source))?;
+    let good = std::fs::read_to_string(snapshots.join(GOOD_SNAPSHOT)).ok();
+
+    std::fs::create_dir_all(run_dir).with_context(|| format!("Failed to create {}", run_dir.display()))?;
+    std::fs::write(run_dir.join(format!("{}_snapshot.html", slug)), &current)
+        .with_context(|| format!("Failed to write the {} snapshot", source))?;
+    let report = match &good {
+        Some(good) => selector_report(good, &current, &rules.selectors()),
+        None => "No known-good snapshot to compare with yet; one is kept after the next healthy run.\n".to_string(),
+    };
+    let path = run_dir.join(format!("{}_selectors.txt", slug));
+    std::fs::write(&path, format!("{}\n\n{}", source, report))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    info!("🔍 {}: compared its search page with the last good one in {}", source, path.display());
+    Ok(())
+}
+
+/// Which of `selectors`, and which element classes, `good` has that `current`
+/// lacks, and the reverse
+pub fn selector_report(good: &str, current: &str, selectors: &[String]) -> String {
+    let good = Html::parse_document(good);
+    let current = Html::parse_document(current);
+    let mut report = String::new();
+
+    let _ = writeln!(report, "{:<40} {:>6} {:>6}", "selector", "good", "now");
+    for selector in selectors {
+        let Ok(parsed) = Selector::parse(selector) else {
+            continue;
+        };
+        let before = good.select(&parsed).count();
+        let after = current.select(&parsed).count();
+        let flag = match (before, after) {
+            (1.., 0) => "  MISSING",
+            (0, 1..) => "  new",
+            _ => "",
+        };
+        let _ = writeln!(report, "{:<40} {:>6} {:>6}{}", selector, before, after, flag);
+    }
+
+    let before = classes(&good);
+    let after = classes(&current);
+    for (heading, from, to) in [
+        ("Element classes gone since the good snapshot", &before, &after),
+        ("Element classes new since the good snapshot", &after, &before),
+    ] {
+        let mut changed: Vec<(&String, &usize)> = from.iter().filter(|(class, _)| !to.contains_key(*class)).collect();
+        changed.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let _ = writeln!(report, "\n{} ({}):", heading, changed.len());
+        for (class, count) in changed.into_iter().take(MAX_CLASSES) {
+            let _ = writeln!(report, "  {} ({})", class, count);
+        }
+    }
+    report
+}
+
+/// Count of every `tag.class` in `document`
+fn classes(document: &Html) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for element in document.root_element().descendent_elements() {
+        for class in element.value().classes() {
+            *counts.entry(format!("{}.{}", element.value().name(), class)).or_default() += 1;
+        }
+    }
+    counts
+}
@@ -0,0 +1,123 @@
+use crate::models::{SoldListing, Source};
+use crate::scrapers::bidding::parse_bid_info;
+use crate::scrapers::detail::parse_swedish_date;
+use crate::scrapers::fees::parse_monthly_fee;
+use crate::scrapers::floor::parse_floor;
+use crate::scrapers::parse::{parse_price, parse_rooms, parse_sqm};
+use crate::scrapers::selectors::CardSelectors;
+use chrono::Utc;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+/// Booli's sold listings ("slutpriser") for Södermalm
+pub const SODERMALM_SOLD_URL: &str = "https://www.booli.se/sok/slutpriser?areaIds=115341";
+
+/// Settings for `scout sold --backfill`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoldConfig {
+    /// Look for the sale of a listing once it has been off the market this long
+    pub backfill_after_days: i64,
+    /// Stop looking for listings that left the market longer ago than this
+    pub backfill_max_days: i64,
+}
+
+impl Default for SoldConfig {
+    fn default() -> Self {
+        Self {
+            backfill_after_days: 2,
+            backfill_max_days: 120,
+        }
+    }
+}
+
+/// Parse sold listing cards from a Booli slutpriser page
+///
+/// Sold cards share the markup, and so the `cards` rules, of for-sale cards:
+/// the aria-label holds rooms and address, list items hold size, fee and
+/// floor, and the price span holds the final price. The sale date is shown as "Såld 12 september 2024".
+#[instrument(name = "parse", level = "debug", skip_all, fields(bytes = html.len()))]
+pub fn parse_sold_cards(html: &str, cards: &CardSelectors) -> Vec<SoldListing> {
+    let document = Html::parse_document(html);
+    let rules = &cards.rules;
+    let today = Utc::now().date_naive();
+
+    let mut sold = Vec::new();
+
+    for card in document.select(&cards.card) {
+        let href = card.value().attr("href").unwrap_or("");
+        let aria_label = card.value().attr(&rules.label_attribute).unwrap_or("").replace("&nbsp;", " ");
+        let text = card.text().collect::<Vec<_>>().join(" ");
+
+        let rooms = parse_rooms(&aria_label);
+
+        // "2 rum lägenhet på Götgatan 120 Södermalm, Stockholms kommun"
+        let (address, area) = match aria_label.find("på ") {
+            Some(pos) => {
+                let after = &aria_label[pos + 3..];
+                let before_comma = after.split(',').next().unwrap_or("").trim();
+                let area = before_comma
+                    .rsplit_once(' ')
+                    .map(|(_, last)| last)
+                    .filter(|last| !last.starts_with(|c: char| c.is_ascii_digit()))
+                    .map(str::to_string);
+                (before_comma.to_string(), area)
+            }
+            None => (String::new(), None),
+        };
+
+        let mut sqm = None;
+        let mut floor = None;
+        let mut monthly_fee = None;
+        for li in card.select(&cards.facts) {
+            let Some(aria) = li.value().attr(&rules.label_attribute) else {
+                continue;
+            };
+            let aria = aria.replace("&nbsp;", " ");
+            if aria.contains(&rules.sqm_marker) {
+                sqm = parse_sqm(&aria).map(|sqm| sqm as i32).filter(|sqm| *sqm > 0);
+            }
+            if aria.to_lowercase().contains(&rules.floor_marker.to_lowercase()) {
+                floor = parse_floor(&aria);
+            }
+            if aria.contains(&rules.fee_marker) {
+                monthly_fee = parse_monthly_fee(&aria);
+            }
+        }
+
+        let sold_price: i64 = card
+            .select(&cards.price)
+            .next()
+            .and_then(|el| parse_price(&el.text().collect::<String>()))
+            .unwrap_or(0);
+
+        let sold_at = text
+            .find("Såld")
+            .and_then(|pos| parse_swedish_date(&text[pos..], today));
+
+        if address.is_empty() || sold_price <= 0 {
+            debug!("Skipped sold card: address='{}', price={}", address, sold_price);
+            continue;
+        }
+
+        sold.push(SoldListing {
+            id: href.split('/').next_back().unwrap_or("unknown").to_string(),
+            source: Source::Booli,
+            address,
+            area,
+            rooms,
+            sqm,
+            floor,
+            monthly_fee,
+            asking_price: parse_bid_info(&text).asking_price,
+            sold_price,
+            sold_at,
+            url: format!("https://www.booli.se{}", href),
+            property_id: None,
+            scraped_at: Utc::now(),
+        });
+    }
+
+    sold
+}
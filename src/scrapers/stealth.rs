@@ -0,0 +1,79 @@
+//! Making headless Chrome look like an everyday desktop Chrome
+//!
+//! Booli now and then serves obvious headless clients a degraded page: fewer
+//! cards, no prices. With [`ChromeConfig::stealth`](crate::scrapers::ChromeConfig::stealth)
+//! every tab gets the usual mitigations before its first page loads.
+
+use anyhow::Result;
+use headless_chrome::protocol::cdp::Emulation::{SetDeviceMetricsOverride, SetLocaleOverride, SetTimezoneOverride};
+use headless_chrome::protocol::cdp::Page::AddScriptToEvaluateOnNewDocument;
+use headless_chrome::Tab;
+
+/// Chrome arguments that leave out the automation banner and flag
+pub const LAUNCH_ARGS: &[&str] = &["--disable-blink-features=AutomationControlled"];
+/// Default Chrome arguments dropped for the same reason
+pub const DROPPED_ARGS: &[&str] = &["--enable-automation"];
+
+/// A common desktop screen
+const VIEWPORT: (u32, u32) = (1920, 1080);
+
+/// Run in every page before its own scripts
+const SCRIPT: &str = r#"
+Object.defineProperty(Navigator.prototype, 'webdriver', { get: () => undefined });
+Object.defineProperty(Navigator.prototype, 'languages', { get: () => ['sv-SE', 'sv', 'en-US', 'en'] });
+if (!window.chrome) {
+    window.chrome = { runtime: {} };
+}
+if (navigator.plugins.length === 0) {
+    Object.defineProperty(Navigator.prototype, 'plugins', { get: () => [1, 2, 3, 4, 5] });
+}
+const query = navigator.permissions && navigator.permissions.query;
+if (query) {
+    navigator.permissions.query = (parameters) =>
+        parameters.name === 'notifications'
+            ? Promise.resolve({ state: Notification.permission })
+            : query.call(navigator.permissions, parameters);
+}
+// UNMASKED_VENDOR_WEBGL and UNMASKED_RENDERER_WEBGL name a software renderer
+// in headless Chrome
+for (const context of [window.WebGLRenderingContext, window.WebGL2RenderingContext]) {
+    if (!context) continue;
+    const getParameter = context.prototype.getParameter;
+    context.prototype.getParameter = function (parameter) {
+        if (parameter === 37445) return 'Intel Inc.';
+        if (parameter === 37446) return 'Intel Iris OpenGL Engine';
+        return getParameter.call(this, parameter);
+    };
+}
+"#;
+
+/// Disguise `tab` before it loads anything; `user_agent` is the browser's own
+pub fn apply(tab: &Tab, user_agent: &str) -> Result<()> {
+    tab.call_method(AddScriptToEvaluateOnNewDocument {
+        source: SCRIPT.to_string(),
+        world_name: None,
+        include_command_line_api: None,
+        run_immediately: None,
+    })?;
+    tab.set_user_agent(&user_agent.replace("HeadlessChrome", "Chrome"), Some("sv-SE,sv;q=0.9,en;q=0.8"), None)?;
+    tab.call_method(SetLocaleOverride { locale: Some("sv-SE".to_string()) })?;
+    tab.call_method(SetTimezoneOverride { timezone_id: "Europe/Stockholm".to_string() })?;
+    let (width, height) = VIEWPORT;
+    tab.call_method(SetDeviceMetricsOverride {
+        width,
+        height,
+        device_scale_factor: 1.0,
+        mobile: false,
+        scale: None,
+        screen_width: Some(width),
+        screen_height: Some(height),
+        position_x: None,
+        position_y: None,
+        dont_set_visible_size: None,
+        screen_orientation: None,
+        viewport: None,
+        display_feature: None,
+        device_posture: None,
+    })?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use crate::models::Property;
+use crate::models::{InvalidListing, Property};
+use crate::shutdown::Shutdown;
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -10,5 +11,16 @@ pub trait ScraperTrait: Send + Sync {
     async fn scrape(&self) -> Result<Vec<Property>>;
     
     /// Get the name of the scraper source
-    fn source_name(&self) -> &'static str;
+    fn source_name(&self) -> &str;
+
+    /// Listings dropped by validation during the last scrape
+    fn rejected(&self) -> Vec<InvalidListing> {
+        Vec::new()
+    }
+
+    /// The flag the source stops on between pages, requested to cut it off
+    /// when it runs out of time; None for sources simply dropped then
+    fn shutdown(&self) -> Option<&Shutdown> {
+        None
+    }
 }
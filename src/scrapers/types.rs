@@ -1,4 +1,12 @@
+use crate::scrapers::account::BooliAccount;
+use crate::scrapers::health::HealthConfig;
+use crate::scrapers::artifacts::DebugConfig;
+use crate::scrapers::budget::BudgetConfig;
+use crate::scrapers::capture::CaptureConfig;
+use crate::scrapers::chrome::ChromeConfig;
+use crate::scrapers::politeness::PolitenessConfig;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Search parameters for property scraping
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +25,42 @@ pub struct SearchParams {
     pub min_sqm: Option<i32>,
     /// Maximum size in square meters
     pub max_sqm: Option<i32>,
+    /// Site whose area IDs `area_ids` are
+    #[serde(default)]
+    pub site: SearchSite,
+    /// Booli `areaIds`, or Hemnet `location_ids`
+    #[serde(default)]
+    pub area_ids: Vec<u64>,
+    /// Kinds of property to include; empty for all
+    #[serde(default)]
+    pub property_types: Vec<PropertyType>,
+}
+
+/// Listing site a search was made on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSite {
+    #[default]
+    Booli,
+    Hemnet,
+}
+
+/// Kind of property a search covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyType {
+    /// Bostadsrätt
+    Apartment,
+    /// Villa
+    House,
+    /// Radhus, parhus or kedjehus
+    Townhouse,
+    /// Fritidshus
+    Cottage,
+    /// Tomt
+    Plot,
+    /// Gård
+    Farm,
 }
 
 impl Default for SearchParams {
@@ -29,6 +73,162 @@ impl Default for SearchParams {
             max_rooms: None,
             min_sqm: None,
             max_sqm: None,
+            site: SearchSite::Booli,
+            area_ids: Vec::new(),
+            property_types: Vec::new(),
+        }
+    }
+}
+
+/// Booli area ID of Södermalm, the default search
+pub const SODERMALM_AREA_ID: u64 = 115341;
+
+/// One Booli search to scrape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaSearch {
+    /// Tag added to every listing found, and their area when the card names none
+    pub name: String,
+    /// Booli area IDs, as in `/sok/till-salu?areaIds=...`
+    #[serde(default)]
+    pub area_ids: Vec<u64>,
+    /// Complete search URL, used instead of `area_ids` when set
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default = "default_city")]
+    pub city: String,
+}
+
+fn default_city() -> String {
+    "Stockholm".to_string()
+}
+
+impl AreaSearch {
+    pub fn url(&self) -> String {
+        match &self.url {
+            Some(url) => url.clone(),
+            None => {
+                let ids: Vec<String> = self.area_ids.iter().map(u64::to_string).collect();
+                format!("https://www.booli.se/sok/till-salu?areaIds={}", ids.join(","))
+            }
+        }
+    }
+
+    /// The same search among Booli's sold listings, if it is a Booli search
+    pub fn sold_url(&self) -> Option<String> {
+        let url = self.url();
+        url.contains("booli.se/sok/till-salu")
+            .then(|| url.replace("/sok/till-salu", "/sok/slutpriser"))
+    }
+}
+
+/// A listing source to scrape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Booli's GraphQL API over `areas`, falling back to `booli_browser` when
+    /// the API has changed
+    BooliGraphql,
+    /// Booli search pages in headless Chrome, covering `areas`
+    BooliBrowser,
+    /// Booli's Södermalm search over plain HTTP
+    BooliHttp,
+    /// Listings from the JSON and HTML files in `fixtures`, for testing runs
+    /// without network or Chrome
+    Fixtures,
+}
+
+/// How the browser source loads Booli pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStrategy {
+    /// Read each page's `__NEXT_DATA__` over plain HTTP, loading only the
+    /// pages that fails for in Chrome; see [`FastPath`](crate::scrapers::fast_path::FastPath)
+    #[default]
+    HttpFirst,
+    /// Load every page in Chrome
+    Browser,
+}
+
+/// What to scrape and how fast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrapeConfig {
+    pub sources: Vec<SourceKind>,
+    /// Sources scraped at the same time
+    pub max_concurrent_sources: usize,
+    /// Give up on a source after this long, keeping the others' results
+    pub source_timeout_secs: u64,
+    pub areas: Vec<AreaSearch>,
+    /// Search pages loaded at the same time
+    pub concurrency: usize,
+    /// Search pages read over HTTP parsed at the same time, each on its own
+    /// thread; 0 for one per CPU core
+    pub parse_threads: usize,
+    /// Minimum time between two page loads from the same domain
+    pub min_interval_ms: u64,
+    /// Per-source waits between pages and detail visits
+    pub politeness: PolitenessConfig,
+    pub budget: BudgetConfig,
+    pub health: HealthConfig,
+    /// Log into Booli before scraping
+    pub account: Option<BooliAccount>,
+    pub capture: CaptureConfig,
+    /// How the browser scraper launches, restarts and cleans up after Chrome
+    pub chrome: ChromeConfig,
+    /// How the browser source loads pages; Chrome only when logged in, as
+    /// the session lives in Chrome
+    pub fetch_strategy: FetchStrategy,
+    pub debug: DebugConfig,
+    /// Directory the `fixtures` source reads
+    pub fixtures: PathBuf,
+    /// WebAssembly module parsing Booli search pages in place of the built-in
+    /// parser, see [`ParserPlugin`](crate::scrapers::ParserPlugin)
+    pub parser_plugin: Option<PathBuf>,
+    /// Directory of per-site selector rules, see
+    /// [`CardRules`](crate::scrapers::selectors::CardRules)
+    pub rules_dir: PathBuf,
+    /// Sites scraped alongside `sources`, each by its rules file in
+    /// `rules_dir`; see [`SiteRules`](crate::scrapers::site::SiteRules)
+    pub sites: Vec<String>,
+}
+
+impl Default for ScrapeConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![SourceKind::BooliGraphql],
+            max_concurrent_sources: 2,
+            source_timeout_secs: 900,
+            areas: vec![AreaSearch {
+                name: "Södermalm".to_string(),
+                area_ids: vec![SODERMALM_AREA_ID],
+                url: None,
+                city: default_city(),
+            }],
+            concurrency: 3,
+            parse_threads: 0,
+            min_interval_ms: 2000,
+            politeness: PolitenessConfig::default(),
+            budget: BudgetConfig::default(),
+            health: HealthConfig::default(),
+            account: None,
+            capture: CaptureConfig::default(),
+            chrome: ChromeConfig::default(),
+            fetch_strategy: FetchStrategy::default(),
+            debug: DebugConfig::default(),
+            fixtures: PathBuf::from("fixtures"),
+            parser_plugin: None,
+            rules_dir: PathBuf::from("rules"),
+            sites: Vec::new(),
+        }
+    }
+}
+
+impl ScrapeConfig {
+    /// `parse_threads`, with 0 resolved to the number of CPU cores
+    pub fn parse_threads(&self) -> usize {
+        match self.parse_threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
         }
     }
 }
@@ -0,0 +1,176 @@
+use crate::export::ExportRecord;
+use crate::models::Property;
+use crate::notify::Notification;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// A Rhai script defining hook functions; see [`Scripts`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptConfig {
+    /// The `.rhai` file; no hooks run without one
+    pub path: Option<PathBuf>,
+    /// Operations one hook call may take before it's stopped, so a runaway
+    /// loop can't hang a scrape
+    pub max_operations: u64,
+}
+
+impl Default for ScriptConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_operations: 1_000_000,
+        }
+    }
+}
+
+/// User functions called at fixed points of the pipeline, for logic the
+/// filter DSL and scoring weights can't express
+///
+/// The script may define any of:
+/// - `keep(listing)`: after a scrape, before anything is stored; listings it
+///   returns false for are dropped
+/// - `score(listing)`: the listing's score, 0.0 - 1.0, replacing the weighted
+///   one; the record with the built-in score is passed in
+/// - `notification(listing, message)`: the notification for a listing, given
+///   as `#{ event, title, body }`; returns that map changed, or a string as
+///   the new body
+///
+/// Listings are passed as maps of their exported JSON. Errors are logged and
+/// the built-in behaviour is kept. Needs a build with `--features scripting`.
+#[derive(Default)]
+pub struct Scripts {
+    #[cfg(feature = "scripting")]
+    script: Option<Script>,
+}
+
+#[cfg(feature = "scripting")]
+struct Script {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+}
+
+impl Scripts {
+    /// Compile the configured script, if any
+    pub fn load(config: &ScriptConfig) -> Result<Self> {
+        let Some(path) = &config.path else {
+            return Ok(Self::default());
+        };
+
+        #[cfg(feature = "scripting")]
+        {
+            use anyhow::Context;
+
+            let mut engine = rhai::Engine::new();
+            engine.set_max_operations(config.max_operations);
+            let ast = engine
+                .compile_file(path.clone())
+                .map_err(|e| anyhow::anyhow!("{}", e))
+                .with_context(|| format!("Failed to compile script {}", path.display()))?;
+            let hooks: Vec<String> = ast.iter_functions().map(|f| f.name.to_string()).collect();
+            tracing::info!("Loaded script {} ({})", path.display(), hooks.join(", "));
+            Ok(Self {
+                script: Some(Script { engine, ast }),
+            })
+        }
+        #[cfg(not(feature = "scripting"))]
+        {
+            let _ = config.max_operations;
+            anyhow::bail!("The script {} needs a build with `--features scripting`", path.display())
+        }
+    }
+
+    /// Whether `keep(listing)` lets the property through; true without the hook
+    pub fn keep(&self, property: &Property) -> bool {
+        if !self.defines("keep", 1) {
+            return true;
+        }
+        match self.call("keep", vec![json!(property)]) {
+            Some(Value::Bool(keep)) => keep,
+            Some(other) => {
+                warn!("Script keep() returned {} for {}, not true or false; keeping it", other, property.id);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// The score `score(listing)` gives the record, clamped to 0.0 - 1.0
+    pub fn score(&self, record: &ExportRecord<'_>) -> Option<f64> {
+        if !self.defines("score", 1) {
+            return None;
+        }
+        match self.call("score", vec![json!(record)])? {
+            Value::Number(score) => score.as_f64().map(|score| score.clamp(0.0, 1.0)),
+            other => {
+                warn!("Script score() returned {} for {}, not a number", other, record.property.id);
+                None
+            }
+        }
+    }
+
+    /// Let `notification(listing, message)` rewrite a listing's notification
+    pub fn format(&self, record: &ExportRecord<'_>, notification: &mut Notification) {
+        if !self.defines("notification", 2) {
+            return;
+        }
+        let message = json!({ "event": notification.event, "title": notification.title, "body": notification.body });
+        match self.call("notification", vec![json!(record), message]) {
+            Some(Value::String(body)) => notification.body = body,
+            Some(Value::Object(message)) => {
+                if let Some(title) = message.get("title").and_then(Value::as_str) {
+                    notification.title = title.to_string();
+                }
+                if let Some(body) = message.get("body").and_then(Value::as_str) {
+                    notification.body = body.to_string();
+                }
+            }
+            Some(Value::Null) | None => {}
+            Some(other) => warn!("Script notification() returned {}, not a map or string", other),
+        }
+    }
+
+    /// Whether the script defines `name` taking `arity` arguments
+    #[cfg(feature = "scripting")]
+    fn defines(&self, name: &str, arity: usize) -> bool {
+        self.script
+            .as_ref()
+            .is_some_and(|script| script.ast.iter_functions().any(|f| f.name == name && f.params.len() == arity))
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    fn defines(&self, _name: &str, _arity: usize) -> bool {
+        false
+    }
+
+    /// Call `name` with JSON arguments; None when it fails
+    #[cfg(feature = "scripting")]
+    fn call(&self, name: &str, args: Vec<Value>) -> Option<Value> {
+        let script = self.script.as_ref()?;
+        let result = args
+            .iter()
+            .map(rhai::serde::to_dynamic)
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|args| {
+                script
+                    .engine
+                    .call_fn::<rhai::Dynamic>(&mut rhai::Scope::new(), &script.ast, name, args)
+            })
+            .and_then(|result| rhai::serde::from_dynamic::<Value>(&result));
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Script {}() failed: {}", name, e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    fn call(&self, _name: &str, _args: Vec<Value>) -> Option<Value> {
+        None
+    }
+}
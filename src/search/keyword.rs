@@ -0,0 +1,65 @@
+use super::{document, SearchHit};
+use crate::models::Property;
+use std::collections::{HashMap, HashSet};
+
+/// Query terms at least this long also match longer words starting with them,
+/// so "balkong" finds "balkongen" and "balkongdörr"
+const MIN_PREFIX_LEN: usize = 4;
+
+/// Rank `properties` by TF-IDF weighted overlap with the words of `query`
+///
+/// Properties matching no query term are left out.
+pub fn keyword_search(properties: &[Property], query: &str) -> Vec<SearchHit> {
+    let terms: Vec<String> = tokens(query).into_iter().collect::<HashSet<_>>().into_iter().collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let documents: Vec<Vec<String>> = properties.iter().map(|p| tokens(&document(p))).collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for words in &documents {
+        for term in &terms {
+            if words.iter().any(|word| matches(word, term)) {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    let total = documents.len() as f64;
+    let mut hits: Vec<SearchHit> = documents
+        .iter()
+        .enumerate()
+        .filter_map(|(index, words)| {
+            let length = (words.len() as f64).max(1.0);
+            let score: f64 = terms
+                .iter()
+                .map(|term| {
+                    let count = words.iter().filter(|word| matches(word, term)).count();
+                    if count == 0 {
+                        return 0.0;
+                    }
+                    let df = document_frequency.get(term.as_str()).copied().unwrap_or(0) as f64;
+                    let idf = (1.0 + total / df.max(1.0)).ln();
+                    // Dampen repeats so one word said five times can't outrank two matched terms
+                    (1.0 + (count as f64).ln()) * idf / length.sqrt()
+                })
+                .sum();
+            (score > 0.0).then_some(SearchHit { index, score })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
+fn matches(word: &str, term: &str) -> bool {
+    word == term || (term.chars().count() >= MIN_PREFIX_LEN && word.starts_with(term))
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,70 @@
+//! Ranked free-text search over listings
+//!
+//! Keyword search is always available. Embedding-based semantic search needs
+//! the `semantic` feature and a local BERT-style sentence embedding model.
+
+pub mod keyword;
+#[cfg(feature = "semantic")]
+pub mod semantic;
+
+use crate::i18n::Language;
+use crate::models::Property;
+use crate::storage::JsonStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub use keyword::keyword_search;
+
+/// Settings for `scout search`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Directory with `config.json`, `tokenizer.json` and `model.safetensors`
+    /// of a sentence embedding model, e.g. a download of
+    /// sentence-transformers/paraphrase-multilingual-MiniLM-L12-v2
+    pub model_dir: Option<PathBuf>,
+}
+
+/// A search match; `index` points into the searched properties
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchHit {
+    pub index: usize,
+    /// Higher is better; only comparable within one search
+    pub score: f64,
+}
+
+/// Text a listing is searched by
+pub fn document(property: &Property) -> String {
+    let mut text = format!("{}\n", property.address);
+    if let Some(area) = &property.location.area {
+        text.push_str(area);
+        text.push('\n');
+    }
+    // In the listings' own wording, like the description
+    text.push_str(&property.features.labels(Language::Sv).join(", "));
+    text.push('\n');
+    text.push_str(&property.description);
+    text
+}
+
+/// Rank `properties` by embedding similarity to `query`
+#[cfg(feature = "semantic")]
+pub async fn semantic_search(
+    properties: &[Property],
+    query: &str,
+    config: &SearchConfig,
+    store: &JsonStore,
+) -> Result<Vec<SearchHit>> {
+    semantic::search(properties, query, config, store).await
+}
+
+#[cfg(not(feature = "semantic"))]
+pub async fn semantic_search(
+    _properties: &[Property],
+    _query: &str,
+    _config: &SearchConfig,
+    _store: &JsonStore,
+) -> Result<Vec<SearchHit>> {
+    anyhow::bail!("Semantic search needs a build with `--features semantic`")
+}
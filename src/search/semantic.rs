@@ -0,0 +1,145 @@
+use super::{document, SearchConfig, SearchHit};
+use crate::models::Property;
+use crate::storage::JsonStore;
+use anyhow::{Context, Result};
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use tracing::{info, warn};
+
+/// Cache file for description embeddings, keyed by model and text hash
+const CACHE_NAME: &str = "embeddings";
+/// Longest input in tokens; longer descriptions are truncated
+const MAX_TOKENS: usize = 256;
+const BATCH_SIZE: usize = 16;
+
+/// Sentence embedding model: BERT with mean pooling over the tokens
+pub struct Embedder {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl Embedder {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let device = Device::Cpu;
+        let config_path = dir.join("config.json");
+        let config: BertConfig = serde_json::from_str(
+            &std::fs::read_to_string(&config_path)
+                .with_context(|| format!("Failed to read {}", config_path.display()))?,
+        )
+        .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json")).map_err(anyhow::Error::msg)?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..Default::default()
+            }))
+            .map_err(anyhow::Error::msg)?;
+
+        // SAFETY: the weights file is only read, and must not be modified while the model is loaded
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[dir.join("model.safetensors")], DTYPE, &device)? };
+        let model = BertModel::load(vb, &config)?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            device,
+        })
+    }
+
+    /// Unit-length embeddings of `texts`, in order
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            let encodings = self
+                .tokenizer
+                .encode_batch(batch.to_vec(), true)
+                .map_err(anyhow::Error::msg)?;
+            let ids = encodings
+                .iter()
+                .map(|e| Tensor::new(e.get_ids(), &self.device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            let masks = encodings
+                .iter()
+                .map(|e| Tensor::new(e.get_attention_mask(), &self.device))
+                .collect::<candle_core::Result<Vec<_>>>()?;
+            let ids = Tensor::stack(&ids, 0)?;
+            let mask = Tensor::stack(&masks, 0)?;
+
+            let output = self.model.forward(&ids, &ids.zeros_like()?, Some(&mask))?;
+
+            // Mean over the real (unpadded) tokens, then normalize
+            let mask = mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+            let pooled = output.broadcast_mul(&mask)?.sum(1)?.broadcast_div(&mask.sum(1)?)?;
+            let norm = pooled.sqr()?.sum_keepdim(1)?.sqrt()?;
+            embeddings.extend(pooled.broadcast_div(&norm)?.to_vec2::<f32>()?);
+        }
+        Ok(embeddings)
+    }
+}
+
+pub(super) async fn search(
+    properties: &[Property],
+    query: &str,
+    config: &SearchConfig,
+    store: &JsonStore,
+) -> Result<Vec<SearchHit>> {
+    let dir = config
+        .model_dir
+        .as_deref()
+        .context("Set search.model_dir in scout.toml to use semantic search")?;
+    let embedder = Embedder::load(dir)?;
+    let model_key = dir.display().to_string();
+
+    let mut cache: HashMap<String, Vec<f32>> = store.load_cache(CACHE_NAME).await.unwrap_or_default();
+    let keys: Vec<String> = properties
+        .iter()
+        .map(|p| format!("{:x}", Sha256::digest(format!("{}\n{}", model_key, document(p)))))
+        .collect();
+
+    let missing: Vec<(String, String)> = properties
+        .iter()
+        .zip(&keys)
+        .filter(|(_, key)| !cache.contains_key(*key))
+        .map(|(p, key)| (key.clone(), document(p)))
+        .collect();
+    if !missing.is_empty() {
+        info!("Embedding {} listing descriptions...", missing.len());
+        let texts: Vec<String> = missing.iter().map(|(_, text)| text.clone()).collect();
+        for ((key, _), embedding) in missing.into_iter().zip(embedder.embed(&texts)?) {
+            cache.insert(key, embedding);
+        }
+        // Drop embeddings of listings that are no longer stored
+        cache.retain(|key, _| keys.contains(key));
+        if let Err(e) = store.save_cache(CACHE_NAME, &cache).await {
+            warn!("Failed to save embedding cache: {}", e);
+        }
+    }
+
+    let query = embedder
+        .embed(&[query.to_string()])?
+        .pop()
+        .context("No embedding for the query")?;
+
+    let mut hits: Vec<SearchHit> = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(index, key)| {
+            let embedding = cache.get(key)?;
+            let similarity: f32 = embedding.iter().zip(&query).map(|(a, b)| a * b).sum();
+            Some(SearchHit {
+                index,
+                score: similarity as f64,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(hits)
+}
@@ -0,0 +1,142 @@
+//! Tokens and passwords kept out of scout.toml
+//!
+//! Config values that may be secret are [`Secret`]s: either the value itself or
+//! `{ secret = "<name>" }`, looked up by name when used. Names are resolved from
+//! the `SCOUT_SECRET_<NAME>` environment variable first, then the OS keyring,
+//! where `scout secrets set <name>` stores them.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Keyring service the secrets are stored under
+const KEYRING_SERVICE: &str = "housing-scout";
+
+/// A config value given inline or by the name of a stored secret
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Named { secret: String },
+    Plain(String),
+}
+
+impl Secret {
+    /// The value, looking named secrets up in every provider
+    pub fn resolve(&self) -> Result<String> {
+        match self {
+            Secret::Plain(value) => Ok(value.clone()),
+            Secret::Named { secret } => lookup(secret),
+        }
+    }
+}
+
+/// Never prints the value, so configs can be logged safely
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::Plain(_) => f.write_str("Secret(***)"),
+            Secret::Named { secret } => write!(f, "Secret({:?})", secret),
+        }
+    }
+}
+
+/// Somewhere secrets can be read from, and possibly written to
+pub trait SecretProvider {
+    /// Name of the provider, used in messages
+    fn name(&self) -> &str;
+
+    fn get(&self, name: &str) -> Result<Option<String>>;
+
+    fn set(&self, name: &str, value: &str) -> Result<()>;
+
+    /// Returns false when there was nothing to delete
+    fn delete(&self, name: &str) -> Result<bool>;
+}
+
+/// `SCOUT_SECRET_<NAME>` environment variables, e.g. for CI; read-only
+pub struct EnvSecrets;
+
+impl EnvSecrets {
+    pub fn variable(name: &str) -> String {
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("SCOUT_SECRET_{}", name)
+    }
+}
+
+impl SecretProvider for EnvSecrets {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(std::env::var(Self::variable(name)).ok())
+    }
+
+    fn set(&self, name: &str, _value: &str) -> Result<()> {
+        bail!("Set {} in the environment instead", Self::variable(name))
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        bail!("Unset {} in the environment instead", Self::variable(name))
+    }
+}
+
+/// The OS keyring: macOS Keychain, Windows Credential Manager or Linux keyutils
+pub struct KeyringSecrets;
+
+impl KeyringSecrets {
+    fn entry(name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, name).context("Failed to open the OS keyring")
+    }
+}
+
+impl SecretProvider for KeyringSecrets {
+    fn name(&self) -> &str {
+        "keyring"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read secret {:?} from the keyring", name)),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        Self::entry(name)?
+            .set_password(value)
+            .with_context(|| format!("Failed to store secret {:?} in the keyring", name))
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete secret {:?} from the keyring", name)),
+        }
+    }
+}
+
+/// Providers in lookup order
+pub fn providers() -> Vec<Box<dyn SecretProvider>> {
+    vec![Box::new(EnvSecrets), Box::new(KeyringSecrets)]
+}
+
+/// The value of the secret `name` from the first provider that has it
+pub fn lookup(name: &str) -> Result<String> {
+    for provider in providers() {
+        if let Some(value) = provider.get(name)? {
+            return Ok(value);
+        }
+    }
+    bail!(
+        "No secret named {:?}; store it with `scout secrets set {}` or set {}",
+        name,
+        name,
+        EnvSecrets::variable(name)
+    )
+}
@@ -0,0 +1,79 @@
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Length of a rate limit window
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Who may call the server and how often
+#[derive(Clone)]
+pub struct Guard {
+    /// SHA-256 of every accepted token; open to anyone when empty
+    tokens: Arc<Vec<[u8; 32]>>,
+    limit: Option<u32>,
+    /// Start of each client's current window and its requests in it
+    windows: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
+}
+
+impl Guard {
+    pub fn new(tokens: &[String], requests_per_minute: Option<u32>) -> Self {
+        Self {
+            tokens: Arc::new(tokens.iter().map(|token| digest(token)).collect()),
+            limit: requests_per_minute.filter(|limit| *limit > 0),
+            windows: Arc::default(),
+        }
+    }
+
+    /// Whether `authorization` carries one of the tokens
+    fn authorized(&self, authorization: Option<&HeaderValue>) -> bool {
+        if self.tokens.is_empty() {
+            return true;
+        }
+        let Some(token) = authorization
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return false;
+        };
+        // Comparing digests keeps timing from revealing how much of a token matched
+        let token = digest(token.trim());
+        self.tokens.contains(&token)
+    }
+
+    /// Count a request from `client`; the time until it may retry when over the limit
+    fn throttle(&self, client: IpAddr) -> Option<Duration> {
+        let limit = self.limit?;
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        windows.retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+        let (start, count) = windows.entry(client).or_insert((now, 0));
+        if *count >= limit {
+            return Some(WINDOW.saturating_sub(now.duration_since(*start)));
+        }
+        *count += 1;
+        None
+    }
+}
+
+fn digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Middleware rejecting requests without a valid token (401) and clients
+/// over the rate limit (429)
+pub async fn guard(State(guard): State<Guard>, ConnectInfo(client): ConnectInfo<SocketAddr>, request: Request, next: Next) -> Response {
+    if let Some(wait) = guard.throttle(client.ip()) {
+        let retry_after = wait.as_secs().max(1).to_string();
+        return (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, retry_after)], "Too many requests").into_response();
+    }
+    if !guard.authorized(request.headers().get(header::AUTHORIZATION)) {
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")], "Missing or invalid token").into_response();
+    }
+    next.run(request).await
+}
@@ -0,0 +1,325 @@
+//! The web dashboard served on GET /: a table and a map of the listings, and
+//! a page per listing with its price history, all read from /graphql
+
+/// The whole dashboard, one page routed by the URL fragment: `#/` for the
+/// table, `#/map` and `#/listing/<id>`
+pub const DASHBOARD: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Housing Scout</title>
+<style>
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 0; color: #222; }
+header { display: flex; align-items: baseline; gap: 1.5em; padding: 0.8em 2em; border-bottom: 1px solid #ddd; }
+header h1 { font-size: 1.3em; margin: 0; }
+header a { color: #444; text-decoration: none; }
+header a.active { font-weight: bold; color: #000; }
+main { padding: 1em 2em; }
+.filters { display: flex; flex-wrap: wrap; gap: 1em; margin-bottom: 1em; align-items: flex-end; }
+.filters label { display: flex; flex-direction: column; font-size: 0.85em; color: #555; }
+.filters input[type=number] { width: 8em; }
+.filters input.expression { width: 22em; }
+.error { color: #b00; }
+table { border-collapse: collapse; width: 100%; }
+th { text-align: left; border-bottom: 2px solid #ccc; white-space: nowrap; }
+th[data-sort] { cursor: pointer; }
+td, th { padding: 0.4em 0.8em 0.4em 0; vertical-align: middle; }
+tr:nth-child(even) td { background: #f7f7f7; }
+td.num { text-align: right; white-space: nowrap; }
+img.thumb { width: 96px; height: 64px; object-fit: cover; }
+.flag { color: #b00; }
+#map { position: relative; overflow: hidden; height: 75vh; background: #eef; }
+#map img.tile { position: absolute; width: 256px; height: 256px; }
+#map a.pin { position: absolute; width: 12px; height: 12px; margin: -6px 0 0 -6px; border-radius: 50%; border: 1px solid #fff; box-shadow: 0 0 2px #000; }
+.legend { font-size: 0.85em; color: #555; margin-top: 0.5em; }
+.detail { max-width: 60em; }
+.photos { display: flex; gap: 0.5em; overflow-x: auto; }
+.photos img { height: 220px; }
+.facts td:first-child { color: #555; padding-right: 2em; }
+svg.chart { width: 100%; height: 260px; }
+svg.chart text { font-size: 11px; fill: #555; }
+</style>
+</head>
+<body>
+<header>
+  <h1>Housing Scout</h1>
+  <a href="#/" data-view="table">Table</a>
+  <a href="#/map" data-view="map">Map</a>
+  <span id="status"></span>
+</header>
+<main>
+<div class="filters" id="filters">
+  <label>Search <input id="q" type="search" placeholder="Address or area"></label>
+  <label>Max price <input id="maxPrice" type="number" step="100000"></label>
+  <label>Min sqm <input id="minSqm" type="number"></label>
+  <label>Min rooms <input id="minRooms" type="number" step="0.5"></label>
+  <label>Max monthly cost <input id="maxMonthlyCost" type="number" step="500"></label>
+  <label>Filter <input id="expression" class="expression" placeholder='e.g. fee_per_sqm < 700 && amenities ~ "balcony"'></label>
+  <label>Underpriced <input id="underpriced" type="checkbox"></label>
+  <label>Favorites <input id="favorites" type="checkbox"></label>
+  <label>Every run <input id="all" type="checkbox"></label>
+</div>
+<div id="view"></div>
+</main>
+<script>
+const LISTINGS = `query ($filter: ListingFilter, $sort: ListingSort, $all: Boolean!) {
+  listings(filter: $filter, sort: $sort, all: $all, first: 2000) {
+    totalCount
+    edges { node { id address area price rooms sqm monthlyFee pricePerSqm monthlyCost score status favorite latitude longitude images underpriced { discount } } }
+  }
+}`;
+const LISTING = `query ($id: ID!) {
+  listing(id: $id) {
+    id address area city url description images floorPlans virtualTours price askingPrice currentBid biddingInProgress comingSoon currency
+    monthlyFee operatingCost rooms sqm floor pricePerSqm feePerSqm monthlyCost score status favorite rating
+    latitude longitude firstSeen lastSeen
+    broker { name agency phone }
+    nearestStation { name kind walkingM }
+    underpriced { cohort discount cohortMedianPricePerSqm }
+    prediction { predictedPrice deltaPct }
+    notes { writtenAt text }
+    priceHistory { observedAt price currentBid biddingInProgress }
+  }
+}`;
+const COLUMNS = [
+  ["address", "Address", null],
+  ["price", "Price", "PRICE"],
+  ["rooms", "Rooms", "ROOMS"],
+  ["sqm", "Sqm", "SQM"],
+  ["pricePerSqm", "Price/m²", "PRICE_PER_SQM"],
+  ["monthlyFee", "Fee", "FEE"],
+  ["monthlyCost", "Monthly", "MONTHLY_COST"],
+  ["score", "Score", "SCORE"],
+];
+
+let token = localStorage.getItem("scout-token");
+let sort = { field: "SCORE", descending: true };
+let listings = [];
+
+const fmt = (v) => v == null ? "" : Math.round(v).toLocaleString("sv-SE");
+const esc = (s) => String(s ?? "").replace(/[&<>"']/g, (c) => ({"&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;", "'": "&#39;"}[c]));
+const num = (id) => { const v = document.getElementById(id).value; return v === "" ? null : Number(v); };
+const day = (t) => t ? t.slice(0, 10) : "";
+
+async function gql(query, variables) {
+  const headers = { "Content-Type": "application/json" };
+  if (token) headers.Authorization = "Bearer " + token;
+  const res = await fetch("/graphql", { method: "POST", headers, body: JSON.stringify({ query, variables }) });
+  if (res.status === 401) {
+    const entered = prompt("API token ([server] tokens in scout.toml)");
+    if (!entered) throw new Error("A token is needed to read the listings");
+    token = entered;
+    localStorage.setItem("scout-token", token);
+    return gql(query, variables);
+  }
+  if (res.status === 429) throw new Error("Too many requests; try again in a minute");
+  const body = await res.json();
+  if (body.errors) throw new Error(body.errors.map((e) => e.message).join("; "));
+  return body.data;
+}
+
+function filter() {
+  const f = {
+    maxPrice: num("maxPrice"), minSqm: num("minSqm"), minRooms: num("minRooms"), maxMonthlyCost: num("maxMonthlyCost"),
+    underpriced: document.getElementById("underpriced").checked, favorites: document.getElementById("favorites").checked,
+  };
+  const expression = document.getElementById("expression").value.trim();
+  if (expression) f.expression = expression;
+  return f;
+}
+
+function searched() {
+  const q = document.getElementById("q").value.toLowerCase();
+  return listings.filter((p) => !q || (p.address + " " + (p.area || "")).toLowerCase().includes(q));
+}
+
+async function load() {
+  const status = document.getElementById("status");
+  status.className = "";
+  status.textContent = "Loading…";
+  try {
+    const data = await gql(LISTINGS, { filter: filter(), sort, all: document.getElementById("all").checked });
+    listings = data.listings.edges.map((e) => e.node);
+    status.textContent = `${data.listings.totalCount} listings`;
+  } catch (e) {
+    listings = [];
+    status.className = "error";
+    status.textContent = e.message;
+  }
+  route();
+}
+
+function renderTable(view) {
+  const heads = COLUMNS.map(([key, label, field]) => {
+    const arrow = field && sort.field === field ? (sort.descending ? " ▾" : " ▴") : "";
+    return `<th${field ? ` data-sort="${field}"` : ""}>${label}${arrow}</th>`;
+  }).join("");
+  const rows = searched().map((p) => `<tr>
+    <td>${p.images.length ? `<img class="thumb" loading="lazy" src="${esc(p.images[0])}" alt="">` : ""}</td>
+    <td><a href="#/listing/${encodeURIComponent(p.id)}">${esc(p.address)}</a>${p.favorite ? " ⭐" : ""}${p.underpriced ? ' <span class="flag">🔥</span>' : ""}<br><small>${esc(p.area)}</small></td>
+    <td class="num">${fmt(p.price)}</td>
+    <td class="num">${p.rooms ?? ""}</td>
+    <td class="num">${p.sqm ?? ""}</td>
+    <td class="num">${fmt(p.pricePerSqm)}</td>
+    <td class="num">${fmt(p.monthlyFee)}</td>
+    <td class="num">${fmt(p.monthlyCost)}</td>
+    <td class="num">${p.score.toFixed(2)}</td>
+  </tr>`).join("");
+  view.innerHTML = `<table><thead><tr><th></th>${heads}</tr></thead><tbody>${rows}</tbody></table>`;
+  view.querySelectorAll("th[data-sort]").forEach((th) => th.addEventListener("click", () => {
+    sort = { field: th.dataset.sort, descending: sort.field === th.dataset.sort ? !sort.descending : true };
+    load();
+  }));
+}
+
+// Web Mercator pixel coordinates at zoom z, 256 px tiles
+const mercX = (lon, z) => (lon + 180) / 360 * 256 * 2 ** z;
+const mercY = (lat, z) => {
+  const r = lat * Math.PI / 180;
+  return (1 - Math.log(Math.tan(r) + 1 / Math.cos(r)) / Math.PI) / 2 * 256 * 2 ** z;
+};
+
+function renderMap(view) {
+  view.innerHTML = `<div id="map"></div><p class="legend">Pins are coloured by price per m², green lowest to red highest. Map data © OpenStreetMap contributors.</p>`;
+  const map = document.getElementById("map");
+  const points = searched().filter((p) => p.latitude != null && p.longitude != null);
+  if (!points.length) {
+    map.textContent = "No listings with coordinates";
+    return;
+  }
+  const width = map.clientWidth, height = map.clientHeight;
+  const extent = (z) => {
+    const xs = points.map((p) => mercX(p.longitude, z)), ys = points.map((p) => mercY(p.latitude, z));
+    return [Math.min(...xs), Math.max(...xs), Math.min(...ys), Math.max(...ys)];
+  };
+  // The closest zoom that still fits every listing
+  let zoom = 16;
+  while (zoom > 3) {
+    const [x0, x1, y0, y1] = extent(zoom);
+    if (x1 - x0 < width - 40 && y1 - y0 < height - 40) break;
+    zoom--;
+  }
+  const [x0, x1, y0, y1] = extent(zoom);
+  const left = (x0 + x1) / 2 - width / 2, top = (y0 + y1) / 2 - height / 2;
+  const tiles = 2 ** zoom;
+  let html = "";
+  for (let tx = Math.floor(left / 256); tx * 256 < left + width; tx++) {
+    for (let ty = Math.max(0, Math.floor(top / 256)); ty * 256 < top + height && ty < tiles; ty++) {
+      const x = ((tx % tiles) + tiles) % tiles;
+      html += `<img class="tile" src="https://tile.openstreetmap.org/${zoom}/${x}/${ty}.png" style="left:${tx * 256 - left}px;top:${ty * 256 - top}px" alt="">`;
+    }
+  }
+  const prices = points.map((p) => p.pricePerSqm).filter((v) => v != null).sort((a, b) => a - b);
+  const rank = (v) => prices.length > 1 && v != null ? prices.indexOf(v) / (prices.length - 1) : 0.5;
+  for (const p of points) {
+    const hue = Math.round(120 * (1 - rank(p.pricePerSqm)));
+    const title = `${p.address}, ${fmt(p.price)} (${fmt(p.pricePerSqm)}/m²)`;
+    html += `<a class="pin" href="#/listing/${encodeURIComponent(p.id)}" title="${esc(title)}" style="left:${mercX(p.longitude, zoom) - left}px;top:${mercY(p.latitude, zoom) - top}px;background:hsl(${hue},70%,45%)"></a>`;
+  }
+  map.innerHTML = html;
+}
+
+// Listed price and bids over time as an SVG step chart
+function priceChart(history) {
+  const points = history.flatMap((o) => [
+    o.price != null ? { t: Date.parse(o.observedAt), v: o.price, kind: "price" } : null,
+    o.currentBid != null ? { t: Date.parse(o.observedAt), v: o.currentBid, kind: "bid" } : null,
+  ]).filter(Boolean);
+  if (!points.length) return "<p>No price history yet</p>";
+  const W = 720, H = 240, pad = 60;
+  const t0 = Math.min(...points.map((p) => p.t)), t1 = Math.max(Date.now(), ...points.map((p) => p.t));
+  const v0 = Math.min(...points.map((p) => p.v)) * 0.98, v1 = Math.max(...points.map((p) => p.v)) * 1.02;
+  const x = (t) => pad + (t1 > t0 ? (t - t0) / (t1 - t0) : 0.5) * (W - 2 * pad);
+  const y = (v) => H - 30 - (v1 > v0 ? (v - v0) / (v1 - v0) : 0.5) * (H - 50);
+  const line = (kind, colour) => {
+    const series = points.filter((p) => p.kind === kind);
+    if (!series.length) return "";
+    let d = `M${x(series[0].t)},${y(series[0].v)}`;
+    for (const p of series.slice(1)) d += ` H${x(p.t)} V${y(p.v)}`;
+    d += ` H${x(t1)}`;
+    const dots = series.map((p) => `<circle cx="${x(p.t)}" cy="${y(p.v)}" r="3" fill="${colour}"><title>${new Date(p.t).toISOString().slice(0, 10)}: ${fmt(p.v)}</title></circle>`).join("");
+    return `<path d="${d}" fill="none" stroke="${colour}" stroke-width="2"/>${dots}`;
+  };
+  const axis = [v0, (v0 + v1) / 2, v1].map((v) => `<text x="4" y="${y(v) + 4}">${fmt(v)}</text>`).join("")
+    + `<text x="${pad}" y="${H - 8}">${new Date(t0).toISOString().slice(0, 10)}</text>`
+    + `<text x="${W - pad}" y="${H - 8}" text-anchor="end">${new Date(t1).toISOString().slice(0, 10)}</text>`;
+  return `<svg class="chart" viewBox="0 0 ${W} ${H}" preserveAspectRatio="none">${axis}${line("price", "#2563eb")}${line("bid", "#dc2626")}</svg>
+    <p class="legend"><span style="color:#2563eb">■</span> listed price <span style="color:#dc2626">■</span> highest bid</p>`;
+}
+
+async function renderListing(view, id) {
+  view.innerHTML = "<p>Loading…</p>";
+  let p;
+  try {
+    p = (await gql(LISTING, { id })).listing;
+  } catch (e) {
+    view.innerHTML = `<p class="error">${esc(e.message)}</p>`;
+    return;
+  }
+  if (!p) {
+    view.innerHTML = "<p>No stored listing with that ID</p>";
+    return;
+  }
+  const row = (label, value) => value == null || value === "" ? "" : `<tr><td>${label}</td><td>${value}</td></tr>`;
+  const money = (v) => v == null ? null : `${fmt(v)} ${esc(p.currency)}`;
+  const facts = [
+    row("Price", p.comingSoon && p.price == null ? "Coming soon" : money(p.price)),
+    row("Asking price", p.askingPrice !== p.price ? money(p.askingPrice) : null),
+    row("Highest bid", p.currentBid != null ? money(p.currentBid) + (p.biddingInProgress ? " (bidding)" : "") : null),
+    row("Rooms", p.rooms), row("Size", p.sqm != null ? `${p.sqm} m²` : null), row("Floor", p.floor),
+    row("Price/m²", money(p.pricePerSqm)), row("Fee", money(p.monthlyFee)), row("Fee/m²", money(p.feePerSqm)),
+    row("Operating cost", money(p.operatingCost)), row("Monthly cost", money(p.monthlyCost)),
+    row("Score", p.score.toFixed(2)), row("Status", esc(p.status.toLowerCase().replace("_", " "))),
+    row("Rating", p.rating != null ? "★".repeat(p.rating) : null),
+    row("Underpriced", p.underpriced ? `${Math.round(p.underpriced.discount * 100)}% below ${esc(p.underpriced.cohort)} (${fmt(p.underpriced.cohortMedianPricePerSqm)}/m²)` : null),
+    row("Predicted price", p.prediction ? `${money(p.prediction.predictedPrice)} (${(p.prediction.deltaPct * 100).toFixed(1)}%)` : null),
+    row("Station", p.nearestStation ? `${esc(p.nearestStation.name)} (${esc(p.nearestStation.kind)}), ${fmt(p.nearestStation.walkingM)} m walk` : null),
+    row("Broker", p.broker ? esc([p.broker.name, p.broker.agency, p.broker.phone].filter(Boolean).join(", ")) : null),
+    row("First seen", day(p.firstSeen)), row("Last seen", day(p.lastSeen)),
+  ].join("");
+  const photos = p.images.slice(0, 12).concat(p.floorPlans).map((src) => `<img loading="lazy" src="${esc(src)}" alt="">`).join("");
+  const tours = p.virtualTours.map((url, i) => ` · <a href="${esc(url)}" target="_blank" rel="noopener">3D tour${p.virtualTours.length > 1 ? ` ${i + 1}` : ""}</a>`).join("");
+  const notes = p.notes.map((n) => `<li>${day(n.writtenAt)}: ${esc(n.text)}</li>`).join("");
+  let map = "";
+  if (p.latitude != null && p.longitude != null) {
+    const d = 0.006;
+    const bbox = [p.longitude - d, p.latitude - d / 2, p.longitude + d, p.latitude + d / 2].join(",");
+    map = `<h2>Map</h2><iframe width="100%" height="350" frameborder="0" src="https://www.openstreetmap.org/export/embed.html?bbox=${bbox}&amp;layer=mapnik&amp;marker=${p.latitude},${p.longitude}"></iframe>`;
+  }
+  view.innerHTML = `<div class="detail">
+    <p><a href="#/">← All listings</a></p>
+    <h1>${esc(p.address)}${p.favorite ? " ⭐" : ""}</h1>
+    <p>${esc([p.area, p.city].filter(Boolean).join(", "))} · <a href="${esc(p.url)}" target="_blank" rel="noopener">Listing</a>${tours}</p>
+    <div class="photos">${photos}</div>
+    <table class="facts">${facts}</table>
+    <h2>Price history</h2>${priceChart(p.priceHistory)}
+    ${notes ? `<h2>Notes</h2><ul>${notes}</ul>` : ""}
+    <h2>Description</h2><p>${esc(p.description).replace(/\n/g, "<br>")}</p>
+    ${map}
+  </div>`;
+}
+
+function route() {
+  const view = document.getElementById("view");
+  const hash = location.hash.replace(/^#\/?/, "");
+  const detail = hash.startsWith("listing/");
+  document.getElementById("filters").style.display = detail ? "none" : "";
+  document.querySelectorAll("header a[data-view]").forEach((a) => a.classList.toggle("active", a.dataset.view === (hash === "map" ? "map" : detail ? "" : "table")));
+  if (detail) renderListing(view, decodeURIComponent(hash.slice("listing/".length)));
+  else if (hash === "map") renderMap(view);
+  else renderTable(view);
+}
+
+let pending;
+document.querySelectorAll("#filters input").forEach((input) => input.addEventListener("input", () => {
+  if (input.id === "q") return route();
+  clearTimeout(pending);
+  pending = setTimeout(load, 400);
+}));
+window.addEventListener("hashchange", route);
+load();
+</script>
+</body>
+</html>
+"##;
@@ -0,0 +1,563 @@
+use crate::anomaly::Anomaly;
+use crate::cli::FilterArgs;
+use crate::config::Config;
+use crate::export::{self, filter_records, sort_records, ExportRecord, RecordContext};
+use crate::metrics::PropertyMetrics;
+use crate::models::{Money, Property};
+use crate::prediction::PricePrediction;
+use crate::stats::area_stats;
+use crate::storage::{Annotation, History, JsonStore, PropertyHistory, ScrapeRun};
+use async_graphql::connection::{self, Connection, Edge, EmptyFields};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Result, Schema, SimpleObject, ID};
+use chrono::{DateTime, Utc};
+
+/// Listings per page when neither `first` nor `last` is given
+const DEFAULT_PAGE: usize = 50;
+
+pub type ScoutSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The schema over `store`; every query reads the store afresh
+pub fn schema(store: JsonStore, config: Config) -> ScoutSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(store)
+        .data(config)
+        .finish()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Listings of the latest run, or with `all` of every stored run, as
+    /// `scout export` would select and order them
+    #[allow(clippy::too_many_arguments)]
+    async fn listings(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ListingFilter>,
+        sort: Option<ListingSort>,
+        #[graphql(default)] all: bool,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, Listing, ListingsInfo, EmptyFields>> {
+        let listings = load(ctx, all, filter, sort).await?;
+        let first = first.or_else(|| last.is_none().then_some(DEFAULT_PAGE as i32));
+        connection::query(after, before, first, last, |after, before, first, last| async move {
+            let total = listings.len();
+            let mut start = after.map(|after: usize| after + 1).unwrap_or(0).min(total);
+            let mut end = before.unwrap_or(total).min(total).max(start);
+            if let Some(first) = first {
+                end = end.min(start + first);
+            }
+            if let Some(last) = last {
+                start = start.max(end.saturating_sub(last));
+            }
+            let mut page = Connection::with_additional_fields(start > 0, end < total, ListingsInfo { total_count: total });
+            page.edges.extend(
+                listings
+                    .into_iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(end - start)
+                    .map(|(i, listing)| Edge::new(i, listing)),
+            );
+            Ok::<_, async_graphql::Error>(page)
+        })
+        .await
+    }
+
+    /// One listing by ID, as last stored
+    async fn listing(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Listing>> {
+        let filter = ListingFilter {
+            include_hidden: Some(true),
+            ..Default::default()
+        };
+        let listings = load(ctx, true, Some(filter), None).await?;
+        Ok(listings.into_iter().find(|listing| listing.property.id == *id))
+    }
+
+    /// Per-area aggregates over the last `days` days, as `scout stats` prints them
+    async fn areas(&self, ctx: &Context<'_>, #[graphql(default = 30)] days: i64, area: Option<String>) -> Result<Vec<AreaStatsNode>> {
+        let store = ctx.data::<JsonStore>()?;
+        let mut properties = store.load_all_properties().await?;
+        if let Some(area) = &area {
+            properties.retain(|p| p.location.area.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(area)));
+        }
+        let history = store.load_history().await?;
+        Ok(area_stats(&properties, &history, Utc::now(), days)
+            .into_iter()
+            .map(|stats| AreaStatsNode {
+                area: stats.area,
+                listings: stats.listings,
+                median_price: stats.median_price,
+                median_price_per_sqm: stats.median_price_per_sqm,
+                median_fee_per_sqm: stats.median_fee_per_sqm,
+                avg_days_on_market: stats.avg_days_on_market,
+                price_per_sqm_trend: stats.price_per_sqm_trend,
+            })
+            .collect())
+    }
+
+    /// The most recent scrape runs, newest first
+    async fn runs(&self, ctx: &Context<'_>, #[graphql(default = 20)] last: usize) -> Result<Vec<Run>> {
+        let mut runs = ctx.data::<JsonStore>()?.load_runs().await?;
+        runs.reverse();
+        runs.truncate(last);
+        Ok(runs.into_iter().map(Run).collect())
+    }
+}
+
+/// Records of the latest snapshot, or of every stored property, filtered and
+/// sorted like `scout export`
+async fn load(ctx: &Context<'_>, all: bool, filter: Option<ListingFilter>, sort: Option<ListingSort>) -> Result<Vec<Listing>> {
+    let store = ctx.data::<JsonStore>()?;
+    let config = ctx.data::<Config>()?;
+    let properties = if all {
+        store.load_all_properties().await?
+    } else {
+        store.load_latest_properties().await?.unwrap_or_default()
+    };
+    let mut history = store.load_history().await?;
+    let context = RecordContext::load(store, config, &properties).await?;
+
+    let mut records = export::records(&properties, config, &context);
+    filter_records(&mut records, &filter.unwrap_or_default().args()?, config)?;
+    if let Some(sort) = sort {
+        sort_records(&mut records, sort.field.into(), sort.descending);
+    }
+    Ok(records.iter().map(|record| Listing::new(record, &mut history)).collect())
+}
+
+/// Filters of `scout export`; every one given must hold
+#[derive(Debug, Default, InputObject)]
+pub struct ListingFilter {
+    max_price: Option<i64>,
+    min_sqm: Option<i32>,
+    min_rooms: Option<f32>,
+    max_price_per_sqm: Option<f64>,
+    max_fee_per_sqm: Option<f64>,
+    max_monthly_cost: Option<f64>,
+    /// Walking distance to the nearest station in meters
+    max_metro_distance: Option<f64>,
+    min_school_merit: Option<f64>,
+    max_noise: Option<f64>,
+    no_flood_risk: Option<bool>,
+    fiber: Option<bool>,
+    no_pipe_replacement: Option<bool>,
+    /// Longest time on the market, e.g. "3d"
+    max_age: Option<String>,
+    underpriced: Option<bool>,
+    favorites: Option<bool>,
+    include_hidden: Option<bool>,
+    status: Option<Vec<PipelineStatus>>,
+    /// Filter expression, e.g. `price < 5m && sqm >= 55`
+    expression: Option<String>,
+    /// A saved search from the config, by name
+    search: Option<String>,
+}
+
+impl ListingFilter {
+    fn args(self) -> Result<FilterArgs> {
+        Ok(FilterArgs {
+            max_price: self.max_price,
+            min_sqm: self.min_sqm,
+            min_rooms: self.min_rooms,
+            max_price_per_sqm: self.max_price_per_sqm,
+            max_fee_per_sqm: self.max_fee_per_sqm,
+            max_monthly_cost: self.max_monthly_cost,
+            max_metro_distance: self.max_metro_distance,
+            min_school_merit: self.min_school_merit,
+            max_noise: self.max_noise,
+            no_flood_risk: self.no_flood_risk.unwrap_or_default(),
+            fiber: self.fiber.unwrap_or_default(),
+            no_pipe_replacement: self.no_pipe_replacement.unwrap_or_default(),
+            max_age: self.max_age.as_deref().map(str::parse).transpose()?,
+            underpriced: self.underpriced.unwrap_or_default(),
+            favorites: self.favorites.unwrap_or_default(),
+            include_hidden: self.include_hidden.unwrap_or_default(),
+            status: self.status.unwrap_or_default().into_iter().map(Into::into).collect(),
+            filter: self.expression.as_deref().map(str::parse).transpose()?,
+            search: self.search,
+        })
+    }
+}
+
+#[derive(Debug, InputObject)]
+pub struct ListingSort {
+    field: SortField,
+    #[graphql(default)]
+    descending: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(remote = "crate::cli::SortKey")]
+pub enum SortField {
+    Price,
+    Sqm,
+    Rooms,
+    Fee,
+    PricePerSqm,
+    FeePerSqm,
+    MonthlyCost,
+    Score,
+    PredictedDelta,
+    MetroDistance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(remote = "crate::storage::Status")]
+pub enum PipelineStatus {
+    New,
+    Shortlisted,
+    ViewingBooked,
+    BidPlaced,
+    Rejected,
+    Won,
+}
+
+#[derive(SimpleObject)]
+pub struct ListingsInfo {
+    /// Listings matching the filter, on every page
+    total_count: usize,
+}
+
+/// A stored listing with everything `scout export` derives for it
+pub struct Listing {
+    property: Property,
+    metrics: PropertyMetrics,
+    score: f64,
+    anomaly: Option<Anomaly>,
+    prediction: Option<PricePrediction>,
+    annotation: Annotation,
+    history: Option<PropertyHistory>,
+}
+
+impl Listing {
+    fn new(record: &ExportRecord<'_>, history: &mut History) -> Self {
+        Self {
+            property: record.property.clone(),
+            metrics: record.metrics.clone(),
+            score: record.score.total,
+            anomaly: record.anomaly.clone(),
+            prediction: record.prediction.clone(),
+            annotation: record.annotation.clone().unwrap_or_default(),
+            history: history.properties.remove(&record.property.id),
+        }
+    }
+}
+
+fn major(money: Option<Money>) -> Option<i64> {
+    money.map(|money| money.major())
+}
+
+#[Object]
+impl Listing {
+    async fn id(&self) -> ID {
+        ID(self.property.id.clone())
+    }
+
+    async fn source(&self) -> String {
+        format!("{:?}", self.property.source)
+    }
+
+    async fn address(&self) -> &str {
+        &self.property.address
+    }
+
+    async fn city(&self) -> &str {
+        &self.property.location.city
+    }
+
+    async fn area(&self) -> Option<&str> {
+        self.property.location.area.as_deref()
+    }
+
+    async fn latitude(&self) -> Option<f64> {
+        self.property.location.latitude
+    }
+
+    async fn longitude(&self) -> Option<f64> {
+        self.property.location.longitude
+    }
+
+    /// Listed price in major units of `currency`
+    async fn price(&self) -> Option<i64> {
+        major(self.property.price)
+    }
+
+    async fn asking_price(&self) -> Option<i64> {
+        major(self.property.asking_price)
+    }
+
+    async fn current_bid(&self) -> Option<i64> {
+        major(self.property.current_bid)
+    }
+
+    async fn bidding_in_progress(&self) -> bool {
+        self.property.bidding_in_progress
+    }
+
+    async fn coming_soon(&self) -> bool {
+        self.property.listing_status == crate::models::ListingStatus::ComingSoon
+    }
+
+    async fn monthly_fee(&self) -> Option<i64> {
+        major(self.property.monthly_fee)
+    }
+
+    async fn operating_cost(&self) -> Option<i64> {
+        major(self.property.operating_cost)
+    }
+
+    async fn currency(&self) -> &str {
+        self.property.currency().code()
+    }
+
+    async fn rooms(&self) -> Option<f32> {
+        self.property.rooms
+    }
+
+    async fn sqm(&self) -> Option<i32> {
+        self.property.sqm
+    }
+
+    async fn floor(&self) -> Option<f32> {
+        self.property.floor
+    }
+
+    async fn description(&self) -> &str {
+        &self.property.description
+    }
+
+    /// Photo URLs in gallery order
+    async fn images(&self) -> Vec<&str> {
+        self.property.photos().map(|image| image.url.as_str()).collect()
+    }
+
+    async fn floor_plans(&self) -> Vec<&str> {
+        self.property.floor_plans().map(|image| image.url.as_str()).collect()
+    }
+
+    async fn virtual_tours(&self) -> &[String] {
+        &self.property.virtual_tours
+    }
+
+    async fn url(&self) -> &str {
+        &self.property.url
+    }
+
+    async fn scraped_at(&self) -> DateTime<Utc> {
+        self.property.scraped_at
+    }
+
+    async fn price_per_sqm(&self) -> Option<f64> {
+        self.metrics.price_per_sqm
+    }
+
+    async fn fee_per_sqm(&self) -> Option<f64> {
+        self.metrics.fee_per_sqm
+    }
+
+    /// Interest, amortization and monthly fee
+    async fn monthly_cost(&self) -> Option<f64> {
+        self.metrics.monthly_cost
+    }
+
+    /// Composite score, 0 - 1, relative to the other listings of the query
+    async fn score(&self) -> f64 {
+        self.score
+    }
+
+    async fn status(&self) -> PipelineStatus {
+        self.annotation.status.into()
+    }
+
+    async fn favorite(&self) -> bool {
+        self.annotation.favorite
+    }
+
+    async fn hidden(&self) -> bool {
+        self.annotation.hidden
+    }
+
+    async fn visited(&self) -> bool {
+        self.annotation.visited
+    }
+
+    async fn rating(&self) -> Option<u8> {
+        self.annotation.rating
+    }
+
+    async fn notes(&self) -> Vec<NoteNode> {
+        self.annotation
+            .notes
+            .iter()
+            .map(|note| NoteNode {
+                written_at: note.written_at,
+                text: note.text.clone(),
+            })
+            .collect()
+    }
+
+    /// Set when the listing is priced well below comparable listings
+    async fn underpriced(&self) -> Option<Underpriced> {
+        self.anomaly.as_ref().map(|anomaly| Underpriced {
+            cohort: anomaly.cohort.clone(),
+            cohort_size: anomaly.cohort_size,
+            cohort_median_price_per_sqm: anomaly.cohort_median_price_per_sqm,
+            discount: anomaly.discount,
+        })
+    }
+
+    /// Expected sale price, when a price model could be trained
+    async fn prediction(&self) -> Option<Prediction> {
+        self.prediction.as_ref().map(|prediction| Prediction {
+            predicted_price: prediction.predicted_price,
+            delta: prediction.delta,
+            delta_pct: prediction.delta_pct,
+        })
+    }
+
+    async fn broker(&self) -> Option<BrokerNode> {
+        self.property.broker.as_ref().map(|broker| BrokerNode {
+            name: broker.name.clone(),
+            agency: broker.agency.clone(),
+            phone: broker.phone.clone(),
+        })
+    }
+
+    async fn nearest_station(&self) -> Option<Station> {
+        self.property.nearest_station.as_ref().map(|station| Station {
+            name: station.name.clone(),
+            kind: format!("{:?}", station.kind).to_lowercase(),
+            walking_m: station.walking_m,
+        })
+    }
+
+    async fn first_seen(&self) -> Option<DateTime<Utc>> {
+        self.history.as_ref().map(|history| history.first_seen)
+    }
+
+    async fn last_seen(&self) -> Option<DateTime<Utc>> {
+        self.history.as_ref().map(|history| history.last_seen)
+    }
+
+    /// IDs of earlier listings of the same home, oldest first; first seen and
+    /// the price history count from the first of them
+    async fn relisted_from(&self) -> Vec<String> {
+        self.history.as_ref().map(|history| history.relisted_from.clone()).unwrap_or_default()
+    }
+
+    /// Every observed price and bidding change, oldest first
+    async fn price_history(&self) -> Vec<PricePoint> {
+        let Some(history) = &self.history else {
+            return Vec::new();
+        };
+        history
+            .observations
+            .iter()
+            .map(|observation| PricePoint {
+                observed_at: observation.observed_at,
+                price: major(observation.price),
+                asking_price: major(observation.asking_price),
+                current_bid: major(observation.current_bid),
+                bidding_in_progress: observation.bidding_in_progress,
+            })
+            .collect()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PricePoint {
+    observed_at: DateTime<Utc>,
+    price: Option<i64>,
+    asking_price: Option<i64>,
+    current_bid: Option<i64>,
+    bidding_in_progress: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct NoteNode {
+    written_at: DateTime<Utc>,
+    text: String,
+}
+
+#[derive(SimpleObject)]
+pub struct Underpriced {
+    /// e.g. "Södermalm 55-75 kvm"
+    cohort: String,
+    cohort_size: usize,
+    cohort_median_price_per_sqm: f64,
+    /// How far below the cohort median, 0 - 1
+    discount: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct Prediction {
+    predicted_price: i64,
+    /// Asking price minus predicted price
+    delta: i64,
+    delta_pct: f64,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "Broker")]
+pub struct BrokerNode {
+    name: Option<String>,
+    agency: Option<String>,
+    phone: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct Station {
+    name: String,
+    /// "tunnelbana" or "pendeltag"
+    kind: String,
+    walking_m: f64,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "AreaStats")]
+pub struct AreaStatsNode {
+    area: String,
+    listings: usize,
+    median_price: Option<f64>,
+    median_price_per_sqm: Option<f64>,
+    median_fee_per_sqm: Option<f64>,
+    avg_days_on_market: Option<f64>,
+    /// Relative change in median price per sqm against the period before
+    price_per_sqm_trend: Option<f64>,
+}
+
+/// A recorded scrape run
+pub struct Run(ScrapeRun);
+
+#[Object]
+impl Run {
+    async fn id(&self) -> ID {
+        ID(self.0.id.clone())
+    }
+
+    async fn started_at(&self) -> DateTime<Utc> {
+        self.0.started_at
+    }
+
+    async fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.0.finished_at
+    }
+
+    /// "running", "completed", "failed" or "aborted"
+    async fn status(&self) -> String {
+        format!("{:?}", self.0.status).to_lowercase()
+    }
+
+    async fn listings(&self) -> usize {
+        self.0.listings
+    }
+
+    async fn new_listings(&self) -> usize {
+        self.0.new_listings
+    }
+}
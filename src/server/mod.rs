@@ -0,0 +1,110 @@
+#[cfg(feature = "server")]
+pub mod auth;
+#[cfg(feature = "server")]
+pub mod dashboard;
+#[cfg(feature = "server")]
+pub mod graphql;
+
+use crate::secrets::Secret;
+use serde::{Deserialize, Serialize};
+
+/// `scout serve`: the stored data over HTTP, as a GraphQL API at /graphql
+/// and a web dashboard at /
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address to listen on; "0.0.0.0:8080" to reach it from other machines
+    pub bind: String,
+    /// Serve the web dashboard on GET /
+    pub dashboard: bool,
+    /// Serve the GraphiQL query editor on GET /graphql
+    pub graphiql: bool,
+    /// Bearer tokens accepted on API requests; anyone may query when empty
+    pub tokens: Vec<Secret>,
+    /// Requests a client IP may make per minute; unlimited when unset
+    pub requests_per_minute: Option<u32>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8080".to_string(),
+            dashboard: true,
+            graphiql: true,
+            tokens: Vec::new(),
+            requests_per_minute: None,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+pub use self::http::{router, serve};
+
+#[cfg(feature = "server")]
+mod http {
+    use super::auth::{self, Guard};
+    use super::{dashboard, graphql};
+    use crate::config::Config;
+    use crate::storage::JsonStore;
+    use anyhow::{Context, Result};
+    use async_graphql::http::GraphiQLSource;
+    use async_graphql_axum::GraphQL;
+    use axum::middleware;
+    use axum::response::Html;
+    use axum::routing::{get, on_service, post_service, MethodFilter};
+    use axum::Router;
+    use std::net::SocketAddr;
+    use tokio::net::TcpListener;
+    use tracing::{info, warn};
+
+    /// Routes of the server over `store`
+    ///
+    /// API requests go through the token check and rate limit; /health and the
+    /// dashboard and GraphiQL pages, which hold no data, don't.
+    pub fn router(store: JsonStore, config: &Config) -> Result<Router> {
+        let tokens = config
+            .server
+            .tokens
+            .iter()
+            .map(|token| token.resolve())
+            .collect::<Result<Vec<_>>>()?;
+        let guard = middleware::from_fn_with_state(Guard::new(&tokens, config.server.requests_per_minute), auth::guard);
+
+        let schema = graphql::schema(store, config.clone());
+        let graphql = if config.server.graphiql {
+            let page = GraphiQLSource::build().endpoint("/graphql").finish();
+            get(move || async move { Html(page) }).merge(post_service(GraphQL::new(schema)).layer(guard))
+        } else {
+            on_service(MethodFilter::GET.or(MethodFilter::POST), GraphQL::new(schema)).layer(guard)
+        };
+        let mut router = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/graphql", graphql);
+        if config.server.dashboard {
+            router = router.route("/", get(|| async { Html(dashboard::DASHBOARD) }));
+        }
+        Ok(router)
+    }
+
+    /// Serve `store` on `bind` until Ctrl-C
+    pub async fn serve(store: JsonStore, config: &Config, bind: &str) -> Result<()> {
+        let router = router(store, config)?;
+        let listener = TcpListener::bind(bind)
+            .await
+            .with_context(|| format!("Failed to listen on {}", bind))?;
+        let address = listener.local_addr()?;
+        if config.server.tokens.is_empty() && !address.ip().is_loopback() {
+            warn!("Serving on {} without [server] tokens; anyone who can reach it can query", address);
+        }
+        if config.server.dashboard {
+            info!("🌐 Serving the dashboard on http://{}/", address);
+        }
+        info!("🌐 Serving GraphQL on http://{}/graphql", address);
+        axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await
+            .context("Server failed")
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tracing::warn;
+
+/// Exit code after an interrupted run (128 + SIGINT, as shells report it)
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Shared flag set on the first Ctrl-C / SIGTERM
+///
+/// The browser scraper stops after the page it is on and closes Chrome, and
+/// the scrape command records the run as aborted. A second signal exits
+/// immediately. A [`child`](Self::child) is also set on its own, to stop one
+/// source.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    requested: AtomicBool,
+    notify: Notify,
+    parent: Option<Shutdown>,
+}
+
+impl Shutdown {
+    /// Start listening for SIGINT and SIGTERM
+    pub fn listen() -> Self {
+        let shutdown = Self::default();
+        let handle = shutdown.clone();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            warn!("Interrupted; finishing the current page (press Ctrl-C again to quit now)");
+            handle.request();
+            wait_for_signal().await;
+            std::process::exit(EXIT_INTERRUPTED);
+        });
+        shutdown
+    }
+
+    /// A flag requested along with this one, or on its own
+    pub fn child(&self) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                parent: Some(self.clone()),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    pub fn request(&self) {
+        self.inner.requested.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn requested(&self) -> bool {
+        self.inner.requested.load(Ordering::SeqCst) || self.inner.parent.as_ref().is_some_and(Shutdown::requested)
+    }
+
+    /// Resolve once a shutdown has been requested
+    pub async fn wait(&self) {
+        let notified = self.inner.notify.notified();
+        if self.requested() {
+            return;
+        }
+        match &self.inner.parent {
+            Some(parent) => tokio::select! {
+                _ = notified => {}
+                _ = Box::pin(parent.wait()) => {}
+            },
+            None => notified.await,
+        }
+    }
+}
+
+/// Returned by commands that stopped because of a shutdown request, so `main`
+/// can exit with [`EXIT_INTERRUPTED`]
+#[derive(Debug)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let Ok(mut term) = signal(SignalKind::terminate()) else {
+        let _ = tokio::signal::ctrl_c().await;
+        return;
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = term.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,116 @@
+use crate::models::Property;
+use crate::stats::median;
+use crate::storage::{on_market_since, History};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Listing aggregates for one area over one period
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaStats {
+    pub area: String,
+    /// Listings on the market at some point during the period
+    pub listings: usize,
+    pub median_price: Option<f64>,
+    pub median_price_per_sqm: Option<f64>,
+    pub median_fee_per_sqm: Option<f64>,
+    /// Mean days between first and last sighting, up to the end of the period
+    pub avg_days_on_market: Option<f64>,
+    /// Median price per sqm over the preceding period of the same length
+    pub previous_median_price_per_sqm: Option<f64>,
+    /// Relative change in median price per sqm vs the previous period
+    pub price_per_sqm_trend: Option<f64>,
+}
+
+/// Aggregate listings per area over the `days` days up to `now`, comparing
+/// against the `days` days before that
+///
+/// A listing counts towards a period when it was seen on the market during
+/// it; its price is the one observed last within the period.
+pub fn area_stats(properties: &[Property], history: &History, now: DateTime<Utc>, days: i64) -> Vec<AreaStats> {
+    let start = now - Duration::days(days);
+    let previous_start = start - Duration::days(days);
+
+    let mut groups: BTreeMap<&str, Vec<&Property>> = BTreeMap::new();
+    for property in properties {
+        let area = property.location.area.as_deref().unwrap_or("Unknown");
+        groups.entry(area).or_default().push(property);
+    }
+
+    let mut stats: Vec<AreaStats> = groups
+        .into_iter()
+        .filter_map(|(area, listings)| {
+            let mut current = Period::collect(&listings, history, start, now);
+            if current.listings == 0 {
+                return None;
+            }
+            let mut previous = Period::collect(&listings, history, previous_start, start);
+
+            let median_price_per_sqm = median(&mut current.price_per_sqm);
+            let previous_median = median(&mut previous.price_per_sqm);
+            let trend = match (median_price_per_sqm, previous_median) {
+                (Some(now), Some(before)) if before > 0.0 => Some(now / before - 1.0),
+                _ => None,
+            };
+            let days = &current.days_on_market;
+
+            Some(AreaStats {
+                area: area.to_string(),
+                listings: current.listings,
+                median_price: median(&mut current.prices),
+                median_price_per_sqm,
+                median_fee_per_sqm: median(&mut current.fee_per_sqm),
+                avg_days_on_market: (!days.is_empty()).then(|| days.iter().sum::<f64>() / days.len() as f64),
+                previous_median_price_per_sqm: previous_median,
+                price_per_sqm_trend: trend,
+            })
+        })
+        .collect();
+
+    stats.sort_by(|a, b| b.listings.cmp(&a.listings).then_with(|| a.area.cmp(&b.area)));
+    stats
+}
+
+/// Raw values of the listings seen during one period
+#[derive(Default)]
+struct Period {
+    listings: usize,
+    prices: Vec<f64>,
+    price_per_sqm: Vec<f64>,
+    fee_per_sqm: Vec<f64>,
+    days_on_market: Vec<f64>,
+}
+
+impl Period {
+    fn collect(listings: &[&Property], history: &History, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let mut period = Period::default();
+
+        for property in listings {
+            let entry = history.get(&property.id);
+            // A relisted listing's time on the market counts towards the new listing
+            if entry.is_some_and(|h| h.relisted_as.is_some()) {
+                continue;
+            }
+            let first_seen = on_market_since(property, entry);
+            let last_seen = entry.map(|h| h.last_seen).unwrap_or(property.scraped_at);
+            if last_seen < start || first_seen > end {
+                continue;
+            }
+
+            period.listings += 1;
+            if let Some(price) = entry.and_then(|h| h.price_at(end)).or(property.price) {
+                period.prices.push(price.as_f64());
+                if let Some(sqm) = property.sqm {
+                    period.price_per_sqm.push(price.as_f64() / sqm as f64);
+                }
+            }
+            if let (Some(fee), Some(sqm)) = (property.monthly_fee, property.sqm) {
+                period.fee_per_sqm.push(fee.as_f64() / sqm as f64);
+            }
+            let on_market = last_seen.min(end) - first_seen;
+            period.days_on_market.push(on_market.num_hours() as f64 / 24.0);
+        }
+
+        period
+    }
+}
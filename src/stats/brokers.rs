@@ -0,0 +1,90 @@
+use crate::models::Property;
+use crate::stats::median;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What to aggregate listings by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokerGrouping {
+    Agency,
+    Broker,
+}
+
+/// Listing statistics for one agency or broker
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerStats {
+    pub name: String,
+    /// Agency of a broker; empty when grouping by agency
+    pub agency: Option<String>,
+    pub listings: usize,
+    /// Share of all listings with a known broker, 0.0 - 1.0
+    pub share: f64,
+    pub median_price: Option<f64>,
+    pub median_price_per_sqm: Option<f64>,
+    pub bidding: usize,
+    /// Areas the listings are in, most common first
+    pub areas: Vec<String>,
+}
+
+/// Aggregate listings per agency or broker, largest first
+///
+/// Listings without broker information are ignored.
+pub fn broker_stats(properties: &[Property], grouping: BrokerGrouping) -> Vec<BrokerStats> {
+    let mut groups: BTreeMap<String, Vec<&Property>> = BTreeMap::new();
+
+    for property in properties {
+        let Some(broker) = &property.broker else {
+            continue;
+        };
+        let key = match grouping {
+            BrokerGrouping::Agency => broker.agency.clone(),
+            BrokerGrouping::Broker => broker.name.clone(),
+        };
+        if let Some(key) = key {
+            groups.entry(key).or_default().push(property);
+        }
+    }
+
+    let total: usize = groups.values().map(Vec::len).sum();
+
+    let mut stats: Vec<BrokerStats> = groups
+        .into_iter()
+        .map(|(name, listings)| {
+            let mut prices: Vec<f64> = listings.iter().filter_map(|p| p.price).map(|price| price.as_f64()).collect();
+            let mut per_sqm: Vec<f64> = listings
+                .iter()
+                .filter_map(|p| Some(p.price?.as_f64() / p.sqm? as f64))
+                .collect();
+
+            let mut area_counts: BTreeMap<String, usize> = BTreeMap::new();
+            for property in &listings {
+                if let Some(area) = &property.location.area {
+                    *area_counts.entry(area.clone()).or_default() += 1;
+                }
+            }
+            let mut areas: Vec<(String, usize)> = area_counts.into_iter().collect();
+            areas.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+            let agency = match grouping {
+                BrokerGrouping::Agency => None,
+                BrokerGrouping::Broker => listings
+                    .iter()
+                    .find_map(|p| p.broker.as_ref().and_then(|b| b.agency.clone())),
+            };
+
+            BrokerStats {
+                name,
+                agency,
+                listings: listings.len(),
+                share: listings.len() as f64 / total as f64,
+                median_price: median(&mut prices),
+                median_price_per_sqm: median(&mut per_sqm),
+                bidding: listings.iter().filter(|p| p.bidding_in_progress).count(),
+                areas: areas.into_iter().map(|(area, _)| area).collect(),
+            }
+        })
+        .collect();
+
+    stats.sort_by(|a, b| b.listings.cmp(&a.listings).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
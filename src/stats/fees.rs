@@ -0,0 +1,131 @@
+use crate::models::Property;
+use crate::stats::median;
+use crate::storage::History;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Fees are compared between the first and last this many days of an
+/// association's observations, and only when they are further apart than that
+const WINDOW_DAYS: i64 = 90;
+
+/// A yearly rise in fee per m² of at least this many percent is flagged
+pub const SHARP_RISE_PERCENT: f64 = 8.0;
+
+/// How one housing association's monthly fee developed, across its listings
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeTrend {
+    pub association: String,
+    /// Listings in the association with a fee and living area
+    pub listings: usize,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+    /// Median monthly fee per m² around the first and last dates
+    pub first_fee_per_sqm: f64,
+    pub last_fee_per_sqm: f64,
+    /// Change per year, in percent
+    pub yearly_change: Option<f64>,
+    /// Rising by at least [`SHARP_RISE_PERCENT`] a year
+    pub sharp_rise: bool,
+}
+
+/// Fees seen of one association
+struct Group {
+    name: String,
+    listings: usize,
+    /// Monthly fee per m² by date
+    fees: Vec<(NaiveDate, f64)>,
+}
+
+/// Fee trend of every association seen in `properties`, steepest rise first
+///
+/// A fee is comparable across apartments as fee per m². Each listing adds
+/// every fee its history observed, and the association's annual report adds
+/// one fee per year of its multi-year overview.
+pub fn fee_trends(properties: &[Property], history: &History) -> Vec<FeeTrend> {
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+
+    for property in properties {
+        let Some(association) = &property.association else {
+            continue;
+        };
+        let group = groups.entry(association.name.to_lowercase()).or_insert_with(|| Group {
+            name: association.name.clone(),
+            listings: 0,
+            fees: Vec::new(),
+        });
+
+        if let Some(sqm) = property.sqm.filter(|sqm| *sqm > 0) {
+            let observed: Vec<(NaiveDate, f64)> = match history.get(&property.id) {
+                Some(entry) => entry
+                    .observations
+                    .iter()
+                    .filter_map(|o| Some((o.observed_at.date_naive(), o.monthly_fee?.as_f64())))
+                    .collect(),
+                None => Vec::new(),
+            };
+            let observed = if observed.is_empty() {
+                property.monthly_fee.map(|fee| (property.scraped_at.date_naive(), fee.as_f64())).into_iter().collect()
+            } else {
+                observed
+            };
+            if !observed.is_empty() {
+                group.listings += 1;
+            }
+            group.fees.extend(observed.into_iter().map(|(date, fee)| (date, fee / sqm as f64)));
+        }
+
+        // Reports give the yearly fee per m², as of the end of the year
+        let reported = association.financials.iter().flat_map(|f| &f.fee_history);
+        for year in reported {
+            if let Some(date) = NaiveDate::from_ymd_opt(year.year, 12, 31) {
+                if !group.fees.iter().any(|(d, _)| *d == date) {
+                    group.fees.push((date, year.fee_per_sqm / 12.0));
+                }
+            }
+        }
+    }
+
+    let mut trends: Vec<FeeTrend> = groups
+        .into_values()
+        .filter_map(|Group { name, listings, mut fees }| {
+            fees.sort_by_key(|(date, _)| *date);
+            let (first_date, _) = *fees.first()?;
+            let (last_date, _) = *fees.last()?;
+            if last_date - first_date < Duration::days(WINDOW_DAYS) {
+                return None;
+            }
+            let mut first: Vec<f64> = fees
+                .iter()
+                .filter(|(date, _)| *date - first_date <= Duration::days(WINDOW_DAYS))
+                .map(|(_, fee)| *fee)
+                .collect();
+            let mut last: Vec<f64> = fees
+                .iter()
+                .filter(|(date, _)| last_date - *date <= Duration::days(WINDOW_DAYS))
+                .map(|(_, fee)| *fee)
+                .collect();
+            let first_fee_per_sqm = median(&mut first)?;
+            let last_fee_per_sqm = median(&mut last)?;
+
+            let years = (last_date - first_date).num_days() as f64 / 365.25;
+            let yearly_change = (first_fee_per_sqm > 0.0)
+                .then(|| ((last_fee_per_sqm / first_fee_per_sqm).powf(1.0 / years) - 1.0) * 100.0);
+            Some(FeeTrend {
+                association: name,
+                listings,
+                first_date,
+                last_date,
+                first_fee_per_sqm,
+                last_fee_per_sqm,
+                sharp_rise: yearly_change.is_some_and(|change| change >= SHARP_RISE_PERCENT),
+                yearly_change,
+            })
+        })
+        .collect();
+    trends.sort_by(|a, b| {
+        let rise = |t: &FeeTrend| t.yearly_change.unwrap_or(f64::NEG_INFINITY);
+        rise(b).total_cmp(&rise(a)).then_with(|| a.association.cmp(&b.association))
+    });
+    trends
+}
@@ -0,0 +1,24 @@
+pub mod areas;
+pub mod brokers;
+pub mod fees;
+pub mod premiums;
+
+pub use areas::{area_stats, AreaStats};
+pub use brokers::{broker_stats, BrokerGrouping, BrokerStats};
+pub use fees::{fee_trends, FeeTrend};
+pub use premiums::{match_sales, premium_stats, sale_premium, PremiumStats};
+
+/// Median of `values`, or None if there are none
+pub fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
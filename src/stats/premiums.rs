@@ -0,0 +1,109 @@
+use crate::models::{Property, SoldListing};
+use crate::relist::normalize_address;
+use crate::stats::median;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Largest difference in living area between a listing and a sale of it
+const SQM_TOLERANCE: i32 = 1;
+
+/// Final prices against asking prices for one area
+#[derive(Debug, Clone, Serialize)]
+pub struct PremiumStats {
+    pub area: String,
+    /// Sales with both a final and an asking price
+    pub sales: usize,
+    /// Of those, sales of listings scout tracked while on the market
+    pub tracked: usize,
+    /// Median final price over asking, in percent
+    pub median_premium: Option<f64>,
+    pub mean_premium: Option<f64>,
+    /// Share of sales above asking, 0.0 - 1.0
+    pub over_asking: f64,
+    pub median_sold_price: Option<f64>,
+}
+
+/// Final price over asking of one sale, in percent
+///
+/// The asking price is the last one of the tracked listing that sold when
+/// there is one, which catches price cuts the slutpriser page doesn't show.
+pub fn sale_premium(sold: &SoldListing, tracked: Option<&Property>) -> Option<f64> {
+    let asking = tracked
+        .and_then(|p| p.asking_price.or(p.price))
+        .map(|asking| asking.major())
+        .or(sold.asking_price)
+        .filter(|asking| *asking > 0)?;
+    Some((sold.sold_price - asking) as f64 / asking as f64 * 100.0)
+}
+
+/// Premiums per area of the sales on or after `since`, most sales first
+pub fn premium_stats(sold: &[SoldListing], properties: &[Property], since: Option<NaiveDate>) -> Vec<PremiumStats> {
+    let tracked: HashMap<&str, &Property> = properties.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    let mut groups: BTreeMap<String, Vec<(f64, i64, bool)>> = BTreeMap::new();
+    for sale in sold {
+        if since.is_some_and(|since| sale.sold_at.is_none_or(|at| at < since)) {
+            continue;
+        }
+        let listing = sale.property_id.as_deref().and_then(|id| tracked.get(id).copied());
+        let Some(premium) = sale_premium(sale, listing) else {
+            continue;
+        };
+        let area = sale
+            .area
+            .clone()
+            .or_else(|| listing.and_then(|p| p.location.area.clone()))
+            .unwrap_or_else(|| "Unknown".to_string());
+        groups.entry(area).or_default().push((premium, sale.sold_price, listing.is_some()));
+    }
+
+    let mut stats: Vec<PremiumStats> = groups
+        .into_iter()
+        .map(|(area, sales)| {
+            let mut premiums: Vec<f64> = sales.iter().map(|(premium, _, _)| *premium).collect();
+            let mut prices: Vec<f64> = sales.iter().map(|(_, price, _)| *price as f64).collect();
+            PremiumStats {
+                sales: sales.len(),
+                tracked: sales.iter().filter(|(_, _, tracked)| *tracked).count(),
+                mean_premium: Some(premiums.iter().sum::<f64>() / premiums.len() as f64),
+                over_asking: premiums.iter().filter(|p| **p > 0.0).count() as f64 / premiums.len() as f64,
+                median_premium: median(&mut premiums),
+                median_sold_price: median(&mut prices),
+                area,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.sales.cmp(&a.sales).then_with(|| a.area.cmp(&b.area)));
+    stats
+}
+
+/// Link sales to the listings in `pending` that sold, by normalised address
+/// and living area, returning how many were linked
+///
+/// A sale counts only when it is dated no earlier than the listing was first
+/// seen, so an earlier sale of the same apartment isn't taken for this one.
+pub fn match_sales(pending: &[(&Property, NaiveDate)], sold: &mut [SoldListing]) -> usize {
+    let mut linked = 0;
+    for (property, first_seen) in pending {
+        let address = normalize_address(&property.address);
+        if address.is_empty() {
+            continue;
+        }
+        let sale = sold
+            .iter_mut()
+            .filter(|sale| sale.property_id.is_none())
+            .filter(|sale| normalize_address(&sale.address) == address)
+            .filter(|sale| match (sale.sqm, property.sqm) {
+                (Some(a), Some(b)) => (a - b).abs() <= SQM_TOLERANCE,
+                _ => true,
+            })
+            .filter(|sale| sale.sold_at.is_none_or(|at| at >= *first_seen))
+            .min_by_key(|sale| sale.sold_at);
+        if let Some(sale) = sale {
+            sale.property_id = Some(property.id.clone());
+            linked += 1;
+        }
+    }
+    linked
+}
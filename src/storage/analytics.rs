@@ -0,0 +1,399 @@
+use serde::{Deserialize, Serialize};
+
+/// Which engine `scout query` and `scout stats` run on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    pub engine: QueryEngine,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryEngine {
+    /// In-memory SQLite, rebuilt from the JSON store on every command
+    #[default]
+    Sqlite,
+    /// DuckDB file kept next to the JSON store (data/scout.duckdb) and only
+    /// brought up to date with what changed; needs `--features duckdb`
+    Duckdb,
+}
+
+#[cfg(feature = "duckdb")]
+pub use self::duck::DuckIndex;
+
+#[cfg(feature = "duckdb")]
+mod duck {
+    use crate::models::{Money, Property};
+    use crate::stats::AreaStats;
+    use crate::storage::sql::SCHEMA;
+    use crate::storage::{snapshot_order, History, JsonStore, QueryResult};
+    use anyhow::{Context, Result};
+    use chrono::{DateTime, Duration, Utc};
+    use duckdb::types::Value as DuckValue;
+    use duckdb::{params, Connection};
+    use serde_json::Value;
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::Path;
+    use std::time::UNIX_EPOCH;
+    use tracing::{debug, info, instrument};
+
+    /// Bookkeeping next to the [`SCHEMA`] tables: the schema the file was built
+    /// with, the store files it holds and which snapshot each property came from
+    const INTERNAL: &str = "\
+CREATE TABLE _meta (schema TEXT);
+CREATE TABLE _files (name TEXT PRIMARY KEY, modified BIGINT, size BIGINT);
+CREATE TABLE _versions (id TEXT PRIMARY KEY, snapshot_order TEXT);
+CREATE TABLE _seen (id TEXT, first_seen TEXT, last_seen TEXT, relisted_as TEXT);
+CREATE TABLE _annotations (id TEXT, status TEXT, favorite BOOLEAN, hidden BOOLEAN, rating INTEGER);
+";
+
+    /// DuckDB copy of everything in a [`JsonStore`], persisted in data/scout.duckdb
+    ///
+    /// Opening it reads only the snapshots that changed since the last open,
+    /// and history, annotations and sold listings when their file did. A
+    /// listing a later run drops keeps the version seen last. The JSON files stay the source of truth: delete the file to rebuild it.
+    pub struct DuckIndex {
+        conn: Connection,
+    }
+
+    /// A store file and the modification time and size it was read at
+    struct FileState {
+        name: String,
+        modified: i64,
+        size: i64,
+    }
+
+    impl DuckIndex {
+        #[instrument(name = "duckdb", level = "debug", skip_all)]
+        pub async fn open(store: &JsonStore) -> Result<Self> {
+            tokio::fs::create_dir_all(store.root()).await?;
+            let path = store.root().join("scout.duckdb");
+            let conn = Connection::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let index = Self { conn };
+            index.ensure_schema()?;
+
+            let known: HashMap<String, (i64, i64)> = {
+                let mut statement = index.conn.prepare("SELECT name, modified, size FROM _files")?;
+                let rows = statement.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            let changed = |file: &FileState| known.get(&file.name) != Some(&(file.modified, file.size));
+
+            let mut updated = Vec::new();
+            for path in store.snapshot_paths().await? {
+                let file = file_state(store.root(), &path).await?;
+                if !changed(&file) {
+                    continue;
+                }
+                let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_string();
+                let properties = store.load_snapshot(&name).await?;
+                index.load_snapshot(&snapshot_order(&name), &properties)?;
+                updated.push(file);
+            }
+            let snapshots = updated.len();
+
+            let history = file_state(store.root(), &store.root().join("history.json")).await?;
+            if changed(&history) {
+                index.load_history(&store.load_history().await?)?;
+                updated.push(history);
+            }
+            let annotations = file_state(store.root(), &store.root().join("annotations.json")).await?;
+            if changed(&annotations) {
+                let annotations_data = store.load_annotations().await?;
+                index.conn.execute_batch("DELETE FROM _annotations")?;
+                let mut appender = index.conn.appender("_annotations")?;
+                for (id, annotation) in &annotations_data.properties {
+                    appender.append_row(params![
+                        id,
+                        serde_json::to_value(annotation.status)?.as_str(),
+                        annotation.favorite,
+                        annotation.hidden,
+                        annotation.rating,
+                    ])?;
+                }
+                drop(appender);
+                updated.push(annotations);
+            }
+            let sold = file_state(store.root(), &store.root().join("sold.json")).await?;
+            if changed(&sold) {
+                index.load_sold(store).await?;
+                updated.push(sold);
+            }
+
+            if !updated.is_empty() {
+                // Columns from history and annotations follow whichever changed
+                index.conn.execute_batch(
+                    "UPDATE properties SET first_seen = NULL, last_seen = NULL,
+                         status = 'new', favorite = 0, hidden = 0, rating = NULL;
+                     UPDATE properties SET first_seen = s.first_seen, last_seen = s.last_seen
+                         FROM _seen s WHERE properties.id = s.id;
+                     UPDATE properties SET status = a.status, favorite = a.favorite::INTEGER,
+                         hidden = a.hidden::INTEGER, rating = a.rating
+                         FROM _annotations a WHERE properties.id = a.id;",
+                )?;
+                let mut insert = index.conn.prepare("INSERT OR REPLACE INTO _files VALUES (?, ?, ?)")?;
+                for file in &updated {
+                    insert.execute(params![file.name, file.modified, file.size])?;
+                }
+                info!("🦆 Updated {} ({} snapshots read)", path.display(), snapshots);
+            }
+            Ok(index)
+        }
+
+        /// Create the tables, starting over when the file was built with another schema
+        fn ensure_schema(&self) -> Result<()> {
+            let built_with: Option<String> = self
+                .conn
+                .query_row("SELECT schema FROM _meta", [], |row| row.get(0))
+                .ok();
+            let schema = format!("{}{}", SCHEMA, INTERNAL);
+            if built_with.as_deref() == Some(schema.as_str()) {
+                return Ok(());
+            }
+            debug!("Building the DuckDB index from scratch");
+            for table in ["properties", "observations", "sold", "_meta", "_files", "_versions", "_seen", "_annotations"] {
+                self.conn.execute_batch(&format!("DROP TABLE IF EXISTS {}", table))?;
+            }
+            self.conn.execute_batch(SCHEMA)?;
+            self.conn.execute_batch(INTERNAL)?;
+            self.conn.execute("INSERT INTO _meta VALUES (?)", params![schema])?;
+            Ok(())
+        }
+
+        /// Take the properties of the snapshot ordered at `order` (see
+        /// [`snapshot_order`]), except those a later snapshot already holds a
+        /// newer version of
+        fn load_snapshot(&self, order: &str, properties: &[Property]) -> Result<()> {
+            self.conn.execute_batch("CREATE OR REPLACE TABLE _incoming AS SELECT * FROM properties LIMIT 0")?;
+            let mut appender = self.conn.appender("_incoming")?;
+            for p in properties {
+                let per_sqm = |value: Money| Some(value.as_f64() / p.sqm? as f64);
+                appender.append_row(params![
+                    p.id,
+                    format!("{:?}", p.source),
+                    p.address,
+                    p.location.city,
+                    p.location.area,
+                    p.location.latitude,
+                    p.location.longitude,
+                    p.price.map(|m| m.major()),
+                    p.asking_price.map(|m| m.major()),
+                    p.current_bid.map(|m| m.major()),
+                    i64::from(p.bidding_in_progress),
+                    p.monthly_fee.map(|m| m.major()),
+                    p.operating_cost.map(|m| m.major()),
+                    p.currency().code(),
+                    p.rooms.map(f64::from),
+                    p.sqm,
+                    p.floor.map(f64::from),
+                    p.price.and_then(per_sqm),
+                    p.monthly_fee.and_then(per_sqm),
+                    p.url,
+                    p.scraped_at.to_rfc3339(),
+                    p.run_id,
+                    p.published_at.map(|at| at.to_rfc3339()),
+                    None::<String>,
+                    None::<String>,
+                    "new",
+                    0,
+                    0,
+                    None::<i64>,
+                    serde_json::to_string(p)?,
+                ])?;
+            }
+            drop(appender);
+
+            // Not one transaction: DuckDB can't re-insert a key deleted in the
+            // same one. An interrupted load is redone, as the file isn't marked read.
+            self.conn.execute(
+                "DELETE FROM _incoming WHERE id IN (SELECT id FROM _versions WHERE snapshot_order > ?)",
+                params![order],
+            )?;
+            self.conn.execute_batch(
+                "DELETE FROM properties WHERE id IN (SELECT id FROM _incoming);
+                 INSERT INTO properties SELECT * FROM _incoming;",
+            )?;
+            self.conn.execute("INSERT OR REPLACE INTO _versions SELECT id, ? FROM _incoming", params![order])?;
+            self.conn.execute_batch("DROP TABLE _incoming")?;
+            Ok(())
+        }
+
+        fn load_history(&self, history: &History) -> Result<()> {
+            self.conn.execute_batch("DELETE FROM observations; DELETE FROM _seen;")?;
+            let mut observations = self.conn.appender("observations")?;
+            for (id, entry) in &history.properties {
+                for o in &entry.observations {
+                    observations.append_row(params![
+                        id,
+                        o.observed_at.to_rfc3339(),
+                        o.price.map(|m| m.major()),
+                        o.asking_price.map(|m| m.major()),
+                        o.current_bid.map(|m| m.major()),
+                        i64::from(o.bidding_in_progress),
+                    ])?;
+                }
+            }
+            drop(observations);
+            let mut seen = self.conn.appender("_seen")?;
+            for (id, entry) in &history.properties {
+                seen.append_row(params![
+                    id,
+                    entry.first_seen.to_rfc3339(),
+                    entry.last_seen.to_rfc3339(),
+                    entry.relisted_as
+                ])?;
+            }
+            Ok(())
+        }
+
+        async fn load_sold(&self, store: &JsonStore) -> Result<()> {
+            // Later entries replace earlier ones, as with the SQLite index
+            let sold: BTreeMap<_, _> = store.load_sold().await?.into_iter().map(|s| (s.id.clone(), s)).collect();
+            self.conn.execute_batch("DELETE FROM sold")?;
+            let mut appender = self.conn.appender("sold")?;
+            for s in sold.values() {
+                appender.append_row(params![
+                    s.id,
+                    s.address,
+                    s.area,
+                    s.rooms.map(f64::from),
+                    s.sqm,
+                    s.floor.map(f64::from),
+                    s.monthly_fee,
+                    s.asking_price,
+                    s.sold_price,
+                    s.sold_at.map(|d| d.to_string()),
+                    s.url,
+                    s.scraped_at.to_rfc3339(),
+                    s.property_id,
+                ])?;
+            }
+            Ok(())
+        }
+
+        /// Run one SQL statement and collect every row
+        pub fn query(&self, sql: &str) -> Result<QueryResult> {
+            let mut statement = self.conn.prepare(sql).context("Invalid query")?;
+            let mut cursor = statement.query([])?;
+            let columns: Vec<String> = cursor.as_ref().map(|s| s.column_names()).unwrap_or_default();
+
+            let mut rows = Vec::new();
+            while let Some(row) = cursor.next()? {
+                let mut values = Vec::with_capacity(columns.len());
+                for i in 0..columns.len() {
+                    values.push(json_value(row.get::<_, DuckValue>(i)?));
+                }
+                rows.push(values);
+            }
+            Ok(QueryResult { columns, rows })
+        }
+
+        /// [`area_stats`](crate::stats::area_stats) computed in DuckDB, for the
+        /// listings in `area` or all of them
+        pub fn area_stats(&self, area: Option<&str>, now: DateTime<Utc>, days: i64) -> Result<Vec<AreaStats>> {
+            let start = now - Duration::days(days);
+            let current = self.period(area, start, now)?;
+            let previous = self.period(area, start - Duration::days(days), start)?;
+
+            let mut stats: Vec<AreaStats> = current
+                .into_iter()
+                .map(|(area, period)| {
+                    let before = previous.get(&area).and_then(|p| p.median_price_per_sqm);
+                    let trend = match (period.median_price_per_sqm, before) {
+                        (Some(now), Some(before)) if before > 0.0 => Some(now / before - 1.0),
+                        _ => None,
+                    };
+                    AreaStats {
+                        previous_median_price_per_sqm: before,
+                        price_per_sqm_trend: trend,
+                        ..period
+                    }
+                })
+                .collect();
+            stats.sort_by(|a, b| b.listings.cmp(&a.listings).then_with(|| a.area.cmp(&b.area)));
+            Ok(stats)
+        }
+
+        /// Per-area figures of the listings on the market between `start` and `end`
+        fn period(&self, area: Option<&str>, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<BTreeMap<String, AreaStats>> {
+            let sql = "
+                WITH listed AS (
+                    SELECT p.id, coalesce(p.area, 'Unknown') AS area, p.sqm, p.monthly_fee,
+                           least(coalesce(p.first_seen, p.scraped_at)::TIMESTAMP,
+                                 coalesce(p.published_at, p.first_seen, p.scraped_at)::TIMESTAMP) AS first_seen,
+                           coalesce(p.last_seen, p.scraped_at)::TIMESTAMP AS last_seen,
+                           coalesce((SELECT o.price FROM observations o
+                                     WHERE o.property_id = p.id AND o.observed_at::TIMESTAMP <= $3::TIMESTAMP
+                                     ORDER BY o.observed_at::TIMESTAMP DESC LIMIT 1), p.price) AS price
+                    FROM properties p
+                    WHERE ($1::TEXT IS NULL OR lower(p.area) = lower($1::TEXT))
+                      AND p.id NOT IN (SELECT id FROM _seen WHERE relisted_as IS NOT NULL)
+                ), seen AS (
+                    SELECT * FROM listed
+                    WHERE last_seen >= $2::TIMESTAMP AND first_seen <= $3::TIMESTAMP
+                )
+                SELECT area, count(*),
+                       median(price::DOUBLE),
+                       median(price::DOUBLE / sqm),
+                       median(monthly_fee::DOUBLE / sqm),
+                       avg(trunc((epoch(least(last_seen, $3::TIMESTAMP)) - epoch(first_seen)) / 3600) / 24)
+                FROM seen GROUP BY area";
+            let mut statement = self.conn.prepare(sql)?;
+            let rows = statement.query_map(params![area, start.to_rfc3339(), end.to_rfc3339()], |row| {
+                Ok(AreaStats {
+                    area: row.get(0)?,
+                    listings: row.get::<_, i64>(1)? as usize,
+                    median_price: row.get(2)?,
+                    median_price_per_sqm: row.get(3)?,
+                    median_fee_per_sqm: row.get(4)?,
+                    avg_days_on_market: row.get(5)?,
+                    previous_median_price_per_sqm: None,
+                    price_per_sqm_trend: None,
+                })
+            })?;
+            let mut periods = BTreeMap::new();
+            for stats in rows {
+                let stats = stats?;
+                periods.insert(stats.area.clone(), stats);
+            }
+            Ok(periods)
+        }
+    }
+
+    async fn file_state(root: &Path, path: &Path) -> Result<FileState> {
+        let name = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+        let (modified, size) = match tokio::fs::metadata(path).await {
+            Ok(metadata) => {
+                let modified = metadata
+                    .modified()?
+                    .duration_since(UNIX_EPOCH)
+                    .map(|since| since.as_nanos() as i64)
+                    .unwrap_or_default();
+                (modified, metadata.len() as i64)
+            }
+            Err(_) => (0, 0),
+        };
+        Ok(FileState { name, modified, size })
+    }
+
+    fn json_value(value: DuckValue) -> Value {
+        match value {
+            DuckValue::Null => Value::Null,
+            DuckValue::Boolean(b) => Value::from(b),
+            DuckValue::TinyInt(n) => Value::from(n),
+            DuckValue::SmallInt(n) => Value::from(n),
+            DuckValue::Int(n) => Value::from(n),
+            DuckValue::BigInt(n) => Value::from(n),
+            DuckValue::HugeInt(n) => Value::from(n as f64),
+            DuckValue::UTinyInt(n) => Value::from(n),
+            DuckValue::USmallInt(n) => Value::from(n),
+            DuckValue::UInt(n) => Value::from(n),
+            DuckValue::UBigInt(n) => Value::from(n),
+            DuckValue::Float(x) => Value::from(x),
+            DuckValue::Double(x) => Value::from(x),
+            DuckValue::Text(text) => Value::from(text),
+            DuckValue::Blob(blob) => Value::from(format!("<{} bytes>", blob.len())),
+            other => Value::from(format!("{:?}", other)),
+        }
+    }
+}
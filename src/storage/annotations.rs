@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Where a property is in the house-hunting pipeline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    New,
+    Shortlisted,
+    ViewingBooked,
+    BidPlaced,
+    Rejected,
+    Won,
+}
+
+impl Status {
+    /// Rejected and won properties need no further action
+    pub fn is_closed(self) -> bool {
+        matches!(self, Status::Rejected | Status::Won)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::New => "New",
+            Status::Shortlisted => "Shortlisted",
+            Status::ViewingBooked => "Viewing booked",
+            Status::BidPlaced => "Bid placed",
+            Status::Rejected => "Rejected",
+            Status::Won => "Won",
+        })
+    }
+}
+
+/// A pipeline status change
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct StatusChange {
+    pub changed_at: DateTime<Utc>,
+    pub status: Status,
+}
+
+/// A free-text note on a property
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Note {
+    pub written_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// The user's own marks on a property
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default)]
+pub struct Annotation {
+    pub status: Status,
+    /// Every status change, oldest first
+    pub status_history: Vec<StatusChange>,
+    pub favorite: bool,
+    /// Hidden listings are left out of exports, rankings and notifications
+    pub hidden: bool,
+    pub visited: bool,
+    /// Personal rating, 1 - 5
+    pub rating: Option<u8>,
+    pub notes: Vec<Note>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Move to `status`, recording the change; returns false if already there
+    pub fn set_status(&mut self, status: Status) -> bool {
+        if self.status == status {
+            return false;
+        }
+        self.status = status;
+        self.status_history.push(StatusChange {
+            changed_at: Utc::now(),
+            status,
+        });
+        true
+    }
+}
+
+/// User annotations per property ID (data/annotations.json)
+///
+/// Kept apart from the run snapshots so they survive every re-scrape.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Annotations {
+    pub properties: BTreeMap<String, Annotation>,
+}
+
+impl Annotations {
+    pub fn get(&self, id: &str) -> Option<&Annotation> {
+        self.properties.get(id)
+    }
+
+    pub fn entry(&mut self, id: &str) -> &mut Annotation {
+        self.properties.entry(id.to_string()).or_default()
+    }
+
+    pub fn is_hidden(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|a| a.hidden)
+    }
+
+    pub fn status(&self, id: &str) -> Status {
+        self.get(id).map(|a| a.status).unwrap_or_default()
+    }
+
+    /// Drop annotations that no longer carry anything
+    pub fn prune(&mut self) {
+        self.properties.retain(|_, annotation| !annotation.is_empty());
+    }
+}
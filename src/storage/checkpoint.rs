@@ -0,0 +1,205 @@
+use crate::models::Property;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{instrument, warn};
+
+/// Progress of one scrape run (data/checkpoints/<run-id>.json)
+///
+/// Written after every search page so `scout scrape --resume` can pick up
+/// where a crashed or interrupted run stopped; the file is replaced whole, so
+/// a crash mid-write leaves the previous one. Listings whose detail page was
+/// fetched are appended one per line to `<run-id>.enriched.jsonl` instead,
+/// which keeps detail pages from rewriting everything. Saving is synchronous
+/// because the browser scraper records progress from blocking threads.
+pub struct Checkpoint {
+    path: PathBuf,
+    progress: Mutex<Progress>,
+    /// `enriched` journal, opened on the first append
+    journal: Mutex<Option<File>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    /// Listings found per search area, by search name
+    #[serde(default)]
+    pages: BTreeMap<String, Vec<Property>>,
+    /// Listings with their detail page already fetched, by listing ID; kept
+    /// in the journal, but read from checkpoints written before it
+    #[serde(default, skip_serializing)]
+    enriched: BTreeMap<String, Property>,
+    /// Merged listings of sources that finished, by source name
+    #[serde(default)]
+    sources: BTreeMap<String, Vec<Property>>,
+}
+
+impl Checkpoint {
+    /// Open the checkpoint at `path`, starting empty when there is none yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut progress: Progress = match read(&path)? {
+            Some(json) => serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))?,
+            None => Progress::default(),
+        };
+        let journal = journal_path(&path);
+        for property in read_journal(&journal)? {
+            progress.enriched.insert(property.id.clone(), property);
+        }
+        Ok(Self {
+            path,
+            progress: Mutex::new(progress),
+            journal: Mutex::new(None),
+        })
+    }
+
+    /// Listings already scraped for a search area
+    pub fn page(&self, search: &str) -> Option<Vec<Property>> {
+        self.lock().pages.get(search).cloned()
+    }
+
+    pub fn record_page(&self, search: &str, properties: &[Property]) {
+        self.update(|progress| {
+            progress.pages.insert(search.to_string(), properties.to_vec());
+        });
+    }
+
+    /// A listing as it was after its detail page was fetched
+    pub fn enriched(&self, id: &str) -> Option<Property> {
+        self.lock().enriched.get(id).cloned()
+    }
+
+    pub fn record_enriched(&self, property: &Property) {
+        if let Err(e) = self.append(property) {
+            warn!("Failed to save checkpoint: {:#}", e);
+        }
+        self.lock().enriched.insert(property.id.clone(), property.clone());
+    }
+
+    /// Everything a source returned, when it already finished
+    pub fn source(&self, name: &str) -> Option<Vec<Property>> {
+        self.lock().sources.get(name).cloned()
+    }
+
+    pub fn record_source(&self, name: &str, properties: &[Property]) {
+        self.update(|progress| {
+            progress.sources.insert(name.to_string(), properties.to_vec());
+        });
+    }
+
+    /// Delete the checkpoint once the run no longer needs it
+    pub fn remove(&self) -> Result<()> {
+        *self.journal.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        for path in [self.path.clone(), journal_path(&self.path)] {
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Progress> {
+        self.progress.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Apply `change` and write the checkpoint; a failed write only costs
+    /// progress on resume, so it is logged rather than returned
+    fn update(&self, change: impl FnOnce(&mut Progress)) {
+        let mut progress = self.lock();
+        change(&mut progress);
+        if let Err(e) = self.save(&progress) {
+            warn!("Failed to save checkpoint: {:#}", e);
+        }
+    }
+
+    /// Write the checkpoint next to the old one and rename it into place
+    #[instrument(name = "write", level = "debug", skip_all, fields(path = %self.path.display()))]
+    fn save(&self, progress: &Progress) -> Result<()> {
+        self.create_dir()?;
+        let json = serde_json::to_string(progress)?;
+        let mut partial = OsString::from(&self.path);
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        std::fs::write(&partial, json).with_context(|| format!("Failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, &self.path).with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Add one enriched listing to the end of the journal
+    fn append(&self, property: &Property) -> Result<()> {
+        let mut line = serde_json::to_vec(property)?;
+        line.push(b'\n');
+        let path = journal_path(&self.path);
+        let mut journal = self.journal.lock().unwrap_or_else(|e| e.into_inner());
+        let file = match journal.as_mut() {
+            Some(file) => file,
+            None => {
+                self.create_dir()?;
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                journal.insert(file)
+            }
+        };
+        file.write_all(&line).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn create_dir(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// `<run-id>.enriched.jsonl` beside `<run-id>.json`
+fn journal_path(path: &Path) -> PathBuf {
+    path.with_extension("enriched.jsonl")
+}
+
+/// The listings in the journal at `path`
+///
+/// A line cut short by a crash is dropped from the file too, so the next
+/// append starts on a line of its own.
+fn read_journal(path: &Path) -> Result<Vec<Property>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let complete = bytes.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+    if complete < bytes.len() {
+        warn!("Dropping a line cut short at the end of {}", path.display());
+        OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_len(complete as u64))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    let mut properties = Vec::new();
+    for line in bytes[..complete].split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+        match serde_json::from_slice::<Property>(line) {
+            Ok(property) => properties.push(property),
+            Err(e) => warn!("Skipping unreadable line in {}: {}", path.display(), e),
+        }
+    }
+    Ok(properties)
+}
+
+/// A file's contents, or None when it doesn't exist
+fn read(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
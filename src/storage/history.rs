@@ -0,0 +1,179 @@
+use crate::models::{zero_as_none, Money, Property};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Price, bidding state and fee of a property at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Observation {
+    pub observed_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "zero_as_none")]
+    pub price: Option<Money>,
+    pub asking_price: Option<Money>,
+    pub current_bid: Option<Money>,
+    pub bidding_in_progress: bool,
+    #[serde(default)]
+    pub monthly_fee: Option<Money>,
+}
+
+impl Observation {
+    fn from_property(property: &Property) -> Self {
+        Self {
+            observed_at: property.scraped_at,
+            price: property.price,
+            asking_price: property.asking_price,
+            current_bid: property.current_bid,
+            bidding_in_progress: property.bidding_in_progress,
+            monthly_fee: property.monthly_fee,
+        }
+    }
+
+    /// Whether anything but the timestamp differs from `other`
+    fn same_state(&self, other: &Observation) -> bool {
+        self.price == other.price
+            && self.asking_price == other.asking_price
+            && self.current_bid == other.current_bid
+            && self.bidding_in_progress == other.bidding_in_progress
+            && self.monthly_fee == other.monthly_fee
+    }
+}
+
+/// Everything we know about one property across runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyHistory {
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// One entry per observed change, oldest first
+    pub observations: Vec<Observation>,
+    /// Earlier listings of the same home, oldest first; their time on the
+    /// market and observations are carried into this history
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relisted_from: Vec<String>,
+    /// The listing this one was put up again as
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relisted_as: Option<String>,
+}
+
+impl PropertyHistory {
+    /// Distinct bids seen over time, oldest first
+    pub fn bid_progression(&self) -> Vec<(DateTime<Utc>, Money)> {
+        let mut bids: Vec<(DateTime<Utc>, Money)> = Vec::new();
+        for observation in &self.observations {
+            if let Some(bid) = observation.current_bid {
+                if bids.last().map(|(_, last)| *last) != Some(bid) {
+                    bids.push((observation.observed_at, bid));
+                }
+            }
+        }
+        bids
+    }
+
+    /// Listed price as last observed at or before `at`
+    pub fn price_at(&self, at: DateTime<Utc>) -> Option<Money> {
+        self.observations
+            .iter()
+            .rev()
+            .find(|observation| observation.observed_at <= at)
+            .and_then(|observation| observation.price)
+    }
+}
+
+/// When `property` went on the market: the earlier of its publication date
+/// and when it was first seen, which for a relisting is when the first
+/// listing of the home was
+pub fn on_market_since(property: &Property, history: Option<&PropertyHistory>) -> DateTime<Utc> {
+    let first_seen = history.map(|h| h.first_seen).unwrap_or(property.scraped_at);
+    property.published_at.map_or(first_seen, |published| published.min(first_seen))
+}
+
+/// Run-over-run history of every property we have scraped (data/history.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    pub properties: BTreeMap<String, PropertyHistory>,
+}
+
+impl History {
+    /// Record the properties of a run, appending an observation only when
+    /// price, bidding state or fee changed since the last one
+    pub fn record(&mut self, properties: &[Property]) {
+        for property in properties {
+            let observation = Observation::from_property(property);
+
+            match self.properties.get_mut(&property.id) {
+                Some(entry) => {
+                    entry.last_seen = entry.last_seen.max(property.scraped_at);
+                    let changed = entry
+                        .observations
+                        .last()
+                        .map(|last| !last.same_state(&observation))
+                        .unwrap_or(true);
+                    if changed {
+                        entry.observations.push(observation);
+                    }
+                }
+                None => {
+                    self.properties.insert(
+                        property.id.clone(),
+                        PropertyHistory {
+                            first_seen: property.scraped_at,
+                            last_seen: property.scraped_at,
+                            observations: vec![observation],
+                            relisted_from: Vec::new(),
+                            relisted_as: None,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Merge observations from any point in time, e.g. imported old runs,
+    /// keeping each history in order and collapsing repeated states
+    pub fn backfill(&mut self, properties: &[Property]) {
+        for property in properties {
+            let observation = Observation::from_property(property);
+            let entry = self.properties.entry(property.id.clone()).or_insert_with(|| PropertyHistory {
+                first_seen: property.scraped_at,
+                last_seen: property.scraped_at,
+                observations: Vec::new(),
+                relisted_from: Vec::new(),
+                relisted_as: None,
+            });
+            entry.first_seen = entry.first_seen.min(property.scraped_at);
+            entry.last_seen = entry.last_seen.max(property.scraped_at);
+            entry.observations.push(observation);
+            entry.observations.sort_by_key(|o| o.observed_at);
+            entry.observations.dedup_by(|later, earlier| later.same_state(earlier));
+        }
+    }
+
+    /// Continue the history of listing `from` as that of `to`, a relisting
+    /// of the same home: `to` starts out with everything seen of `from`, so
+    /// its days on market and price history count from the first listing
+    pub fn link_relist(&mut self, from: &str, to: &str) {
+        let Some(earlier) = self.properties.get_mut(from) else {
+            return;
+        };
+        earlier.relisted_as = Some(to.to_string());
+        let mut chain = earlier.relisted_from.clone();
+        chain.push(from.to_string());
+        let mut carried = PropertyHistory {
+            relisted_from: chain,
+            relisted_as: None,
+            ..earlier.clone()
+        };
+
+        if let Some(own) = self.properties.remove(to) {
+            carried.first_seen = carried.first_seen.min(own.first_seen);
+            carried.last_seen = carried.last_seen.max(own.last_seen);
+            carried.observations.extend(own.observations);
+            carried.observations.sort_by_key(|o| o.observed_at);
+            carried.observations.dedup_by(|later, earlier| later.same_state(earlier));
+        }
+        self.properties.insert(to.to_string(), carried);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&PropertyHistory> {
+        self.properties.get(id)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::models::Property;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Listings read from old `scout scrape` output files
+#[derive(Debug, Default)]
+pub struct LegacyImport {
+    pub properties: Vec<Property>,
+    pub files: usize,
+    /// Files that weren't scrape output, with why
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Read every JSON file under `dir`, e.g. `scraped_properties.json` and
+/// `raw_scrape/<id>.json`
+pub async fn read_legacy_dir(dir: &Path) -> Result<LegacyImport> {
+    let mut import = LegacyImport::default();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir)
+            .await
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                match read_legacy_file(&path).await {
+                    Ok(properties) => {
+                        import.files += 1;
+                        import.properties.extend(properties);
+                    }
+                    Err(e) => import.skipped.push((path, format!("{:#}", e))),
+                }
+            }
+        }
+    }
+
+    Ok(import)
+}
+
+async fn read_legacy_file(path: &Path) -> Result<Vec<Property>> {
+    let json = tokio::fs::read_to_string(path).await?;
+    let value: Value = serde_json::from_str(&json).context("Not valid JSON")?;
+    // Files without a timestamp were written when they were last modified
+    let modified: DateTime<Utc> = tokio::fs::metadata(path).await?.modified()?.into();
+    parse_legacy(value, modified)
+}
+
+/// Parse a property, an array of them or `{"properties": [...]}`, upgrading
+/// older shapes to the current model
+///
+/// Older outputs may lack `raw_data`, `scraped_at` (taken from `fallback`) or
+/// the nested `location`, write the source in lowercase, and carry numbers as
+/// display strings such as "5 195 000 kr" or "2,5 rum".
+pub fn parse_legacy(value: Value, fallback: DateTime<Utc>) -> Result<Vec<Property>> {
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(mut object) => match object.remove("properties") {
+            Some(Value::Array(items)) => items,
+            Some(other) => {
+                object.insert("properties".to_string(), other);
+                vec![Value::Object(object)]
+            }
+            None => vec![Value::Object(object)],
+        },
+        _ => anyhow::bail!("Expected a property or an array of properties"),
+    };
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let Value::Object(object) = item else {
+                anyhow::bail!("Item {} is not an object", i);
+            };
+            let upgraded = upgrade(object, fallback);
+            serde_json::from_value(Value::Object(upgraded)).with_context(|| format!("Item {} is not a property", i))
+        })
+        .collect()
+}
+
+fn upgrade(mut p: Map<String, Value>, fallback: DateTime<Utc>) -> Map<String, Value> {
+    if let Some(Value::Number(n)) = p.get("id") {
+        let id = n.to_string();
+        p.insert("id".to_string(), json!(id));
+    }
+
+    let source = p.get("source").and_then(Value::as_str).unwrap_or("booli").to_lowercase();
+    if source == "booli" {
+        p.insert("source".to_string(), json!("Booli"));
+    }
+
+    if !p.get("location").is_some_and(Value::is_object) {
+        let location = json!({
+            "city": take_str(&mut p, &["city"]).unwrap_or_else(|| "Stockholm".to_string()),
+            "area": take_str(&mut p, &["area", "location"]),
+            "latitude": take_f64(&mut p, &["latitude", "lat"]),
+            "longitude": take_f64(&mut p, &["longitude", "lng", "lon"]),
+        });
+        p.insert("location".to_string(), location);
+    }
+
+    for (field, integer) in [("price", true), ("sqm", true), ("rooms", false)] {
+        if let Some(Value::String(text)) = p.get(field) {
+            let number = parse_number(text);
+            p.insert(field.to_string(), number_value(number, integer));
+        }
+    }
+
+    for (field, default) in [
+        ("address", json!("")),
+        ("description", json!("")),
+        ("url", json!("")),
+        ("features", json!([])),
+        ("images", json!([])),
+        ("raw_data", json!({})),
+    ] {
+        if p.get(field).is_none_or(Value::is_null) {
+            p.insert(field.to_string(), default);
+        }
+    }
+    if !p.contains_key("scraped_at") {
+        p.insert("scraped_at".to_string(), json!(fallback));
+    }
+    p
+}
+
+fn take_str(p: &mut Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| p.remove(*key))
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+fn take_f64(p: &mut Map<String, Value>, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| p.remove(*key)).and_then(|value| match value {
+        Value::String(text) => text.replace(',', ".").trim().parse().ok(),
+        other => other.as_f64(),
+    })
+}
+
+/// "5 195 000 kr" -> 5195000, "2,5 rum" -> 2.5
+fn parse_number(text: &str) -> Option<f64> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == ',' || *c == '.')
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    cleaned.trim_matches('.').parse().ok()
+}
+
+fn number_value(number: Option<f64>, integer: bool) -> Value {
+    match number {
+        Some(n) if integer => json!(n.round() as i64),
+        Some(n) => json!(n),
+        None => Value::Null,
+    }
+}
@@ -0,0 +1,403 @@
+use crate::models::{Property, SoldListing};
+use crate::notify::{Ledger, Outbox};
+use crate::storage::{Annotations, Checkpoint, History, ScrapeRun};
+use crate::watch::Watchlist;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tracing::{instrument, warn};
+
+/// JSON file storage rooted at a data directory
+///
+/// Layout:
+/// - `properties/<run-id>.json` — properties scraped by that run
+///   (`<date>.json` for a day's listings imported, or stored before runs had
+///   snapshots of their own)
+/// - `history.json` — run-over-run observations per property
+/// - `sold.json` — final prices of sold listings
+/// - `runs.json` — parameters and per-source outcome of every scrape run
+/// - `checkpoints/<run-id>.json` — progress of runs that haven't completed
+///   (with `<run-id>.enriched.jsonl`, their listings with detail pages fetched)
+/// - `annotations.json` — pipeline status, favorites, hidden listings, notes and ratings
+/// - `notifications.json` — notifications sent, per listing and channel
+/// - `outbox.json` — notifications waiting for their channel's next delivery
+/// - `watchlist.json` — listings `scout watch` checks, as of their last check
+/// - `cache/<name>.json` — cached lookups from external data sources
+pub struct JsonStore {
+    root: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Save the properties a run scraped as its own snapshot
+    pub async fn save_properties(&self, run_id: &str, properties: &[Property]) -> Result<PathBuf> {
+        self.write_snapshot(run_id, properties).await
+    }
+
+    /// Save the snapshot of one day, replacing what was stored for it; for
+    /// listings imported from elsewhere, which belong to no run
+    pub async fn save_snapshot(&self, date: NaiveDate, properties: &[Property]) -> Result<PathBuf> {
+        self.write_snapshot(&date.format("%Y-%m-%d").to_string(), properties).await
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(snapshot = name, listings = properties.len()))]
+    async fn write_snapshot(&self, name: &str, properties: &[Property]) -> Result<PathBuf> {
+        let dir = self.root.join("properties");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let path = dir.join(format!("{}.json", name));
+        write_json_array(&path, properties).await?;
+        Ok(path)
+    }
+
+    /// Load the most recent properties snapshot, if any run has been stored
+    pub async fn load_latest_properties(&self) -> Result<Option<Vec<Property>>> {
+        let Some(path) = self.snapshot_paths().await?.pop() else {
+            return Ok(None);
+        };
+
+        Ok(Some(read_snapshot(&path).await?))
+    }
+
+    /// Load one snapshot, named by its run ID, or by its date (YYYY-MM-DD)
+    /// for a day snapshot
+    pub async fn load_snapshot(&self, name: &str) -> Result<Vec<Property>> {
+        let path = self.root.join("properties").join(format!("{}.json", name));
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            anyhow::bail!("No stored snapshot {}", name);
+        }
+        read_snapshot(&path).await
+    }
+
+    /// Load every property ever stored, keeping the most recent version of each
+    pub async fn load_all_properties(&self) -> Result<Vec<Property>> {
+        let mut latest: BTreeMap<String, Property> = BTreeMap::new();
+
+        for path in self.snapshot_paths().await? {
+            for property in read_snapshot(&path).await? {
+                latest.insert(property.id.clone(), property);
+            }
+        }
+
+        Ok(latest.into_values().collect())
+    }
+
+    /// Paths of all stored snapshots, oldest first
+    pub async fn snapshot_paths(&self) -> Result<Vec<PathBuf>> {
+        let dir = self.root.join("properties");
+        if !tokio::fs::try_exists(&dir).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+
+        paths.sort_by_cached_key(|path| snapshot_order(&path.file_stem().unwrap_or_default().to_string_lossy()));
+        Ok(paths)
+    }
+
+    /// Load the run history, starting empty if none has been recorded yet
+    pub async fn load_history(&self) -> Result<History> {
+        let path = self.history_path();
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(History::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(file = "history.json"))]
+    pub async fn save_history(&self, history: &History) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.history_path();
+        let json = serde_json::to_string_pretty(history)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load user annotations, starting empty if there are none yet
+    pub async fn load_annotations(&self) -> Result<Annotations> {
+        let path = self.root.join("annotations.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Annotations::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(file = "annotations.json"))]
+    pub async fn save_annotations(&self, annotations: &Annotations) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("annotations.json");
+        let json = serde_json::to_string_pretty(annotations)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load the notifications sent so far, starting empty if none were
+    pub async fn load_ledger(&self) -> Result<Ledger> {
+        let path = self.root.join("notifications.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Ledger::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(file = "notifications.json"))]
+    pub async fn save_ledger(&self, ledger: &Ledger) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("notifications.json");
+        let json = serde_json::to_string_pretty(ledger)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load the notifications waiting for delivery, starting empty if none are
+    pub async fn load_outbox(&self) -> Result<Outbox> {
+        let path = self.root.join("outbox.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Outbox::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(file = "outbox.json"))]
+    pub async fn save_outbox(&self, outbox: &Outbox) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("outbox.json");
+        let json = serde_json::to_string_pretty(outbox)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load the records of earlier scrape runs, oldest first
+    pub async fn load_runs(&self) -> Result<Vec<ScrapeRun>> {
+        let path = self.root.join("runs.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Add or replace a run record in data/runs.json, by run ID
+    #[instrument(name = "write", level = "debug", skip_all, fields(run_id = %run.id))]
+    pub async fn save_run(&self, run: &ScrapeRun) -> Result<()> {
+        let mut runs = self.load_runs().await?;
+        match runs.iter_mut().find(|r| r.id == run.id) {
+            Some(existing) => *existing = run.clone(),
+            None => runs.push(run.clone()),
+        }
+
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("runs.json");
+        let json = serde_json::to_string_pretty(&runs)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Open the progress checkpoint of a run, empty for a new run
+    pub fn checkpoint(&self, run_id: &str) -> Result<Checkpoint> {
+        Checkpoint::open(self.root.join("checkpoints").join(format!("{}.json", run_id)))
+    }
+
+    /// Load all stored sold listings (data/sold.json)
+    pub async fn load_sold(&self) -> Result<Vec<SoldListing>> {
+        let path = self.root.join("sold.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Merge sold listings into storage by ID, returning how many were new
+    pub async fn merge_sold(&self, sold: Vec<SoldListing>) -> Result<usize> {
+        let mut all: BTreeMap<String, SoldListing> = self
+            .load_sold()
+            .await?
+            .into_iter()
+            .map(|s| (s.id.clone(), s))
+            .collect();
+
+        let before = all.len();
+        for listing in sold {
+            all.insert(listing.id.clone(), listing);
+        }
+        let added = all.len() - before;
+
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("sold.json");
+        let json = serde_json::to_string_pretty(&all.into_values().collect::<Vec<_>>())?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(added)
+    }
+
+    /// Load the watched listings, starting empty if none are watched yet
+    pub async fn load_watchlist(&self) -> Result<Watchlist> {
+        let path = self.root.join("watchlist.json");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(Watchlist::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(file = "watchlist.json"))]
+    pub async fn save_watchlist(&self, watchlist: &Watchlist) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let path = self.root.join("watchlist.json");
+        let json = serde_json::to_string_pretty(watchlist)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load a cached lookup result from `cache/<name>.json`, if present and readable
+    pub async fn load_cache<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let path = self.cache_path(name);
+        let json = tokio::fs::read_to_string(&path).await.ok()?;
+        match serde_json::from_str(&json) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Ignoring unreadable cache {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    #[instrument(name = "write", level = "debug", skip_all, fields(cache = name))]
+    pub async fn save_cache<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        let path = self.cache_path(name);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(value)?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn cache_path(&self, name: &str) -> PathBuf {
+        self.root.join("cache").join(format!("{}.json", name))
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.root.join("history.json")
+    }
+}
+
+async fn read_snapshot(path: &Path) -> Result<Vec<Property>> {
+    let json = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Write `items` to `path` as a pretty-printed JSON array
+///
+/// Items are serialized one at a time, so the file's text never sits in
+/// memory as a whole; `path` is replaced once the file is complete.
+pub async fn write_json_array<T: Serialize>(path: &Path, items: impl IntoIterator<Item = T>) -> Result<()> {
+    write_items(path, items, false).await
+}
+
+/// Write `items` to `path` as JSON Lines, one compact item per line, like
+/// [`write_json_array`]
+pub async fn write_json_lines<T: Serialize>(path: &Path, items: impl IntoIterator<Item = T>) -> Result<()> {
+    write_items(path, items, true).await
+}
+
+/// Sort key of a snapshot name, in the order the snapshots were taken
+///
+/// Run IDs (20261015T…) and day snapshot dates (2026-10-15) both start with
+/// the day, so without the dashes they compare chronologically; a day
+/// snapshot comes before the runs of its day.
+pub fn snapshot_order(name: &str) -> String {
+    name.replace('-', "")
+}
+
+async fn write_items<T: Serialize>(path: &Path, items: impl IntoIterator<Item = T>, lines: bool) -> Result<()> {
+    let mut partial = OsString::from(path);
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let file = tokio::fs::File::create(&partial)
+        .await
+        .with_context(|| format!("Failed to create {}", partial.display()))?;
+    let mut out = BufWriter::new(file);
+
+    let mut buf = Vec::new();
+    let mut empty = true;
+    for item in items {
+        buf.clear();
+        if lines {
+            serde_json::to_writer(&mut buf, &item)?;
+            buf.push(b'\n');
+        } else {
+            buf.extend_from_slice(if empty { b"[\n" } else { b",\n" });
+            serde_json::to_writer_pretty(&mut buf, &item)?;
+        }
+        empty = false;
+        out.write_all(&buf).await.with_context(|| format!("Failed to write {}", partial.display()))?;
+    }
+    if !lines {
+        out.write_all(if empty { b"[]\n" } else { b"\n]\n" }).await?;
+    }
+    out.flush().await.with_context(|| format!("Failed to write {}", partial.display()))?;
+    drop(out);
+
+    tokio::fs::rename(&partial, path)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,19 @@
+pub mod analytics;
+pub mod annotations;
+pub mod checkpoint;
+pub mod history;
+pub mod import;
+pub mod json;
+pub mod runs;
+pub mod sql;
+
+#[cfg(feature = "duckdb")]
+pub use analytics::DuckIndex;
+pub use analytics::{AnalyticsConfig, QueryEngine};
+pub use annotations::{Annotation, Annotations, Note, Status, StatusChange};
+pub use checkpoint::Checkpoint;
+pub use history::{on_market_since, History, Observation, PropertyHistory};
+pub use import::{parse_legacy, read_legacy_dir, LegacyImport};
+pub use json::{snapshot_order, write_json_array, write_json_lines, JsonStore};
+pub use runs::{RunParams, RunStatus, ScrapeRun, SourceSummary};
+pub use sql::{write_sqlite, QueryResult, SqlIndex};
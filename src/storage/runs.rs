@@ -0,0 +1,150 @@
+use crate::models::InvalidListing;
+use crate::scrapers::SourceKind;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// How one source did in a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSummary {
+    pub source: String,
+    pub listings: usize,
+    pub duration_secs: f64,
+    /// Empty when the source succeeded
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Listings dropped because they failed validation
+    #[serde(default)]
+    pub rejected: Vec<InvalidListing>,
+    /// Share of listings with each tracked field filled in
+    #[serde(default)]
+    pub fill_rates: BTreeMap<String, f64>,
+    /// Where extraction fell short of the source's recent runs
+    #[serde(default)]
+    pub health: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Completed,
+    /// Every source failed; nothing was stored
+    Failed,
+    /// Stopped by Ctrl-C / SIGTERM; resumable from its checkpoint
+    Aborted,
+}
+
+/// What a run was asked to scrape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunParams {
+    pub sources: Vec<SourceKind>,
+    /// Names of the configured search areas
+    pub areas: Vec<String>,
+    pub details: bool,
+    pub download_images: bool,
+}
+
+/// Start time part of a run ID, to the millisecond; a random suffix follows
+/// so runs started together still get IDs of their own
+const ID_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// When the run with this ID started; also reads the per-second IDs of older runs
+pub fn run_started(id: &str) -> Option<NaiveDateTime> {
+    let time = id.split('-').next()?;
+    NaiveDateTime::parse_from_str(time, ID_TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%SZ"))
+        .ok()
+}
+
+/// One `scout scrape` run (data/runs.json); stored properties carry its `id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrapeRun {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub status: RunStatus,
+    /// Version of the scout binary that ran it
+    pub version: String,
+    pub params: RunParams,
+    pub sources: Vec<SourceSummary>,
+    /// Listings stored after merging every source
+    pub listings: usize,
+    /// Listings not seen in any earlier run
+    pub new_listings: usize,
+    /// Scrape budget caps the run hit, so it stopped short of everything
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncated: Vec<String>,
+}
+
+impl ScrapeRun {
+    pub fn start(params: RunParams) -> Self {
+        let started_at = Utc::now();
+        Self {
+            id: format!("{}-{:04x}", started_at.format(ID_TIME_FORMAT), rand::random::<u16>()),
+            started_at,
+            finished_at: None,
+            status: RunStatus::Running,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            params,
+            sources: Vec::new(),
+            listings: 0,
+            new_listings: 0,
+            truncated: Vec::new(),
+        }
+    }
+
+    /// Pick an unfinished run up again; its source outcomes are re-recorded
+    pub fn resume(&mut self) {
+        self.status = RunStatus::Running;
+        self.finished_at = None;
+        self.sources.clear();
+        self.truncated.clear();
+    }
+
+    pub fn finish(&mut self, status: RunStatus) {
+        self.status = status;
+        self.finished_at = Some(Utc::now());
+    }
+
+    pub fn failed_sources(&self) -> usize {
+        self.sources.iter().filter(|s| !s.errors.is_empty()).count()
+    }
+
+    /// Plain-text table, one line per source
+    pub fn table(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{:<24} {:>8} {:>9}  Status", "Source", "Listings", "Duration");
+        for source in &self.sources {
+            let mut status = match source.errors.first() {
+                None if source.rejected.is_empty() => "ok".to_string(),
+                None => format!("ok, {} invalid listings dropped", source.rejected.len()),
+                Some(error) => format!("failed: {}", error),
+            };
+            if !source.health.is_empty() {
+                status.push_str(&format!(", {} health warnings", source.health.len()));
+            }
+            let _ = writeln!(
+                out,
+                "{:<24} {:>8} {:>8.1}s  {}",
+                source.source, source.listings, source.duration_secs, status
+            );
+        }
+        let elapsed = self.finished_at.unwrap_or_else(Utc::now) - self.started_at;
+        let _ = writeln!(
+            out,
+            "Run {}: {} listings ({} new) in {:.0} s, {} of {} sources failed",
+            self.id,
+            self.listings,
+            self.new_listings,
+            elapsed.num_milliseconds() as f64 / 1000.0,
+            self.failed_sources(),
+            self.sources.len()
+        );
+        for truncation in &self.truncated {
+            let _ = writeln!(out, "Truncated: {}", truncation);
+        }
+        out
+    }
+}
@@ -0,0 +1,257 @@
+use crate::models::{Money, Property, SoldListing};
+use crate::storage::{Annotations, History, JsonStore, PropertyHistory};
+use anyhow::{Context, Result};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, Transaction};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Tables available to `scout query`, for `--schema` and error hints
+pub const SCHEMA: &str = "\
+CREATE TABLE properties (
+    id TEXT PRIMARY KEY, source TEXT, address TEXT, city TEXT, area TEXT,
+    -- amounts in whole units of `currency` (kronor for SEK)
+    latitude REAL, longitude REAL, price INTEGER, asking_price INTEGER,
+    current_bid INTEGER, bidding_in_progress INTEGER, monthly_fee INTEGER,
+    operating_cost INTEGER, currency TEXT, rooms REAL, sqm INTEGER, floor REAL, price_per_sqm REAL, fee_per_sqm REAL,
+    url TEXT, scraped_at TEXT, run_id TEXT, published_at TEXT, first_seen TEXT, last_seen TEXT,
+    -- status as in JSON exports: new, shortlisted, viewing_booked, ...
+    status TEXT, favorite INTEGER, hidden INTEGER, rating INTEGER,
+    -- the full stored property, for json_extract()
+    json TEXT
+);
+CREATE TABLE observations (
+    property_id TEXT, observed_at TEXT, price INTEGER, asking_price INTEGER,
+    current_bid INTEGER, bidding_in_progress INTEGER
+);
+CREATE TABLE sold (
+    id TEXT PRIMARY KEY, address TEXT, area TEXT, rooms REAL, sqm INTEGER,
+    floor REAL, monthly_fee INTEGER, asking_price INTEGER, sold_price INTEGER,
+    sold_at TEXT, url TEXT, scraped_at TEXT,
+    -- the tracked listing that sold, found by `scout sold --backfill`
+    property_id TEXT
+);
+";
+
+/// Result of an ad-hoc query
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl QueryResult {
+    /// The rows as CSV with a header line, quoting fields where needed
+    pub fn to_csv(&self) -> String {
+        let field = |value: String| {
+            if value.contains([',', '"', '\n']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value
+            }
+        };
+        let mut csv = self.columns.iter().cloned().map(field).collect::<Vec<_>>().join(",");
+        csv.push('\n');
+        for row in &self.rows {
+            let values: Vec<String> = row
+                .iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .map(field)
+                .collect();
+            csv.push_str(&values.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// In-memory SQLite copy of everything in a [`JsonStore`]
+///
+/// Rebuilt on every open; the JSON files stay the source of truth.
+pub struct SqlIndex {
+    conn: Connection,
+}
+
+impl SqlIndex {
+    pub async fn load(store: &JsonStore) -> Result<Self> {
+        let properties = store.load_all_properties().await?;
+        let history = store.load_history().await?;
+        let annotations = store.load_annotations().await?;
+        let sold = store.load_sold().await?;
+        Self::build(&properties, &history, &annotations, &sold)
+    }
+
+    pub fn build(
+        properties: &[Property],
+        history: &History,
+        annotations: &Annotations,
+        sold: &[SoldListing],
+    ) -> Result<Self> {
+        let mut conn = Connection::open_in_memory()?;
+        conn.execute_batch(SCHEMA)?;
+        let tx = conn.transaction()?;
+        insert_properties(&tx, properties, history, annotations)?;
+        insert_observations(&tx, history.properties.iter())?;
+        insert_sold(&tx, sold)?;
+        tx.commit()?;
+
+        Ok(Self { conn })
+    }
+
+    /// Run one SQL statement and collect every row
+    pub fn query(&self, sql: &str) -> Result<QueryResult> {
+        let mut statement = self.conn.prepare(sql).context("Invalid query")?;
+        let columns: Vec<String> = statement.column_names().into_iter().map(str::to_string).collect();
+
+        let mut rows = Vec::new();
+        let mut cursor = statement.query([])?;
+        while let Some(row) = cursor.next()? {
+            let mut values = Vec::with_capacity(columns.len());
+            for i in 0..columns.len() {
+                values.push(match row.get_ref(i)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(n) => Value::from(n),
+                    ValueRef::Real(x) => Value::from(x),
+                    ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text).into_owned()),
+                    ValueRef::Blob(blob) => Value::from(format!("<{} bytes>", blob.len())),
+                });
+            }
+            rows.push(values);
+        }
+
+        Ok(QueryResult { columns, rows })
+    }
+}
+
+/// Properties, or properties' price histories, [`write_sqlite`] writes per
+/// transaction, so a nationwide write never holds every row uncommitted
+const CHUNK: usize = 1000;
+
+/// Upsert properties and their price history into the SQLite database file at
+/// `path`, creating it with the tables of [`SCHEMA`]; rows from earlier
+/// writes stay
+///
+/// Rows are committed [`CHUNK`] properties at a time; a write that fails
+/// partway leaves the chunks before it, each property with its whole history.
+pub fn write_sqlite(path: &Path, properties: &[Property], history: &History, annotations: &Annotations) -> Result<()> {
+    let mut conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    conn.execute_batch(&SCHEMA.replace("CREATE TABLE", "CREATE TABLE IF NOT EXISTS"))?;
+    for chunk in properties.chunks(CHUNK) {
+        let tx = conn.transaction()?;
+        insert_properties(&tx, chunk, history, annotations)?;
+        tx.commit()?;
+    }
+
+    let entries: Vec<_> = history.properties.iter().collect();
+    for chunk in entries.chunks(CHUNK) {
+        let tx = conn.transaction()?;
+        {
+            // The history holds every observation, so replace what an earlier write stored
+            let mut delete = tx.prepare_cached("DELETE FROM observations WHERE property_id = ?1")?;
+            for (id, _) in chunk {
+                delete.execute([id])?;
+            }
+        }
+        insert_observations(&tx, chunk.iter().copied())?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn insert_properties(
+    tx: &Transaction<'_>,
+    properties: &[Property],
+    history: &History,
+    annotations: &Annotations,
+) -> Result<()> {
+    let mut insert = tx.prepare_cached(
+        "INSERT OR REPLACE INTO properties VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+         ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30)",
+    )?;
+    for p in properties {
+        let entry = history.get(&p.id);
+        let annotation = annotations.get(&p.id);
+        let per_sqm = |value: Money| Some(value.as_f64() / p.sqm? as f64);
+        insert.execute(params![
+            p.id,
+            format!("{:?}", p.source),
+            p.address,
+            p.location.city,
+            p.location.area,
+            p.location.latitude,
+            p.location.longitude,
+            p.price.map(|m| m.major()),
+            p.asking_price.map(|m| m.major()),
+            p.current_bid.map(|m| m.major()),
+            p.bidding_in_progress,
+            p.monthly_fee.map(|m| m.major()),
+            p.operating_cost.map(|m| m.major()),
+            p.currency().code(),
+            p.rooms.map(f64::from),
+            p.sqm,
+            p.floor.map(f64::from),
+            p.price.and_then(per_sqm),
+            p.monthly_fee.and_then(per_sqm),
+            p.url,
+            p.scraped_at.to_rfc3339(),
+            p.run_id,
+            p.published_at.map(|at| at.to_rfc3339()),
+            entry.map(|h| h.first_seen.to_rfc3339()),
+            entry.map(|h| h.last_seen.to_rfc3339()),
+            serde_json::to_value(annotations.status(&p.id))?.as_str(),
+            annotation.is_some_and(|a| a.favorite),
+            annotation.is_some_and(|a| a.hidden),
+            annotation.and_then(|a| a.rating),
+            serde_json::to_string(p)?,
+        ])?;
+    }
+    Ok(())
+}
+
+fn insert_observations<'a>(
+    tx: &Transaction<'_>,
+    entries: impl Iterator<Item = (&'a String, &'a PropertyHistory)>,
+) -> Result<()> {
+    let mut insert = tx.prepare_cached("INSERT INTO observations VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+    for (id, entry) in entries {
+        for o in &entry.observations {
+            insert.execute(params![
+                id,
+                o.observed_at.to_rfc3339(),
+                o.price.map(|m| m.major()),
+                o.asking_price.map(|m| m.major()),
+                o.current_bid.map(|m| m.major()),
+                o.bidding_in_progress,
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_sold(tx: &Transaction<'_>, sold: &[SoldListing]) -> Result<()> {
+    let mut insert =
+        tx.prepare("INSERT OR REPLACE INTO sold VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)")?;
+    for s in sold {
+        insert.execute(params![
+            s.id,
+            s.address,
+            s.area,
+            s.rooms.map(f64::from),
+            s.sqm,
+            s.floor.map(f64::from),
+            s.monthly_fee,
+            s.asking_price,
+            s.sold_price,
+            s.sold_at.map(|d| d.to_string()),
+            s.url,
+            s.scraped_at.to_rfc3339(),
+            s.property_id,
+        ])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::cli::LogFormat;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Layer};
+
+/// Export of tracing spans to an OpenTelemetry collector
+///
+/// Spans cover sources, result pages and listings, and below them browser
+/// navigation, HTTP requests, parsing and storage writes, so a scheduled run
+/// can be followed in Jaeger or Tempo. Exporting needs a build with
+/// `--features otel`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// OTLP/HTTP collector spans are sent to, e.g. "http://localhost:4318";
+    /// nothing is exported when unset
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` of the exported spans
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "housing-scout".to_string(),
+        }
+    }
+}
+
+/// Sends the spans still buffered when dropped, so keep it until exit
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to export the last spans: {}", e);
+            }
+        }
+    }
+}
+
+/// Log to stderr in `format` and, if configured, export spans over OTLP
+///
+/// Logs stay at info level; the exporter also gets the debug spans for
+/// navigation, requests, parsing and writes.
+pub fn init(format: LogFormat, config: &TelemetryConfig) -> Result<Telemetry> {
+    let logs = match format {
+        LogFormat::Text => fmt::layer().with_writer(std::io::stderr).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(std::io::stderr)
+            .boxed(),
+    };
+    let registry = tracing_subscriber::registry().with(logs.with_filter(LevelFilter::INFO));
+
+    #[cfg(feature = "otel")]
+    {
+        let provider = config
+            .otlp_endpoint
+            .as_deref()
+            .map(|endpoint| otel::provider(endpoint, &config.service_name))
+            .transpose()?;
+        let spans = provider
+            .as_ref()
+            .map(|provider| otel::layer(provider).with_filter(LevelFilter::DEBUG));
+        registry.with(spans).init();
+        Ok(Telemetry { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+        if config.otlp_endpoint.is_some() {
+            tracing::warn!("Not exporting spans: scout was built without the `otel` feature");
+        }
+        Ok(Telemetry {})
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use anyhow::{Context, Result};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    /// Batching exporter to the collector at `endpoint`
+    pub fn provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .context("Failed to create the OTLP exporter")?;
+        Ok(SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+            .build())
+    }
+
+    pub fn layer<S>(provider: &SdkTracerProvider) -> OpenTelemetryLayer<S, SdkTracer>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer("housing-scout"))
+    }
+}
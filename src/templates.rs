@@ -0,0 +1,127 @@
+use crate::i18n::Language;
+use crate::models::Amenity;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tera::Tera;
+use tracing::{info, warn};
+
+/// Template names looked up in the template directory
+pub const NOTIFICATION_TITLE: &str = "notification_title.tera";
+pub const NOTIFICATION_BODY: &str = "notification.tera";
+pub const SUMMARY: &str = "summary.tera";
+
+/// Where user templates live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateConfig {
+    /// Directory of `*.tera` files; built-in formats are used for missing templates
+    pub dir: PathBuf,
+}
+
+impl Default for TemplateConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("templates"),
+        }
+    }
+}
+
+/// User supplied Tera templates overriding built-in output formats
+///
+/// Templates are rendered with the export record as `property`: every
+/// property field plus `metrics`, `finance`, `score`, `anomaly` and `prediction`.
+/// The `t` filter translates message keys and amenities into the output
+/// language: `{{ "score" | t(lang=language) }}`, `{{ amenity | t(lang=language) }}`.
+#[derive(Default)]
+pub struct Templates {
+    tera: Option<Tera>,
+}
+
+impl Templates {
+    /// Load all templates in the configured directory, if it exists
+    pub fn load(config: &TemplateConfig) -> Result<Self> {
+        if !config.dir.is_dir() {
+            return Ok(Self::default());
+        }
+
+        let glob = config.dir.join("**").join("*.tera");
+        let mut tera = Tera::new(&glob.to_string_lossy())
+            .with_context(|| format!("Failed to load templates from {}", config.dir.display()))?;
+        tera.register_filter("t", translate);
+        let names: Vec<&str> = tera.get_template_names().collect();
+        if !names.is_empty() {
+            info!("Loaded templates: {}", names.join(", "));
+        }
+        Ok(Self { tera: Some(tera) })
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tera
+            .as_ref()
+            .is_some_and(|tera| tera.get_template_names().any(|n| n == name))
+    }
+
+    /// Render `name`, or `None` if the user didn't supply it
+    ///
+    /// Errors are logged so a broken template falls back to the built-in format.
+    pub fn render(&self, name: &str, context: &impl Serialize) -> Option<String> {
+        let tera = self.tera.as_ref().filter(|_| self.has(name))?;
+        let result = tera::Context::from_serialize(context).and_then(|context| tera.render(name, &context));
+        match result {
+            Ok(text) => Some(text),
+            Err(e) => {
+                warn!("Failed to render template {}: {}", name, error_chain(&e));
+                None
+            }
+        }
+    }
+}
+
+/// Render a single template file
+pub fn render_file(path: &Path, context: &impl Serialize) -> Result<String> {
+    let template = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let context = tera::Context::from_serialize(context)?;
+    let mut tera = Tera::default();
+    tera.register_filter("t", translate);
+    tera.add_raw_template("one_off", &template)
+        .and_then(|()| tera.render("one_off", &context))
+        .map_err(|e| anyhow::anyhow!("Failed to render {}: {}", path.display(), error_chain(&e)))
+}
+
+/// The `t` filter: an amenity key ("balcony") or message key ("score"), or a
+/// list of them, in the language given as `lang`, English by default;
+/// anything else is kept as is
+fn translate(value: &tera::Value, args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    let language: Language = match args.get("lang") {
+        Some(lang) => tera::from_value(lang.clone())?,
+        None => Language::default(),
+    };
+    Ok(translate_value(value, language))
+}
+
+fn translate_value(value: &tera::Value, language: Language) -> tera::Value {
+    match value {
+        tera::Value::String(key) => {
+            let text = match Amenity::ALL.into_iter().find(|amenity| amenity.key() == key) {
+                Some(amenity) => amenity.label(language),
+                None => language.t(key),
+            };
+            tera::Value::String(text.to_string())
+        }
+        tera::Value::Array(items) => tera::Value::Array(items.iter().map(|item| translate_value(item, language)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Tera's top-level error only names the template; the cause is in the source chain
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
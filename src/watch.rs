@@ -0,0 +1,213 @@
+//! `scout watch`: checking a few hand-picked listings often, e.g. the ones
+//! being bid on, and telling about every change to their price, bidding or
+//! description
+
+use crate::i18n::Language;
+use crate::models::{Money, Property};
+#[cfg(feature = "graphql")]
+use crate::scrapers::booli_graphql::BOOLI_GRAPHQL_URL;
+#[cfg(feature = "graphql")]
+use crate::scrapers::BooliGraphqlClient;
+use crate::scrapers::detail::ListingDetails;
+use crate::scrapers::ScrapeConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+#[cfg(feature = "graphql")]
+use std::time::Duration;
+
+/// Settings for `scout watch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// Minutes between checks of the watched listings
+    pub interval_minutes: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self { interval_minutes: 10 }
+    }
+}
+
+/// Listings `scout watch` checks, by ID (data/watchlist.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub listings: BTreeMap<String, WatchedListing>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedListing {
+    pub url: String,
+    /// Address of the stored listing, if it was stored when added
+    pub address: Option<String>,
+    pub added_at: DateTime<Utc>,
+    pub last_checked: Option<DateTime<Utc>>,
+    /// The listing at the last check
+    pub state: Option<WatchState>,
+}
+
+impl WatchedListing {
+    pub fn new(url: String, address: Option<String>, now: DateTime<Utc>) -> Self {
+        Self {
+            url,
+            address,
+            added_at: now,
+            last_checked: None,
+            state: None,
+        }
+    }
+
+    /// The address, or the URL when there's none
+    pub fn label(&self) -> &str {
+        self.address.as_deref().unwrap_or(&self.url)
+    }
+}
+
+/// What a check compares
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchState {
+    pub price: Option<Money>,
+    pub current_bid: Option<Money>,
+    pub bidding_in_progress: bool,
+    pub description: Option<String>,
+    /// Booli no longer has the listing: sold or withdrawn
+    pub removed: bool,
+}
+
+impl WatchState {
+    /// The listing as fetched, with what the fetch lacked kept from `earlier`
+    pub fn fetched(details: &ListingDetails, earlier: Option<&WatchState>) -> Self {
+        Self {
+            price: details.bids.asking_price.map(Money::sek).or(earlier.and_then(|e| e.price)),
+            current_bid: details.bids.current_bid.map(Money::sek).or(earlier.and_then(|e| e.current_bid)),
+            bidding_in_progress: details.bids.in_progress,
+            description: details
+                .description
+                .clone()
+                .or_else(|| earlier.and_then(|e| e.description.clone())),
+            removed: false,
+        }
+    }
+
+    /// The listing gone from Booli, as it last was
+    pub fn removed(earlier: Option<&WatchState>) -> Self {
+        Self {
+            removed: true,
+            bidding_in_progress: false,
+            ..earlier.cloned().unwrap_or_default()
+        }
+    }
+
+    /// What changed since `earlier`, one line each
+    pub fn changes_since(&self, earlier: &WatchState, language: Language) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.removed != earlier.removed {
+            changes.push(language.t(if self.removed { "watch.removed" } else { "watch.relisted" }).to_string());
+        }
+        if let (Some(before), Some(after)) = (earlier.price, self.price) {
+            if before != after {
+                changes.push(language.format("watch.price", &[("before", &before), ("after", &after)]));
+            }
+        }
+        match (earlier.current_bid, self.current_bid) {
+            (None, Some(bid)) => changes.push(language.format("watch.first_bid", &[("bid", &bid)])),
+            (Some(before), Some(after)) if before != after => {
+                changes.push(language.format("watch.bid", &[("before", &before), ("after", &after)]));
+            }
+            _ => {}
+        }
+        if self.bidding_in_progress != earlier.bidding_in_progress && !self.removed {
+            let key = if self.bidding_in_progress { "watch.bidding_started" } else { "watch.bidding_closed" };
+            changes.push(language.t(key).to_string());
+        }
+        if let (Some(before), Some(after)) = (&earlier.description, &self.description) {
+            if before.trim() != after.trim() {
+                changes.push(language.t("watch.description").to_string());
+                changes.extend(new_sentences(before, after).into_iter().take(3).map(|s| format!("+ {}", s)));
+            }
+        }
+        changes
+    }
+}
+
+/// Sentences of `after` that aren't in `before`, e.g. an added "Budgivningen
+/// avslutas fredag"
+fn new_sentences<'a>(before: &str, after: &'a str) -> Vec<&'a str> {
+    let known: HashSet<&str> = sentences(before).collect();
+    sentences(after).filter(|s| !known.contains(s)).collect()
+}
+
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive(['.', '!', '?', '\n']).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Listing ID and URL of a `scout watch` argument: a listing URL, the ID of
+/// a stored listing or a Booli listing ID
+pub fn resolve(target: &str, stored: &[Property]) -> Result<(String, String)> {
+    let target = target.trim();
+    if let Some(property) = stored
+        .iter()
+        .find(|p| p.id == target || p.url.trim_end_matches('/') == target.trim_end_matches('/'))
+    {
+        return Ok((property.id.clone(), property.url.clone()));
+    }
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let url = Url::parse(target).with_context(|| format!("Invalid listing URL {}", target))?;
+        // Booli listing URLs end in the listing ID, as in /annons/4839201
+        let id = url
+            .path_segments()
+            .and_then(|mut segments| segments.rfind(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())))
+            .with_context(|| format!("No listing ID in {}", target))?;
+        return Ok((id.to_string(), target.to_string()));
+    }
+    if !target.is_empty() && target.chars().all(|c| c.is_ascii_digit()) {
+        return Ok((target.to_string(), format!("https://www.booli.se/annons/{}", target)));
+    }
+    anyhow::bail!("No stored listing with ID {}", target)
+}
+
+/// Fetches watched listings: from Booli's GraphQL API, or in builds without
+/// the `graphql` feature, from the listing page
+pub struct Checker {
+    #[cfg(feature = "graphql")]
+    client: BooliGraphqlClient,
+    #[cfg(not(feature = "graphql"))]
+    client: reqwest::Client,
+}
+
+impl Checker {
+    #[cfg(feature = "graphql")]
+    pub fn new(config: &ScrapeConfig) -> Result<Self> {
+        Ok(Self {
+            client: BooliGraphqlClient::new(BOOLI_GRAPHQL_URL, Duration::from_millis(config.min_interval_ms))?,
+        })
+    }
+
+    #[cfg(not(feature = "graphql"))]
+    pub fn new(_config: &ScrapeConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("housing-scout/", env!("CARGO_PKG_VERSION")))
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        Ok(Self { client })
+    }
+
+    /// The listing now, or None when Booli no longer has it
+    #[cfg(feature = "graphql")]
+    pub async fn fetch(&self, id: &str, _url: &str) -> Result<Option<ListingDetails>> {
+        self.client.find_listing(id).await
+    }
+
+    #[cfg(not(feature = "graphql"))]
+    pub async fn fetch(&self, _id: &str, url: &str) -> Result<Option<ListingDetails>> {
+        let response = self.client.get(url).send().await.with_context(|| format!("Failed to fetch {}", url))?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => Ok(None),
+            status if !status.is_success() => anyhow::bail!("{} returned status: {}", url, status),
+            _ => Ok(Some(crate::scrapers::detail::parse_detail_page(&response.text().await?))),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! `BooliScraper` against a stub Booli serving recorded search pages
+
+use housing_scout::models::{Amenity, ListingStatus};
+use housing_scout::scrapers::{BooliScraper, ScraperTrait};
+use std::path::Path;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A recorded search page from tests/fixtures/booli
+fn recorded(name: &str) -> String {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/booli").join(name);
+    std::fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {}: {}", file.display(), e))
+}
+
+fn page(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/html; charset=utf-8")
+}
+
+/// The Södermalm search, answering each page with its recording
+async fn serve_search(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/sok/till-salu"))
+        .and(query_param("areaIds", "115341"))
+        .and(query_param_is_missing("page"))
+        .respond_with(page(recorded("sodermalm-page-1.html")))
+        .mount(server)
+        .await;
+    for n in [2, 3] {
+        Mock::given(method("GET"))
+            .and(path("/sok/till-salu"))
+            .and(query_param("page", n.to_string()))
+            .respond_with(page(recorded(&format!("sodermalm-page-{}.html", n))))
+            .mount(server)
+            .await;
+    }
+}
+
+fn scraper(server: &MockServer) -> BooliScraper {
+    BooliScraper::new().unwrap().with_base_url(&server.uri())
+}
+
+/// Requests the server got for the first result page
+async fn first_page_requests(server: &MockServer) -> usize {
+    let requests = server.received_requests().await.unwrap_or_default();
+    requests.iter().filter(|r| !r.url.query().unwrap_or_default().contains("page=")).count()
+}
+
+#[tokio::test]
+async fn scrapes_pages_until_one_adds_no_listings() {
+    let server = MockServer::start().await;
+    serve_search(&server).await;
+
+    let properties = scraper(&server).scrape().await.unwrap();
+
+    // Page 2 repeats one listing of page 1; page 3 has none, so page 4 isn't asked for
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    let ids: Vec<&str> = properties.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["5551001", "5551002", "5551003", "5551004"]);
+
+    let gotgatan = &properties[0];
+    assert_eq!(gotgatan.address, "Götgatan 120");
+    assert_eq!(gotgatan.location.area.as_deref(), Some("Södermalm"));
+    assert_eq!(gotgatan.price.map(|p| p.major()), Some(5_195_000));
+    assert_eq!(gotgatan.monthly_fee.map(|p| p.major()), Some(3_449));
+    assert_eq!(gotgatan.sqm, Some(70));
+    assert_eq!(gotgatan.rooms, Some(2.0));
+    assert_eq!(gotgatan.floor, Some(3.0));
+    assert!(gotgatan.features.has(Amenity::Elevator) && gotgatan.features.has(Amenity::Balcony));
+    assert_eq!(gotgatan.url, "https://www.booli.se/annons/5551001");
+
+    assert_eq!(properties[1].rooms, Some(1.5));
+
+    let coming_soon = &properties[2];
+    assert_eq!(coming_soon.listing_status, ListingStatus::ComingSoon);
+    assert_eq!(coming_soon.price, None);
+
+    let bondegatan = &properties[3];
+    assert_eq!(bondegatan.location.area.as_deref(), Some("Sofo"));
+    assert!(bondegatan.bidding_in_progress);
+    assert!(bondegatan.features.has(Amenity::Fireplace));
+}
+
+#[tokio::test]
+async fn sends_browser_headers() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(page(recorded("sodermalm-page-3.html")))
+        .mount(&server)
+        .await;
+
+    // An empty page falls back to the built-in listings, but was still fetched
+    scraper(&server).scrape().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let headers = &requests[0].headers;
+    assert!(headers["user-agent"].to_str().unwrap().starts_with("Mozilla/5.0"));
+    assert!(headers["accept"].to_str().unwrap().starts_with("text/html"));
+    assert!(headers["accept-language"].to_str().unwrap().starts_with("sv-SE"));
+}
+
+#[tokio::test]
+async fn retries_rate_limits_and_server_errors() {
+    let server = MockServer::start().await;
+    for status in [429, 503] {
+        Mock::given(method("GET"))
+            .and(query_param_is_missing("page"))
+            .respond_with(ResponseTemplate::new(status).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .with_priority(if status == 429 { 1 } else { 2 })
+            .mount(&server)
+            .await;
+    }
+    serve_search(&server).await;
+
+    let properties = scraper(&server).scrape().await.unwrap();
+
+    assert_eq!(first_page_requests(&server).await, 3);
+    assert_eq!(properties.len(), 4);
+}
+
+#[tokio::test]
+async fn gives_up_after_repeated_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500).insert_header("Retry-After", "0"))
+        .mount(&server)
+        .await;
+
+    let error = scraper(&server).scrape().await.unwrap_err();
+
+    assert!(format!("{:#}", error).contains("after 3 attempts"), "{:#}", error);
+    assert_eq!(first_page_requests(&server).await, 3);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(ResponseTemplate::new(403)).mount(&server).await;
+
+    let error = scraper(&server).scrape().await.unwrap_err();
+
+    assert!(format!("{:#}", error).contains("403"), "{:#}", error);
+    assert_eq!(first_page_requests(&server).await, 1);
+}
+
+#[tokio::test]
+async fn a_failing_later_page_keeps_the_earlier_ones() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param_is_missing("page"))
+        .respond_with(page(recorded("sodermalm-page-1.html")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let properties = scraper(&server).scrape().await.unwrap();
+
+    assert_eq!(properties.len(), 3);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
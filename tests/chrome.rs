@@ -0,0 +1,38 @@
+//! The shared Chrome of browser sources running side by side
+#![cfg(feature = "browser")]
+
+use housing_scout::scrapers::chrome::{Chrome, ChromeConfig};
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn checking_for_a_restart_with_a_tab_open_does_not_wait_for_it() {
+    let chrome = Chrome::new(ChromeConfig {
+        kill_orphans: false,
+        restart_above_mb: Some(0),
+        ..ChromeConfig::default()
+    });
+    let (done, finished) = mpsc::channel();
+    std::thread::spawn(move || {
+        let tab = match chrome.tab() {
+            Ok(tab) => tab,
+            Err(e) => {
+                eprintln!("Skipping, Chrome doesn't launch here: {:#}", e);
+                return done.send(()).unwrap();
+            }
+        };
+        std::thread::scope(|scope| {
+            // Another source restarting Chrome waits for this tab to close
+            let restart = scope.spawn(|| chrome.close());
+            std::thread::sleep(Duration::from_millis(200));
+            // Measures Chrome's memory, as restart_above_mb is set
+            chrome.needs_restart();
+            drop(tab);
+            restart.join().unwrap();
+        });
+        done.send(()).unwrap();
+    });
+    finished
+        .recv_timeout(Duration::from_secs(60))
+        .expect("a tab's thread and a restart waited on each other");
+}
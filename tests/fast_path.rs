@@ -0,0 +1,112 @@
+//! `FastPath` against a stub Booli embedding its listings in `__NEXT_DATA__`
+
+use housing_scout::models::ListingStatus;
+use housing_scout::scrapers::fast_path::FastPath;
+use housing_scout::scrapers::{AreaSearch, Politeness};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn page(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/html; charset=utf-8")
+}
+
+fn next_data(data: serde_json::Value) -> String {
+    format!(
+        r#"<html><body><div id="__next"></div><script id="__NEXT_DATA__" type="application/json">{}</script></body></html>"#,
+        data
+    )
+}
+
+fn listing(server: &MockServer, id: u64, price: Option<u64>) -> serde_json::Value {
+    json!({
+        "__typename": "Listing",
+        "booliId": id,
+        "url": format!("{}/annons/{}", server.uri(), id),
+        "streetAddress": format!("Götgatan {}", id),
+        "descriptiveAreaName": "Södermalm",
+        "municipalityName": "Stockholm",
+        "upcomingSale": price.is_none(),
+        "listPrice": price.map(|raw| json!({ "raw": raw })),
+        "rent": { "raw": 3200 },
+        "livingArea": { "raw": 54.5 },
+        "rooms": { "raw": 2 },
+        "floor": { "raw": 3 },
+        "latitude": 59.31,
+        "longitude": 18.07,
+        "amenities": [{ "key": "balcony", "label": "Balkong" }],
+        "biddingOpen": false
+    })
+}
+
+fn fast_path() -> FastPath {
+    let politeness = Politeness {
+        page_delay_ms: 0,
+        detail_delay_ms: 0,
+        ..Politeness::default()
+    };
+    FastPath::new(Duration::ZERO, politeness).unwrap()
+}
+
+fn search(server: &MockServer) -> AreaSearch {
+    AreaSearch {
+        name: "Söder".to_string(),
+        area_ids: Vec::new(),
+        url: Some(format!("{}/sok/till-salu?areaIds=115341", server.uri())),
+        city: "Stockholm".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn reads_listings_and_leaves_failed_detail_pages_for_chrome() {
+    let server = MockServer::start().await;
+    let data = json!({
+        "props": { "pageProps": { "__APOLLO_STATE__": {
+            "Listing:1": listing(&server, 1, Some(4_500_000)),
+            "Listing:2": listing(&server, 2, None),
+        }}}
+    });
+    Mock::given(method("GET"))
+        .and(path("/sok/till-salu"))
+        .respond_with(page(next_data(data)))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/annons/1"))
+        .respond_with(page(next_data(json!({ "props": {} }))))
+        .mount(&server)
+        .await;
+    // A challenge page rather than the listing
+    Mock::given(method("GET"))
+        .and(path("/annons/2"))
+        .respond_with(page("<html><body>Checking your browser</body></html>".to_string()))
+        .mount(&server)
+        .await;
+
+    let fast = fast_path();
+    let (mut properties, rejected) = fast.search(&search(&server)).await.unwrap();
+    assert!(rejected.is_empty());
+    properties.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(properties.len(), 2);
+    assert_eq!(properties[0].address, "Götgatan 1");
+    assert_eq!(properties[0].sqm, Some(55));
+    assert_eq!(properties[0].area_tags, vec!["Söder".to_string()]);
+    assert_eq!(properties[1].listing_status, ListingStatus::ComingSoon);
+
+    let (done, for_chrome) = fast.details(properties).await.unwrap();
+    assert_eq!(done.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["1"]);
+    assert_eq!(for_chrome.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["2"]);
+}
+
+#[tokio::test]
+async fn a_page_without_listings_fails() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/sok/till-salu"))
+        .respond_with(page("<html><body>Just a moment...</body></html>".to_string()))
+        .mount(&server)
+        .await;
+
+    assert!(fast_path().search(&search(&server)).await.is_err());
+}
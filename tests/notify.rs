@@ -0,0 +1,60 @@
+//! Notifications that couldn't be delivered, or are already waiting to be
+
+use chrono::Utc;
+use housing_scout::i18n::Language;
+use housing_scout::notify::{dispatch, DedupConfig, Ledger, Notification, NotificationEvent, NotifyConfig, Outbox};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn notification(id: &str) -> Notification {
+    Notification {
+        event: NotificationEvent::NewListing,
+        title: format!("Götgatan {}", id),
+        body: String::new(),
+        url: None,
+        property_id: Some(id.to_string()),
+        state: None,
+        searches: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn failed_notifications_wait_for_the_next_delivery() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+    let config: NotifyConfig = toml::from_str(&format!(
+        r#"
+        [[channels]]
+        type = "webhook"
+        url = "{}"
+        "#,
+        server.uri()
+    ))
+    .unwrap();
+    let (mut ledger, mut outbox) = (Ledger::default(), Outbox::default());
+
+    dispatch(&config, &[notification("1001")], Language::default(), &mut ledger, &mut outbox).await;
+    assert!(ledger.entries.is_empty());
+    let queued: Vec<_> = outbox.channels.values().flat_map(|queue| &queue.queued).collect();
+    assert_eq!(queued.len(), 1);
+
+    // The listing isn't new on the next run; the queued notification still goes out
+    dispatch(&config, &[], Language::default(), &mut ledger, &mut outbox).await;
+    assert_eq!(ledger.entries.len(), 1);
+    assert!(outbox.channels.values().all(|queue| queue.queued.is_empty()));
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[test]
+fn queued_notifications_are_not_queued_again() {
+    let ledger = Ledger::default();
+    let dedup = DedupConfig::default();
+    let queued = [notification("1001")];
+    assert!(!ledger.should_send(&notification("1001"), "webhook", &queued, Utc::now(), &dedup));
+    assert!(ledger.should_send(&notification("1002"), "webhook", &queued, Utc::now(), &dedup));
+}
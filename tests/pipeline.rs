@@ -0,0 +1,150 @@
+//! Whole `scout scrape` runs over the fixtures source, from reading the
+//! listings through dedup, storage and notifications to `scout export`
+
+use housing_scout::cli::{DiffArgs, DiffFormat, ExportArgs, ExportFormat, FilterArgs, ScrapeArgs};
+use housing_scout::commands::{diff, export, scrape};
+use housing_scout::config::Config;
+use housing_scout::storage::{JsonStore, RunStatus};
+use serde_json::Value;
+use std::path::Path;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn config(webhook: &str) -> Config {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/listings");
+    let toml = format!(
+        r#"
+        [scrape]
+        sources = ["fixtures"]
+        fixtures = {fixtures:?}
+
+        [enrich]
+        transit = false
+        amenities = false
+        orientation = false
+        schools = false
+
+        [[notify.channels]]
+        type = "webhook"
+        url = "{webhook}"
+        "#,
+        fixtures = fixtures.display().to_string(),
+    );
+    toml::from_str(&toml).expect("test config parses")
+}
+
+fn scrape_args() -> ScrapeArgs {
+    ScrapeArgs {
+        skip_details: false,
+        download_images: false,
+        debug_artifacts: false,
+        resume: None,
+        concurrency: None,
+        parse_threads: None,
+    }
+}
+
+/// Property IDs of the notifications the webhook received
+async fn notified(server: &MockServer) -> Vec<String> {
+    let mut ids: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|request| request.body_json::<Value>().ok())
+        .filter_map(|body| body["property_id"].as_str().map(str::to_string))
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn fixture_runs_store_dedup_notify_and_export() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+    let config = config(&server.uri());
+
+    // Every command reads and writes data/ in the working directory
+    let workdir = tempfile::tempdir().unwrap();
+    std::env::set_current_dir(workdir.path()).unwrap();
+    let store = JsonStore::new("data");
+
+    scrape::run(&scrape_args(), &config).await.unwrap();
+
+    let runs = store.load_runs().await.unwrap();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run.status, RunStatus::Completed);
+    assert_eq!(run.listings, 3);
+    assert_eq!(run.new_listings, 3);
+    assert_eq!(run.sources.len(), 1);
+    assert_eq!(run.sources[0].source, "Fixtures");
+    // 1003 has no price and isn't coming soon
+    let rejected: Vec<&str> = run.sources[0].rejected.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(rejected, ["1003"]);
+
+    let stored = store.load_latest_properties().await.unwrap().expect("a stored snapshot");
+    let ids: Vec<&str> = stored.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["1001", "1002", "2001"]);
+    // 1002 is in both files: kept once, tagged with both searches
+    let hornsgatan = &stored[1];
+    assert_eq!(hornsgatan.area_tags, ["Södermalm", "Innerstan"]);
+    assert_eq!(hornsgatan.rooms, Some(3.0));
+    assert!(stored.iter().all(|p| p.run_id.as_deref() == Some(run.id.as_str())));
+    // 1001.html is its detail page
+    let gotgatan = &stored[0];
+    assert_eq!(gotgatan.monthly_fee.map(|fee| fee.major()), Some(3150));
+    assert_eq!(gotgatan.operating_cost.map(|cost| cost.major()), Some(500));
+    assert_eq!(gotgatan.floor, Some(3.0));
+    let photos: Vec<&str> = gotgatan.photos().map(|image| image.url.as_str()).collect();
+    assert_eq!(photos, ["https://bcdn.se/images/1001/1.jpg"]);
+    assert_eq!(gotgatan.broker.as_ref().and_then(|b| b.name.as_deref()), Some("Anna Svensson"));
+
+    assert_eq!(notified(&server).await, ["1001", "1002", "2001"]);
+
+    // The same listings again are no longer new, so nobody is notified twice
+    scrape::run(&scrape_args(), &config).await.unwrap();
+    let mut runs = store.load_runs().await.unwrap();
+    assert_eq!(runs.len(), 2);
+    let rerun = runs.pop().unwrap();
+    assert_eq!(rerun.listings, 3);
+    assert_eq!(rerun.new_listings, 0);
+    assert_eq!(notified(&server).await, ["1001", "1002", "2001"]);
+
+    // Runs of the same day each keep their listings, to compare by run ID
+    let diff_args = |from: &str| DiffArgs {
+        from: Some(from.to_string()),
+        to: Some(rerun.id.clone()),
+        format: DiffFormat::Json,
+    };
+    diff::run(&diff_args(&runs[0].id)).await.unwrap();
+    assert!(diff::run(&diff_args("20000101T000000000Z-0000")).await.is_err());
+
+    // Even listings that look new again aren't re-sent: the ledger has them
+    std::fs::remove_file("data/history.json").unwrap();
+    scrape::run(&scrape_args(), &config).await.unwrap();
+    let mut runs = store.load_runs().await.unwrap();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs.pop().unwrap().new_listings, 3);
+    assert_eq!(notified(&server).await, ["1001", "1002", "2001"]);
+
+    let output = workdir.path().join("export.json");
+    let args = ExportArgs {
+        format: ExportFormat::Json,
+        output: Some(output.clone()),
+        template: None,
+        ids: Vec::new(),
+        filters: FilterArgs {
+            max_price: Some(5_000_000),
+            ..FilterArgs::default()
+        },
+        sort: None,
+        desc: false,
+    };
+    export::run(&args, &config).await.unwrap();
+    let exported: Vec<Value> = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    let ids: Vec<&str> = exported.iter().filter_map(|record| record["id"].as_str()).collect();
+    assert_eq!(ids, ["1001", "2001"]);
+    let per_sqm = exported[0]["metrics"]["price_per_sqm"].as_f64().unwrap();
+    assert_eq!(per_sqm.round(), (4_495_000.0_f64 / 54.0).round());
+}
@@ -0,0 +1,86 @@
+//! `SiteScraper` against a stub broker site, read by rules/example-broker.toml
+
+use housing_scout::models::{Amenity, ListingStatus, Source};
+use housing_scout::scrapers::{ScraperTrait, Site, SiteRules, SiteScraper};
+use std::path::Path;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn card(id: u32, address: &str, price: &str, tags: &[&str]) -> String {
+    let tags: String = tags.iter().map(|tag| format!("<li>{}</li>", tag)).collect();
+    format!(
+        r#"<article class="object">
+             <a class="object__link" href="/objekt/{id}/"><img src="/bilder/{id}.jpg"></a>
+             <h2 class="object__address">{address}</h2>
+             <p class="object__area">Södermalm, Stockholm</p>
+             <p class="object__price">{price}</p>
+             <p class="object__fee">3 120 kr/mån</p>
+             <p class="object__rooms">2,5 rum</p>
+             <p class="object__size">64 m²</p>
+             <p class="object__floor">3 tr</p>
+             <ul class="object__tags">{tags}</ul>
+           </article>"#
+    )
+}
+
+fn page(cards: &[String]) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(
+        format!("<html><body><main>{}</main></body></html>", cards.concat()),
+        "text/html; charset=utf-8",
+    )
+}
+
+/// The example rules, pointed at `server` and without delays
+fn example_site(server: &MockServer) -> Site {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("rules/example-broker.toml");
+    let mut rules: SiteRules = toml::from_str(&std::fs::read_to_string(file).unwrap()).unwrap();
+    rules.name = "example-broker".to_string();
+    rules.start_url = format!("{}/till-salu?sida={{page}}", server.uri());
+    rules.politeness.page_delay_ms = 0;
+    Site::new(rules).unwrap()
+}
+
+#[tokio::test]
+async fn scrapes_pages_by_the_rules_until_one_adds_no_listings() {
+    let server = MockServer::start().await;
+    let hornsgatan = card(101, "Hornsgatan 12", "4 250 000 kr", &["Balkong", "Hiss"]);
+    let pages = [
+        vec![hornsgatan.clone(), card(102, "Ringvägen 3", "Kommer snart", &[])],
+        vec![hornsgatan.clone(), card(103, "Götgatan 40", "3 900 000 kr", &[])],
+        vec![hornsgatan],
+    ];
+    for (n, cards) in pages.iter().enumerate() {
+        Mock::given(method("GET"))
+            .and(path("/till-salu"))
+            .and(query_param("sida", (n + 1).to_string()))
+            .respond_with(page(cards))
+            .mount(&server)
+            .await;
+    }
+
+    let scraper = SiteScraper::new(example_site(&server), Duration::ZERO).unwrap();
+    let properties = scraper.scrape().await.unwrap();
+
+    // Page 3 adds nothing new, so page 4 isn't asked for
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    assert_eq!(scraper.source_name(), "example-broker");
+    let ids: Vec<&str> = properties.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, ["example_broker_101", "example_broker_102", "example_broker_103"]);
+
+    let hornsgatan = &properties[0];
+    assert_eq!(hornsgatan.source, Source::Broker);
+    assert_eq!(hornsgatan.address, "Hornsgatan 12");
+    assert_eq!(hornsgatan.location.area.as_deref(), Some("Södermalm"));
+    assert_eq!(hornsgatan.location.city, "Stockholm");
+    assert_eq!(hornsgatan.price.map(|p| p.major()), Some(4_250_000));
+    assert_eq!(hornsgatan.monthly_fee.map(|p| p.major()), Some(3_120));
+    assert_eq!(hornsgatan.rooms, Some(2.5));
+    assert_eq!(hornsgatan.sqm, Some(64));
+    assert_eq!(hornsgatan.floor, Some(3.0));
+    assert!(hornsgatan.features.has(Amenity::Balcony) && hornsgatan.features.has(Amenity::Elevator));
+    assert_eq!(hornsgatan.url, format!("{}/objekt/101/", server.uri()));
+
+    assert_eq!(properties[1].listing_status, ListingStatus::ComingSoon);
+    assert_eq!(properties[1].price, None);
+}
@@ -0,0 +1,62 @@
+//! Sources cut off when they run out of time
+
+use anyhow::Result;
+use async_trait::async_trait;
+use housing_scout::models::Property;
+use housing_scout::scrapers::{run_sources, ScraperTrait};
+use housing_scout::shutdown::{Interrupted, Shutdown};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Reads a page every 20 ms on a blocking thread, as the browser source does
+struct SlowSource {
+    shutdown: Shutdown,
+    pages: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ScraperTrait for SlowSource {
+    async fn scrape(&self) -> Result<Vec<Property>> {
+        let (shutdown, pages) = (self.shutdown.clone(), self.pages.clone());
+        tokio::task::spawn_blocking(move || {
+            for _ in 0..100 {
+                if shutdown.requested() {
+                    return Err(Interrupted.into());
+                }
+                std::thread::sleep(Duration::from_millis(20));
+                pages.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(Vec::new())
+        })
+        .await?
+    }
+
+    fn source_name(&self) -> &str {
+        "slow"
+    }
+
+    fn shutdown(&self) -> Option<&Shutdown> {
+        Some(&self.shutdown)
+    }
+}
+
+#[tokio::test]
+async fn timed_out_sources_stop_their_blocking_work() {
+    let run = Shutdown::default();
+    let pages = Arc::new(AtomicUsize::new(0));
+    let source = SlowSource {
+        shutdown: run.child(),
+        pages: pages.clone(),
+    };
+
+    let outcomes = run_sources(vec![Box::new(source)], 1, Duration::from_millis(200), &run).await;
+    assert!(outcomes[0].result.as_ref().unwrap_err().to_string().starts_with("Timed out"));
+    assert!(!run.requested());
+
+    // The page in flight may still finish, but no more after it
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let read = pages.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(pages.load(Ordering::SeqCst), read);
+}
@@ -0,0 +1,170 @@
+//! Snapshots and outputs written a listing at a time
+
+use housing_scout::models::{Location, Money, Property, Source};
+use housing_scout::output::{write_outputs, OutputConfig};
+use housing_scout::storage::{
+    write_json_array, write_json_lines, write_sqlite, Annotations, Checkpoint, History, JsonStore, RunParams, ScrapeRun,
+};
+
+fn listings(n: usize) -> Vec<Property> {
+    (0..n)
+        .map(|i| {
+            Property::builder(format!("{}", 1000 + i), Source::Booli)
+                .location(Location {
+                    city: "Stockholm".to_string(),
+                    area: Some("Södermalm".to_string()),
+                    latitude: None,
+                    longitude: None,
+                })
+                .address(format!("Götgatan {}", i + 1))
+                .price(Some(Money::sek(4_000_000 + i as i64 * 1000)))
+                .sqm(Some(50))
+                .url(format!("https://www.booli.se/annons/{}", 1000 + i))
+                .build()
+                .expect("valid listing")
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn snapshots_read_back_what_was_streamed() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = JsonStore::new(dir.path());
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+
+    for n in [0, 1, 2500] {
+        let path = store.save_snapshot(date, &listings(n)).await.unwrap();
+        let read = store.load_snapshot("2026-10-01").await.unwrap();
+        assert_eq!(read.len(), n);
+        assert_eq!(read.last().map(|p| p.address.clone()), n.checked_sub(1).map(|i| format!("Götgatan {}", i + 1)));
+        assert!(!path.with_extension("json.partial").exists());
+    }
+}
+
+#[tokio::test]
+async fn json_lines_hold_one_listing_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let lines = dir.path().join("listings.jsonl");
+    let array = dir.path().join("listings.json");
+    write_json_lines(&lines, &listings(3)).await.unwrap();
+    write_json_array(&array, &listings(3)).await.unwrap();
+
+    let text = std::fs::read_to_string(&lines).unwrap();
+    let ids: Vec<String> = text
+        .lines()
+        .map(|line| serde_json::from_str::<Property>(line).unwrap().id)
+        .collect();
+    assert_eq!(ids, ["1000", "1001", "1002"]);
+
+    let from_array: Vec<Property> = serde_json::from_str(&std::fs::read_to_string(&array).unwrap()).unwrap();
+    assert_eq!(from_array.len(), 3);
+}
+
+#[test]
+fn sqlite_writes_span_several_transactions() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("listings.sqlite");
+    write_sqlite(&path, &listings(2500), &History::default(), &Annotations::default()).unwrap();
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    let count: i64 = conn.query_row("SELECT count(*) FROM properties", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 2500);
+}
+
+#[tokio::test]
+async fn unchanged_listings_are_not_written_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = JsonStore::new(dir.path().join("data"));
+    let config = OutputConfig {
+        properties: String::new(),
+        listing: dir.path().join("raw_scrape/{run}/{id}.json").to_string_lossy().into_owned(),
+        keep_listing_versions: Some(2),
+        ..OutputConfig::default()
+    };
+    let mut run = ScrapeRun::start(RunParams {
+        sources: Vec::new(),
+        areas: Vec::new(),
+        details: false,
+        download_images: false,
+    });
+    let mut properties = listings(2);
+    let mut write = |id: &str, properties: &[Property]| {
+        run.id = id.to_string();
+        let properties: Vec<Property> = properties
+            .iter()
+            .cloned()
+            .map(|mut p| {
+                p.run_id = Some(id.to_string());
+                p.scraped_at = chrono::Utc::now();
+                p
+            })
+            .collect();
+        let (run, config, store) = (run.clone(), &config, &store);
+        async move { write_outputs(&properties, &run, config, store).await.unwrap() }
+    };
+
+    assert_eq!(write("r1", &properties).await.len(), 2);
+    assert!(write("r2", &properties).await.is_empty());
+
+    for (i, id) in ["r3", "r4"].into_iter().enumerate() {
+        properties[0].price = Some(Money::sek(3_000_000 + i as i64));
+        assert_eq!(write(id, &properties).await, [dir.path().join(format!("raw_scrape/{}/1000.json", id))]);
+    }
+    let kept = |id: &str| dir.path().join(format!("raw_scrape/{}/1000.json", id)).exists();
+    assert!(!kept("r1"));
+    assert!(kept("r3") && kept("r4"));
+    assert!(dir.path().join("raw_scrape/r1/1001.json").exists());
+}
+
+#[test]
+fn checkpoints_survive_a_write_cut_short() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints/run.json");
+    let journal = dir.path().join("checkpoints/run.enriched.jsonl");
+    let checkpoint = Checkpoint::open(&path).unwrap();
+    checkpoint.record_page("Södermalm", &listings(3));
+    for property in &listings(2) {
+        checkpoint.record_enriched(property);
+    }
+    drop(checkpoint);
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&journal)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, b"{\"id\":\"1002\",\"address\":\"G\xc3"))
+        .unwrap();
+
+    let checkpoint = Checkpoint::open(&path).unwrap();
+    assert_eq!(checkpoint.page("Södermalm").map(|page| page.len()), Some(3));
+    assert!(checkpoint.enriched("1001").is_some());
+    assert!(checkpoint.enriched("1002").is_none());
+
+    // Resuming appends after the cut-short line, not onto it
+    checkpoint.record_enriched(&listings(3)[2]);
+    drop(checkpoint);
+    let checkpoint = Checkpoint::open(&path).unwrap();
+    assert!(checkpoint.enriched("1000").is_some());
+    assert!(checkpoint.enriched("1002").is_some());
+    checkpoint.remove().unwrap();
+    assert!(!path.exists() && !journal.exists());
+}
+
+#[tokio::test]
+async fn runs_of_one_day_keep_snapshots_of_their_own() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = JsonStore::new(dir.path());
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+    store.save_properties("20261001T090000000Z-b2c3", &listings(2)).await.unwrap();
+    store.save_properties("20261001T080000000Z-a1b2", &listings(3)).await.unwrap();
+    store.save_snapshot(date, &listings(1)).await.unwrap();
+
+    let names: Vec<String> = store
+        .snapshot_paths()
+        .await
+        .unwrap()
+        .iter()
+        .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["2026-10-01", "20261001T080000000Z-a1b2", "20261001T090000000Z-b2c3"]);
+    assert_eq!(store.load_latest_properties().await.unwrap().unwrap().len(), 2);
+    assert_eq!(store.load_snapshot("20261001T080000000Z-a1b2").await.unwrap().len(), 3);
+}